@@ -0,0 +1,520 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+
+use crate::bus::Bus;
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use crate::{Byte, Word};
+
+// The registers, at these offsets from the base address of the ACIA
+pub const DATA: Word = 0x0;
+pub const STATUS: Word = 0x1; // writing it is a programmed reset
+pub const COMMAND: Word = 0x2;
+pub const CONTROL: Word = 0x3;
+
+// The bits of the status register
+pub const STATUS_PARITY_ERROR: Byte = 0x01;
+pub const STATUS_FRAMING_ERROR: Byte = 0x02;
+pub const STATUS_OVERRUN: Byte = 0x04;
+/// a byte was received, reading the data register clears it
+pub const STATUS_RDRF: Byte = 0x08;
+/// the data register can take the next byte to send
+pub const STATUS_TDRE: Byte = 0x10;
+// bit 5 is DCD and bit 6 DSR, both active low: the modem is always there
+pub const STATUS_IRQ: Byte = 0x80;
+
+// The bits of the command register
+/// 0: the receiver and the interrupts are off
+pub const COMMAND_DTR: Byte = 0x01;
+pub const COMMAND_RX_IRQ_DISABLE: Byte = 0x02;
+/// 00: the transmitter is off, 01: on with its interrupt, 10: on without
+/// it, 11: on and sending a break
+pub const COMMAND_TX_MASK: Byte = 0x0C;
+pub const COMMAND_TX_IRQ: Byte = 0x04;
+/// the received bytes are sent back, with the transmitter off
+pub const COMMAND_ECHO: Byte = 0x10;
+pub const COMMAND_PARITY: Byte = 0x20;
+
+// The bits of the control register
+/// bits 0-3, see BAUD_RATES
+pub const CONTROL_BAUD_MASK: Byte = 0x0F;
+/// bits 5-6: 8, 7, 6 or 5 data bits
+pub const CONTROL_WORD_LENGTH_MASK: Byte = 0x60;
+pub const CONTROL_TWO_STOP_BITS: Byte = 0x80;
+
+/// The rates of the control register. 0 is the external clock, the
+/// 1.8432 MHz crystal divided by 16 here. 109.92 and 134.58 are rounded
+pub const BAUD_RATES: [u64; 16] = [
+    115_200, 50, 75, 110, 135, 150, 300, 600, 1200, 1800, 2400, 3600, 4800, 7200, 9600, 19200,
+];
+
+/// The host side of the serial line: what a terminal typed, and where the
+/// bytes the 6502 sends go
+pub trait SerialPort {
+    /// the next byte from the host, without waiting for it
+    fn receive(&mut self) -> Option<Byte>;
+
+    fn transmit(&mut self, value: Byte);
+
+    /// the user asked to quit
+    fn closed(&self) -> bool {
+        false
+    }
+}
+
+/// A serial line to a buffer, for tests and scripts
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BufferedSerial {
+    pub input: VecDeque<Byte>,
+    pub output: Vec<Byte>,
+}
+
+impl BufferedSerial {
+    pub fn new(input: &str) -> Self {
+        BufferedSerial {
+            input: input.bytes().collect(),
+            output: Vec::new(),
+        }
+    }
+}
+
+impl SerialPort for BufferedSerial {
+    fn receive(&mut self) -> Option<Byte> {
+        self.input.pop_front()
+    }
+
+    fn transmit(&mut self, value: Byte) {
+        self.output.push(value);
+    }
+}
+
+/// The MOS 6551 Asynchronous Communications Interface Adapter, seen through
+/// 4 addresses (mirrored above). The bytes go to and come from a SerialPort
+/// at the pace of the baud rate: a byte takes start bit + data bits + parity
+/// + stop bits bit times of cpu_hz / baud cycles, counted by tick().
+///
+/// A byte written to the data register goes to the shift register at once
+/// when it is idle (TDRE stays set), the next one waits in the register.
+/// The host is asked for a byte only when the receive register is empty, as
+/// if it followed the flow control: pasted text is not lost, overruns and
+/// parity or framing errors never happen
+#[derive(Debug, Clone, PartialEq)]
+pub struct Acia6551<S: SerialPort> {
+    pub port: S,
+    pub status: Byte,
+    pub command: Byte,
+    pub control: Byte,
+    /// the clock of the CPU, the cycles of tick() are counted in it
+    pub cpu_hz: u64,
+
+    received: Byte,
+    // the byte waiting in the transmit data register, and the one being
+    // shifted out with the cycles it still takes
+    to_send: Option<Byte>,
+    sending: Option<Byte>,
+    send_cycles: u64,
+    // cycles before the receiver can take the next byte
+    receive_cycles: u64,
+}
+
+impl<S: SerialPort> Acia6551<S> {
+    /// after a reset the receiver interrupt is disabled, the transmitter is
+    /// off and the data register is empty
+    pub fn new(port: S) -> Self {
+        Acia6551 {
+            port,
+            status: STATUS_TDRE,
+            command: COMMAND_RX_IRQ_DISABLE,
+            control: 0,
+            cpu_hz: 1_000_000,
+            received: 0,
+            to_send: None,
+            sending: None,
+            send_cycles: 0,
+            receive_cycles: 0,
+        }
+    }
+
+    pub fn baud_rate(&self) -> u64 {
+        BAUD_RATES[(self.control & CONTROL_BAUD_MASK) as usize]
+    }
+
+    /// the cycles a byte takes on the line
+    pub fn frame_cycles(&self) -> u64 {
+        let data_bits: u64 = 8 - ((self.control & CONTROL_WORD_LENGTH_MASK) >> 5) as u64;
+        let parity_bits: u64 = (self.command & COMMAND_PARITY != 0) as u64;
+        let stop_bits: u64 = 1 + (self.control & CONTROL_TWO_STOP_BITS != 0) as u64;
+        let bits: u64 = 1 + data_bits + parity_bits + stop_bits;
+        self.cpu_hz * bits / self.baud_rate()
+    }
+
+    fn receiver_on(&self) -> bool {
+        self.command & COMMAND_DTR != 0
+    }
+
+    fn transmitter_on(&self) -> bool {
+        self.command & COMMAND_TX_MASK != 0
+    }
+
+    // the 7, 6 or 5 bits of the word length
+    fn word_mask(&self) -> Byte {
+        0xFF >> ((self.control & CONTROL_WORD_LENGTH_MASK) >> 5)
+    }
+
+    // Moves the waiting byte to the idle shift register, the data register
+    // is free again
+    fn start_sending(&mut self) {
+        if self.sending.is_some() || !self.transmitter_on() {
+            return;
+        }
+        if let Some(value) = self.to_send.take() {
+            self.sending = Some(value & self.word_mask());
+            self.send_cycles = self.frame_cycles();
+            self.status |= STATUS_TDRE;
+            if self.command & COMMAND_TX_MASK == COMMAND_TX_IRQ && self.receiver_on() {
+                self.status |= STATUS_IRQ;
+            }
+        }
+    }
+
+    fn send(&mut self, cycles: u64) {
+        self.send_cycles = self.send_cycles.saturating_sub(cycles);
+        if self.send_cycles == 0 {
+            if let Some(value) = self.sending.take() {
+                self.port.transmit(value);
+            }
+            self.start_sending();
+        }
+    }
+
+    fn receive(&mut self, cycles: u64) {
+        self.receive_cycles = self.receive_cycles.saturating_sub(cycles);
+        if self.receive_cycles > 0 || !self.receiver_on() || self.status & STATUS_RDRF != 0 {
+            return;
+        }
+        if let Some(value) = self.port.receive() {
+            self.received = value & self.word_mask();
+            self.receive_cycles = self.frame_cycles();
+            self.status |= STATUS_RDRF;
+            if self.command & COMMAND_RX_IRQ_DISABLE == 0 {
+                self.status |= STATUS_IRQ;
+            }
+            if self.command & (COMMAND_ECHO | COMMAND_TX_MASK) == COMMAND_ECHO {
+                self.port.transmit(self.received);
+            }
+        }
+    }
+
+    fn register(&self, address: Word) -> Byte {
+        match address & 0x03 {
+            DATA => self.received,
+            STATUS => self.status,
+            COMMAND => self.command,
+            _ => self.control,
+        }
+    }
+}
+
+impl<S: SerialPort> Bus for Acia6551<S> {
+    fn read(&mut self, address: Word) -> Byte {
+        let value: Byte = self.register(address);
+        match address & 0x03 {
+            DATA => self.status &= !(STATUS_RDRF | STATUS_OVERRUN),
+            STATUS => self.status &= !STATUS_IRQ,
+            _ => {}
+        }
+        value
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        match address & 0x03 {
+            DATA => {
+                self.to_send = Some(value);
+                self.status &= !STATUS_TDRE;
+                self.start_sending();
+            }
+            // the programmed reset keeps the parity and the control register
+            STATUS => {
+                self.command = (self.command & 0xE0) | COMMAND_RX_IRQ_DISABLE;
+                self.status &= !STATUS_OVERRUN;
+            }
+            COMMAND => {
+                self.command = value;
+                self.start_sending();
+            }
+            _ => self.control = value,
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.register(address)
+    }
+
+    fn poke(&mut self, _address: Word, _value: Byte) {}
+
+    fn irq(&self) -> bool {
+        self.status & STATUS_IRQ != 0
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.send(cycles);
+        self.receive(cycles);
+    }
+
+    // the bytes on the line are not saved, the host is gone after a load
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        for register in [self.status, self.command, self.control, self.received] {
+            out.byte(register);
+        }
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        self.status = input.byte()?;
+        self.command = input.byte()?;
+        self.control = input.byte()?;
+        self.received = input.byte()?;
+        Ok(())
+    }
+}
+
+/// A pseudo-terminal the 6502 is wired to: a terminal program opens path
+/// (screen /dev/pts/3 or picocom, minicom...) to talk with it
+#[cfg(target_os = "linux")]
+pub struct PtySerial {
+    pub path: String,
+    master: std::fs::File,
+    // the emulator keeps the terminal side open, so the pty stays up when
+    // the terminal program quits
+    _slave: std::fs::File,
+    received: Receiver<Byte>,
+}
+
+// The pty functions of the C library, std links it already
+#[cfg(target_os = "linux")]
+mod ffi {
+    use std::os::raw::{c_char, c_int};
+
+    // open(2) flags differ between the architectures, O_NONBLOCK is one of
+    // them
+    #[cfg(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "loongarch64"
+    ))]
+    pub const O_NONBLOCK: i32 = 0o4000;
+    #[cfg(any(target_arch = "mips", target_arch = "mips64"))]
+    pub const O_NONBLOCK: i32 = 0o200;
+    #[cfg(any(target_arch = "sparc", target_arch = "sparc64"))]
+    pub const O_NONBLOCK: i32 = 0o40000;
+    #[cfg(not(any(
+        target_arch = "x86",
+        target_arch = "x86_64",
+        target_arch = "arm",
+        target_arch = "aarch64",
+        target_arch = "riscv32",
+        target_arch = "riscv64",
+        target_arch = "powerpc",
+        target_arch = "powerpc64",
+        target_arch = "s390x",
+        target_arch = "loongarch64",
+        target_arch = "mips",
+        target_arch = "mips64",
+        target_arch = "sparc",
+        target_arch = "sparc64"
+    )))]
+    compile_error!("O_NONBLOCK of this architecture is not known, add it to acia.rs");
+
+    extern "C" {
+        pub fn grantpt(fd: c_int) -> c_int;
+        pub fn unlockpt(fd: c_int) -> c_int;
+        pub fn ptsname_r(fd: c_int, buf: *mut c_char, buflen: usize) -> c_int;
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl PtySerial {
+    pub fn open() -> std::io::Result<Self> {
+        use std::ffi::CStr;
+        use std::fs::OpenOptions;
+        use std::io::{Error, Read};
+        use std::os::raw::c_char;
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::io::AsRawFd;
+        use std::process::Command;
+
+        // writes don't block when nobody reads the terminal, the bytes are
+        // dropped once its buffer is full
+        let master = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(ffi::O_NONBLOCK)
+            .open("/dev/ptmx")?;
+        let fd = master.as_raw_fd();
+        let mut name: [c_char; 128] = [0; 128];
+        // SAFETY: fd is the open master and name is big enough for the
+        // terminating 0 ptsname_r always writes
+        let path: String = unsafe {
+            if ffi::grantpt(fd) != 0 || ffi::unlockpt(fd) != 0 {
+                return Err(Error::last_os_error());
+            }
+            let error = ffi::ptsname_r(fd, name.as_mut_ptr(), name.len());
+            if error != 0 {
+                return Err(Error::from_raw_os_error(error));
+            }
+            CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned()
+        };
+        let slave = OpenOptions::new().read(true).write(true).open(&path)?;
+
+        // raw: no echo, no line editing, CR and LF go through untouched
+        let raw = Command::new("stty")
+            .args(["-F", path.as_str(), "raw", "-echo"])
+            .status()?;
+        if !raw.success() {
+            return Err(Error::other("stty failed on the pty"));
+        }
+
+        let (sender, received) = std::sync::mpsc::channel();
+        let mut reader = master.try_clone()?;
+        thread::spawn(move || {
+            let mut buffer: [Byte; 256] = [0; 256];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(length) if length > 0 => {
+                        if buffer[..length].iter().any(|&b| sender.send(b).is_err()) {
+                            break;
+                        }
+                    }
+                    // nothing typed (or nobody on the other side) yet
+                    _ => thread::sleep(Duration::from_millis(10)),
+                }
+            }
+        });
+
+        Ok(PtySerial {
+            path,
+            master,
+            _slave: slave,
+            received,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl SerialPort for PtySerial {
+    fn receive(&mut self) -> Option<Byte> {
+        self.received.try_recv().ok()
+    }
+
+    fn transmit(&mut self, value: Byte) {
+        let _ = self.master.write_all(&[value]);
+    }
+}
+
+pub const ACIA_BASE: Word = 0x8000;
+pub const ROM_MAX: usize = 0x4000;
+
+/// A computer for ROMs that talk through a 6551, like the 6502 single
+/// board computers running EhBASIC or a monitor:
+///  $0000-$7FFF  RAM
+///  $8000-$80FF  the ACIA, mirrored every 4 bytes
+///  the ROM at the top of the memory, 16 KiB at most
+pub struct SerialBus<S: SerialPort> {
+    pub ram: Vec<Byte>,
+    pub acia: Acia6551<S>,
+    pub rom: Vec<Byte>,
+}
+
+impl<S: SerialPort> SerialBus<S> {
+    pub fn new(rom: Vec<Byte>, port: S) -> Self {
+        assert!(
+            !rom.is_empty() && rom.len() <= ROM_MAX,
+            "the ROM takes 1 byte to 16 KiB, not {} bytes",
+            rom.len()
+        );
+
+        SerialBus {
+            ram: vec![0; 0x8000],
+            acia: Acia6551::new(port),
+            rom,
+        }
+    }
+
+    fn rom_start(&self) -> usize {
+        0x10000 - self.rom.len()
+    }
+
+    fn is_acia(address: Word) -> bool {
+        address & 0xFF00 == ACIA_BASE
+    }
+}
+
+impl<S: SerialPort> Bus for SerialBus<S> {
+    fn read(&mut self, address: Word) -> Byte {
+        if Self::is_acia(address) {
+            self.acia.read(address)
+        } else {
+            self.peek(address)
+        }
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize] = value;
+        } else if Self::is_acia(address) {
+            self.acia.write(address, value);
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize]
+        } else if Self::is_acia(address) {
+            self.acia.peek(address)
+        } else if address as usize >= self.rom_start() {
+            self.rom[address as usize - self.rom_start()]
+        } else {
+            0
+        }
+    }
+
+    fn poke(&mut self, address: Word, value: Byte) {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize] = value;
+        }
+    }
+
+    fn irq(&self) -> bool {
+        self.acia.irq()
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.acia.tick(cycles);
+    }
+
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        out.bytes(&self.ram);
+        self.acia.save_state(out);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        let ram = input.bytes()?;
+        if ram.len() != self.ram.len() {
+            return Err(SnapshotError::Invalid(format!(
+                "{} bytes of RAM, the snapshot has {}",
+                self.ram.len(),
+                ram.len()
+            )));
+        }
+        self.ram.copy_from_slice(ram);
+        self.acia.load_state(input)
+    }
+}
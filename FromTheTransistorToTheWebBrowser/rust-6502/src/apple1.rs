@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+
+use crate::bus::Bus;
+use crate::pia::{Pia6821, CONTROL_IRQ1_FLAG, CONTROL_PORT_SELECT};
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use crate::{Byte, Word};
+
+// The memory map of the Apple-1:
+//  $0000-     4 to 8 KiB of RAM
+//  $D010-$D013  the PIA: keyboard on port A, display on port B
+//  $FF00-$FFFF  Wozmon
+pub const KBD: Word = 0xD010; // the key, bit 7 set
+pub const KBDCR: Word = 0xD011; // bit 7 is set when a key is ready
+pub const DSP: Word = 0xD012; // the character to show, bit 7 is busy
+pub const DSPCR: Word = 0xD013;
+pub const WOZMON_START: Word = 0xFF00;
+pub const COLUMNS: usize = 40;
+
+/// Wozmon assembled at $FF00, the source is in wozmon.asm
+pub const WOZMON: [Byte; 256] = [
+    0xd8, 0x58, 0xa0, 0x7f, 0x8c, 0x12, 0xd0, 0xa9, 0xa7, 0x8d, 0x11, 0xd0, 0x8d, 0x13, 0xd0, 0xc9,
+    0xdf, 0xf0, 0x13, 0xc9, 0x9b, 0xf0, 0x03, 0xc8, 0x10, 0x0f, 0xa9, 0xdc, 0x20, 0xef, 0xff, 0xa9,
+    0x8d, 0x20, 0xef, 0xff, 0xa0, 0x01, 0x88, 0x30, 0xf6, 0xad, 0x11, 0xd0, 0x10, 0xfb, 0xad, 0x10,
+    0xd0, 0x99, 0x00, 0x02, 0x20, 0xef, 0xff, 0xc9, 0x8d, 0xd0, 0xd4, 0xa0, 0xff, 0xa9, 0x00, 0xaa,
+    0x0a, 0x85, 0x2b, 0xc8, 0xb9, 0x00, 0x02, 0xc9, 0x8d, 0xf0, 0xd4, 0xc9, 0xae, 0x90, 0xf4, 0xf0,
+    0xf0, 0xc9, 0xba, 0xf0, 0xeb, 0xc9, 0xd2, 0xf0, 0x3b, 0x86, 0x28, 0x86, 0x29, 0x84, 0x2a, 0xb9,
+    0x00, 0x02, 0x49, 0xb0, 0xc9, 0x0a, 0x90, 0x06, 0x69, 0x88, 0xc9, 0xfa, 0x90, 0x11, 0x0a, 0x0a,
+    0x0a, 0x0a, 0xa2, 0x04, 0x0a, 0x26, 0x28, 0x26, 0x29, 0xca, 0xd0, 0xf8, 0xc8, 0xd0, 0xe0, 0xc4,
+    0x2a, 0xf0, 0x97, 0x24, 0x2b, 0x50, 0x10, 0xa5, 0x28, 0x81, 0x26, 0xe6, 0x26, 0xd0, 0xb5, 0xe6,
+    0x27, 0x4c, 0x44, 0xff, 0x6c, 0x24, 0x00, 0x30, 0x2b, 0xa2, 0x02, 0xb5, 0x27, 0x95, 0x25, 0x95,
+    0x23, 0xca, 0xd0, 0xf7, 0xd0, 0x14, 0xa9, 0x8d, 0x20, 0xef, 0xff, 0xa5, 0x25, 0x20, 0xdc, 0xff,
+    0xa5, 0x24, 0x20, 0xdc, 0xff, 0xa9, 0xba, 0x20, 0xef, 0xff, 0xa9, 0xa0, 0x20, 0xef, 0xff, 0xa1,
+    0x24, 0x20, 0xdc, 0xff, 0x86, 0x2b, 0xa5, 0x24, 0xc5, 0x28, 0xa5, 0x25, 0xe5, 0x29, 0xb0, 0xc1,
+    0xe6, 0x24, 0xd0, 0x02, 0xe6, 0x25, 0xa5, 0x24, 0x29, 0x07, 0x10, 0xc8, 0x48, 0x4a, 0x4a, 0x4a,
+    0x4a, 0x20, 0xe5, 0xff, 0x68, 0x29, 0x0f, 0x09, 0xb0, 0xc9, 0xba, 0x90, 0x02, 0x69, 0x06, 0x2c,
+    0x12, 0xd0, 0x30, 0xfb, 0x8d, 0x12, 0xd0, 0x60, 0x00, 0x00, 0x00, 0x0f, 0x00, 0xff, 0x00, 0x00,
+];
+
+/// An Apple-1: RAM from $0000, the PIA and Wozmon. The keyboard sends 7 bit
+/// upper case ASCII and strobes CA1, the display takes the characters
+/// written to port B and shows them on 40 columns
+pub struct Apple1Bus {
+    pub ram: Vec<Byte>,
+    pub pia: Pia6821,
+    // typed keys the keyboard hasn't sent yet
+    keys: VecDeque<Byte>,
+    // what the display showed since the last take_output, with \n for
+    // carriage returns and for lines longer than 40 columns
+    output: String,
+    column: usize,
+}
+
+impl Apple1Bus {
+    pub fn new(ram_size: usize) -> Self {
+        assert!(
+            (0x1000..=0x2000).contains(&ram_size),
+            "the Apple-1 has 4 to 8 KiB of RAM, not {} bytes",
+            ram_size
+        );
+
+        Apple1Bus {
+            ram: vec![0; ram_size],
+            pia: Pia6821::new(),
+            keys: VecDeque::new(),
+            output: String::new(),
+            column: 0,
+        }
+    }
+
+    /// The keyboard has no lower case and no backspace, Wozmon takes _ as
+    /// a rub out. Return is CR, the keys above $7F don't exist
+    pub fn press_key(&mut self, key: Byte) {
+        let key: Byte = match key {
+            b'\n' | b'\r' => 0x0D,
+            0x08 | 0x7F => b'_',
+            key => key.to_ascii_uppercase(),
+        };
+        if key < 0x80 {
+            self.keys.push_back(key);
+            self.strobe_key();
+        }
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        for key in text.bytes() {
+            self.press_key(key);
+        }
+    }
+
+    // Sends the next key once the program has read the last one
+    fn strobe_key(&mut self) {
+        if self.pia.control_a & CONTROL_IRQ1_FLAG != 0 {
+            return;
+        }
+        if let Some(key) = self.keys.pop_front() {
+            // bit 7 of the keyboard port is wired high
+            self.pia.input_a = key | 0x80;
+            self.pia.set_ca1(true);
+            self.pia.set_ca1(false);
+        }
+    }
+
+    // The characters $60-$7F show as $40-$5F, the other control
+    // characters than CR are not shown
+    fn display(&mut self, value: Byte) {
+        match value & 0x7F {
+            0x0D => {
+                self.output.push('\n');
+                self.column = 0;
+            }
+            0x00..=0x1F | 0x7F => {}
+            character => {
+                let character: Byte = if character >= 0x60 {
+                    character - 0x20
+                } else {
+                    character
+                };
+                self.output.push(character as char);
+                self.column += 1;
+                if self.column == COLUMNS {
+                    self.output.push('\n');
+                    self.column = 0;
+                }
+            }
+        }
+    }
+
+    /// What the display showed since the last call
+    pub fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    fn is_pia(address: Word) -> bool {
+        (KBD..=DSPCR).contains(&address)
+    }
+}
+
+// Nothing answers outside of the RAM, the PIA and the ROM. The IRQ outputs
+// of the PIA are not wired to the CPU: Wozmon enables them and then runs
+// with interrupts enabled
+impl Bus for Apple1Bus {
+    fn read(&mut self, address: Word) -> Byte {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize]
+        } else if Self::is_pia(address) {
+            let value: Byte = self.pia.read(address - KBD);
+            if address == KBD {
+                self.strobe_key();
+            }
+            value
+        } else {
+            self.peek(address)
+        }
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize] = value;
+        } else if Self::is_pia(address) {
+            self.pia.write(address - KBD, value);
+            // the display is always ready, bit 7 of port B (busy) stays low
+            if address == DSP && self.pia.control_b & CONTROL_PORT_SELECT != 0 {
+                self.display(self.pia.port_b());
+            }
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize]
+        } else if Self::is_pia(address) {
+            self.pia.peek(address - KBD)
+        } else if address >= WOZMON_START {
+            WOZMON[(address - WOZMON_START) as usize]
+        } else {
+            0
+        }
+    }
+
+    fn poke(&mut self, address: Word, value: Byte) {
+        if (address as usize) < self.ram.len() {
+            self.ram[address as usize] = value;
+        }
+    }
+
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        out.bytes(&self.ram);
+        self.pia.save_state(out);
+        out.byte(self.column as Byte);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        let ram = input.bytes()?;
+        if ram.len() != self.ram.len() {
+            return Err(SnapshotError::Invalid(format!(
+                "{} bytes of RAM, the snapshot has {}",
+                self.ram.len(),
+                ram.len()
+            )));
+        }
+        self.ram.copy_from_slice(ram);
+        self.pia.load_state(input)?;
+        self.column = (input.byte()? as usize).min(COLUMNS - 1);
+        Ok(())
+    }
+}
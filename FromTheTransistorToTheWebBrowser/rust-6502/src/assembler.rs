@@ -0,0 +1,886 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::disasm::{decode, AddressingMode};
+use crate::{Byte, Word};
+
+/// 6502js assembles to $0600 when the source has no .org
+pub const DEFAULT_ORIGIN: Word = 0x0600;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssemblerError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AssemblerError {}
+
+/// Labels are relocatable, constants from `define` are not
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Symbol {
+    pub value: Word,
+    pub relocatable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RelocationKind {
+    Word,
+    Low,
+    High,
+}
+
+/// A place in the image holding (part of) an address that moves with the
+/// program, value is the full address the bytes were computed from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relocation {
+    pub offset: usize,
+    pub kind: RelocationKind,
+    pub value: Word,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub origin: Word,
+    pub bytes: Vec<Byte>,
+    pub symbols: BTreeMap<String, Symbol>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Program {
+    pub fn symbol(&self, name: &str) -> Option<Word> {
+        self.symbols.get(name).map(|symbol| symbol.value)
+    }
+
+    /// Copy of the program moved to origin: the relocations are patched and
+    /// the labels moved, constants stay as they are
+    pub fn relocate(&self, origin: Word) -> Program {
+        let delta: Word = origin.wrapping_sub(self.origin);
+        let mut program = self.clone();
+        program.origin = origin;
+
+        for relocation in program.relocations.iter_mut() {
+            relocation.value = relocation.value.wrapping_add(delta);
+            let [lo, hi] = relocation.value.to_le_bytes();
+            match relocation.kind {
+                RelocationKind::Word => {
+                    program.bytes[relocation.offset] = lo;
+                    program.bytes[relocation.offset + 1] = hi;
+                }
+                RelocationKind::Low => program.bytes[relocation.offset] = lo,
+                RelocationKind::High => program.bytes[relocation.offset] = hi,
+            }
+        }
+
+        for symbol in program.symbols.values_mut() {
+            if symbol.relocatable {
+                symbol.value = symbol.value.wrapping_add(delta);
+            }
+        }
+
+        program
+    }
+
+    /// write the image at its origin
+    pub fn load<M: Bus>(&self, memory: &mut M) {
+        for (i, &byte) in self.bytes.iter().enumerate() {
+            memory.write(self.origin.wrapping_add(i as Word), byte);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Modifier {
+    Low,  // <
+    High, // >
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Number { value: i32, wide: bool },
+    Symbol(String),
+    ProgramCounter, // *
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Expression {
+    modifier: Option<Modifier>,
+    terms: Vec<(i32, Term)>, // sign and term
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Value {
+    value: i32,
+    // the value is an address inside the program
+    relocatable: bool,
+    // a hex number written with more than two digits, e.g. $0044, forces
+    // the absolute addressing modes
+    wide: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    None,
+    Accumulator,
+    Immediate(Expression),
+    Direct(Expression),
+    DirectX(Expression),
+    DirectY(Expression),
+    Indirect(Expression),
+    IndirectX(Expression),
+    IndirectY(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Data {
+    Expression(Expression),
+    Text(Vec<Byte>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Statement {
+    Define(String, Expression),
+    Org(Expression),
+    Bytes(Vec<Data>),
+    Words(Vec<Expression>),
+    Instruction(&'static str, Operand),
+}
+
+struct Line {
+    number: usize,
+    label: Option<String>,
+    statement: Option<Statement>,
+}
+
+fn mnemonic(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_uppercase();
+    (0..=0xFF)
+        .filter_map(decode)
+        .map(|(mnemonic, _)| mnemonic)
+        .find(|&mnemonic| mnemonic == name)
+}
+
+fn opcode(mnemonic: &str, mode: AddressingMode) -> Option<Byte> {
+    (0..=0xFF).find(|&opcode| decode(opcode) == Some((mnemonic, mode)))
+}
+
+fn has_mode(mnemonic: &str, mode: AddressingMode) -> bool {
+    opcode(mnemonic, mode).is_some()
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '.' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+// removes the comment, a ; inside quotes is not a comment
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ';' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+// splits at the commas that are not inside quotes
+fn split_list(text: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == ',' => {
+                items.push(text[start..i].trim());
+                start = i + 1;
+            }
+            None => {}
+        }
+    }
+    items.push(text[start..].trim());
+    items
+}
+
+fn parse_number(text: &str) -> Result<Term, String> {
+    let invalid = || format!("invalid number '{}'", text);
+
+    let (digits, radix) = if let Some(hex) = text.strip_prefix('$') {
+        (hex, 16)
+    } else if let Some(binary) = text.strip_prefix('%') {
+        (binary, 2)
+    } else {
+        (text, 10)
+    };
+
+    let value = i32::from_str_radix(digits, radix).map_err(|_| invalid())?;
+    if value > 0xFFFF {
+        return Err(format!("number '{}' does not fit in 16 bits", text));
+    }
+
+    Ok(Term::Number {
+        value,
+        wide: radix == 16 && digits.len() > 2,
+    })
+}
+
+fn parse_term(text: &str) -> Result<Term, String> {
+    if text == "*" {
+        return Ok(Term::ProgramCounter);
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() == 3 && chars[0] == '\'' && chars[2] == '\'' {
+        return Ok(Term::Number {
+            value: chars[1] as i32,
+            wide: false,
+        });
+    }
+
+    if is_identifier(text) {
+        return Ok(Term::Symbol(text.to_string()));
+    }
+
+    match chars.first() {
+        Some(c) if c.is_ascii_digit() || *c == '$' || *c == '%' => parse_number(text),
+        _ => Err(format!("invalid expression '{}'", text)),
+    }
+}
+
+// [<|>] term {(+|-) term}
+fn parse_expression(text: &str) -> Result<Expression, String> {
+    let mut text = text.trim();
+
+    let modifier = if let Some(rest) = text.strip_prefix('<') {
+        text = rest.trim_start();
+        Some(Modifier::Low)
+    } else if let Some(rest) = text.strip_prefix('>') {
+        text = rest.trim_start();
+        Some(Modifier::High)
+    } else {
+        None
+    };
+
+    if text.is_empty() {
+        return Err("missing expression".to_string());
+    }
+
+    let mut terms = Vec::new();
+    let mut sign: i32 = 1;
+    let mut current = String::new();
+    let mut in_char = false;
+
+    for c in text.chars() {
+        if c == '\'' {
+            in_char = !in_char;
+        }
+
+        if !in_char && (c == '+' || c == '-') {
+            if current.trim().is_empty() {
+                // a sign in front of the first term, e.g. -1
+                if terms.is_empty() && c == '-' {
+                    sign = -sign;
+                    continue;
+                }
+                return Err(format!("invalid expression '{}'", text));
+            }
+            terms.push((sign, parse_term(current.trim())?));
+            current.clear();
+            sign = if c == '-' { -1 } else { 1 };
+        } else {
+            current.push(c);
+        }
+    }
+
+    if current.trim().is_empty() {
+        return Err(format!("invalid expression '{}'", text));
+    }
+    terms.push((sign, parse_term(current.trim())?));
+
+    Ok(Expression { modifier, terms })
+}
+
+fn parse_operand(text: &str) -> Result<Operand, String> {
+    // spaces are not significant in instruction operands, except in 'x'
+    let mut compact = String::new();
+    let mut in_char = false;
+    for c in text.chars() {
+        if c == '\'' {
+            in_char = !in_char;
+        }
+        if in_char || !c.is_whitespace() {
+            compact.push(c);
+        }
+    }
+
+    let upper = compact.to_ascii_uppercase();
+    let inner = |start: usize, end: usize| parse_expression(&compact[start..compact.len() - end]);
+
+    if compact.is_empty() {
+        Ok(Operand::None)
+    } else if upper == "A" {
+        Ok(Operand::Accumulator)
+    } else if let Some(rest) = compact.strip_prefix('#') {
+        Ok(Operand::Immediate(parse_expression(rest)?))
+    } else if upper.starts_with('(') && upper.ends_with("),Y") {
+        Ok(Operand::IndirectY(inner(1, 3)?))
+    } else if upper.starts_with('(') && upper.ends_with(",X)") {
+        Ok(Operand::IndirectX(inner(1, 3)?))
+    } else if upper.starts_with('(') && upper.ends_with(')') {
+        Ok(Operand::Indirect(inner(1, 1)?))
+    } else if upper.ends_with(",X") {
+        Ok(Operand::DirectX(inner(0, 2)?))
+    } else if upper.ends_with(",Y") {
+        Ok(Operand::DirectY(inner(0, 2)?))
+    } else {
+        Ok(Operand::Direct(parse_expression(&compact)?))
+    }
+}
+
+fn parse_data(text: &str) -> Result<Vec<Data>, String> {
+    split_list(text)
+        .into_iter()
+        .map(|item| {
+            if item.len() >= 2 && item.starts_with('"') && item.ends_with('"') {
+                Ok(Data::Text(item[1..item.len() - 1].bytes().collect()))
+            } else {
+                Ok(Data::Expression(parse_expression(item)?))
+            }
+        })
+        .collect()
+}
+
+fn parse_statement(text: &str) -> Result<Statement, String> {
+    let (first, rest) = match text.find(char::is_whitespace) {
+        Some(i) => (&text[..i], text[i..].trim()),
+        None => (text, ""),
+    };
+
+    match first.to_ascii_lowercase().as_str() {
+        "define" => {
+            let (name, value) = match rest.find(char::is_whitespace) {
+                Some(i) => (&rest[..i], rest[i..].trim()),
+                None => return Err("define needs a name and a value".to_string()),
+            };
+            if !is_identifier(name) {
+                return Err(format!("invalid symbol name '{}'", name));
+            }
+            Ok(Statement::Define(
+                name.to_string(),
+                parse_expression(value)?,
+            ))
+        }
+        ".org" => Ok(Statement::Org(parse_expression(rest)?)),
+        ".byte" | ".db" | "dcb" => Ok(Statement::Bytes(parse_data(rest)?)),
+        ".word" | ".dw" => Ok(Statement::Words(
+            split_list(rest)
+                .into_iter()
+                .map(parse_expression)
+                .collect::<Result<Vec<Expression>, String>>()?,
+        )),
+        _ => {
+            // ca65 style constant: name = value
+            if let Some(i) = text.find('=') {
+                let name = text[..i].trim();
+                if is_identifier(name) {
+                    return Ok(Statement::Define(
+                        name.to_string(),
+                        parse_expression(&text[i + 1..])?,
+                    ));
+                }
+            }
+
+            if first.starts_with('.') {
+                return Err(format!("unknown directive '{}'", first));
+            }
+
+            match mnemonic(first) {
+                Some(mnemonic) => Ok(Statement::Instruction(mnemonic, parse_operand(rest)?)),
+                None => Err(format!("unknown instruction '{}'", first)),
+            }
+        }
+    }
+}
+
+fn parse_line(number: usize, text: &str) -> Result<Line, AssemblerError> {
+    let error = |message: String| AssemblerError {
+        line: number,
+        message,
+    };
+
+    let mut text = strip_comment(text).trim();
+    let mut label = None;
+
+    if let Some(i) = text.find(':') {
+        let name = text[..i].trim();
+        if is_identifier(name) {
+            label = Some(name.to_string());
+            text = text[i + 1..].trim();
+        }
+    }
+
+    let statement = if text.is_empty() {
+        None
+    } else {
+        Some(parse_statement(text).map_err(error)?)
+    };
+
+    Ok(Line {
+        number,
+        label,
+        statement,
+    })
+}
+
+struct Assembler {
+    symbols: BTreeMap<String, Symbol>,
+    pc: u32,
+    // address of the line being assembled, the value of *
+    line_address: u32,
+    origin: Option<Word>,
+    // addressing mode chosen for each instruction in the first pass
+    modes: BTreeMap<usize, AddressingMode>,
+    bytes: Vec<Byte>,
+    relocations: Vec<Relocation>,
+}
+
+impl Assembler {
+    // None when a symbol is not defined (yet)
+    fn evaluate(&self, expression: &Expression) -> Result<Option<Value>, String> {
+        let mut value: i32 = 0;
+        let mut relocatable: i32 = 0;
+        let mut wide = false;
+
+        for (sign, term) in expression.terms.iter() {
+            match term {
+                Term::Number {
+                    value: number,
+                    wide: is_wide,
+                } => {
+                    value += sign * number;
+                    wide |= *is_wide;
+                }
+                Term::Symbol(name) => match self.symbols.get(name) {
+                    Some(symbol) => {
+                        value += sign * symbol.value as i32;
+                        if symbol.relocatable {
+                            relocatable += sign;
+                        }
+                    }
+                    None => return Ok(None),
+                },
+                Term::ProgramCounter => {
+                    value += sign * self.line_address as i32;
+                    relocatable += sign;
+                }
+            }
+        }
+
+        // the difference between two labels does not move with the program
+        let relocatable = match relocatable {
+            0 => false,
+            1 => true,
+            _ => return Err("expression can not be relocated".to_string()),
+        };
+
+        match expression.modifier {
+            Some(Modifier::Low) => value &= 0xFF,
+            Some(Modifier::High) => value = (value >> 8) & 0xFF,
+            None => {}
+        }
+
+        Ok(Some(Value {
+            value,
+            relocatable,
+            wide: wide && expression.modifier.is_none(),
+        }))
+    }
+
+    fn resolve(&self, expression: &Expression) -> Result<Value, String> {
+        match self.evaluate(expression)? {
+            Some(value) => Ok(value),
+            None => {
+                let undefined = expression
+                    .terms
+                    .iter()
+                    .find_map(|(_, term)| match term {
+                        Term::Symbol(name) if !self.symbols.contains_key(name) => Some(name),
+                        _ => None,
+                    })
+                    .unwrap();
+                Err(format!("undefined symbol '{}'", undefined))
+            }
+        }
+    }
+
+    // Zero page when the address is known and fits in a byte, otherwise
+    // absolute. Forward references are assumed to be absolute.
+    fn choose_mode(
+        &self,
+        mnemonic: &str,
+        expression: &Expression,
+        zero_page: AddressingMode,
+        absolute: AddressingMode,
+    ) -> Result<AddressingMode, String> {
+        let small = match self.evaluate(expression)? {
+            Some(value) => !value.wide && (0..=0xFF).contains(&value.value),
+            None => false,
+        };
+
+        if small && has_mode(mnemonic, zero_page) {
+            Ok(zero_page)
+        } else if has_mode(mnemonic, absolute) {
+            Ok(absolute)
+        } else if has_mode(mnemonic, zero_page) {
+            Ok(zero_page)
+        } else {
+            Err(format!(
+                "{} does not support this addressing mode",
+                mnemonic
+            ))
+        }
+    }
+
+    fn addressing_mode(&self, mnemonic: &str, operand: &Operand) -> Result<AddressingMode, String> {
+        let only = |mode: AddressingMode| {
+            if has_mode(mnemonic, mode) {
+                Ok(mode)
+            } else {
+                Err(format!(
+                    "{} does not support this addressing mode",
+                    mnemonic
+                ))
+            }
+        };
+
+        match operand {
+            Operand::None => {
+                if has_mode(mnemonic, AddressingMode::Accumulator) {
+                    Ok(AddressingMode::Accumulator)
+                } else {
+                    only(AddressingMode::Implied)
+                }
+            }
+            Operand::Accumulator => only(AddressingMode::Accumulator),
+            Operand::Immediate(_) => only(AddressingMode::Immediate),
+            Operand::Direct(expression) => {
+                if has_mode(mnemonic, AddressingMode::Relative) {
+                    Ok(AddressingMode::Relative)
+                } else {
+                    self.choose_mode(
+                        mnemonic,
+                        expression,
+                        AddressingMode::ZeroPage,
+                        AddressingMode::Absolute,
+                    )
+                }
+            }
+            Operand::DirectX(expression) => self.choose_mode(
+                mnemonic,
+                expression,
+                AddressingMode::ZeroPageX,
+                AddressingMode::AbsoluteX,
+            ),
+            Operand::DirectY(expression) => self.choose_mode(
+                mnemonic,
+                expression,
+                AddressingMode::ZeroPageY,
+                AddressingMode::AbsoluteY,
+            ),
+            Operand::Indirect(_) => only(AddressingMode::Indirect),
+            Operand::IndirectX(_) => only(AddressingMode::IndirectX),
+            Operand::IndirectY(_) => only(AddressingMode::IndirectY),
+        }
+    }
+
+    fn statement_size(&mut self, line: &Line, statement: &Statement) -> Result<u32, String> {
+        match statement {
+            Statement::Bytes(data) => Ok(data
+                .iter()
+                .map(|item| match item {
+                    Data::Expression(_) => 1,
+                    Data::Text(text) => text.len() as u32,
+                })
+                .sum()),
+            Statement::Words(words) => Ok(2 * words.len() as u32),
+            Statement::Instruction(mnemonic, operand) => {
+                let mode = self.addressing_mode(mnemonic, operand)?;
+                self.modes.insert(line.number, mode);
+                Ok(1 + mode.operand_bytes() as u32)
+            }
+            Statement::Define(_, _) | Statement::Org(_) => Ok(0),
+        }
+    }
+
+    fn define(&mut self, name: &str, symbol: Symbol) -> Result<(), String> {
+        if self.symbols.insert(name.to_string(), symbol).is_some() {
+            return Err(format!("symbol '{}' is already defined", name));
+        }
+        Ok(())
+    }
+
+    fn org(&mut self, expression: &Expression) -> Result<(), String> {
+        let address = self.resolve(expression)?.value;
+        if !(0..=0xFFFF).contains(&address) {
+            return Err(format!(".org ${:X} is out of the address space", address));
+        }
+        if self.origin.is_some() && (address as u32) < self.pc {
+            return Err(format!(".org ${:04X} moves backwards", address));
+        }
+        self.pc = address as u32;
+        Ok(())
+    }
+
+    // defines the symbols and chooses the addressing modes
+    fn first_pass(&mut self, lines: &[Line]) -> Result<(), AssemblerError> {
+        for line in lines {
+            let error = |message: String| AssemblerError {
+                line: line.number,
+                message,
+            };
+            self.line_address = self.pc;
+
+            if let Some(label) = &line.label {
+                let value = self.pc as Word;
+                self.define(
+                    label,
+                    Symbol {
+                        value,
+                        relocatable: true,
+                    },
+                )
+                .map_err(error)?;
+            }
+
+            match &line.statement {
+                Some(Statement::Define(name, expression)) => {
+                    let value = self.resolve(expression).map_err(error)?;
+                    let symbol = Symbol {
+                        value: value.value as Word,
+                        relocatable: value.relocatable,
+                    };
+                    self.define(name, symbol).map_err(error)?;
+                }
+                Some(Statement::Org(expression)) => self.org(expression).map_err(error)?,
+                Some(statement) => {
+                    let size = self.statement_size(line, statement).map_err(error)?;
+                    if size > 0 && self.origin.is_none() {
+                        self.origin = Some(self.pc as Word);
+                    }
+                    self.pc += size;
+                    if self.pc > 0x10000 {
+                        return Err(error("the program does not fit in 64K".to_string()));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, byte: Byte) {
+        let origin = self.origin.unwrap_or(0) as u32;
+        let offset = (self.pc - origin) as usize;
+        if self.bytes.len() < offset {
+            // the gap left by an .org
+            self.bytes.resize(offset, 0);
+        }
+        self.bytes.push(byte);
+        self.pc += 1;
+    }
+
+    fn relocation(&mut self, kind: RelocationKind, value: Word) {
+        let origin = self.origin.unwrap_or(0) as u32;
+        self.relocations.push(Relocation {
+            offset: (self.pc - origin) as usize,
+            kind,
+            value,
+        });
+    }
+
+    fn emit_byte(&mut self, expression: &Expression) -> Result<(), String> {
+        let value = self.resolve(expression)?;
+        if !(-128..=0xFF).contains(&value.value) {
+            return Err(format!("value ${:X} does not fit in a byte", value.value));
+        }
+
+        if value.relocatable {
+            // a full address keeps the carry when the low byte is relocated
+            let full = match expression.modifier {
+                Some(_) => self.evaluate(&Expression {
+                    modifier: None,
+                    terms: expression.terms.clone(),
+                })?,
+                None => Some(value),
+            };
+            let kind = match expression.modifier {
+                Some(Modifier::High) => RelocationKind::High,
+                _ => RelocationKind::Low,
+            };
+            self.relocation(kind, full.unwrap().value as Word);
+        }
+
+        self.emit(value.value as Byte);
+        Ok(())
+    }
+
+    fn emit_word(&mut self, expression: &Expression) -> Result<(), String> {
+        let value = self.resolve(expression)?;
+        if !(-0x8000..=0xFFFF).contains(&value.value) {
+            return Err(format!("value ${:X} does not fit in a word", value.value));
+        }
+
+        if value.relocatable {
+            self.relocation(RelocationKind::Word, value.value as Word);
+        }
+
+        let [lo, hi] = (value.value as Word).to_le_bytes();
+        self.emit(lo);
+        self.emit(hi);
+        Ok(())
+    }
+
+    fn emit_instruction(
+        &mut self,
+        mnemonic: &str,
+        mode: AddressingMode,
+        operand: &Operand,
+    ) -> Result<(), String> {
+        self.emit(opcode(mnemonic, mode).unwrap());
+
+        let expression = match operand {
+            Operand::None | Operand::Accumulator => return Ok(()),
+            Operand::Immediate(expression)
+            | Operand::Direct(expression)
+            | Operand::DirectX(expression)
+            | Operand::DirectY(expression)
+            | Operand::Indirect(expression)
+            | Operand::IndirectX(expression)
+            | Operand::IndirectY(expression) => expression,
+        };
+
+        if mode == AddressingMode::Relative {
+            let target = self.resolve(expression)?.value;
+            let offset = target - (self.line_address as i32 + 2);
+            if !(-128..=127).contains(&offset) {
+                return Err(format!(
+                    "branch to ${:04X} is out of range ({} bytes)",
+                    target, offset
+                ));
+            }
+            self.emit(offset as Byte);
+            return Ok(());
+        }
+
+        match mode.operand_bytes() {
+            1 => self.emit_byte(expression),
+            _ => self.emit_word(expression),
+        }
+    }
+
+    // emits the bytes, every symbol is known now
+    fn second_pass(&mut self, lines: &[Line], origin: Word) -> Result<(), AssemblerError> {
+        self.pc = origin as u32;
+
+        for line in lines {
+            let error = |message: String| AssemblerError {
+                line: line.number,
+                message,
+            };
+            self.line_address = self.pc;
+
+            match &line.statement {
+                Some(Statement::Org(expression)) => {
+                    self.pc = self.resolve(expression).map_err(error)?.value as u32;
+                }
+                Some(Statement::Bytes(data)) => {
+                    for item in data {
+                        match item {
+                            Data::Expression(expression) => {
+                                self.emit_byte(expression).map_err(error)?
+                            }
+                            Data::Text(text) => text.iter().for_each(|&byte| self.emit(byte)),
+                        }
+                    }
+                }
+                Some(Statement::Words(words)) => {
+                    for expression in words {
+                        self.emit_word(expression).map_err(error)?;
+                    }
+                }
+                Some(Statement::Instruction(mnemonic, operand)) => {
+                    let mode = self.modes[&line.number];
+                    self.emit_instruction(mnemonic, mode, operand)
+                        .map_err(error)?;
+                }
+                Some(Statement::Define(_, _)) | None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Assembles 6502js / ca65 style source:
+///
+///  define sysRandom $fe     ; constants, also `name = value`
+///  .org $0600
+///  start:                   ; labels
+///    lda #<table            ; < low byte, > high byte
+///    sta ($10),y            ; all the addressing modes
+///    bne start
+///  table:
+///    .byte 1, 2, "text"
+///    .word start
+///
+/// Without .org the program starts at DEFAULT_ORIGIN
+pub fn assemble(source: &str) -> Result<Program, AssemblerError> {
+    assemble_at(source, DEFAULT_ORIGIN)
+}
+
+pub fn assemble_at(source: &str, origin: Word) -> Result<Program, AssemblerError> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, text)| parse_line(i + 1, text))
+        .collect::<Result<Vec<Line>, AssemblerError>>()?;
+
+    let mut assembler = Assembler {
+        symbols: BTreeMap::new(),
+        pc: origin as u32,
+        line_address: origin as u32,
+        origin: None,
+        modes: BTreeMap::new(),
+        bytes: Vec::new(),
+        relocations: Vec::new(),
+    };
+
+    assembler.first_pass(&lines)?;
+    let origin = assembler.origin.unwrap_or(origin);
+    assembler.second_pass(&lines, origin)?;
+
+    Ok(Program {
+        origin,
+        bytes: assembler.bytes,
+        symbols: assembler.symbols,
+        relocations: assembler.relocations,
+    })
+}
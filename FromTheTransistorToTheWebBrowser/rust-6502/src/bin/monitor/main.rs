@@ -0,0 +1,41 @@
+mod monitor;
+#[cfg(test)]
+mod monitor_tests;
+
+use monitor::Monitor;
+
+// The machine language monitor, reading the commands from the terminal, or
+// with --batch running the commands of a script and printing a transcript
+fn main() {
+    let usage = "usage: monitor [--batch <script>]";
+    let mut args = std::env::args().skip(1);
+    match (args.next().as_deref(), args.next(), args.next()) {
+        (None, _, _) => {
+            let stdin = std::io::stdin();
+            Monitor::new().repl(stdin.lock(), &mut std::io::stdout());
+        }
+        (Some("--batch"), Some(path), None) => std::process::exit(run_script(&path)),
+        _ => {
+            eprintln!("{}", usage);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn run_script(path: &str) -> i32 {
+    let script = match std::fs::read_to_string(path) {
+        Ok(script) => script,
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            return 2;
+        }
+    };
+
+    match Monitor::new().run_script(&script, &mut std::io::stdout()) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("{}: {}", path, error);
+            1
+        }
+    }
+}
@@ -0,0 +1,612 @@
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use rust_6502::bus::Bus;
+use rust_6502::debugger::{Debugger, StopReason};
+use rust_6502::disasm::disassemble_instruction_for;
+use rust_6502::loader::{self, Format, LoaderError};
+use rust_6502::{Byte, Word};
+use rust_6502::{Flags, Mem, CPU};
+
+const HELP: &str = "\
+m [start [end]]          show memory
+> address byte...        deposit bytes
+d [start [end]]          disassemble
+r [reg=value...]         show or set registers (A X Y SP PC P) and flags (N V B D I Z C)
+l file [address]         load a file, raw binaries need the address
+b [address]              set a breakpoint, or list them
+del address|all          delete breakpoints
+g [address]              go until a breakpoint
+z [count]                step into
+n [count]                step over (next)
+back [count]             step backwards
+bw address               run backwards to the last write to address
+stack                    show the stack page
+reset                    load the PC from the reset vector
+history                  list the commands, numbered for !n
+!!                       repeat the last command
+!n                       repeat command n of the list
+x                        exit
+Numbers are hex, the $ is optional";
+
+// bytes shown by m without an end address
+const MEMORY_LINES: Word = 8;
+// instructions shown by d without an end address
+const DISASSEMBLY_LINES: usize = 16;
+// instructions back and bw can undo
+const HISTORY_STEPS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitorError {
+    UnknownCommand(String),
+    Syntax(String),
+    Loader(LoaderError),
+}
+
+impl fmt::Display for MonitorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MonitorError::UnknownCommand(command) => {
+                write!(f, "unknown command {:?}, try help", command)
+            }
+            MonitorError::Syntax(message) => write!(f, "{}", message),
+            MonitorError::Loader(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for MonitorError {}
+
+// The first command of a script that failed, line is counted from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub error: MonitorError,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn syntax(message: &str) -> MonitorError {
+    MonitorError::Syntax(message.to_string())
+}
+
+// Monitor numbers are hex, with or without the $
+fn parse_number(text: &str, max: u32) -> Result<u32, MonitorError> {
+    let digits = text.strip_prefix('$').unwrap_or(text);
+    match u32::from_str_radix(digits, 16) {
+        Ok(value) if value <= max => Ok(value),
+        _ => Err(MonitorError::Syntax(format!(
+            "{:?} is not a number up to ${:X}",
+            text, max
+        ))),
+    }
+}
+
+fn parse_word(text: &str) -> Result<Word, MonitorError> {
+    parse_number(text, 0xFFFF).map(|value| value as Word)
+}
+
+fn parse_byte(text: &str) -> Result<Byte, MonitorError> {
+    parse_number(text, 0xFF).map(|value| value as Byte)
+}
+
+// An optional address, or else the default
+fn address_or(argument: Option<&&str>, default: Word) -> Result<Word, MonitorError> {
+    argument.map_or(Ok(default), |text| parse_word(text))
+}
+
+// A VICE / Wozmon style machine language monitor: every command line is
+// turned into text, so the same commands can be typed or read from a script
+pub struct Monitor {
+    pub cpu: CPU,
+    pub memory: Mem,
+    pub debugger: Debugger,
+    // the commands run so far, as they were executed (!n expanded)
+    pub history: Vec<String>,
+    // go, next and step over give up after this many instructions
+    pub limit: usize,
+    pub quit: bool,
+    // where m and d go on when they have no address
+    next_memory: Word,
+    next_disassembly: Word,
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        let mut cpu = CPU::new();
+        let mut memory = Mem::new();
+        cpu.reset(&mut memory);
+        let mut debugger = Debugger::new();
+        debugger.record(HISTORY_STEPS);
+
+        Monitor {
+            cpu,
+            memory,
+            debugger,
+            history: Vec::new(),
+            limit: 1_000_000,
+            quit: false,
+            next_memory: 0,
+            next_disassembly: cpu.pc(),
+        }
+    }
+
+    // Runs one command line and returns what it prints
+    pub fn execute(&mut self, line: &str) -> Result<String, MonitorError> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(String::new());
+        }
+
+        let line: String = self.expand_history(line)?;
+        self.history.push(line.clone());
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let command: String = words[0].to_ascii_lowercase();
+        let arguments = &words[1..];
+
+        match command.as_str() {
+            "m" | "mem" => self.show_memory(arguments),
+            ">" => self.deposit(arguments),
+            "d" | "disass" => self.disassemble(arguments),
+            "r" | "registers" => self.registers(arguments),
+            "l" | "load" => self.load(arguments),
+            "b" | "break" => self.breakpoint(arguments),
+            "del" | "delete" => self.delete(arguments),
+            "g" | "go" => self.go(arguments),
+            "z" | "step" => self.step(arguments, false),
+            "n" | "next" => self.step(arguments, true),
+            "back" => self.step_back(arguments),
+            "bw" => self.back_to_write(arguments),
+            "stack" => Ok(self.stack()),
+            "reset" => {
+                self.cpu.power_on(&mut self.memory);
+                self.forget_history();
+                self.next_disassembly = self.cpu.pc();
+                Ok(self.show_registers())
+            }
+            "history" => Ok(self.show_history()),
+            "help" | "?" => Ok(HELP.to_string()),
+            "x" | "q" | "exit" | "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            _ => Err(MonitorError::UnknownCommand(words[0].to_string())),
+        }
+    }
+
+    // !! is the last command, !n the command n of the history
+    fn expand_history(&self, line: &str) -> Result<String, MonitorError> {
+        let number = match line.strip_prefix('!') {
+            Some(number) => number,
+            None => return Ok(line.to_string()),
+        };
+
+        let index: Option<usize> = if number == "!" {
+            self.history.len().checked_sub(1)
+        } else {
+            number
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+        };
+        index
+            .and_then(|index| self.history.get(index))
+            .cloned()
+            .ok_or_else(|| MonitorError::Syntax(format!("{} is not in the history", line)))
+    }
+
+    fn show_history(&self) -> String {
+        let lines: Vec<String> = self
+            .history
+            .iter()
+            .enumerate()
+            .map(|(i, command)| format!("{:>4}  {}", i + 1, command))
+            .collect();
+        lines.join("\n")
+    }
+
+    // 16 bytes a line with their ASCII, e.g.
+    // `0600  A9 01 8D 00 02 4C 05 06  00 00 00 00 00 00 00 00  ..........`
+    fn show_memory(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        let start: Word = address_or(arguments.first(), self.next_memory)?;
+        let end: Word = match arguments.get(1) {
+            Some(end) => parse_word(end)?,
+            None => start.saturating_add(MEMORY_LINES * 16 - 1),
+        };
+        if end < start {
+            return Err(syntax("the end address is before the start"));
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        for line_start in (start as u32..=end as u32).step_by(16) {
+            let line_end: u32 = (line_start + 15).min(end as u32);
+            let bytes: Vec<Byte> = (line_start..=line_end)
+                .map(|address| self.memory.peek(address as Word))
+                .collect();
+
+            let mut hex = String::new();
+            for (i, byte) in bytes.iter().enumerate() {
+                hex.push_str(if i == 8 { "  " } else { " " });
+                hex.push_str(&format!("{:02X}", byte));
+            }
+            let ascii: String = bytes
+                .iter()
+                .map(|&b| {
+                    if (0x20..0x7F).contains(&b) {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            lines.push(format!("{:04X} {:<49}  {}", line_start, hex, ascii));
+        }
+
+        self.next_memory = end.wrapping_add(1);
+        Ok(lines.join("\n"))
+    }
+
+    fn deposit(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        if arguments.len() < 2 {
+            return Err(syntax("> needs an address and the bytes"));
+        }
+        let address: Word = parse_word(arguments[0])?;
+        let bytes: Vec<Byte> = arguments[1..]
+            .iter()
+            .map(|text| parse_byte(text))
+            .collect::<Result<_, _>>()?;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.memory.write(address.wrapping_add(i as Word), byte);
+        }
+        self.next_memory = address;
+        Ok(String::new())
+    }
+
+    fn disassemble(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        let start: Word = address_or(arguments.first(), self.next_disassembly)?;
+        let end: Option<Word> = arguments.get(1).map(|end| parse_word(end)).transpose()?;
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut address: u32 = start as u32;
+        loop {
+            let done = match end {
+                Some(end) => address > end as u32,
+                None => lines.len() == DISASSEMBLY_LINES,
+            };
+            if done || address > 0xFFFF {
+                break;
+            }
+
+            let instruction =
+                disassemble_instruction_for(self.cpu.variant(), &self.memory, address as Word);
+            address += instruction.bytes.len() as u32;
+            lines.push(instruction.to_string());
+        }
+
+        self.next_disassembly = address as Word;
+        Ok(lines.join("\n"))
+    }
+
+    fn show_registers(&self) -> String {
+        format!(
+            "  PC   A  X  Y  SP NV-BDIZC CYC\n  {:04X} {:02X} {:02X} {:02X} {:02X} {:08b} {}",
+            self.cpu.pc(),
+            self.cpu.a(),
+            self.cpu.x(),
+            self.cpu.y(),
+            self.cpu.sp(),
+            self.cpu.status(),
+            self.cpu.cycles()
+        )
+    }
+
+    // r A=01 PC=0600 C=1 ...
+    fn registers(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        for argument in arguments {
+            let (name, value) = argument
+                .split_once('=')
+                .ok_or_else(|| MonitorError::Syntax(format!("{:?} is not name=value", argument)))?;
+
+            let flag: Option<Flags> = match name.to_ascii_uppercase().as_str() {
+                "N" => Some(Flags::NegativeFlagBit),
+                "V" => Some(Flags::OverflowFlagBit),
+                "B" => Some(Flags::BreakFlagBit),
+                "D" => Some(Flags::DecimalModeFlagBit),
+                "I" => Some(Flags::InterruptDisableFlagBit),
+                "Z" => Some(Flags::ZeroFlagBit),
+                "C" => Some(Flags::CarryFlagBit),
+                _ => None,
+            };
+            if let Some(flag) = flag {
+                match value {
+                    "0" => self.cpu.set_flag(flag, false),
+                    "1" => self.cpu.set_flag(flag, true),
+                    _ => return Err(syntax("a flag is 0 or 1")),
+                }
+                continue;
+            }
+
+            match name.to_ascii_uppercase().as_str() {
+                "A" => self.cpu.set_a(parse_byte(value)?),
+                "X" => self.cpu.set_x(parse_byte(value)?),
+                "Y" => self.cpu.set_y(parse_byte(value)?),
+                "SP" => self.cpu.set_sp(parse_byte(value)?),
+                "P" | "PS" => self.cpu.set_status(parse_byte(value)?),
+                "PC" => {
+                    self.cpu.set_pc(parse_word(value)?);
+                    self.next_disassembly = self.cpu.pc();
+                }
+                _ => {
+                    return Err(MonitorError::Syntax(format!(
+                        "{} is not a register or a flag",
+                        name
+                    )))
+                }
+            }
+        }
+
+        Ok(self.show_registers())
+    }
+
+    // The format comes from the extension, a file loaded at an address is a
+    // raw binary
+    fn load(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        let path: &str = arguments
+            .first()
+            .ok_or_else(|| syntax("load needs a file"))?;
+        let format: Format = match arguments.get(1) {
+            Some(address) => Format::Raw(parse_word(address)?),
+            None => Format::from_path(path).ok_or_else(|| {
+                MonitorError::Syntax(format!("{} is a raw binary, give its address", path))
+            })?,
+        };
+
+        let image = loader::read_file(path, format).map_err(MonitorError::Loader)?;
+        image.write_to(&mut self.memory);
+        self.forget_history();
+
+        let mut lines: Vec<String> = image
+            .segments
+            .iter()
+            .map(|segment| {
+                format!(
+                    "loaded ${:04X}-${:04X}",
+                    segment.address,
+                    segment.address as usize + segment.data.len() - 1
+                )
+            })
+            .collect();
+        if let Some(entry) = image.entry() {
+            self.next_memory = entry;
+            self.next_disassembly = entry;
+            lines.push(format!("entry ${:04X}", entry));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn breakpoint(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        match arguments.first() {
+            Some(address) => {
+                self.debugger.add_breakpoint(parse_word(address)?);
+                Ok(String::new())
+            }
+            None if self.debugger.breakpoints.is_empty() => Ok("no breakpoints".to_string()),
+            None => {
+                let lines: Vec<String> = self
+                    .debugger
+                    .breakpoints
+                    .iter()
+                    .map(|address| format!("break ${:04X}", address))
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+        }
+    }
+
+    fn delete(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        match arguments.first() {
+            Some(&"all") => self.debugger.breakpoints.clear(),
+            Some(address) => self.debugger.remove_breakpoint(parse_word(address)?),
+            None => return Err(syntax("delete needs an address or all")),
+        }
+        Ok(String::new())
+    }
+
+    // why the CPU stopped, then the registers
+    fn stopped(&mut self, reason: StopReason) -> String {
+        let why: String = match reason {
+            StopReason::Breakpoint(address) => format!("break at ${:04X}\n", address),
+            StopReason::Condition { address, .. } => {
+                format!("condition holds at ${:04X}\n", address)
+            }
+            StopReason::Watchpoint {
+                pc,
+                address,
+                value,
+                access,
+            } => format!(
+                "${:04X}: {:?} ${:02X} at ${:04X}\n",
+                pc, access, value, address
+            ),
+            StopReason::StepComplete => String::new(),
+            StopReason::InstructionLimit => {
+                format!("still running after {} instructions\n", self.limit)
+            }
+        };
+        let jammed: String = if self.cpu.is_jammed() {
+            format!("the CPU is jammed at ${:04X}\n", self.cpu.pc())
+        } else {
+            String::new()
+        };
+
+        self.next_disassembly = self.cpu.pc();
+        format!("{}{}{}", why, jammed, self.show_registers())
+    }
+
+    fn go(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        self.cpu
+            .set_pc(address_or(arguments.first(), self.cpu.pc())?);
+        let reason = self
+            .debugger
+            .run(&mut self.cpu, &mut self.memory, self.limit);
+        Ok(self.stopped(reason))
+    }
+
+    // Every instruction stepped is shown before it runs. A breakpoint stops
+    // the steps that are left
+    fn step(&mut self, arguments: &[&str], over: bool) -> Result<String, MonitorError> {
+        let count: u32 = match arguments.first() {
+            Some(count) => parse_number(count, 0xFFFF)?,
+            None => 1,
+        };
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut reason = StopReason::StepComplete;
+        for _ in 0..count {
+            let instruction =
+                disassemble_instruction_for(self.cpu.variant(), &self.memory, self.cpu.pc());
+            lines.push(instruction.to_string());
+
+            reason = if over {
+                self.debugger
+                    .step_over(&mut self.cpu, &mut self.memory, self.limit)
+            } else {
+                self.debugger.step(&mut self.cpu, &mut self.memory)
+            };
+            if reason != StopReason::StepComplete {
+                break;
+            }
+        }
+
+        lines.push(self.stopped(reason));
+        Ok(lines.join("\n"))
+    }
+
+    // A load or a reset can't be undone, the history starts over after them
+    fn forget_history(&mut self) {
+        if let Some(history) = self.debugger.history.as_mut() {
+            history.clear();
+        }
+    }
+
+    fn step_back(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        let count: u32 = match arguments.first() {
+            Some(count) => parse_number(count, 0xFFFF)?,
+            None => 1,
+        };
+
+        let undone: usize =
+            self.debugger
+                .step_back(&mut self.cpu, &mut self.memory, count as usize);
+        let why: String = if undone < count as usize {
+            format!("the history ends, {} of {} undone\n", undone, count)
+        } else {
+            String::new()
+        };
+        Ok(format!("{}{}", why, self.stopped(StopReason::StepComplete)))
+    }
+
+    // Leaves the CPU on the instruction that wrote, it runs again with z
+    fn back_to_write(&mut self, arguments: &[&str]) -> Result<String, MonitorError> {
+        let address: Word = parse_word(
+            arguments
+                .first()
+                .ok_or_else(|| syntax("bw needs an address"))?,
+        )?;
+
+        match self
+            .debugger
+            .back_to_write(&mut self.cpu, &mut self.memory, address)
+        {
+            Some(pc) => Ok(format!(
+                "${:04X} wrote ${:04X}\n{}",
+                pc,
+                address,
+                self.stopped(StopReason::StepComplete)
+            )),
+            None => Err(MonitorError::Syntax(format!(
+                "no write to ${:04X} in the history",
+                address
+            ))),
+        }
+    }
+
+    // The bytes pushed on the stack, from the top ($0100 + SP + 1) up to $01FF
+    fn stack(&self) -> String {
+        if self.cpu.sp() == 0xFF {
+            return "the stack is empty".to_string();
+        }
+
+        let top: Word = 0x0100 + self.cpu.sp() as Word + 1;
+        let mut lines: Vec<String> = Vec::new();
+        for line_start in (top..=0x01FF).step_by(8) {
+            let bytes: Vec<String> = (line_start..=(line_start + 7).min(0x01FF))
+                .map(|address| format!("{:02X}", self.memory.peek(address)))
+                .collect();
+            lines.push(format!("{:04X}  {}", line_start, bytes.join(" ")));
+        }
+        lines.join("\n")
+    }
+
+    // Batch mode: runs the commands of a script, echoing each of them before
+    // its output so the transcript can be compared from run to run. Empty
+    // lines and lines starting with ; are skipped, the first error stops it
+    pub fn run_script<W: Write>(&mut self, script: &str, out: &mut W) -> Result<(), ScriptError> {
+        for (i, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            let _ = writeln!(out, ". {}", line);
+            match self.execute(line) {
+                Ok(text) if text.is_empty() => {}
+                Ok(text) => {
+                    let _ = writeln!(out, "{}", text);
+                }
+                Err(error) => return Err(ScriptError { line: i + 1, error }),
+            }
+            if self.quit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    // The interactive monitor, until x or the end of the input
+    pub fn repl<R: BufRead, W: Write>(&mut self, input: R, out: &mut W) {
+        let mut lines = input.lines();
+        while !self.quit {
+            let _ = write!(out, "(${:04X}) ", self.cpu.pc());
+            let _ = out.flush();
+
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                _ => break,
+            };
+            match self.execute(&line) {
+                Ok(text) if text.is_empty() => {}
+                Ok(text) => {
+                    let _ = writeln!(out, "{}", text);
+                }
+                Err(error) => {
+                    let _ = writeln!(out, "error: {}", error);
+                }
+            }
+        }
+    }
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod monitor_tests {
+
+    use crate::monitor::*;
+    use rust_6502::bus::Bus;
+
+    // LDA #$01; STA $0200; JSR $060B; BRK; INX; RTS at $0600
+    const PROGRAM: &str = "> 0600 A9 01 8D 00 02 20 0B 06 00 00 00 E8 60";
+
+    #[test]
+    fn bytes_are_deposited_and_shown() {
+        let mut monitor = Monitor::new();
+
+        // when:
+        monitor.execute("> $0600 A9 01 41 42").unwrap();
+        let dump = monitor.execute("m 0600 0603").unwrap();
+
+        // then:
+        assert_eq!(monitor.memory.peek(0x0601), 0x01);
+        assert!(dump.starts_with("0600  A9 01 41 42"));
+        assert!(dump.ends_with("..AB"));
+    }
+
+    #[test]
+    fn memory_goes_on_where_it_stopped() {
+        let mut monitor = Monitor::new();
+
+        // when:
+        let first = monitor.execute("m 1000").unwrap();
+        let next = monitor.execute("m").unwrap();
+
+        // then:
+        assert_eq!(first.lines().count(), 8);
+        assert!(next.starts_with("1080"));
+    }
+
+    #[test]
+    fn a_range_is_disassembled() {
+        let mut monitor = Monitor::new();
+        monitor.execute(PROGRAM).unwrap();
+
+        // when:
+        let listing = monitor.execute("d 0600 0605").unwrap();
+
+        // then:
+        assert_eq!(
+            listing,
+            "0600  A9 01     LDA #$01\n0602  8D 00 02  STA $0200\n0605  20 0B 06  JSR $060B"
+        );
+    }
+
+    #[test]
+    fn registers_and_flags_are_set() {
+        let mut monitor = Monitor::new();
+
+        // when:
+        let text = monitor
+            .execute("r a=42 X=$10 pc=0600 P=00 C=1 N=1")
+            .unwrap();
+
+        // then:
+        assert_eq!(monitor.cpu.a(), 0x42);
+        assert_eq!(monitor.cpu.x(), 0x10);
+        assert_eq!(monitor.cpu.pc(), 0x0600);
+        assert_eq!(monitor.cpu.status(), 0b1000_0001);
+        assert!(text.ends_with("0600 42 10 00 FF 10000001 0"));
+        assert!(matches!(
+            monitor.execute("r Q=1"),
+            Err(MonitorError::Syntax(_))
+        ));
+        assert!(matches!(
+            monitor.execute("r A=100"),
+            Err(MonitorError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn go_stops_at_a_breakpoint() {
+        let mut monitor = Monitor::new();
+        monitor.execute(PROGRAM).unwrap();
+
+        // when:
+        monitor.execute("b 0608").unwrap();
+        let text = monitor.execute("g 0600").unwrap();
+
+        // then:
+        assert!(text.starts_with("break at $0608"));
+        assert_eq!(monitor.cpu.pc(), 0x0608);
+        assert_eq!(monitor.memory.peek(0x0200), 0x01);
+        assert_eq!(monitor.cpu.x(), 1);
+        assert_eq!(monitor.execute("b").unwrap(), "break $0608");
+
+        // when:
+        monitor.execute("del all").unwrap();
+
+        // then:
+        assert_eq!(monitor.execute("b").unwrap(), "no breakpoints");
+    }
+
+    #[test]
+    fn step_shows_the_instructions_and_next_steps_over_subroutines() {
+        let mut monitor = Monitor::new();
+        monitor.execute(PROGRAM).unwrap();
+        monitor.execute("r pc=0600").unwrap();
+
+        // when:
+        let stepped = monitor.execute("z 2").unwrap();
+        monitor.execute("n").unwrap();
+
+        // then:
+        assert!(stepped.starts_with("0600  A9 01     LDA #$01\n0602  8D 00 02  STA $0200\n"));
+        assert_eq!(monitor.cpu.pc(), 0x0608);
+        assert_eq!(monitor.cpu.x(), 1);
+        assert_eq!(monitor.cpu.sp(), 0xFF);
+    }
+
+    #[test]
+    fn the_stack_page_is_shown_from_the_top() {
+        let mut monitor = Monitor::new();
+
+        // then:
+        assert_eq!(monitor.execute("stack").unwrap(), "the stack is empty");
+
+        // when:
+        monitor.execute("> 01FD 11 22").unwrap();
+        monitor.execute("r SP=FC").unwrap();
+
+        // then:
+        assert_eq!(monitor.execute("stack").unwrap(), "01FD  11 22 00");
+    }
+
+    #[test]
+    fn commands_are_repeated_from_the_history() {
+        let mut monitor = Monitor::new();
+
+        // when:
+        monitor.execute("r X=01").unwrap();
+        monitor.execute("> 0000 01").unwrap();
+        monitor.execute("!1").unwrap();
+        monitor.execute("!!").unwrap();
+
+        // then:
+        assert_eq!(
+            monitor.history,
+            vec!["r X=01", "> 0000 01", "r X=01", "r X=01"]
+        );
+        assert_eq!(
+            monitor.execute("history").unwrap(),
+            "   1  r X=01\n   2  > 0000 01\n   3  r X=01\n   4  r X=01\n   5  history"
+        );
+        assert!(matches!(
+            monitor.execute("!9"),
+            Err(MonitorError::Syntax(_))
+        ));
+        assert_eq!(
+            monitor.execute("frobnicate"),
+            Err(MonitorError::UnknownCommand("frobnicate".to_string()))
+        );
+    }
+
+    #[test]
+    fn files_are_loaded_at_their_address() {
+        let mut monitor = Monitor::new();
+        let path =
+            std::env::temp_dir().join(format!("rust-6502-monitor-{}.bin", std::process::id()));
+
+        // given:
+        std::fs::write(&path, [0xA9, 0x07]).unwrap();
+
+        // when:
+        let text = monitor.execute(&format!("l {} C000", path.display()));
+        let raw = monitor.execute(&format!("l {}", path.display()));
+        let _ = std::fs::remove_file(&path);
+
+        // then:
+        assert_eq!(text.unwrap(), "loaded $C000-$C001\nentry $C000");
+        assert_eq!(monitor.memory.peek(0xC001), 0x07);
+        assert!(matches!(raw, Err(MonitorError::Syntax(_))));
+        assert!(matches!(
+            monitor.execute("l /nonexistent/file.prg"),
+            Err(MonitorError::Loader(_))
+        ));
+    }
+
+    #[test]
+    fn a_script_echoes_its_commands_and_stops_at_the_first_error() {
+        let mut monitor = Monitor::new();
+        let mut out: Vec<u8> = Vec::new();
+
+        // given:
+        let script = "; a comment\n> 0600 E8\nr pc=0600\n\nz\nwrong\nr X=05\n";
+
+        // when:
+        let result = monitor.run_script(script, &mut out);
+
+        // then:
+        let transcript = String::from_utf8(out).unwrap();
+        assert!(transcript.starts_with(". > 0600 E8\n. r pc=0600\n"));
+        assert!(transcript.contains(". z\n0600  E8        INX\n"));
+        assert_eq!(
+            result,
+            Err(ScriptError {
+                line: 6,
+                error: MonitorError::UnknownCommand("wrong".to_string())
+            })
+        );
+        assert_eq!(monitor.cpu.x(), 1);
+    }
+
+    #[test]
+    fn the_repl_runs_until_exit() {
+        let mut monitor = Monitor::new();
+        let mut out: Vec<u8> = Vec::new();
+
+        // when:
+        monitor.repl("r PC=0600\nnope\nx\nr X=01\n".as_bytes(), &mut out);
+
+        // then:
+        let transcript = String::from_utf8(out).unwrap();
+        assert!(transcript.contains("error: unknown command \"nope\""));
+        assert!(transcript.ends_with("($0600) "));
+        assert!(monitor.quit);
+        assert_eq!(monitor.cpu.x(), 0);
+    }
+
+    #[test]
+    fn back_undoes_the_steps_and_bw_finds_the_last_write() {
+        let mut monitor = Monitor::new();
+        monitor.execute(PROGRAM).unwrap();
+        monitor.execute("r pc=0600").unwrap();
+
+        // given:
+        monitor.execute("z 4").unwrap();
+
+        // when:
+        let text = monitor.execute("bw 0200").unwrap();
+
+        // then:
+        assert!(text.starts_with("$0602 wrote $0200\n"));
+        assert_eq!(monitor.cpu.pc(), 0x0602);
+        assert_eq!(monitor.memory.peek(0x0200), 0x00);
+
+        // when:
+        let text = monitor.execute("back 5").unwrap();
+
+        // then:
+        assert!(text.starts_with("the history ends, 1 of 5 undone\n"));
+        assert_eq!(monitor.cpu.pc(), 0x0600);
+        assert!(matches!(
+            monitor.execute("bw 0200"),
+            Err(MonitorError::Syntax(_))
+        ));
+    }
+}
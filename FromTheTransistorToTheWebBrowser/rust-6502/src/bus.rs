@@ -0,0 +1,333 @@
+use crate::memory_map::{Target, Violation};
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use crate::Mem;
+use crate::{Byte, Word};
+
+/// Everything the CPU reads or writes goes through a Bus, so memory mapped
+/// devices (keyboard ports, video RAM, timers...) can react to the accesses
+pub trait Bus {
+    /// A read of the CPU, devices may react to it (e.g. clear a flag)
+    fn read(&mut self, address: Word) -> Byte;
+
+    /// A write of the CPU
+    fn write(&mut self, address: Word, value: Byte);
+
+    /// Read without side effects, used by tools that inspect memory
+    /// (debugger, disassembler) and must not disturb the devices
+    fn peek(&self, address: Word) -> Byte;
+
+    /// Reads of opcodes and operands from the program go through fetch, so
+    /// a bus can tell them apart from the data reads of the instruction
+    fn fetch(&mut self, address: Word) -> Byte {
+        self.read(address)
+    }
+
+    /// Write without side effects, used to put memory back the way it was
+    /// (History::step_back). Memory takes it as a write, devices ignore it so
+    /// their registers don't see the old values written again
+    fn poke(&mut self, address: Word, value: Byte) {
+        self.write(address, value);
+    }
+
+    /// Interrupt lines driven by the devices, sampled by the CPU between
+    /// instructions
+    fn irq(&self) -> bool {
+        false
+    }
+
+    /// See irq, the CPU latches the edge of the line
+    fn nmi(&self) -> bool {
+        false
+    }
+
+    /// Time passing: the CPU calls it with the cycles of every instruction
+    /// (or interrupt sequence) once it has run, and with the cycles it spends
+    /// jammed or waiting for an interrupt. Timers count them down
+    fn tick(&mut self, _cycles: u64) {}
+
+    /// The state that goes into a snapshot: memory contents, device
+    /// registers... Devices without state (ROMs, I/O callbacks) save nothing
+    fn save_state(&self, _out: &mut SnapshotWriter) {}
+
+    /// Reads back what save_state wrote
+    fn load_state(&mut self, _input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        Ok(())
+    }
+}
+
+// Reads a block saved with SnapshotWriter::bytes into memory of the same size
+fn load_block(input: &mut SnapshotReader, data: &mut [Byte]) -> Result<(), SnapshotError> {
+    let block = input.bytes()?;
+    if block.len() != data.len() {
+        return Err(SnapshotError::Invalid(format!(
+            "{} bytes of memory, the snapshot has {}",
+            data.len(),
+            block.len()
+        )));
+    }
+    data.copy_from_slice(block);
+    Ok(())
+}
+
+// The flat 64 KiB array is the simplest bus. Its regions can make parts
+// of it read only, unmapped or mirrors of other parts
+impl Bus for Mem {
+    fn read(&mut self, address: Word) -> Byte {
+        match self.resolve(address) {
+            Target::Ram(index) | Target::Rom(index) => self.data[index],
+            Target::Unmapped => {
+                self.flag(Violation::UnmappedRead { address });
+                self.open_bus
+            }
+        }
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        match self.resolve(address) {
+            Target::Ram(index) => self.data[index] = value,
+            Target::Rom(_) => self.flag(Violation::RomWrite { address, value }),
+            Target::Unmapped => self.flag(Violation::UnmappedWrite { address, value }),
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        match self.resolve(address) {
+            Target::Ram(index) | Target::Rom(index) => self.data[index],
+            Target::Unmapped => self.open_bus,
+        }
+    }
+
+    // not checked against the regions, nothing is flagged in strict mode
+    fn poke(&mut self, address: Word, value: Byte) {
+        match self.resolve(address) {
+            Target::Ram(index) | Target::Rom(index) => self.data[index] = value,
+            Target::Unmapped => {}
+        }
+    }
+
+    // always MAX_MEM bytes, a Mem that was never reset saves zeros
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        let mut data: Vec<Byte> = self.data.clone();
+        data.resize(self.max_mem as usize, 0);
+        out.bytes(&data);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        self.data.resize(self.max_mem as usize, 0);
+        load_block(input, &mut self.data)
+    }
+}
+
+/// Read/write memory. Addresses past the end of a Ram or a Rom wrap around,
+/// so mapping a small chip on a bigger region mirrors it. Neither can be empty
+pub struct Ram {
+    pub data: Vec<Byte>,
+}
+
+impl Ram {
+    /// size bytes of zeros
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "a RAM of 0 bytes");
+        Ram {
+            data: vec![0; size],
+        }
+    }
+}
+
+impl Bus for Ram {
+    fn read(&mut self, address: Word) -> Byte {
+        self.data[address as usize % self.data.len()]
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        let len = self.data.len();
+        self.data[address as usize % len] = value;
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.data[address as usize % self.data.len()]
+    }
+
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        out.bytes(&self.data);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        load_block(input, &mut self.data)
+    }
+}
+
+/// Writes to a ROM are ignored
+pub struct Rom {
+    pub data: Vec<Byte>,
+}
+
+impl Rom {
+    /// A ROM holding data
+    pub fn new(data: Vec<Byte>) -> Self {
+        assert!(!data.is_empty(), "a ROM of 0 bytes");
+        Rom { data }
+    }
+}
+
+impl Bus for Rom {
+    fn read(&mut self, address: Word) -> Byte {
+        self.data[address as usize % self.data.len()]
+    }
+
+    fn write(&mut self, _address: Word, _value: Byte) {}
+
+    fn peek(&self, address: Word) -> Byte {
+        self.data[address as usize % self.data.len()]
+    }
+}
+
+type ReadCallback = Box<dyn FnMut(Word) -> Byte>;
+type WriteCallback = Box<dyn FnMut(Word, Byte)>;
+
+/// A device built from two closures, handy for simple I/O ports.
+/// peek() has no side effects so it always returns 0
+pub struct IoPort {
+    on_read: ReadCallback,
+    on_write: WriteCallback,
+}
+
+impl IoPort {
+    pub fn new(on_read: ReadCallback, on_write: WriteCallback) -> Self {
+        IoPort { on_read, on_write }
+    }
+}
+
+impl Bus for IoPort {
+    fn read(&mut self, address: Word) -> Byte {
+        (self.on_read)(address)
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        (self.on_write)(address, value);
+    }
+
+    fn peek(&self, _address: Word) -> Byte {
+        0
+    }
+
+    fn poke(&mut self, _address: Word, _value: Byte) {}
+}
+
+struct Region {
+    start: Word,
+    end: Word, // inclusive
+    device: Box<dyn Bus>,
+}
+
+/// Maps address ranges to devices. Devices see addresses relative to the
+/// start of their region, and when regions overlap the first one mapped wins.
+/// An AddressDecoder is a Bus itself so decoders can be nested.
+pub struct AddressDecoder {
+    regions: Vec<Region>,
+    /// value returned when nothing is mapped at an address
+    pub unmapped_value: Byte,
+}
+
+impl AddressDecoder {
+    pub fn new() -> Self {
+        AddressDecoder {
+            regions: Vec::new(),
+            unmapped_value: 0,
+        }
+    }
+
+    /// Maps the device at start to end (inclusive), it sees start as its
+    /// address 0
+    pub fn map(&mut self, start: Word, end: Word, device: Box<dyn Bus>) {
+        assert!(start <= end, "region {:04X}-{:04X} is empty", start, end);
+        self.regions.push(Region { start, end, device });
+    }
+
+    fn find(&self, address: Word) -> Option<usize> {
+        self.regions
+            .iter()
+            .position(|region| address >= region.start && address <= region.end)
+    }
+}
+
+impl Default for AddressDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus for AddressDecoder {
+    fn read(&mut self, address: Word) -> Byte {
+        match self.find(address) {
+            Some(i) => {
+                let region = &mut self.regions[i];
+                region.device.read(address - region.start)
+            }
+            None => self.unmapped_value,
+        }
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        if let Some(i) = self.find(address) {
+            let region = &mut self.regions[i];
+            region.device.write(address - region.start, value);
+        }
+    }
+
+    fn fetch(&mut self, address: Word) -> Byte {
+        match self.find(address) {
+            Some(i) => {
+                let region = &mut self.regions[i];
+                region.device.fetch(address - region.start)
+            }
+            None => self.unmapped_value,
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        match self.find(address) {
+            Some(i) => {
+                let region = &self.regions[i];
+                region.device.peek(address - region.start)
+            }
+            None => self.unmapped_value,
+        }
+    }
+
+    fn poke(&mut self, address: Word, value: Byte) {
+        if let Some(i) = self.find(address) {
+            let region = &mut self.regions[i];
+            region.device.poke(address - region.start, value);
+        }
+    }
+
+    // the interrupt lines are open collector, any device can pull them
+    fn irq(&self) -> bool {
+        self.regions.iter().any(|region| region.device.irq())
+    }
+
+    fn nmi(&self) -> bool {
+        self.regions.iter().any(|region| region.device.nmi())
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        for region in &mut self.regions {
+            region.device.tick(cycles);
+        }
+    }
+
+    // the devices in the order they were mapped
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        for region in &self.regions {
+            region.device.save_state(out);
+        }
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        for region in &mut self.regions {
+            region.device.load_state(input)?;
+        }
+        Ok(())
+    }
+}
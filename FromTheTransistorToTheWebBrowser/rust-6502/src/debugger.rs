@@ -0,0 +1,323 @@
+use crate::bus::Bus;
+use crate::history::{History, RecordingBus, Step};
+use crate::opcodes::{INS_JSR, INS_RTS};
+use crate::CPU;
+use crate::{Byte, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Watchpoint {
+    pub start: Word,
+    pub end: Word, // inclusive
+    pub access: Access,
+}
+
+impl Watchpoint {
+    fn matches(&self, address: Word, access: Access) -> bool {
+        let access_matches = self.access == Access::ReadWrite || self.access == access;
+        access_matches && address >= self.start && address <= self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Register {
+    A,
+    X,
+    Y,
+    SP,
+    PS,
+    PC,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    /// `value <comparison> reference`, e.g. `value < reference` for Less
+    pub fn holds(self, value: Word, reference: Word) -> bool {
+        match self {
+            Comparison::Equal => value == reference,
+            Comparison::NotEqual => value != reference,
+            Comparison::Less => value < reference,
+            Comparison::LessOrEqual => value <= reference,
+            Comparison::Greater => value > reference,
+            Comparison::GreaterOrEqual => value >= reference,
+        }
+    }
+}
+
+/// a register compared with a value, e.g. X == 0x05
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub register: Register,
+    pub comparison: Comparison,
+    pub value: Word,
+}
+
+impl Condition {
+    pub fn matches(&self, cpu: &CPU) -> bool {
+        let register: Word = match self.register {
+            Register::A => cpu.a() as Word,
+            Register::X => cpu.x() as Word,
+            Register::Y => cpu.y() as Word,
+            Register::SP => cpu.sp() as Word,
+            Register::PS => cpu.status() as Word,
+            Register::PC => cpu.pc(),
+        };
+
+        self.comparison.holds(register, self.value)
+    }
+}
+
+/// A conditional breakpoint without an address is checked before every
+/// instruction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConditionalBreakpoint {
+    pub address: Option<Word>,
+    pub condition: Condition,
+}
+
+/// Why the debugger gave control back to the caller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    /// the PC reached a breakpoint, the instruction has not been executed
+    Breakpoint(Word),
+    /// the condition holds before executing the instruction at address
+    Condition {
+        address: Word,
+        condition: Condition,
+    },
+    /// the instruction at pc accessed a watched address, it has been executed
+    Watchpoint {
+        pc: Word,
+        address: Word,
+        value: Byte,
+        access: Access,
+    },
+    /// the step, step over or step out finished
+    StepComplete,
+    /// the maximum number of instructions was executed without stopping
+    InstructionLimit,
+}
+
+pub struct Debugger {
+    pub breakpoints: Vec<Word>,
+    pub watchpoints: Vec<Watchpoint>,
+    pub conditions: Vec<ConditionalBreakpoint>,
+    /// when set, every instruction executed is recorded so it can be undone
+    pub history: Option<History>,
+}
+
+impl Debugger {
+    pub fn new() -> Self {
+        Debugger {
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+            conditions: Vec::new(),
+            history: None,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, address: Word) {
+        if !self.breakpoints.contains(&address) {
+            self.breakpoints.push(address);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, address: Word) {
+        self.breakpoints.retain(|&breakpoint| breakpoint != address);
+    }
+
+    pub fn add_watchpoint(&mut self, start: Word, end: Word, access: Access) {
+        self.watchpoints.push(Watchpoint { start, end, access });
+    }
+
+    pub fn remove_watchpoint(&mut self, start: Word, end: Word) {
+        self.watchpoints
+            .retain(|watchpoint| watchpoint.start != start || watchpoint.end != end);
+    }
+
+    pub fn add_condition(&mut self, address: Option<Word>, condition: Condition) {
+        self.conditions
+            .push(ConditionalBreakpoint { address, condition });
+    }
+
+    pub fn clear(&mut self) {
+        self.breakpoints.clear();
+        self.watchpoints.clear();
+        self.conditions.clear();
+    }
+
+    /// Starts recording the last capacity instructions, see History
+    pub fn record(&mut self, capacity: usize) {
+        self.history = Some(History::new(capacity));
+    }
+
+    /// Undoes up to count instructions, returns how many were undone
+    pub fn step_back<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M, count: usize) -> usize {
+        match self.history.as_mut() {
+            Some(history) => history.step_back_n(cpu, memory, count),
+            None => 0,
+        }
+    }
+
+    /// Runs backwards until before the last instruction that wrote address
+    /// and returns its PC, None when the history doesn't have it
+    pub fn back_to_write<M: Bus>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        address: Word,
+    ) -> Option<Word> {
+        self.history
+            .as_mut()
+            .and_then(|history| history.back_to_write(cpu, memory, address))
+    }
+
+    // breakpoints and conditions are checked before the instruction at PC
+    fn check_breakpoints(&self, cpu: &CPU) -> Option<StopReason> {
+        if self.breakpoints.contains(&cpu.pc()) {
+            return Some(StopReason::Breakpoint(cpu.pc()));
+        }
+
+        self.conditions
+            .iter()
+            .find(|breakpoint| {
+                breakpoint.address.is_none_or(|address| address == cpu.pc())
+                    && breakpoint.condition.matches(cpu)
+            })
+            .map(|breakpoint| StopReason::Condition {
+                address: cpu.pc(),
+                condition: breakpoint.condition,
+            })
+    }
+
+    // executes one instruction, watchpoints are checked after it
+    fn execute_instruction<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M) -> Option<StopReason> {
+        let pc: Word = cpu.pc();
+        let before: CPU = *cpu;
+        let mut bus = RecordingBus::new(memory);
+        cpu.step(&mut bus);
+
+        if let Some(history) = self.history.as_mut() {
+            history.push(Step {
+                cpu: before,
+                writes: std::mem::take(&mut bus.writes),
+            });
+        }
+
+        for &(address, value, access) in bus.accesses.iter() {
+            if self
+                .watchpoints
+                .iter()
+                .any(|watchpoint| watchpoint.matches(address, access))
+            {
+                return Some(StopReason::Watchpoint {
+                    pc,
+                    address,
+                    value,
+                    access,
+                });
+            }
+        }
+
+        None
+    }
+
+    // Runs until `done` returns true after an instruction, or a breakpoint or
+    // watchpoint stops it. The breakpoints are not checked on the first
+    // instruction so it's possible to continue from a breakpoint.
+    fn run_until<M: Bus, F: FnMut(&CPU, Byte, Byte) -> bool>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        max_instructions: usize,
+        mut done: F,
+    ) -> StopReason {
+        for executed in 0..max_instructions {
+            if executed > 0 {
+                if let Some(reason) = self.check_breakpoints(cpu) {
+                    return reason;
+                }
+            }
+
+            let opcode: Byte = memory.peek(cpu.pc());
+            let sp_before: Byte = cpu.sp();
+            if let Some(reason) = self.execute_instruction(cpu, memory) {
+                return reason;
+            }
+
+            if done(cpu, opcode, sp_before) {
+                return StopReason::StepComplete;
+            }
+        }
+
+        StopReason::InstructionLimit
+    }
+
+    /// run until a breakpoint, a watchpoint or a condition stops the CPU
+    pub fn run<M: Bus>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        max_instructions: usize,
+    ) -> StopReason {
+        self.run_until(cpu, memory, max_instructions, |_, _, _| false)
+    }
+
+    /// step into: execute a single instruction
+    pub fn step<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M) -> StopReason {
+        self.run_until(cpu, memory, 1, |_, _, _| true)
+    }
+
+    /// step over: like step, but a JSR runs the whole subroutine as one step
+    pub fn step_over<M: Bus>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        max_instructions: usize,
+    ) -> StopReason {
+        if memory.peek(cpu.pc()) != INS_JSR {
+            return self.step(cpu, memory);
+        }
+
+        let return_address: Word = cpu.pc().wrapping_add(3);
+        let sp: Byte = cpu.sp();
+        self.run_until(cpu, memory, max_instructions, |cpu, _, _| {
+            cpu.pc() == return_address && cpu.sp() == sp
+        })
+    }
+
+    /// step out: run until the RTS that returns from the current subroutine.
+    /// RTS of nested subroutines run with the stack pointer below the current one
+    pub fn step_out<M: Bus>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        max_instructions: usize,
+    ) -> StopReason {
+        let sp: Byte = cpu.sp();
+        self.run_until(cpu, memory, max_instructions, |_, opcode, sp_before| {
+            opcode == INS_RTS && sp_before >= sp
+        })
+    }
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
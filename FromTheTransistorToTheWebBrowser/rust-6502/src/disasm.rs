@@ -0,0 +1,210 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::opcodes::{opcode_table, Opcode, NMOS_6502};
+use crate::CpuVariant;
+use crate::{Byte, Word};
+
+pub use crate::opcodes::AddressingMode;
+
+/// Mnemonic and addressing mode of the documented opcodes
+pub fn decode(opcode: Byte) -> Option<(&'static str, AddressingMode)> {
+    let entry: Opcode = NMOS_6502[opcode as usize];
+    if entry.documented {
+        Some((entry.mnemonic.name(), entry.mode))
+    } else {
+        None
+    }
+}
+
+/// Mnemonic and addressing mode of the undocumented NMOS opcodes, named
+/// as in "No More Secrets" (NMOS 6510 Unintended Opcodes)
+pub fn decode_undocumented(opcode: Byte) -> Option<(&'static str, AddressingMode)> {
+    let entry: Opcode = NMOS_6502[opcode as usize];
+    if entry.documented {
+        None
+    } else {
+        Some((entry.mnemonic.name(), entry.mode))
+    }
+}
+
+/// A decoded instruction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instruction {
+    pub address: Word,
+    pub bytes: Vec<Byte>,
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    /// operand in 6502 syntax, e.g. `#$10`, `($44),Y` or `$0612` for a branch
+    pub operand: String,
+    /// destination of branches, JMP absolute and JSR
+    pub target: Option<Word>,
+}
+
+impl Instruction {
+    /// the address of the next instruction
+    pub fn next_address(&self) -> Word {
+        self.address.wrapping_add(self.bytes.len() as Word)
+    }
+
+    /// e.g. `LDA ($44),Y`
+    pub fn text(&self) -> String {
+        if self.operand.is_empty() {
+            self.mnemonic.to_string()
+        } else {
+            format!("{} {}", self.mnemonic, self.operand)
+        }
+    }
+}
+
+// e.g. `0612  B1 44     LDA ($44),Y`, handy for traces and debugger output
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes: Vec<String> = self.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+        write!(
+            f,
+            "{:04X}  {:<8}  {}",
+            self.address,
+            bytes.join(" "),
+            self.text()
+        )
+    }
+}
+
+/// The operand of an instruction in 6502 syntax and the destination of
+/// branches, JMP absolute and JSR. bytes starts with the opcode
+pub fn format_operand(
+    mnemonic: &str,
+    mode: AddressingMode,
+    address: Word,
+    bytes: &[Byte],
+) -> (String, Option<Word>) {
+    let byte: Byte = bytes.get(1).copied().unwrap_or(0);
+    let word: Word = byte as Word | (bytes.get(2).copied().unwrap_or(0) as Word) << 8;
+    let next_address: Word = address.wrapping_add(bytes.len() as Word);
+
+    let mut target: Option<Word> = None;
+    let operand: String = match mode {
+        AddressingMode::Implied => String::new(),
+        AddressingMode::Accumulator => "A".to_string(),
+        AddressingMode::Immediate => format!("#${:02X}", byte),
+        AddressingMode::ZeroPage => format!("${:02X}", byte),
+        AddressingMode::ZeroPageX => format!("${:02X},X", byte),
+        AddressingMode::ZeroPageY => format!("${:02X},Y", byte),
+        AddressingMode::Absolute => {
+            if mnemonic == "JMP" || mnemonic == "JSR" {
+                target = Some(word);
+            }
+            format!("${:04X}", word)
+        }
+        AddressingMode::AbsoluteX => format!("${:04X},X", word),
+        AddressingMode::AbsoluteY => format!("${:04X},Y", word),
+        AddressingMode::Indirect => format!("(${:04X})", word),
+        AddressingMode::IndirectX => format!("(${:02X},X)", byte),
+        AddressingMode::IndirectY => format!("(${:02X}),Y", byte),
+        AddressingMode::Relative => {
+            let destination: Word = next_address.wrapping_add(byte as i8 as Word);
+            target = Some(destination);
+            format!("${:04X}", destination)
+        }
+        AddressingMode::ZeroPageIndirect => format!("(${:02X})", byte),
+        AddressingMode::AbsoluteIndexedIndirect => format!("(${:04X},X)", word),
+        AddressingMode::ZeroPageRelative => {
+            let offset: Byte = bytes.get(2).copied().unwrap_or(0);
+            let destination: Word = next_address.wrapping_add(offset as i8 as Word);
+            target = Some(destination);
+            format!("${:02X},${:04X}", byte, destination)
+        }
+    };
+
+    (operand, target)
+}
+
+/// Decodes the instruction at address. Memory is read with peek() so
+/// disassembling never disturbs the devices on the bus.
+pub fn disassemble_instruction<M: Bus>(memory: &M, address: Word) -> Instruction {
+    disassemble_instruction_for(CpuVariant::Nmos6502, memory, address)
+}
+
+/// The same for the opcodes of a CPU variant, the 65C02 decodes the NMOS
+/// undocumented opcodes as its own instructions or as NOPs
+pub fn disassemble_instruction_for<M: Bus>(
+    variant: CpuVariant,
+    memory: &M,
+    address: Word,
+) -> Instruction {
+    let opcode: Byte = memory.peek(address);
+
+    // every opcode does something, documented or not
+    let entry: Opcode = opcode_table(variant)[opcode as usize];
+    let (mnemonic, mode) = (entry.mnemonic.name(), entry.mode);
+
+    let mut bytes: Vec<Byte> = vec![opcode];
+    for i in 1..=mode.operand_bytes() {
+        bytes.push(memory.peek(address.wrapping_add(i as Word)));
+    }
+
+    let (operand, target) = format_operand(mnemonic, mode, address, &bytes);
+
+    Instruction {
+        address,
+        bytes,
+        mnemonic,
+        mode,
+        operand,
+        target,
+    }
+}
+
+/// Disassembles the bytes from start up to end (inclusive). The last
+/// instruction can read operand bytes past end.
+pub fn disassemble<M: Bus>(memory: &M, start: Word, end: Word) -> Vec<Instruction> {
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut address: u32 = start as u32;
+
+    while address <= end as u32 {
+        let instruction = disassemble_instruction(memory, address as Word);
+        address += instruction.bytes.len() as u32;
+        instructions.push(instruction);
+    }
+
+    instructions
+}
+
+/// Formats instructions as a listing. Addresses that are the target of a
+/// branch, JMP or JSR of the listing get a label line, and the instructions
+/// jumping there are annotated with it:
+///
+///  L0606:
+///  0606  A9 02     LDA #$02
+///  ...
+///  0614  D0 F0     BNE $0606     ; L0606
+pub fn listing(instructions: &[Instruction]) -> String {
+    let addresses: BTreeSet<Word> = instructions.iter().map(|i| i.address).collect();
+    let labels: BTreeSet<Word> = instructions
+        .iter()
+        .filter_map(|i| i.target)
+        .filter(|target| addresses.contains(target))
+        .collect();
+
+    let mut text = String::new();
+    for instruction in instructions {
+        if labels.contains(&instruction.address) {
+            text.push_str(&format!("L{:04X}:\n", instruction.address));
+        }
+
+        let line = instruction.to_string();
+        match instruction.target {
+            Some(target) if labels.contains(&target) => {
+                text.push_str(&format!("{:<32}; L{:04X}\n", line, target))
+            }
+            _ => {
+                text.push_str(&line);
+                text.push('\n');
+            }
+        }
+    }
+
+    text
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+
+use crate::bus::Bus;
+use crate::debugger::Access;
+use crate::CPU;
+use crate::{Byte, Word};
+
+/// What undoes an instruction: the CPU before it, and the bytes its writes
+/// overwrote in the order they were written
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub cpu: CPU,
+    pub writes: Vec<(Word, Byte)>,
+}
+
+impl Step {
+    pub fn wrote(&self, address: Word) -> bool {
+        self.writes.iter().any(|&(written, _)| written == address)
+    }
+}
+
+// Wraps the bus during an instruction to record its data accesses and
+// what its writes overwrite. Opcode and operand fetches are not recorded.
+// The debugger checks its watchpoints against the accesses
+pub(crate) struct RecordingBus<'a, M: Bus> {
+    memory: &'a mut M,
+    pub(crate) accesses: Vec<(Word, Byte, Access)>,
+    pub(crate) writes: Vec<(Word, Byte)>,
+}
+
+impl<'a, M: Bus> RecordingBus<'a, M> {
+    pub(crate) fn new(memory: &'a mut M) -> Self {
+        RecordingBus {
+            memory,
+            accesses: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+}
+
+impl<'a, M: Bus> Bus for RecordingBus<'a, M> {
+    fn read(&mut self, address: Word) -> Byte {
+        let value = self.memory.read(address);
+        self.accesses.push((address, value, Access::Read));
+        value
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        self.accesses.push((address, value, Access::Write));
+        self.writes.push((address, self.memory.peek(address)));
+        self.memory.write(address, value);
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.memory.peek(address)
+    }
+
+    fn fetch(&mut self, address: Word) -> Byte {
+        self.memory.fetch(address)
+    }
+
+    fn poke(&mut self, address: Word, value: Byte) {
+        self.memory.poke(address, value);
+    }
+
+    fn irq(&self) -> bool {
+        self.memory.irq()
+    }
+
+    fn nmi(&self) -> bool {
+        self.memory.nmi()
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.memory.tick(cycles);
+    }
+}
+
+/// A journal of the last `capacity` instructions, the oldest ones are
+/// forgotten. Going back restores the registers, the cycle count and the
+/// bytes written, with Bus::poke: the memory comes back exactly, the
+/// devices are left alone and their state is not rewound
+pub struct History {
+    capacity: usize,
+    steps: VecDeque<Step>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        History {
+            capacity,
+            steps: VecDeque::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// the number of instructions that can be undone
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.steps.clear();
+    }
+
+    /// the last instruction recorded, what step_back() undoes
+    pub fn last(&self) -> Option<&Step> {
+        self.steps.back()
+    }
+
+    pub fn push(&mut self, step: Step) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.steps.len() == self.capacity {
+            self.steps.pop_front();
+        }
+        self.steps.push_back(step);
+    }
+
+    /// Executes one instruction (and the interrupt taken before it) and
+    /// records it, returns its cycles
+    pub fn step<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M) -> isize {
+        let before: CPU = *cpu;
+        let mut bus = RecordingBus::new(memory);
+        let cycles: isize = cpu.step(&mut bus);
+        self.push(Step {
+            cpu: before,
+            writes: bus.writes,
+        });
+        cycles
+    }
+
+    /// Puts the machine back to before the last instruction recorded, false
+    /// when there is nothing left to undo
+    pub fn step_back<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M) -> bool {
+        match self.steps.pop_back() {
+            Some(step) => {
+                for &(address, value) in step.writes.iter().rev() {
+                    memory.poke(address, value);
+                }
+                *cpu = step.cpu;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Undoes up to count instructions, returns how many were undone
+    pub fn step_back_n<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M, count: usize) -> usize {
+        let mut undone: usize = 0;
+        while undone < count && self.step_back(cpu, memory) {
+            undone += 1;
+        }
+        undone
+    }
+
+    /// How many instructions back the last write to address is, 1 being the
+    /// last instruction
+    pub fn last_write(&self, address: Word) -> Option<usize> {
+        self.steps
+            .iter()
+            .rev()
+            .position(|step| step.wrote(address))
+            .map(|position| position + 1)
+    }
+
+    /// Runs backwards to the last instruction that wrote address, the CPU is
+    /// left before it and its PC is returned. Nothing is undone when the
+    /// history has no such write
+    pub fn back_to_write<M: Bus>(
+        &mut self,
+        cpu: &mut CPU,
+        memory: &mut M,
+        address: Word,
+    ) -> Option<Word> {
+        let count: usize = self.last_write(address)?;
+        self.step_back_n(cpu, memory, count);
+        Some(cpu.pc())
+    }
+}
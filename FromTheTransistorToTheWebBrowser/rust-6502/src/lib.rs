@@ -0,0 +1,196 @@
+//! A 6502 emulator: the NMOS 6502 with its undocumented opcodes, the WDC
+//! 65C02 and the Rockwell R65C02, cycle counted, with the tools built on it
+//! (assembler, disassembler, debugger, profiler, loaders) and a few machines
+//! (the Apple-1, NROM cartridges of the NES).
+//!
+//! The CPU reads and writes everything through a [`Bus`]. [`Mem`] is a flat
+//! 64 KiB one, [`AddressDecoder`] maps devices ([`Ram`], [`Rom`], [`IoPort`]
+//! or anything implementing [`Bus`]) at address ranges:
+//!
+//! ```
+//! use rust_6502::{Bus, Flags, Mem, CPU};
+//!
+//! let mut memory = Mem::new();
+//! let mut cpu = CPU::new();
+//! cpu.reset_vector(&mut memory, 0x0600); // the PC and a blank 64 KiB
+//!
+//! memory.write(0x0600, 0xA9); // LDA #$80
+//! memory.write(0x0601, 0x80);
+//! memory.write(0x0602, 0xAA); // TAX
+//!
+//! let cycles = cpu.step(&mut memory);
+//! assert_eq!(cycles, 2);
+//! assert_eq!(cpu.a(), 0x80);
+//! assert!(cpu.flag(Flags::NegativeFlagBit));
+//!
+//! cpu.execute(&mut 2, &mut memory); // runs for at least 2 cycles
+//! assert_eq!(cpu.x(), 0x80);
+//! assert_eq!(cpu.pc(), 0x0603);
+//! ```
+//!
+//! - construction: [`CPU::new`], [`CPU::new_variant`]
+//! - reset: [`CPU::power_on`] loads the PC from the reset vector of the bus,
+//!   [`CPU::reset_vector`] starts at an address with a blank [`Mem`]
+//! - running: [`CPU::step`] executes one instruction (or interrupt),
+//!   [`CPU::execute`] a number of cycles, [`CPU::execute_traced`] hands
+//!   every instruction to a [`trace::TraceSink`], [`CPU::run`] runs until
+//!   one of a set of [`run::StopCondition`]s holds and tells which one in
+//!   a [`run::StopReason`]
+//! - registers and flags: [`CPU::pc`], [`CPU::a`], ... and their setters,
+//!   [`CPU::flag`] and [`CPU::set_flag`]
+//! - interrupts: [`CPU::set_irq`], [`CPU::set_nmi`], or the lines of the bus
+//! - state: [`snapshot::save_snapshot`] and [`snapshot::load_snapshot`]
+
+pub mod acia;
+pub mod apple1;
+pub mod assembler;
+pub mod bus;
+pub mod debugger;
+pub mod disasm;
+pub mod history;
+pub mod loader;
+pub mod memory_map;
+mod mos6502;
+pub mod nes;
+pub mod opcodes;
+pub mod pia;
+pub mod profiler;
+pub mod run;
+pub mod single_step;
+pub mod snapshot;
+pub mod trace;
+pub mod via;
+
+pub use crate::bus::{AddressDecoder, Bus, IoPort, Ram, Rom};
+use crate::loader::LoaderError;
+use crate::memory_map::{MemRegion, Violation};
+
+pub type Byte = u8;
+pub type Word = u16;
+
+/// The 64 KiB the CPU sees when nothing else is attached: RAM, with the
+/// ROM, unmapped and mirrored regions declared with map()
+#[derive(Debug, Clone)]
+pub struct Mem {
+    pub(crate) max_mem: u32,
+    pub(crate) data: Vec<Byte>,
+
+    // ROM, unmapped and mirrored ranges, see memory_map.rs. Addresses
+    // outside of them are RAM
+    pub(crate) regions: Vec<MemRegion>,
+    /// what reads of unmapped addresses return
+    pub open_bus: Byte,
+    /// in strict mode every invalid access is recorded in violations
+    pub strict: bool,
+    pub(crate) violations: Vec<Violation>,
+}
+
+/// The bits of the status register (PS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flags {
+    NegativeFlagBit = 0b10000000,
+    OverflowFlagBit = 0b01000000,
+    BreakFlagBit = 0b000010000,
+    UnusedFlagBit = 0b000100000,
+    DecimalModeFlagBit = 0b000001000,
+    InterruptDisableFlagBit = 0b000000100,
+    ZeroFlagBit = 0b00000010,
+    CarryFlagBit = 0b00000001,
+}
+
+/// ANE ($8B) and LXA ($AB) OR the accumulator with a constant before the
+/// AND. The constant changes from chip to chip (and even with temperature),
+/// $EE is the value most NMOS 6502s show
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnstableConstants {
+    pub ane_magic: Byte,
+    pub lxa_magic: Byte,
+}
+
+impl Default for UnstableConstants {
+    fn default() -> Self {
+        UnstableConstants {
+            ane_magic: 0xEE,
+            lxa_magic: 0xEE,
+        }
+    }
+}
+
+/// The chips the CPU can behave like:
+/// - the NMOS 6502, with the undocumented opcodes, the JMP ($xxFF) bug and
+///   the odd N, V & Z flags in decimal mode
+/// - the WDC 65C02 (W65C02S), which adds BRA, PHX/PHY/PLX/PLY, STZ, TRB/TSB,
+///   the (zp) addressing mode, INC A/DEC A, WAI/STP and the Rockwell bit
+///   instructions, fixes JMP indirect and the decimal mode flags, and turns
+///   every unused opcode into a NOP
+/// - the Rockwell R65C02, a 65C02 with the bit instructions
+///   (BBR/BBS/RMB/SMB) but without WAI and STP
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CpuVariant {
+    #[default]
+    Nmos6502,
+    Wdc65C02,
+    Rockwell65C02,
+}
+
+impl CpuVariant {
+    pub fn is_cmos(self) -> bool {
+        self != CpuVariant::Nmos6502
+    }
+
+    pub fn has_wai_and_stp(self) -> bool {
+        self == CpuVariant::Wdc65C02
+    }
+}
+
+/// A 6502: the registers, the interrupt lines and the cycle counter. The
+/// memory is a Bus handed to every call
+#[derive(Debug, Clone, Copy)]
+pub struct CPU {
+    pub(crate) pc: Word, // program counter
+    pub(crate) sp: Byte, // stack pointer
+
+    // Registers
+    pub(crate) a: Byte, // Accumulator
+    pub(crate) x: Byte,
+    pub(crate) y: Byte,
+
+    // Status flags
+    pub(crate) ps: Byte,
+
+    // Interrupt lines, true while the line is asserted
+    pub(crate) irq_line: bool,
+    pub(crate) nmi_line: bool,
+    // NMI is edge triggered, the edge is latched until it is serviced
+    pub(crate) nmi_pending: bool,
+    pub(crate) nmi_previous: bool,
+
+    pub(crate) variant: CpuVariant,
+
+    // set by the JAM opcodes (and STP on the 65C02), the CPU stops until the
+    // next reset
+    pub(crate) jammed: bool,
+    // set by WAI on the 65C02, the CPU sleeps until an interrupt line is
+    // asserted
+    pub(crate) waiting: bool,
+    /// the constants of the unstable undocumented opcodes
+    pub unstable: UnstableConstants,
+
+    // cycles executed since the reset, the CYC column of a trace
+    pub(crate) total_cycles: u64,
+}
+
+impl CPU {
+    /// Loads a Commodore PRG (the load address followed by the program) and
+    /// returns the load address. See loader.rs for the other formats
+    pub fn load_prg<M: Bus>(
+        &mut self,
+        program: &[Byte],
+        memory: &mut M,
+    ) -> Result<Word, LoaderError> {
+        let image = loader::parse_prg(program)?;
+        image.write_to(memory);
+        // parse_prg made sure the load address is there
+        Ok(program[0] as Word | (program[1] as Word) << 8)
+    }
+}
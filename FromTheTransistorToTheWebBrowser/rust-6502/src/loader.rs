@@ -0,0 +1,359 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::bus::Bus;
+use crate::{Byte, Word};
+
+// The 6502 sees 64 KiB, nothing may be loaded past $FFFF
+const ADDRESS_SPACE: u32 = 0x10000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoaderError {
+    Io(String),
+    /// a PRG file needs at least the two bytes of its load address
+    MissingLoadAddress,
+    /// line is counted from 1
+    Syntax {
+        line: usize,
+        message: String,
+    },
+    Checksum {
+        line: usize,
+        expected: Byte,
+        found: Byte,
+    },
+    /// length bytes at address don't fit below $FFFF
+    AddressOverflow {
+        address: u32,
+        length: usize,
+    },
+    /// two records write the byte at address
+    Overlap {
+        address: Word,
+    },
+}
+
+impl fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoaderError::Io(message) => write!(f, "{}", message),
+            LoaderError::MissingLoadAddress => write!(f, "the PRG file has no load address"),
+            LoaderError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            LoaderError::Checksum {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: checksum is {:02X}, expected {:02X}",
+                line, found, expected
+            ),
+            LoaderError::AddressOverflow { address, length } => {
+                write!(f, "{} bytes at ${:04X} go past $FFFF", length, address)
+            }
+            LoaderError::Overlap { address } => {
+                write!(f, "${:04X} is loaded more than once", address)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// the bytes as they are, loaded at the given address
+    Raw(Word),
+    /// Commodore PRG: the load address (little endian) then the bytes
+    Prg,
+    IntelHex,
+    SRecord,
+}
+
+impl Format {
+    /// The format from the file extension. Raw binaries need an address
+    /// so they are never guessed
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Format> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "prg" => Some(Format::Prg),
+            "hex" | "ihx" | "ihex" => Some(Format::IntelHex),
+            "srec" | "s19" | "s28" | "s37" | "mot" => Some(Format::SRecord),
+            _ => None,
+        }
+    }
+}
+
+/// Bytes that go to consecutive addresses
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub address: Word,
+    pub data: Vec<Byte>,
+}
+
+/// A loaded program: its segments and the start address when the file has
+/// one (the start record of Intel HEX, the S9 record of S-record)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Image {
+    pub segments: Vec<Segment>,
+    pub start: Option<Word>,
+}
+
+impl Image {
+    // adds data at address, checking that it fits in the address space and
+    // that no other record wrote there
+    fn add(&mut self, address: u32, data: &[Byte]) -> Result<(), LoaderError> {
+        if address as u64 + data.len() as u64 > ADDRESS_SPACE as u64 {
+            return Err(LoaderError::AddressOverflow {
+                address,
+                length: data.len(),
+            });
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        let end: u32 = address + data.len() as u32;
+        for segment in &self.segments {
+            let segment_end: u32 = segment.address as u32 + segment.data.len() as u32;
+            if address < segment_end && (segment.address as u32) < end {
+                return Err(LoaderError::Overlap {
+                    address: address.max(segment.address as u32) as Word,
+                });
+            }
+        }
+
+        // records usually follow each other, they make a single segment
+        if let Some(last) = self.segments.last_mut() {
+            if last.address as u32 + last.data.len() as u32 == address {
+                last.data.extend_from_slice(data);
+                return Ok(());
+            }
+        }
+        self.segments.push(Segment {
+            address: address as Word,
+            data: data.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// The address to start the program at: the start address of the file,
+    /// or else the first byte loaded
+    pub fn entry(&self) -> Option<Word> {
+        self.start
+            .or_else(|| self.segments.first().map(|segment| segment.address))
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|segment| segment.data.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn write_to<M: Bus>(&self, memory: &mut M) {
+        for segment in &self.segments {
+            for (i, &byte) in segment.data.iter().enumerate() {
+                memory.write(segment.address + i as Word, byte);
+            }
+        }
+    }
+
+    /// Points the reset vector ($FFFC) at the entry, returns the entry
+    pub fn set_reset_vector<M: Bus>(&self, memory: &mut M) -> Option<Word> {
+        let entry: Word = self.entry()?;
+        memory.write(0xFFFC, entry as Byte);
+        memory.write(0xFFFD, (entry >> 8) as Byte);
+        Some(entry)
+    }
+}
+
+pub fn parse_raw(data: &[Byte], address: Word) -> Result<Image, LoaderError> {
+    let mut image = Image::default();
+    image.add(address as u32, data)?;
+    Ok(image)
+}
+
+pub fn parse_prg(data: &[Byte]) -> Result<Image, LoaderError> {
+    if data.len() < 2 {
+        return Err(LoaderError::MissingLoadAddress);
+    }
+    let address: Word = data[0] as Word | (data[1] as Word) << 8;
+    parse_raw(&data[2..], address)
+}
+
+// The bytes of a record written in hex digits, e.g. "10010000..."
+fn hex_bytes(line: usize, text: &str) -> Result<Vec<Byte>, LoaderError> {
+    let syntax = |message: String| LoaderError::Syntax { line, message };
+
+    if !text.len().is_multiple_of(2) {
+        return Err(syntax("odd number of hex digits".to_string()));
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|digits| Byte::from_str_radix(digits, 16).ok())
+                .ok_or_else(|| syntax(format!("{:?} is not hex", text)))
+        })
+        .collect()
+}
+
+/// Intel HEX: `:LLAAAATT<data>CC` with the byte count, the address, the
+/// record type, the data and a checksum that makes the sum of all the bytes
+/// zero. Extended addresses (types 02 and 04) are only accepted when they
+/// stay below $FFFF
+pub fn parse_intel_hex(text: &str) -> Result<Image, LoaderError> {
+    let mut image = Image::default();
+    // from the extended segment / linear address records
+    let mut base: u32 = 0;
+
+    for (i, line) in text.lines().enumerate() {
+        let number: usize = i + 1;
+        let syntax = |message: &str| LoaderError::Syntax {
+            line: number,
+            message: message.to_string(),
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let digits = line
+            .strip_prefix(':')
+            .ok_or_else(|| syntax("a record starts with ':'"))?;
+        let bytes = hex_bytes(number, digits)?;
+        if bytes.len() < 5 || bytes.len() != 5 + bytes[0] as usize {
+            return Err(syntax("the byte count doesn't match the record"));
+        }
+
+        let (record, checksum) = bytes.split_at(bytes.len() - 1);
+        let sum: Byte = record.iter().fold(0, |sum: Byte, &b| sum.wrapping_add(b));
+        let expected: Byte = sum.wrapping_neg();
+        if checksum[0] != expected {
+            return Err(LoaderError::Checksum {
+                line: number,
+                expected,
+                found: checksum[0],
+            });
+        }
+
+        let address: u32 = (bytes[1] as u32) << 8 | bytes[2] as u32;
+        let data = &record[4..];
+        let value = || data.iter().fold(0u32, |value, &b| value << 8 | b as u32);
+        match bytes[3] {
+            0x00 => image.add(base + address, data)?,
+            0x01 => break,
+            0x02 if data.len() == 2 => base = value() << 4,
+            0x04 if data.len() == 2 => base = value() << 16,
+            0x03 if data.len() == 4 => {
+                // CS:IP
+                let start: u32 = (value() >> 16) * 16 + (value() & 0xFFFF);
+                image.start = Some(start_address(number, start)?);
+            }
+            0x05 if data.len() == 4 => image.start = Some(start_address(number, value())?),
+            0x02..=0x05 => return Err(syntax("wrong length for the record type")),
+            kind => return Err(syntax(&format!("unknown record type {:02X}", kind))),
+        }
+    }
+
+    Ok(image)
+}
+
+fn start_address(line: usize, address: u32) -> Result<Word, LoaderError> {
+    if address >= ADDRESS_SPACE {
+        return Err(LoaderError::Syntax {
+            line,
+            message: format!("start address ${:X} is past $FFFF", address),
+        });
+    }
+    Ok(address as Word)
+}
+
+/// Motorola S-record: `S<type><count><address><data><checksum>`. The count
+/// covers the address, the data and the checksum, the checksum is the ones
+/// complement of the sum of the other bytes. S1/S2/S3 carry data with 16,
+/// 24 or 32 bit addresses, S7/S8/S9 the start address. S0 (header) and
+/// S5/S6 (record counts) are skipped
+pub fn parse_srecord(text: &str) -> Result<Image, LoaderError> {
+    let mut image = Image::default();
+
+    for (i, line) in text.lines().enumerate() {
+        let number: usize = i + 1;
+        let syntax = |message: &str| LoaderError::Syntax {
+            line: number,
+            message: message.to_string(),
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let rest = line
+            .strip_prefix('S')
+            .or_else(|| line.strip_prefix('s'))
+            .ok_or_else(|| syntax("a record starts with 'S'"))?;
+        let kind: char = rest
+            .chars()
+            .next()
+            .ok_or_else(|| syntax("the record type is missing"))?;
+        let bytes = hex_bytes(number, &rest[kind.len_utf8()..])?;
+        if bytes.len() < 2 || bytes.len() != 1 + bytes[0] as usize {
+            return Err(syntax("the byte count doesn't match the record"));
+        }
+
+        let (record, checksum) = bytes.split_at(bytes.len() - 1);
+        let sum: Byte = record.iter().fold(0, |sum: Byte, &b| sum.wrapping_add(b));
+        let expected: Byte = !sum;
+        if checksum[0] != expected {
+            return Err(LoaderError::Checksum {
+                line: number,
+                expected,
+                found: checksum[0],
+            });
+        }
+
+        let address_bytes: usize = match kind {
+            '0' | '1' | '5' | '9' => 2,
+            '2' | '6' | '8' => 3,
+            '3' | '7' => 4,
+            _ => return Err(syntax(&format!("unknown record type S{}", kind))),
+        };
+        if record.len() < 1 + address_bytes {
+            return Err(syntax("the record is too short for its address"));
+        }
+        let (address, data) = record[1..].split_at(address_bytes);
+        let address: u32 = address.iter().fold(0, |value, &b| value << 8 | b as u32);
+
+        match kind {
+            '1' | '2' | '3' => image.add(address, data)?,
+            '7' | '8' | '9' => image.start = Some(start_address(number, address)?),
+            _ => {}
+        }
+    }
+
+    Ok(image)
+}
+
+pub fn parse(format: Format, data: &[Byte]) -> Result<Image, LoaderError> {
+    let text = || {
+        std::str::from_utf8(data).map_err(|_| LoaderError::Syntax {
+            line: 1,
+            message: "not a text file".to_string(),
+        })
+    };
+
+    match format {
+        Format::Raw(address) => parse_raw(data, address),
+        Format::Prg => parse_prg(data),
+        Format::IntelHex => parse_intel_hex(text()?),
+        Format::SRecord => parse_srecord(text()?),
+    }
+}
+
+pub fn read_file<P: AsRef<Path>>(path: P, format: Format) -> Result<Image, LoaderError> {
+    let data = fs::read(&path)
+        .map_err(|error| LoaderError::Io(format!("{}: {}", path.as_ref().display(), error)))?;
+    parse(format, &data)
+}
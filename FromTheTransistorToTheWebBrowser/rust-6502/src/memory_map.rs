@@ -0,0 +1,106 @@
+use crate::bus::Bus;
+use crate::Mem;
+use crate::{Byte, Word};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegionKind {
+    Ram,
+    /// writes are ignored (and flagged in strict mode)
+    Rom,
+    /// nothing answers: reads return the open bus value, writes are lost
+    Unmapped,
+    /// the region repeats size bytes starting at target, like the 2 KiB of
+    /// RAM of the NES that shows up four times in $0000-$1FFF
+    Mirror { target: Word, size: Word },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemRegion {
+    pub start: Word,
+    pub end: Word, // inclusive
+    pub kind: RegionKind,
+}
+
+/// An access strict mode records, address is the one the CPU used (before
+/// mirroring)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    RomWrite { address: Word, value: Byte },
+    UnmappedRead { address: Word },
+    UnmappedWrite { address: Word, value: Byte },
+}
+
+// Where an address ends up after the regions are applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Target {
+    Ram(usize),
+    Rom(usize),
+    Unmapped,
+}
+
+impl Mem {
+    /// Declares a region. When regions overlap the first one declared wins,
+    /// like the regions of an AddressDecoder
+    pub fn map(&mut self, start: Word, end: Word, kind: RegionKind) {
+        assert!(start <= end, "region {:04X}-{:04X} is empty", start, end);
+        if let RegionKind::Mirror { size, .. } = kind {
+            assert!(size > 0, "mirror {:04X}-{:04X} has no size", start, end);
+        }
+        self.regions.push(MemRegion { start, end, kind });
+    }
+
+    pub fn region(&self, address: Word) -> Option<&MemRegion> {
+        self.regions
+            .iter()
+            .find(|region| address >= region.start && address <= region.end)
+    }
+
+    pub(crate) fn resolve(&self, address: Word) -> Target {
+        let mut address: Word = address;
+
+        // a mirror can point into another mirror, but not around in circles
+        for _ in 0..=self.regions.len() {
+            let kind = self
+                .region(address)
+                .map(|region| (region.start, region.kind));
+            let index: usize = address as usize;
+            // a Mem that was never reset has no memory at all
+            let present: bool = index < self.data.len();
+
+            match kind {
+                None | Some((_, RegionKind::Ram)) if present => return Target::Ram(index),
+                Some((_, RegionKind::Rom)) if present => return Target::Rom(index),
+                Some((start, RegionKind::Mirror { target, size })) => {
+                    address = target.wrapping_add((address - start) % size);
+                }
+                _ => return Target::Unmapped,
+            }
+        }
+
+        Target::Unmapped
+    }
+
+    pub(crate) fn flag(&mut self, violation: Violation) {
+        if self.strict {
+            self.violations.push(violation);
+        }
+    }
+
+    /// The invalid accesses recorded since the last call
+    pub fn take_violations(&mut self) -> Vec<Violation> {
+        std::mem::take(&mut self.violations)
+    }
+
+    /// Copies bytes from address on with poke, into ROM too
+    pub fn load(&mut self, address: Word, bytes: &[Byte]) {
+        for (i, &byte) in bytes.iter().enumerate() {
+            self.poke(address.wrapping_add(i as Word), byte);
+        }
+    }
+
+    /// Little endian like the 6502, the high byte of $FFFF goes to $0000
+    pub fn write_word(&mut self, address: Word, value: Word) {
+        self.write(address, value as Byte);
+        self.write(address.wrapping_add(1), (value >> 8) as Byte);
+    }
+}
@@ -1,41 +1,82 @@
 use bit_field::BitField;
-use num_traits::{WrappingShl, WrappingShr};
-use std::os::raw::*;
 
-use crate::address::{Address, AddressDiff};
+use crate::bus::Bus;
+use crate::opcodes::{opcode_table, AddressingMode, Mnemonic};
+use crate::trace::{NoTrace, TraceRecord, TraceSink};
+use crate::CpuVariant;
 use crate::Flags;
 use crate::Mem;
+use crate::UnstableConstants;
 use crate::CPU;
-
-type Byte = c_uchar;
-type Word = c_ushort;
+use crate::{Byte, Word};
 
 impl Mem {
+    // the first reset allocates the memory, the next ones keep its size
     fn initialize(&mut self) {
-        for _ in 0..self.MAX_MEM {
-            self.Data.push(0);
-        }
+        self.data.resize(self.max_mem as usize, 0);
     }
 
+    /// Empty until the first reset allocates it
     pub fn new() -> Self {
         Mem {
-            MAX_MEM: 1024 * 64,
-            Data: Vec::new(),
+            max_mem: 1024 * 64,
+            data: Vec::new(),
+            regions: Vec::new(),
+            open_bus: 0,
+            strict: false,
+            violations: Vec::new(),
         }
     }
+
+    /// 64 KiB of zeros, the memory a reset leaves behind
+    pub fn blank() -> Self {
+        let mut mem = Mem::new();
+        mem.initialize();
+        mem
+    }
+
+    /// All the bytes, as they are stored (the regions are not applied)
+    pub fn data(&self) -> &[Byte] {
+        &self.data
+    }
+}
+
+impl Default for Mem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What the addressing mode of an instruction resolves to
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Implied,
+    Accumulator,
+    Immediate(Byte),
+    // the effective address, or the target of a jump or a branch
+    Address(Word),
+    // BBR / BBS: the zero page address to test and the branch target
+    ZeroPageBranch(Word, Word),
+}
+
+impl Default for CPU {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CPU {
+    /// An NMOS 6502, reset() or power_on() before running it
     pub fn new() -> Self {
         CPU {
-            PC: 0, // Program Counter
-            SP: 0, // Stack Pointer
+            pc: 0, // Program Counter
+            sp: 0, // Stack Pointer
 
-            A: 0, // Accumulator
-            X: 0, // Index Register X
-            Y: 0, // Index Register Y
+            a: 0, // Accumulator
+            x: 0, // Index Register X
+            y: 0, // Index Register Y
 
-            PS: 0b11111111, // Processor Status
+            ps: 0b11110111, // Processor Status
             //  0bNVUBDIZC
             // C: Carry Flag,          0
             // Z: Zero Flag,           1
@@ -45,1679 +86,1161 @@ impl CPU {
             // U: Unused,              5
             // V: Overflow Flag,       6
             // N: Negative Flag,       7
+            irq_line: false,
+            nmi_line: false,
+            nmi_pending: false,
+            nmi_previous: false,
 
-            // Opcodes
-
-            // LDA
-            INS_LDA_IM: 0xA9,
-            INS_LDA_ZP: 0xA5,
-            INS_LDA_ZPX: 0xB5,
-            INS_LDA_ABS: 0xAD,
-            INS_LDA_ABSX: 0xBD,
-            INS_LDA_ABSY: 0xB9,
-            INS_LDA_INDX: 0xA1,
-            INS_LDA_INDY: 0xB1,
-
-            // LDX
-            INS_LDX_IM: 0xA2,
-            INS_LDX_ZP: 0xA6,
-            INS_LDX_ZPY: 0xB6,
-            INS_LDX_ABS: 0xAE,
-            INS_LDX_ABSY: 0xBE,
-
-            // LDY
-            INS_LDY_IM: 0xA0,
-            INS_LDY_ZP: 0xA4,
-            INS_LDY_ZPX: 0xB4,
-            INS_LDY_ABS: 0xAC,
-            INS_LDY_ABSX: 0xBC,
-
-            // STA
-            INS_STA_ZP: 0x85,
-            INS_STA_ZPX: 0x95,
-            INS_STA_ABS: 0x8D,
-            INS_STA_ABSX: 0x9D,
-            INS_STA_ABSY: 0x99,
-            INS_STA_INDX: 0x81,
-            INS_STA_INDY: 0x91,
-
-            // STX
-            INS_STX_ZP: 0x86,
-            INS_STX_ZPY: 0x96,
-            INS_STX_ABS: 0x8E,
-
-            // STY
-            INS_STY_ZP: 0x84,
-            INS_STY_ZPX: 0x94,
-            INS_STY_ABS: 0x8C,
-
-            // Jumps And Calls
-            INS_JMP_ABS: 0x4C,
-            INS_JMP_IND: 0x6C,
-            INS_JSR: 0x20,
-            INS_RTS: 0x60,
-
-            // Stack Operations
-            INS_TSX: 0xBA,
-            INS_TXS: 0x9A,
-            INS_PHA: 0x48,
-            INS_PHP: 0x08,
-            INS_PLA: 0x68,
-            INS_PLP: 0x28,
+            variant: CpuVariant::default(),
 
-            // Logical Operations
-            INS_AND_IM: 0x29,
-            INS_AND_ZP: 0x25,
-            INS_AND_ZPX: 0x35,
-            INS_AND_ABS: 0x2D,
-            INS_AND_ABSX: 0x3D,
-            INS_AND_ABSY: 0x39,
-            INS_AND_INDX: 0x21,
-            INS_AND_INDY: 0x31,
-
-            INS_EOR_IM: 0x49,
-            INS_EOR_ZP: 0x45,
-            INS_EOR_ZPX: 0x55,
-            INS_EOR_ABS: 0x4D,
-            INS_EOR_ABSX: 0x5D,
-            INS_EOR_ABSY: 0x59,
-            INS_EOR_INDX: 0x41,
-            INS_EOR_INDY: 0x51,
-
-            INS_ORA_IM: 0x09,
-            INS_ORA_ZP: 0x05,
-            INS_ORA_ZPX: 0x15,
-            INS_ORA_ABS: 0x0D,
-            INS_ORA_ABSX: 0x1D,
-            INS_ORA_ABSY: 0x19,
-            INS_ORA_INDX: 0x01,
-            INS_ORA_INDY: 0x11,
-
-            INS_BIT_ZP: 0x24,
-            INS_BIT_ABS: 0x2C,
+            jammed: false,
+            waiting: false,
+            unstable: UnstableConstants::default(),
 
-            // Register Transfers
-            INS_TAX: 0xAA,
-            INS_TAY: 0xA8,
-            INS_TXA: 0x8A,
-            INS_TYA: 0x98,
+            total_cycles: 0,
+        }
+    }
 
-            // Increments & Decrements
-            INS_INC_ZP: 0xE6,
-            INS_INC_ZPX: 0xF6,
-            INS_INC_ABS: 0xEE,
-            INS_INC_ABSX: 0xFE,
+    /// A CPU that behaves like variant, see CpuVariant
+    pub fn new_variant(variant: CpuVariant) -> Self {
+        let mut cpu = CPU::new();
+        cpu.variant = variant;
+        cpu
+    }
 
-            INS_INX: 0xE8,
+    /// Resets the registers with the PC at $FFFC and allocates the memory
+    pub fn reset(&mut self, memory: &mut Mem) {
+        self.reset_registers(0xFFFC);
+        memory.initialize();
+    }
 
-            INS_INY: 0xC8,
+    /// The same, with the PC at address
+    pub fn reset_vector(&mut self, memory: &mut Mem, address: Word) {
+        self.reset_registers(address);
+        memory.initialize();
+    }
 
-            INS_DEC_ZP: 0xC6,
-            INS_DEC_ZPX: 0xD6,
-            INS_DEC_ABS: 0xCE,
-            INS_DEC_ABSX: 0xDE,
+    /// reset like the real 6502 does, loading the PC from the reset vector
+    /// (0xFFFC) of whatever is attached to the bus
+    pub fn power_on<M: Bus>(&mut self, memory: &mut M) {
+        let lo_byte: Word = memory.read(0xFFFC) as Word;
+        let hi_byte: Word = memory.read(0xFFFD) as Word;
+        self.reset_registers(lo_byte | (hi_byte << 8));
+    }
 
-            INS_DEX: 0xCA,
+    fn reset_registers(&mut self, address: Word) {
+        self.pc = address;
+        self.sp = 0xFF;
 
-            INS_DEY: 0x88,
+        self.a = 0;
+        self.x = 0;
+        self.y = 0;
 
-            // Branches
-            INS_BCC: 0x90,
-            INS_BCS: 0xB0,
-            INS_BEQ: 0xF0,
-            INS_BMI: 0x30,
-            INS_BNE: 0xD0,
-            INS_BPL: 0x10,
-            INS_BVC: 0x50,
-            INS_BVS: 0x70,
-
-            // Status Flags Changes
-            INS_CLC: 0x18,
-            INS_CLD: 0xD8,
-            INS_CLI: 0x58,
-            INS_CLV: 0xB8,
-            INS_SEC: 0x38,
-            INS_SED: 0xF8,
-            INS_SEI: 0x78,
+        // Decimal mode starts cleared, programs like snake6502 never execute CLD
+        self.ps = 0b11110111;
 
-            // Arithmetic
+        self.irq_line = false;
+        self.nmi_line = false;
+        self.nmi_pending = false;
+        self.nmi_previous = false;
 
-            // Add With Carry
-            INS_ADC_IM: 0x69,
-            INS_ADC_ZP: 0x65,
-            INS_ADC_ZPX: 0x75,
-            INS_ADC_ABS: 0x6D,
-            INS_ADC_ABSX: 0x7D,
-            INS_ADC_ABSY: 0x79,
-            INS_ADC_INDX: 0x61,
-            INS_ADC_INDY: 0x71,
-
-            // Sub With Carry
-            INS_SBC_IM: 0xE9,
-            INS_SBC_ZP: 0xE5,
-            INS_SBC_ZPX: 0xF5,
-            INS_SBC_ABS: 0xED,
-            INS_SBC_ABSX: 0xFD,
-            INS_SBC_ABSY: 0xF9,
-            INS_SBC_INDX: 0xE1,
-            INS_SBC_INDY: 0xF1,
-
-            // Compare Accumulator
-            INS_CMP_IM: 0xC9,
-            INS_CMP_ZP: 0xC5,
-            INS_CMP_ZPX: 0xD5,
-            INS_CMP_ABS: 0xCD,
-            INS_CMP_ABSX: 0xDD,
-            INS_CMP_ABSY: 0xD9,
-            INS_CMP_INDX: 0xC1,
-            INS_CMP_INDY: 0xD1,
-
-            // Compare X Register
-            INS_CPX_IM: 0xE0,
-            INS_CPX_ZP: 0xE4,
-            INS_CPX_ABS: 0xEC,
-
-            //Compare Y Register
-            INS_CPY_IM: 0xC0,
-            INS_CPY_ZP: 0xC4,
-            INS_CPY_ABS: 0xCC,
+        self.jammed = false;
+        self.waiting = false;
 
-            // Shifts
+        self.total_cycles = 0;
+    }
 
-            // Arithmetic Shift Left
-            INS_ASL_ACC: 0x0A,
-            INS_ASL_ZP: 0x06,
-            INS_ASL_ZPX: 0x16,
-            INS_ASL_ABS: 0x0E,
-            INS_ASL_ABSX: 0x1E,
-
-            //  Logical Shift Right
-            INS_LSR_ACC: 0x4A,
-            INS_LSR_ZP: 0x46,
-            INS_LSR_ZPX: 0x56,
-            INS_LSR_ABS: 0x4E,
-            INS_LSR_ABSX: 0x5E,
-
-            // Rotate Left
-            INS_ROL_ACC: 0x2A,
-            INS_ROL_ZP: 0x26,
-            INS_ROL_ZPX: 0x36,
-            INS_ROL_ABS: 0x2E,
-            INS_ROL_ABSX: 0x3E,
-
-            // Rotate Right
-            INS_ROR_ACC: 0x6A,
-            INS_ROR_ZP: 0x66,
-            INS_ROR_ZPX: 0x76,
-            INS_ROR_ABS: 0x6E,
-            INS_ROR_ABSX: 0x7E,
+    /// IRQ is level triggered: it is serviced between instructions for as
+    /// long as the line is asserted and the I flag is clear
+    pub fn set_irq(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
 
-            // System Functions
-            INS_BRK: 0x00,
-            INS_NOP: 0xEA,
-            INS_RTI: 0x40,
+    /// NMI is edge triggered: asserting the line latches one interrupt that
+    /// is serviced before the next instruction, whatever the I flag
+    pub fn set_nmi(&mut self, asserted: bool) {
+        if asserted && !self.nmi_line {
+            self.nmi_pending = true;
         }
+        self.nmi_line = asserted;
     }
 
-    pub fn reset(&mut self, memory: &mut Mem) {
-        self.PC = 0xFFFC;
-        self.SP = 0xFF;
-
-        self.A = 0;
-        self.X = 0;
-        self.Y = 0;
-
-        self.PS = 0b11111111;
+    // The registers, for the users of the library
 
-        memory.initialize();
+    /// the program counter
+    pub fn pc(&self) -> Word {
+        self.pc
     }
 
-    pub fn reset_vector(&mut self, memory: &mut Mem, address: Word) {
-        self.PC = address;
-        self.SP = 0xFF;
-
-        self.A = 0;
-        self.X = 0;
-        self.Y = 0;
-
-        self.PS = 0b11111111;
-
-        memory.initialize();
+    pub fn set_pc(&mut self, pc: Word) {
+        self.pc = pc;
     }
 
-    fn fetch_word(&mut self, cycles: &mut isize, memory: &mut Mem) -> Word {
-        // 6502 is little endian
-        let mut data: Word = memory.Data[self.PC as usize] as Word;
-        self.PC += 1;
+    /// the stack pointer, the stack is at $0100 + sp
+    pub fn sp(&self) -> Byte {
+        self.sp
+    }
 
-        data |= WrappingShl::wrapping_shl(&(memory.Data[self.PC as usize] as Word), 8);
-        self.PC += 1;
+    pub fn set_sp(&mut self, sp: Byte) {
+        self.sp = sp;
+    }
 
-        *cycles -= 2;
-        data
+    /// the accumulator
+    pub fn a(&self) -> Byte {
+        self.a
     }
 
-    fn fetch_byte(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let data: Byte = memory.Data[self.PC as usize];
-        self.PC = self.PC.wrapping_add(1);
-        *cycles = cycles.wrapping_sub(1);
-        data
+    pub fn set_a(&mut self, a: Byte) {
+        self.a = a;
     }
 
-    fn read_byte(&mut self, cycles: &mut isize, address: Word, memory: &mut Mem) -> Byte {
-        let data: Byte = memory.Data[address as usize];
-        *cycles = cycles.wrapping_sub(1);
-        data
+    pub fn x(&self) -> Byte {
+        self.x
     }
 
-    fn read_word(&mut self, cycles: &mut isize, address: Word, memory: &mut Mem) -> Word {
-        let lo_byte: Byte = self.read_byte(cycles, address, memory);
-        let hi_byte: Byte = self.read_byte(cycles, address + 1, memory);
+    pub fn set_x(&mut self, x: Byte) {
+        self.x = x;
+    }
 
-        let mut data: Word = lo_byte as Word;
-        data |= WrappingShl::wrapping_shl(&(hi_byte as Word), 8);
+    pub fn y(&self) -> Byte {
+        self.y
+    }
 
-        data
+    pub fn set_y(&mut self, y: Byte) {
+        self.y = y;
     }
 
-    fn write_byte(&mut self, value: Byte, cycles: &mut isize, address: Word, memory: &mut Mem) {
-        memory.Data[address as usize] = value;
-        *cycles = cycles.wrapping_sub(1);
+    /// the processor status, the bits are Flags
+    pub fn status(&self) -> Byte {
+        self.ps
     }
 
-    // write 2 bytes
-    fn write_word(&mut self, value: Byte, cycles: &mut isize, address: Word, memory: &mut Mem) {
-        memory.Data[address as usize] = (value & 0xFF) as Byte;
-        let mut x: u16 = value as Word;
-        x = x.wrapping_shr(8);
-        memory.Data[(address + 1) as usize] = x as Byte;
-        *cycles -= 2;
+    pub fn set_status(&mut self, status: Byte) {
+        self.ps = status;
     }
 
-    // return the stack pointer as a full 16-bit address
-    pub fn sp_to_address(&mut self) -> Word {
-        0x100 as Word | self.SP as Word
+    /// one bit of the status register
+    pub fn flag(&self, flag: Flags) -> bool {
+        self.ps & flag as Byte != 0
     }
 
-    fn push_byte_to_stack(&mut self, cycles: &mut isize, memory: &mut Mem, value: Byte) {
-        memory.Data[self.sp_to_address() as usize] = value;
-        *cycles -= 1;
-        self.SP = self.SP.wrapping_sub(1);
-        *cycles -= 1;
+    pub fn set_flag(&mut self, flag: Flags, set: bool) {
+        if set {
+            self.ps |= flag as Byte;
+        } else {
+            self.ps &= !(flag as Byte);
+        }
     }
 
-    fn pop_byte_from_stack(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        self.SP += 1;
-        *cycles -= 1;
-        let sp_word: Word = self.sp_to_address();
-        let value: Byte = memory.Data[sp_word as usize];
-        *cycles -= 1;
+    /// cycles executed since the last reset
+    pub fn cycles(&self) -> u64 {
+        self.total_cycles
+    }
 
-        value
+    /// a JAM opcode (or STP) stopped the CPU until the next reset
+    pub fn is_jammed(&self) -> bool {
+        self.jammed
     }
 
-    fn push_word_to_stack(&mut self, cycles: &mut isize, memory: &mut Mem, value: Word) {
-        let mut sp_16_bit = self.sp_to_address();
-        self.write_byte(value.wrapping_shr(8) as Byte, cycles, sp_16_bit, memory);
-        self.SP = self.SP.wrapping_sub(1);
-        sp_16_bit = self.sp_to_address();
-        self.write_byte((value & 0xFF) as Byte, cycles, sp_16_bit, memory);
-        self.SP = self.SP.wrapping_sub(1);
+    /// WAI put the CPU to sleep until an interrupt
+    pub fn is_waiting(&self) -> bool {
+        self.waiting
     }
 
-    // push the PC onto the stack
-    fn push_pc_to_stack(&mut self, cycles: &mut isize, memory: &mut Mem) {
-        self.push_word_to_stack(cycles, memory, self.PC as Word);
+    pub fn variant(&self) -> CpuVariant {
+        self.variant
     }
 
-    // push the PC + 1 onto the stack
-    fn push_pc_to_stack_plus_one(&mut self, cycles: &mut isize, memory: &mut Mem) {
-        self.push_word_to_stack(cycles, memory, (self.PC + 1) as Word);
+    /// the IRQ line set with set_irq, the devices on the bus have their own
+    pub fn irq_line(&self) -> bool {
+        self.irq_line
     }
 
-    // push the PC + 2 onto the stack
-    fn push_pc_to_stack_plus_two(&mut self, cycles: &mut isize, memory: &mut Mem) {
-        self.push_word_to_stack(cycles, memory, (self.PC + 2) as Word);
+    /// an NMI edge was latched and the interrupt has not run yet
+    pub fn nmi_pending(&self) -> bool {
+        self.nmi_pending
     }
 
-    // Push Processor Status onto the stack
-    // Setting bits 4 & 5 on the  stack
-    fn push_ps_to_stack(&mut self, cycles: &mut isize, memory: &mut Mem) {
-        let ps_stack: Byte = self.PS | Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8;
-        self.push_byte_to_stack(cycles, memory, ps_stack);
+    // detect a rising edge on the NMI line of the devices on the bus
+    fn poll_nmi<M: Bus>(&mut self, memory: &mut M) {
+        let nmi: bool = memory.nmi();
+        if nmi && !self.nmi_previous {
+            self.nmi_pending = true;
+        }
+        self.nmi_previous = nmi;
     }
 
-    // Pop Processor Status from the stack
-    // Clearing bits 4 & 5 (Break & Unused)
-    fn pop_ps_from_stack(&mut self, cycles: &mut isize, memory: &mut Mem) {
-        self.PS = self.pop_byte_from_stack(cycles, memory);
-        self.PS.set_bit(4, false); // B
-        self.PS.set_bit(5, false); // U
+    // An NMI that arrives while an interrupt sequence (IRQ or BRK) is pushing
+    // the return address takes over its vector, the pushed B flag is kept
+    fn interrupt_vector<M: Bus>(&mut self, memory: &mut M, vector: Word) -> Word {
+        self.poll_nmi(memory);
+        if self.nmi_pending {
+            self.nmi_pending = false;
+            return 0xFFFA;
+        }
+
+        vector
     }
 
-    fn pop_word_from_stack(&mut self, cycles: &mut isize, memory: &mut Mem) -> Word {
-        let sp_16_bit = self.sp_to_address();
+    // Samples the interrupt lines between instructions and runs the
+    // 7 cycle interrupt sequence when one has to be serviced
+    fn service_interrupts<M: Bus>(&mut self, cycles: &mut isize, memory: &mut M) -> bool {
+        self.poll_nmi(memory);
+
+        let irq: bool = (self.irq_line || memory.irq()) && !self.ps.get_bit(2);
+        if !self.nmi_pending && !irq {
+            return false;
+        }
 
-        let value_from_stack: Word = self.read_word(cycles, sp_16_bit + 1, memory);
-        self.SP = self.SP.wrapping_add(2);
+        self.push_word_to_stack(memory, self.pc);
+        // B is cleared on the stack, that's how a handler tells IRQ from BRK
+        let ps_stack: Byte =
+            (self.ps | Flags::UnusedFlagBit as Byte) & !(Flags::BreakFlagBit as Byte);
+        self.push_byte_to_stack(memory, ps_stack);
+        let vector: Word = self.interrupt_vector(memory, 0xFFFE);
+        self.pc = self.read_word(vector, memory);
+        self.ps.set_bit(2, true); // I
+        if self.variant.is_cmos() {
+            // the 65C02 leaves decimal mode when it takes an interrupt
+            self.ps.set_bit(3, false); // D
+        }
+        *cycles -= 7;
 
-        *cycles -= 1;
-        value_from_stack
+        true
     }
 
-    fn zero_page_address(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-        let value: Byte = self.read_byte(cycles, zero_page_address as Word, memory);
+    fn fetch_word<M: Bus>(&mut self, memory: &mut M) -> Word {
+        // 6502 is little endian
+        let lo_byte: Word = self.fetch_byte(memory) as Word;
+        let hi_byte: Word = self.fetch_byte(memory) as Word;
 
-        value
+        lo_byte | (hi_byte << 8)
     }
 
-    fn zero_page_address_x(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let mut zero_page_address_x: Byte = self.fetch_byte(cycles, memory);
-        zero_page_address_x = zero_page_address_x.wrapping_add(self.X);
-        *cycles -= 1;
-        let value: Byte = self.read_byte(cycles, zero_page_address_x as Word, memory);
-
-        value
+    fn fetch_byte<M: Bus>(&mut self, memory: &mut M) -> Byte {
+        let data: Byte = memory.fetch(self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        data
     }
 
-    fn zero_page_address_y(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let mut zero_page_address_y: Byte = self.fetch_byte(cycles, memory);
-        zero_page_address_y = zero_page_address_y.wrapping_add(self.Y);
-        *cycles -= 1;
-        let value: Byte = self.read_byte(cycles, zero_page_address_y as Word, memory);
+    fn read_word<M: Bus>(&mut self, address: Word, memory: &mut M) -> Word {
+        let lo_byte: Word = memory.read(address) as Word;
+        let hi_byte: Word = memory.read(address.wrapping_add(1)) as Word;
 
-        value
+        lo_byte | (hi_byte << 8)
     }
 
-    fn absolute_address(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let abs_addrress: Word = self.fetch_word(cycles, memory);
-        let value = self.read_byte(cycles, abs_addrress as Word, memory);
+    // pointers in the zero page wrap around inside it
+    fn read_zero_page_word<M: Bus>(&mut self, address: Byte, memory: &mut M) -> Word {
+        let lo_byte: Word = memory.read(address as Word) as Word;
+        let hi_byte: Word = memory.read(address.wrapping_add(1) as Word) as Word;
 
-        value
+        lo_byte | (hi_byte << 8)
     }
 
-    fn absolute_address_x(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let abs_address: Word = self.fetch_word(cycles, memory);
-        let abs_address_plus_x: Word = abs_address + self.X as Word;
-        let value = self.read_byte(cycles, abs_address_plus_x, memory);
-        if abs_address_plus_x - abs_address >= 0xFF {
-            *cycles -= 1;
-        }
+    // return the stack pointer as a full 16-bit address
+    pub(crate) fn sp_to_address(&self) -> Word {
+        0x100 as Word | self.sp as Word
+    }
 
-        value
+    fn push_byte_to_stack<M: Bus>(&mut self, memory: &mut M, value: Byte) {
+        let sp_word: Word = self.sp_to_address();
+        memory.write(sp_word, value);
+        self.sp = self.sp.wrapping_sub(1);
     }
 
-    fn absolute_address_y(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let abs_address: Word = self.fetch_word(cycles, memory);
-        let abs_address_plus_y: Word = abs_address + self.Y as Word;
-        let value = self.read_byte(cycles, abs_address_plus_y, memory);
-        if abs_address_plus_y - abs_address >= 0xFF {
-            *cycles -= 1;
-        }
+    fn pop_byte_from_stack<M: Bus>(&mut self, memory: &mut M) -> Byte {
+        self.sp = self.sp.wrapping_add(1);
+        let sp_word: Word = self.sp_to_address();
+        memory.read(sp_word)
+    }
 
-        value
+    fn push_word_to_stack<M: Bus>(&mut self, memory: &mut M, value: Word) {
+        self.push_byte_to_stack(memory, (value >> 8) as Byte);
+        self.push_byte_to_stack(memory, (value & 0xFF) as Byte);
     }
 
-    fn indirect_address_x(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-        zero_page_address += self.X;
-        *cycles -= 1;
-        let effective_address: Word = self.read_word(cycles, zero_page_address as Word, memory);
-        let value: Byte = self.read_byte(cycles, effective_address, memory);
+    fn pop_word_from_stack<M: Bus>(&mut self, memory: &mut M) -> Word {
+        let lo_byte: Word = self.pop_byte_from_stack(memory) as Word;
+        let hi_byte: Word = self.pop_byte_from_stack(memory) as Word;
 
-        value
+        lo_byte | (hi_byte << 8)
     }
 
-    fn indirect_address_y(&mut self, cycles: &mut isize, memory: &mut Mem) -> Byte {
-        let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-        let effective_address: Word = self.read_word(cycles, zero_page_address as Word, memory);
-        let effective_address_y: Word = effective_address + self.Y as Word;
-        let value = self.read_byte(cycles, effective_address_y, memory);
-        if effective_address_y - effective_address >= 0xFF {
-            *cycles -= 1;
-        }
-
-        value
+    // Push Processor Status onto the stack
+    // Setting bits 4 & 5 on the  stack
+    fn push_ps_to_stack<M: Bus>(&mut self, memory: &mut M) {
+        let ps_stack: Byte = self.ps | Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8;
+        self.push_byte_to_stack(memory, ps_stack);
     }
 
-    fn add_with_carry(&mut self, operand: Byte) {
-        let are_sign_bits_the_same: bool =
-            !((self.A ^ operand) & Flags::NegativeFlagBit as Byte) != 0;
+    // Pop Processor Status from the stack
+    // Clearing bits 4 & 5 (Break & Unused)
+    fn pop_ps_from_stack<M: Bus>(&mut self, memory: &mut M) {
+        self.ps = self.pop_byte_from_stack(memory);
+        self.ps.set_bit(4, false); // B
+        self.ps.set_bit(5, false); // U
+    }
 
-        let c_flag_value: Byte = if self.PS.get_bit(0) == true { 1 } else { 0 };
-        self.A = self.A.wrapping_add(c_flag_value);
-        let sum = self.A.checked_add(operand);
+    // Indexing crosses a page when the high byte of the address changes,
+    // the instructions with a page penalty in the decode table take one
+    // more cycle then
+    fn indexed_address(address: Word, index: Byte) -> (Word, bool) {
+        let effective_address: Word = address.wrapping_add(index as Word);
+        (effective_address, effective_address >> 8 != address >> 8)
+    }
 
-        match sum {
-            Some(x) => {
-                self.A = x;
-                self.PS.set_bit(0, false); // set Carry Flag
-                self.ld_register_set_status(self.A);
+    // Fetches the operand bytes of an instruction and resolves them, the one
+    // operand path every opcode goes through
+    fn fetch_operand<M: Bus>(&mut self, mode: AddressingMode, memory: &mut M) -> (Operand, bool) {
+        match mode {
+            AddressingMode::Implied => (Operand::Implied, false),
+            AddressingMode::Accumulator => (Operand::Accumulator, false),
+            AddressingMode::Immediate => (Operand::Immediate(self.fetch_byte(memory)), false),
+            AddressingMode::ZeroPage => {
+                let zero_page_address: Byte = self.fetch_byte(memory);
+                (Operand::Address(zero_page_address as Word), false)
             }
-
-            None => {
-                // Overflow
-                self.A = self.A.wrapping_add(operand);
-                self.PS.set_bit(0, true); // set Carry Flag
-                self.ld_register_set_status(self.A);
+            AddressingMode::ZeroPageX => {
+                let zero_page_address: Byte = self.fetch_byte(memory).wrapping_add(self.x);
+                (Operand::Address(zero_page_address as Word), false)
+            }
+            AddressingMode::ZeroPageY => {
+                let zero_page_address: Byte = self.fetch_byte(memory).wrapping_add(self.y);
+                (Operand::Address(zero_page_address as Word), false)
+            }
+            AddressingMode::Absolute => (Operand::Address(self.fetch_word(memory)), false),
+            AddressingMode::AbsoluteX => {
+                let abs_address: Word = self.fetch_word(memory);
+                let (address, page_crossed) = CPU::indexed_address(abs_address, self.x);
+                (Operand::Address(address), page_crossed)
+            }
+            AddressingMode::AbsoluteY => {
+                let abs_address: Word = self.fetch_word(memory);
+                let (address, page_crossed) = CPU::indexed_address(abs_address, self.y);
+                (Operand::Address(address), page_crossed)
+            }
+            // NB:
+            //      An original 6502 has does not correctly fetch the target address
+            //      if the indirect vector falls on a page boundary
+            //      (e.g. $xxFF where xx is any value from $00 to $FF).
+            //      In this case fetches the LSB from $xxFF as expected but takes
+            //      the MSB from $xx00. This is fixed in some later chips like
+            //      the 65SC02 so for compatibility always ensure the indirect vector
+            //      is not at the end of the page.
+            AddressingMode::Indirect => {
+                let abs_address: Word = self.fetch_word(memory);
+                if self.variant.is_cmos() {
+                    return (Operand::Address(self.read_word(abs_address, memory)), false);
+                }
+
+                // the high byte comes from the start of the same page
+                let lo_byte: Word = memory.read(abs_address) as Word;
+                let hi_address: Word =
+                    (abs_address & 0xFF00) | (abs_address.wrapping_add(1) & 0x00FF);
+                let hi_byte: Word = memory.read(hi_address) as Word;
+                (Operand::Address(lo_byte | (hi_byte << 8)), false)
+            }
+            AddressingMode::IndirectX => {
+                let zero_page_address: Byte = self.fetch_byte(memory).wrapping_add(self.x);
+                let address: Word = self.read_zero_page_word(zero_page_address, memory);
+                (Operand::Address(address), false)
+            }
+            AddressingMode::IndirectY => {
+                let zero_page_address: Byte = self.fetch_byte(memory);
+                let pointer: Word = self.read_zero_page_word(zero_page_address, memory);
+                let (address, page_crossed) = CPU::indexed_address(pointer, self.y);
+                (Operand::Address(address), page_crossed)
+            }
+            AddressingMode::Relative => {
+                let offset: Byte = self.fetch_byte(memory);
+                let target: Word = CPU::signed_8_bit_to_16(offset).wrapping_add(self.pc);
+                (Operand::Address(target), target >> 8 != self.pc >> 8)
+            }
+            AddressingMode::ZeroPageIndirect => {
+                let zero_page_address: Byte = self.fetch_byte(memory);
+                let address: Word = self.read_zero_page_word(zero_page_address, memory);
+                (Operand::Address(address), false)
+            }
+            AddressingMode::AbsoluteIndexedIndirect => {
+                let pointer: Word = self.fetch_word(memory).wrapping_add(self.x as Word);
+                (Operand::Address(self.read_word(pointer, memory)), false)
+            }
+            AddressingMode::ZeroPageRelative => {
+                let zero_page_address: Word = self.fetch_byte(memory) as Word;
+                let offset: Byte = self.fetch_byte(memory);
+                let target: Word = CPU::signed_8_bit_to_16(offset).wrapping_add(self.pc);
+                (
+                    Operand::ZeroPageBranch(zero_page_address, target),
+                    target >> 8 != self.pc >> 8,
+                )
             }
         }
-
-        // FIXME: Overflow Flag
-        let v: bool =
-            are_sign_bits_the_same && ((self.A ^ operand) & Flags::NegativeFlagBit as Byte) != 0;
-        self.PS.set_bit(6, v); // V flag
     }
 
-    fn rotate_left(&mut self, cycles: &mut isize, mut operand: Byte) -> Byte {
-        let new_bit_0: Byte = if self.PS.get_bit(0) {
-            Flags::ZeroBit as Byte
-        } else {
-            0
-        };
-        let c: bool = (operand & Flags::NegativeFlagBit as Byte) > 0;
-        self.PS.set_bit(0, c);
-        operand = operand << 1;
-        operand |= new_bit_0;
-        *cycles -= 1;
-
-        operand
-    }
-
-    fn rotate_right(&mut self, cycles: &mut isize, mut operand: Byte) -> Byte {
-        let old_bit_0: bool = (operand & Flags::ZeroBit as Byte) > 0;
-        operand = operand >> 1;
-        if self.PS.get_bit(0) {
-            // C
-            operand |= Flags::NegativeFlagBit as Byte;
+    // the value an instruction works on
+    fn read_operand<M: Bus>(&mut self, operand: Operand, memory: &mut M) -> Byte {
+        match operand {
+            Operand::Accumulator => self.a,
+            Operand::Immediate(value) => value,
+            Operand::Address(address) | Operand::ZeroPageBranch(address, _) => memory.read(address),
+            Operand::Implied => 0,
         }
-        *cycles -= 1;
-        self.PS.set_bit(0, old_bit_0); // C
-
-        operand
     }
 
-    fn branch_if(&mut self, cycles: &mut isize, memory: &mut Mem, value: bool, condition: bool) {
-        // TODO: review this function
-        let offset: Byte = self.fetch_byte(cycles, memory);
-        let address = CPU::signed_8_bit_to_16(offset).wrapping_add(self.PC);
-        if value == condition {
-            let old_pc: Word = self.PC;
-            self.PC = address;
-            *cycles -= 1;
-
-            let page_changed: bool = self.PC >> 8 != old_pc >> 8;
-
-            if page_changed {
-                *cycles -= 2;
+    // where a read-modify-write instruction leaves its result
+    fn write_operand<M: Bus>(&mut self, operand: Operand, value: Byte, memory: &mut M) {
+        match operand {
+            Operand::Accumulator => self.a = value,
+            Operand::Address(address) | Operand::ZeroPageBranch(address, _) => {
+                memory.write(address, value)
             }
+            Operand::Implied | Operand::Immediate(_) => {}
         }
     }
 
-    pub fn execute(&mut self, cycles: &mut isize, memory: &mut Mem) -> isize {
-        let cycles_requested = *cycles;
-        while cycles > &mut 0 {
-            let ins: Byte = self.fetch_byte(cycles, memory);
-
-            match ins {
-                // Load Instructions
-                0xA9 => {
-                    println!("Instruction LDA Inmediate");
-                    self.A = self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xA2 => {
-                    println!("Instruction LDX Inmediate");
-                    self.X = self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xA0 => {
-                    println!("Instruction LDY Inmediate");
-                    self.Y = self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0xA5 => {
-                    println!("Instruction LDA ZP");
-                    self.A = self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xA6 => {
-                    println!("Instruction LDX ZP");
-                    self.X = self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xA4 => {
-                    println!("Instruction LDY ZP");
-                    self.Y = self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0xB4 => {
-                    println!("Instruction LDY ZPX");
-                    self.Y = self.zero_page_address_x(cycles, memory);
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0xB5 => {
-                    println!("Instruction LDA ZPX");
-                    self.A = self.zero_page_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xB6 => {
-                    println!("Instruction LDX ZPY");
-                    self.X = self.zero_page_address_y(cycles, memory);
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xAD => {
-                    println!("Instruction LDA Absolute");
-                    self.A = self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xAE => {
-                    println!("Instruction LDX Absolute");
-                    self.X = self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xAC => {
-                    println!("Instruction LDY Absolute");
-                    self.Y = self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0xBC => {
-                    println!("Instruction LDY Absolute X");
-                    self.Y = self.absolute_address_x(cycles, memory);
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0xBD => {
-                    println!("Instruction LDA Absolute X");
-                    self.A = self.absolute_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xBE => {
-                    println!("Instruction LDX Absolute Y");
-                    self.X = self.absolute_address_y(cycles, memory);
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xB9 => {
-                    println!("Instruction LDA Absolute Y");
-                    self.A = self.absolute_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xA1 => {
-                    println!("Instruction LDA Indirect X");
-                    self.A = self.indirect_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xB1 => {
-                    println!("Instruction LDA Indirect Y");
-                    self.A = self.indirect_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                // Store Instructions
-                0x85 => {
-                    println!("Instruction STA Zero Page");
-                    let zp_address: Byte = self.fetch_byte(cycles, memory);
-                    self.write_byte(self.A, cycles, zp_address as Word, memory);
-                }
-
-                0x95 => {
-                    println!("Instruction STA Zero Page X");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    self.write_byte(self.A, cycles, zero_page_address as Word, memory);
-                }
-
-                0x86 => {
-                    println!("Instruction STX Zero Page");
-                    let zp_address: Byte = self.fetch_byte(cycles, memory);
-                    self.write_byte(self.X, cycles, zp_address as Word, memory);
-                }
-
-                0x96 => {
-                    println!("Instruction STX Zero Page Y ");
-                    let mut zp_address: Byte = self.fetch_byte(cycles, memory);
-                    zp_address = zp_address.wrapping_add(self.Y);
-                    *cycles -= 1;
-                    self.write_byte(self.X, cycles, zp_address as Word, memory);
-                }
-
-                0x84 => {
-                    println!("Instruction STY Zero Page");
-                    let zp_address: Byte = self.fetch_byte(cycles, memory);
-                    self.write_byte(self.Y, cycles, zp_address as Word, memory);
-                }
-
-                0x94 => {
-                    println!("Instruction STY Zero Page X");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    self.write_byte(self.Y, cycles, zero_page_address as Word, memory);
-                }
-
-                0x8D => {
-                    println!("Instriction STA Absolute");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    self.write_byte(self.A, cycles, abs_addrress as Word, memory);
-                }
-
-                0x8E => {
-                    println!("Instriction STX Absolute");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    self.write_byte(self.X, cycles, abs_addrress as Word, memory);
-                }
-
-                0x8C => {
-                    println!("Instruction STY Absolute");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    self.write_byte(self.Y, cycles, abs_addrress as Word, memory);
-                }
-
-                0x9D => {
-                    println!("Instruction STA Absolute X");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x: Word = abs_addrress + self.X as Word;
-                    self.write_byte(self.A, cycles, abs_address_plus_x, memory);
-                    *cycles -= 1;
-                }
-
-                0x99 => {
-                    println!("Instruction STA Absolute Y");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x: Word = abs_addrress + self.Y as Word;
-                    self.write_byte(self.A, cycles, abs_address_plus_x, memory);
-                    *cycles -= 1;
-                }
-
-                0x81 => {
-                    println!("Instruction STA Indirect X");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address += self.X;
-                    *cycles -= 1;
-                    let effective_address: Word =
-                        self.read_word(cycles, zero_page_address as Word, memory);
-                    self.write_byte(self.A, cycles, effective_address, memory);
-                }
-
-                0x91 => {
-                    println!("Instruction STA Indirect Y");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let effective_address: Word =
-                        self.read_word(cycles, zero_page_address as Word, memory);
-                    let effective_address_y: Word = effective_address + self.Y as Word;
-                    self.write_byte(self.A, cycles, effective_address_y, memory);
-                    *cycles -= 1;
-                }
-
-                // Jumps and Calls
-
-                // NB:
-                //      An original 6502 has does not correctly fetch the target address
-                //      if the indirect vector falls on a page boundary
-                //      (e.g. $xxFF where xx is any value from $00 to $FF).
-                //      In this case fetches the LSB from $xxFF as expected but takes
-                //      the MSB from $xx00. This is fixed in some later chips like
-                //      the 65SC02 so for compatibility always ensure the indirect vector
-                //      is not at the end of the page.
-                0x4C => {
-                    println!("Instruction JMP Absolute");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    self.PC = abs_addrress;
-                }
-
-                0x6C => {
-                    println!("Instruction JMP Indirect");
-                    let mut abs_addrress: Word = self.fetch_word(cycles, memory);
-                    abs_addrress = self.read_word(cycles, abs_addrress, memory);
-                    self.PC = abs_addrress;
-                }
-
-                0x20 => {
-                    println!("Instruction JSR");
-                    let sub_addr: Word = self.fetch_word(cycles, memory);
-                    self.push_pc_to_stack(cycles, memory);
-                    self.PC = sub_addr;
-                    *cycles -= 1;
-                }
-
-                0x60 => {
-                    println!("Instruction RTS");
-                    let return_address = self.pop_word_from_stack(cycles, memory);
-                    self.PC = return_address;
-                    *cycles -= 2;
-                }
-
-                // Stack Operations
-                0xBA => {
-                    println!("Instruction TSX");
-                    self.X = self.SP;
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.X);
-                }
-
-                0x9A => {
-                    println!("Instruction TXS");
-                    self.SP = self.X;
-                    *cycles -= 1
-                }
-
-                0x48 => {
-                    println!("Instruction PHA");
-                    self.push_byte_to_stack(cycles, memory, self.A);
-                }
-
-                0x08 => {
-                    println!("Instruction PHP");
-                    self.push_ps_to_stack(cycles, memory);
-                }
-
-                0x68 => {
-                    println!("Instruction PLA");
-                    self.A = self.pop_byte_from_stack(cycles, memory);
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x28 => {
-                    println!("Instruction PLP");
-                    self.pop_ps_from_stack(cycles, memory);
-                    *cycles -= 1;
-                }
-
-                // Logical Operations
-                0x29 => {
-                    println!("instruction AND Inmediate");
-                    self.A &= self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x09 => {
-                    println!("instruction ORA Inmediate");
-                    self.A |= self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x49 => {
-                    println!("instructin EOR Inmediate");
-                    self.A ^= self.fetch_byte(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x25 => {
-                    println!("Instruction AND ZP");
-                    self.A &= self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x05 => {
-                    println!("Instruction ORA ZP");
-                    self.A |= self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x45 => {
-                    println!("Instruction EOR ZP");
-                    self.A ^= self.zero_page_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x35 => {
-                    println!("Instruction AND ZPX");
-                    self.A &= self.zero_page_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x15 => {
-                    println!("Instruction ORA ZPX");
-                    self.A |= self.zero_page_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x55 => {
-                    println!("Instruction EOR ZPX");
-                    self.A ^= self.zero_page_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x2D => {
-                    println!("Instruction AND Absolute");
-                    self.A &= self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x0D => {
-                    println!("Instruction ORA Absolute");
-                    self.A |= self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x4D => {
-                    println!("Instruction EOR Absolute");
-                    self.A ^= self.absolute_address(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x3D => {
-                    println!("Instruction AND Absolute X");
-                    self.A &= self.absolute_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x1D => {
-                    println!("Instruction ORA Absolute X");
-                    self.A |= self.absolute_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x5D => {
-                    println!("Instruction EOR Absolute X");
-                    self.A ^= self.absolute_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x39 => {
-                    println!("Instruction AND Absolute Y");
-                    self.A &= self.absolute_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x19 => {
-                    println!("Instruction ORA Absolute Y");
-                    self.A |= self.absolute_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x59 => {
-                    println!("Instruction EOR Absolute Y");
-                    self.A ^= self.absolute_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x21 => {
-                    println!("Instruction AND Indirect X");
-                    self.A &= self.indirect_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x01 => {
-                    println!("Instruction ORA Indirect X");
-                    self.A |= self.indirect_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x41 => {
-                    println!("Instruction EOR Indirect X");
-                    self.A ^= self.indirect_address_x(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x31 => {
-                    println!("Instruction AND Indirect Y");
-                    self.A &= self.indirect_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x11 => {
-                    println!("Instruction ORA Indirect Y");
-                    self.A |= self.indirect_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x51 => {
-                    println!("Instruction EOR Indirect Y");
-                    self.A ^= self.indirect_address_y(cycles, memory);
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x24 => {
-                    println!("Instruction BIT ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let value = self.read_byte(cycles, zero_page_address as Word, memory);
-
-                    let z = self.A & value;
-                    let z_bool: bool;
-                    if z == 0 {
-                        z_bool = true;
-                    } else {
-                        z_bool = false;
-                    }
-
-                    self.PS.set_bit(1, z_bool);
-
-                    let n = (value & Flags::NegativeFlagBit as Byte) != 0;
-                    self.PS.set_bit(7, n);
-
-                    let v = (value & Flags::OverflowFlagBit as Byte) != 0;
-                    self.PS.set_bit(6, v);
-                }
-
-                0x2C => {
-                    println!("Instruction BIT ABS");
-                    let abs_addrress: Word = self.fetch_word(cycles, memory);
-                    let value = self.read_byte(cycles, abs_addrress as Word, memory);
-
-                    let z = self.A & value;
-                    let z_bool: bool;
-                    if z == 0 {
-                        z_bool = true;
-                    } else {
-                        z_bool = false;
-                    }
-
-                    self.PS.set_bit(1, z_bool);
-
-                    let n = (value & Flags::NegativeFlagBit as Byte) != 0;
-                    self.PS.set_bit(7, n);
-
-                    let v = (value & Flags::OverflowFlagBit as Byte) != 0;
-                    self.PS.set_bit(6, v);
-                }
-
-                0xAA => {
-                    println!("Instruction TAX");
-                    self.X = self.A;
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.X);
-                }
-
-                0xA8 => {
-                    println!("Instruction TAY");
-                    self.Y = self.A;
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.Y);
-                }
-
-                0x8A => {
-                    println!("Instruction TXA");
-                    self.A = self.X;
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.A);
-                }
-
-                0x98 => {
-                    println!("Instruction TYA");
-                    self.A = self.Y;
-                    *cycles -= 1;
-                    self.ld_register_set_status(self.A);
-                }
-
-                0xE6 => {
-                    println!("Instruction Increment Memory ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    value += 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xF6 => {
-                    println!("Instruction Increment Memory ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    value += 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xEE => {
-                    println!("Instruction Increment Memory Absolute");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let mut value = self.read_byte(cycles, abs_address as Word, memory);
-                    value += 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xFE => {
-                    println!("Instruction Increment Memory Absolute X");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x = abs_address + self.X as Word;
-                    let mut value = self.read_byte(cycles, abs_address_plus_x, memory);
-                    value += 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address_plus_x.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xC6 => {
-                    println!("Instruction Decrement Memory ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    value -= 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xD6 => {
-                    println!("Instruction Decrement Memory ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    value -= 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xCE => {
-                    println!("Instruction Decrement Memory Absolute");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let mut value = self.read_byte(cycles, abs_address as Word, memory);
-                    value -= 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xDE => {
-                    println!("Instruction Decrement Memory Absolute X");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x = abs_address + self.X as Word;
-                    let mut value = self.read_byte(cycles, abs_address_plus_x, memory);
-                    value -= 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address_plus_x.into(), memory);
-                    self.ld_register_set_status(value);
-                }
-
-                0xE8 => {
-                    println!("Instruction Inc X");
-                    self.X = self.X.wrapping_add(1);
-                    self.ld_register_set_status(self.X);
-                    *cycles -= 1;
-                }
-
-                0xC8 => {
-                    println!("Instruction Inc Y");
-                    self.Y = self.Y.wrapping_add(1);
-                    self.ld_register_set_status(self.Y);
-                    *cycles -= 1;
-                }
-
-                0xCA => {
-                    println!("Instruction Dec X");
-                    self.X = self.X.wrapping_sub(1);
-                    self.ld_register_set_status(self.X);
-                    *cycles -= 1;
-                }
-
-                0x88 => {
-                    println!("Instruction Dec Y");
-                    self.Y = self.Y.wrapping_sub(1);
-                    self.ld_register_set_status(self.Y);
-                    *cycles -= 1;
-                }
-
-                // Branches
-                0x90 => {
-                    println!("Instruction BCC");
-                    self.branch_if(cycles, memory, self.PS.get_bit(0), false);
-                }
-
-                0xB0 => {
-                    println!("Instruction BCS");
-                    self.branch_if(cycles, memory, self.PS.get_bit(0), true);
-                }
-
-                0xF0 => {
-                    println!("Instruction BEQ");
-                    self.branch_if(cycles, memory, self.PS.get_bit(1), true);
-                }
-
-                0xD0 => {
-                    println!("Instruction BNE");
-                    self.branch_if(cycles, memory, self.PS.get_bit(1), false);
-                }
-
-                0x30 => {
-                    println!("Instruction BMI");
-                    self.branch_if(cycles, memory, self.PS.get_bit(7), true);
-                }
-
-                0x10 => {
-                    println!("Instruction BPL");
-                    self.branch_if(cycles, memory, self.PS.get_bit(7), false);
-                }
-
-                0x50 => {
-                    println!("Instruction BVC");
-                    self.branch_if(cycles, memory, self.PS.get_bit(6), false);
-                }
-
-                0x70 => {
-                    println!("Instruction BVS");
-                    self.branch_if(cycles, memory, self.PS.get_bit(6), true);
-                }
+    fn operand_address(operand: Operand) -> Word {
+        match operand {
+            Operand::Address(address) | Operand::ZeroPageBranch(address, _) => address,
+            _ => 0,
+        }
+    }
 
-                // Status Flags Changes
-                0x18 => {
-                    println!("Instruction CLC");
-                    self.PS.set_bit(0, false);
-                    *cycles -= 1;
-                }
+    // ARR: AND, then ROR A. C comes from bit 6 of the result and V from
+    // bit 6 XOR bit 5. In decimal mode N, Z and V are computed like in
+    // binary mode but the result gets a BCD fix up that also sets C
+    // ("No More Secrets", NMOS 6510 Unintended Opcodes)
+    fn arr(&mut self, operand: Byte) {
+        let value: Byte = self.a & operand;
+        let carry_in: Byte = if self.ps.get_bit(0) { 0x80 } else { 0 };
+        let mut result: Byte = (value >> 1) | carry_in;
+        self.ld_register_set_status(result);
+
+        if !self.ps.get_bit(3) {
+            // D
+            self.ps.set_bit(0, result.get_bit(6)); // C
+            self.ps.set_bit(6, result.get_bit(6) ^ result.get_bit(5)); // V
+        } else {
+            self.ps.set_bit(6, ((value ^ result) & 0x40) != 0); // V
 
-                0xD8 => {
-                    println!("Instruction CLD");
-                    self.PS.set_bit(3, false);
-                    *cycles -= 1;
-                }
+            let lo_nibble: Byte = value & 0x0F;
+            if lo_nibble + (lo_nibble & 1) > 5 {
+                result = (result & 0xF0) | (result.wrapping_add(6) & 0x0F);
+            }
 
-                0x58 => {
-                    println!("Instruction CLI");
-                    self.PS.set_bit(2, false);
-                    *cycles -= 1;
-                }
+            let hi_nibble: Byte = value >> 4;
+            let c: bool = hi_nibble + (hi_nibble & 1) > 5;
+            if c {
+                result = result.wrapping_add(0x60);
+            }
+            self.ps.set_bit(0, c); // C
+        }
 
-                0xB8 => {
-                    println!("Instruction CLV");
-                    self.PS.set_bit(6, false);
-                    *cycles -= 1;
-                }
+        self.a = result;
+    }
 
-                0x38 => {
-                    println!("Instruction SEC");
-                    self.PS.set_bit(0, true);
-                    *cycles -= 1;
-                }
+    // SHA, SHX, SHY and TAS store the value ANDed with the high byte of the
+    // base address + 1. When indexing crosses a page, the high byte of the
+    // address is replaced by the stored value. That is what most NMOS chips
+    // do, as long as no DMA steals a cycle in the middle of the instruction
+    fn store_and_high_byte<M: Bus>(
+        &mut self,
+        value: Byte,
+        effective_address: Word,
+        index: Byte,
+        memory: &mut M,
+    ) {
+        let address: Word = effective_address.wrapping_sub(index as Word);
+        let result: Byte = value & ((address >> 8) as Byte).wrapping_add(1);
+        let mut effective_address: Word = effective_address;
+        if effective_address >> 8 != address >> 8 {
+            effective_address = ((result as Word) << 8) | (effective_address & 0xFF);
+        }
+        memory.write(effective_address, result);
+    }
 
-                0xF8 => {
-                    println!("Instruction SED");
-                    self.PS.set_bit(3, true);
-                    *cycles -= 1;
-                }
+    fn add_with_carry(&mut self, operand: Byte) {
+        if self.ps.get_bit(3) {
+            // D
+            self.add_with_carry_decimal(operand);
+            return;
+        }
 
-                0x78 => {
-                    println!("Instruction SEI");
-                    self.PS.set_bit(2, true);
-                    *cycles -= 1;
-                }
+        let c_flag_value: Word = if self.ps.get_bit(0) { 1 } else { 0 };
+        let sum: Word = self.a as Word + operand as Word + c_flag_value;
+        let result: Byte = (sum & 0xFF) as Byte;
 
-                // Arithmetic
+        // the sign of the result is wrong when both operands have the same sign
+        // and the result has a different one
+        let v: bool =
+            ((self.a ^ result) & (operand ^ result) & Flags::NegativeFlagBit as Byte) != 0;
 
-                // ADC
-                0x6D => {
-                    println!("Instruction ADC Absolute");
-                    let operand: Byte = self.absolute_address(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+        self.a = result;
+        self.ps.set_bit(0, sum > 0xFF); // C
+        self.ps.set_bit(6, v); // V
+        self.ld_register_set_status(self.a);
+    }
 
-                0x7D => {
-                    println!("Instruction ADC Absolute X");
-                    let operand: Byte = self.absolute_address_x(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+    fn subtract_with_carry(&mut self, operand: Byte) {
+        if self.ps.get_bit(3) {
+            // D
+            self.subtract_with_carry_decimal(operand);
+        } else {
+            self.add_with_carry(!operand);
+        }
+    }
 
-                0x79 => {
-                    println!("Instruction ADC Absolute Y");
-                    let operand: Byte = self.absolute_address_y(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+    // NMOS 6502 decimal mode ADC, as described in
+    // http://www.6502.org/tutorials/decimal_mode.html (Appendix A)
+    // A and C hold the BCD result, but Z is taken from the binary sum and
+    // N & V from the intermediate result before the high nibble is adjusted
+    fn add_with_carry_decimal(&mut self, operand: Byte) {
+        let c_flag_value: i16 = if self.ps.get_bit(0) { 1 } else { 0 };
+        let binary_sum: Word = self.a as Word + operand as Word + c_flag_value as Word;
+
+        let mut lo_nibble: i16 = (self.a & 0x0F) as i16 + (operand & 0x0F) as i16 + c_flag_value;
+        if lo_nibble >= 0x0A {
+            lo_nibble = ((lo_nibble + 0x06) & 0x0F) + 0x10;
+        }
 
-                0x69 => {
-                    println!("Instruction ADC Inmediate");
-                    let operand: Byte = self.fetch_byte(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+        let mut sum: i16 = (self.a & 0xF0) as i16 + (operand & 0xF0) as i16 + lo_nibble;
+        let signed_sum: i16 =
+            (self.a & 0xF0) as i8 as i16 + (operand & 0xF0) as i8 as i16 + lo_nibble;
 
-                0x65 => {
-                    println!("Instruction ADC Zero Page");
-                    let operand: Byte = self.zero_page_address(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+        self.ps.set_bit(7, (sum & 0x80) != 0); // N
+        self.ps.set_bit(6, !(-128..=127).contains(&signed_sum)); // V
+        self.ps.set_bit(1, (binary_sum & 0xFF) == 0); // Z
 
-                0x75 => {
-                    println!("Instruction ADC Zero Page X");
-                    let operand: Byte = self.zero_page_address_x(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+        if sum >= 0xA0 {
+            sum += 0x60;
+        }
 
-                0x61 => {
-                    println!("Instruction ADC Indirect X");
-                    let operand: Byte = self.indirect_address_x(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+        self.a = (sum & 0xFF) as Byte;
+        self.ps.set_bit(0, sum >= 0x100); // C
+        if self.variant.is_cmos() {
+            // the 65C02 spends a cycle to get N & Z from the BCD result
+            self.ld_register_set_status(self.a);
+        }
+    }
 
-                0x71 => {
-                    println!("Instruction ADC Indirect Y");
-                    let operand: Byte = self.indirect_address_y(cycles, memory);
-                    self.add_with_carry(operand);
-                }
+    // NMOS 6502 decimal mode SBC, as described in
+    // http://www.6502.org/tutorials/decimal_mode.html (Appendix A)
+    // only A is decimal adjusted, every flag is the same as in binary mode
+    fn subtract_with_carry_decimal(&mut self, operand: Byte) {
+        let c_flag_value: i16 = if self.ps.get_bit(0) { 1 } else { 0 };
+        let binary_difference: i16 = self.a as i16 - operand as i16 - (1 - c_flag_value);
+        let binary_result: Byte = (binary_difference & 0xFF) as Byte;
 
-                // SBC
-                0xED => {
-                    println!("Instruction SBC Absolute");
-                    let operand: Byte = self.absolute_address(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+        let v: bool =
+            ((self.a ^ operand) & (self.a ^ binary_result) & Flags::NegativeFlagBit as Byte) != 0;
 
-                0xFD => {
-                    println!("Instruction SBC Absolute X");
-                    let operand: Byte = self.absolute_address_x(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+        let mut lo_nibble: i16 =
+            (self.a & 0x0F) as i16 - (operand & 0x0F) as i16 + c_flag_value - 1;
+        if lo_nibble < 0 {
+            lo_nibble = ((lo_nibble - 0x06) & 0x0F) - 0x10;
+        }
 
-                0xF9 => {
-                    println!("Instruction SBC Absolute Y");
-                    let operand: Byte = self.absolute_address_y(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+        let mut difference: i16 = (self.a & 0xF0) as i16 - (operand & 0xF0) as i16 + lo_nibble;
+        if difference < 0 {
+            difference -= 0x60;
+        }
 
-                0xE9 => {
-                    println!("Instruction SBC Inmediate");
-                    let operand: Byte = self.fetch_byte(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+        if self.variant.is_cmos() {
+            // the 65C02 adjusts the binary difference instead (Appendix B),
+            // N & Z come from the BCD result
+            difference = binary_difference;
+            if binary_difference < 0 {
+                difference -= 0x60;
+            }
+            if lo_nibble < 0 {
+                difference -= 0x06;
+            }
+        }
 
-                0xE5 => {
-                    println!("Instruction SBC Zero Page");
-                    let operand: Byte = self.zero_page_address(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+        self.a = (difference & 0xFF) as Byte;
+        self.ps.set_bit(0, binary_difference >= 0); // C
+        self.ps.set_bit(6, v); // V
+        if self.variant.is_cmos() {
+            self.ld_register_set_status(self.a);
+        } else {
+            self.ld_register_set_status(binary_result);
+        }
+    }
 
-                0xF5 => {
-                    println!("Instruction SBC Zero Page X");
-                    let operand: Byte = self.zero_page_address_x(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+    fn shift_left(&mut self, operand: Byte) -> Byte {
+        self.ps.set_bit(0, operand.get_bit(7)); // C
+        operand << 1
+    }
 
-                0xE1 => {
-                    println!("Instruction SBC Indirect X");
-                    let operand: Byte = self.indirect_address_x(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+    fn shift_right(&mut self, operand: Byte) -> Byte {
+        self.ps.set_bit(0, operand.get_bit(0)); // C
+        operand >> 1
+    }
 
-                0xF1 => {
-                    println!("Instruction SBC Indirect Y");
-                    let operand: Byte = self.indirect_address_y(cycles, memory);
-                    self.add_with_carry(!operand);
-                }
+    fn rotate_left(&mut self, mut operand: Byte) -> Byte {
+        let new_bit_0: Byte = if self.ps.get_bit(0) {
+            Flags::CarryFlagBit as Byte
+        } else {
+            0
+        };
+        let c: bool = (operand & Flags::NegativeFlagBit as Byte) > 0;
+        self.ps.set_bit(0, c);
+        operand <<= 1;
+        operand |= new_bit_0;
 
-                // CMP
-                0xC9 => {
-                    println!("Instruction CMP Inmediate");
-                    let operand: Byte = self.fetch_byte(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+        operand
+    }
 
-                0xC5 => {
-                    println!("Instruction CMP ZP");
-                    let operand: Byte = self.zero_page_address(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+    fn rotate_right(&mut self, mut operand: Byte) -> Byte {
+        let old_bit_0: bool = (operand & Flags::CarryFlagBit as Byte) > 0;
+        operand >>= 1;
+        if self.ps.get_bit(0) {
+            // C
+            operand |= Flags::NegativeFlagBit as Byte;
+        }
+        self.ps.set_bit(0, old_bit_0); // C
 
-                0xD5 => {
-                    println!("Instruction CMP ZPX");
-                    let operand: Byte = self.zero_page_address_x(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+        operand
+    }
 
-                0xCD => {
-                    println!("Instruction CMP ABS");
-                    let operand: Byte = self.absolute_address(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+    fn compare(&mut self, register: Byte, operand: Byte) {
+        let temp: Byte = register.wrapping_sub(operand);
+        self.cmp_register_set_status(operand, temp, register);
+    }
 
-                0xDD => {
-                    println!("Instruction CMP ABS X");
-                    let operand: Byte = self.absolute_address_x(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+    // returns the extra cycles of a taken branch
+    fn branch_if(
+        &mut self,
+        target: Word,
+        page_crossed: bool,
+        value: bool,
+        condition: bool,
+    ) -> isize {
+        if value != condition {
+            return 0;
+        }
 
-                0xD9 => {
-                    println!("Instruction CMP ABS Y");
-                    let operand: Byte = self.absolute_address_y(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+        self.pc = target;
+        // a taken branch costs one more cycle, and another one when the
+        // target is in a different page
+        if page_crossed {
+            2
+        } else {
+            1
+        }
+    }
 
-                0xC1 => {
-                    println!("Instruction CMP IND X");
-                    let operand: Byte = self.indirect_address_x(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+    fn bit_test(&mut self, value: Byte) {
+        self.ps.set_bit(1, self.a & value == 0); // Z
+        self.ps.set_bit(7, value.get_bit(7)); // N
+        self.ps.set_bit(6, value.get_bit(6)); // V
+    }
 
-                0xD1 => {
-                    println!("Instruction CMP IND Y");
-                    let operand: Byte = self.indirect_address_y(cycles, memory);
-                    let temp: Byte = self.A.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.A);
-                }
+    /// execute a single instruction (or interrupt sequence), returns the
+    /// cycles it took
+    pub fn step<M: Bus>(&mut self, memory: &mut M) -> isize {
+        self.execute(&mut 1, memory)
+    }
 
-                // CPX
-                0xE0 => {
-                    println!("Instruction CPX Inmediate");
-                    let operand: Byte = self.fetch_byte(cycles, memory);
-                    let temp: Byte = self.X.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.X);
-                }
+    /// Executes instructions until cycles is spent, the last one can take
+    /// it below 0. Returns the cycles executed
+    pub fn execute<M: Bus>(&mut self, cycles: &mut isize, memory: &mut M) -> isize {
+        self.execute_traced(cycles, memory, &mut NoTrace)
+    }
 
-                0xE4 => {
-                    println!("Instruction CPX ZP");
-                    let operand: Byte = self.zero_page_address(cycles, memory);
-                    let temp: Byte = self.X.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.X);
-                }
+    /// The same, handing every instruction to the trace sink before it runs
+    pub fn execute_traced<M: Bus, T: TraceSink>(
+        &mut self,
+        cycles: &mut isize,
+        memory: &mut M,
+        trace: &mut T,
+    ) -> isize {
+        let cycles_requested = *cycles;
+        while cycles > &mut 0 {
+            if self.jammed {
+                // only a reset gets the CPU out of a JAM
+                memory.tick(*cycles as u64);
+                *cycles = 0;
+                break;
+            }
 
-                0xEC => {
-                    println!("Instruction CPX ABS");
-                    let operand: Byte = self.absolute_address(cycles, memory);
-                    let temp: Byte = self.X.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.X);
+            if self.waiting {
+                // WAI sleeps until an IRQ or NMI, even when the I flag is set
+                self.poll_nmi(memory);
+                if !self.nmi_pending && !self.irq_line && !memory.irq() {
+                    memory.tick(*cycles as u64);
+                    *cycles = 0;
+                    break;
                 }
+                self.waiting = false;
+            }
 
-                // CPY
-                0xC0 => {
-                    println!("Instruction CPY Inmediate");
-                    let operand: Byte = self.fetch_byte(cycles, memory);
-                    let temp: Byte = self.Y.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.Y);
-                }
+            let before: isize = *cycles;
+            if self.service_interrupts(cycles, memory) {
+                memory.tick((before - *cycles) as u64);
+                continue;
+            }
 
-                0xC4 => {
-                    println!("Instruction CPY ZP");
-                    let operand: Byte = self.zero_page_address(cycles, memory);
-                    let temp: Byte = self.Y.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.Y);
-                }
+            if trace.enabled() {
+                let executed: u64 = (cycles_requested - *cycles) as u64;
+                trace.trace(&self.trace_record(self.total_cycles + executed, memory));
+            }
 
-                0xCC => {
-                    println!("Instruction CPY ABS");
-                    let operand: Byte = self.absolute_address(cycles, memory);
-                    let temp: Byte = self.Y.wrapping_sub(operand);
-                    self.cmp_register_set_status(operand, temp, self.Y);
-                }
+            let pc: Word = self.pc;
+            let ins: Byte = self.fetch_byte(memory);
+            trace.instruction(pc, ins);
+            let opcode = opcode_table(self.variant)[ins as usize];
 
-                // ASL
-                0x0A => {
-                    println!("Instruction ASL ACC");
-                    self.PS.set_bit(0, self.A.get_bit(7));
-                    self.A = self.A << 1;
-                    self.ld_register_set_status(self.A);
-                    *cycles -= 1;
-                }
+            let (operand, page_crossed) = self.fetch_operand(opcode.mode, memory);
+            *cycles -= opcode.cycles as isize;
+            if page_crossed && opcode.page_penalty {
+                *cycles -= 1;
+            }
+            *cycles -= self.execute_instruction(opcode.mnemonic, operand, page_crossed, memory);
+            memory.tick((before - *cycles) as u64);
+        }
 
-                0x06 => {
-                    println!("Instruction ASL ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(7));
-                    value = value << 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
+        let executed: isize = cycles_requested - *cycles;
+        self.total_cycles += executed as u64;
+        executed
+    }
 
-                0x16 => {
-                    println!("Instruction ASL ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(7));
-                    value = value << 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
+    // The instruction at PC and the registers before it runs, the bytes are
+    // read with peek() so tracing doesn't disturb the devices on the bus
+    fn trace_record<M: Bus>(&self, cycles: u64, memory: &M) -> TraceRecord {
+        let opcode = opcode_table(self.variant)[memory.peek(self.pc) as usize];
+        let length: usize = 1 + opcode.mode.operand_bytes();
+        let mut bytes: [Byte; 3] = [0; 3];
+        for (i, byte) in bytes.iter_mut().enumerate().take(length) {
+            *byte = memory.peek(self.pc.wrapping_add(i as Word));
+        }
 
-                0x0E => {
-                    println!("Instruction ASL ABS");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let mut value = self.read_byte(cycles, abs_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(7));
-                    value = value << 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
+        TraceRecord {
+            pc: self.pc,
+            bytes,
+            length,
+            mnemonic: opcode.mnemonic.name(),
+            mode: opcode.mode,
+            documented: opcode.documented,
+            a: self.a,
+            x: self.x,
+            y: self.y,
+            sp: self.sp,
+            ps: self.ps,
+            cycles,
+        }
+    }
 
-                0x1E => {
-                    println!("Instruction ASL ABSX");
-                    let mut abs_address_x: Word = self.fetch_word(cycles, memory);
-                    abs_address_x = abs_address_x.wrapping_add(self.X as Word);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, abs_address_x as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(7));
-                    value = value << 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address_x.into(), memory);
-                    self.ld_register_set_status(value);
-                }
+    // Runs the decoded instruction, returns the cycles it takes on top of
+    // the ones in the decode table
+    fn execute_instruction<M: Bus>(
+        &mut self,
+        mnemonic: Mnemonic,
+        operand: Operand,
+        page_crossed: bool,
+        memory: &mut M,
+    ) -> isize {
+        use Mnemonic::*;
+
+        match mnemonic {
+            // Load / Store Operations
+            Lda => {
+                self.a = self.read_operand(operand, memory);
+                self.ld_register_set_status(self.a);
+            }
+            Ldx => {
+                self.x = self.read_operand(operand, memory);
+                self.ld_register_set_status(self.x);
+            }
+            Ldy => {
+                self.y = self.read_operand(operand, memory);
+                self.ld_register_set_status(self.y);
+            }
+            Sta => self.write_operand(operand, self.a, memory),
+            Stx => self.write_operand(operand, self.x, memory),
+            Sty => self.write_operand(operand, self.y, memory),
+            Stz => self.write_operand(operand, 0, memory),
 
-                // LSR
-                0x4A => {
-                    println!("Instruction LSR ACC");
-                    self.PS.set_bit(0, self.A.get_bit(0));
-                    self.A = self.A >> 1;
-                    self.ld_register_set_status(self.A);
-                    *cycles -= 1;
-                }
+            // Register Transfers
+            Tax => {
+                self.x = self.a;
+                self.ld_register_set_status(self.x);
+            }
+            Tay => {
+                self.y = self.a;
+                self.ld_register_set_status(self.y);
+            }
+            Txa => {
+                self.a = self.x;
+                self.ld_register_set_status(self.a);
+            }
+            Tya => {
+                self.a = self.y;
+                self.ld_register_set_status(self.a);
+            }
 
-                0x46 => {
-                    println!("Instruction LSR ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(0));
-                    value = value >> 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
-                }
+            // Stack Operations
+            Tsx => {
+                self.x = self.sp;
+                self.ld_register_set_status(self.x);
+            }
+            Txs => self.sp = self.x,
+            Pha => self.push_byte_to_stack(memory, self.a),
+            Phx => self.push_byte_to_stack(memory, self.x),
+            Phy => self.push_byte_to_stack(memory, self.y),
+            Php => self.push_ps_to_stack(memory),
+            Pla => {
+                self.a = self.pop_byte_from_stack(memory);
+                self.ld_register_set_status(self.a);
+            }
+            Plx => {
+                self.x = self.pop_byte_from_stack(memory);
+                self.ld_register_set_status(self.x);
+            }
+            Ply => {
+                self.y = self.pop_byte_from_stack(memory);
+                self.ld_register_set_status(self.y);
+            }
+            Plp => self.pop_ps_from_stack(memory),
 
-                0x56 => {
-                    println!("Instruction LSR ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(0));
-                    value = value >> 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(value);
+            // Logical Operations
+            And => {
+                self.a &= self.read_operand(operand, memory);
+                self.ld_register_set_status(self.a);
+            }
+            Eor => {
+                self.a ^= self.read_operand(operand, memory);
+                self.ld_register_set_status(self.a);
+            }
+            Ora => {
+                self.a |= self.read_operand(operand, memory);
+                self.ld_register_set_status(self.a);
+            }
+            Bit => {
+                let value: Byte = self.read_operand(operand, memory);
+                if let Operand::Immediate(_) = operand {
+                    // the 65C02 immediate mode only changes Z
+                    self.ps.set_bit(1, self.a & value == 0); // Z
+                } else {
+                    self.bit_test(value);
                 }
+            }
 
-                0x4E => {
-                    println!("Instruction LSR ABS");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let mut value = self.read_byte(cycles, abs_address as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(0));
-                    value = value >> 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(value);
+            // Arithmetic
+            Adc | Sbc => {
+                let value: Byte = self.read_operand(operand, memory);
+                if mnemonic == Adc {
+                    self.add_with_carry(value);
+                } else {
+                    self.subtract_with_carry(value);
                 }
-
-                0x5E => {
-                    println!("Instruction LSR ABSX");
-                    let mut abs_address_x: Word = self.fetch_word(cycles, memory);
-                    abs_address_x = abs_address_x.wrapping_add(self.X as Word);
-                    *cycles -= 1;
-                    let mut value = self.read_byte(cycles, abs_address_x as Word, memory);
-                    self.PS.set_bit(0, value.get_bit(0));
-                    value = value >> 1;
-                    *cycles -= 1;
-                    self.write_byte(value, cycles, abs_address_x.into(), memory);
-                    self.ld_register_set_status(value);
+                if self.variant.is_cmos() && self.ps.get_bit(3) {
+                    // D: valid N & Z flags cost one more cycle in decimal mode
+                    return 1;
                 }
+            }
+            Cmp => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.compare(self.a, value);
+            }
+            Cpx => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.compare(self.x, value);
+            }
+            Cpy => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.compare(self.y, value);
+            }
 
-                // ROL
-                0x2A => {
-                    println!("Instruction ROL ACC");
-                    self.A = self.rotate_left(cycles, self.A);
-                    self.ld_register_set_status(self.A);
-                }
+            // Increments & Decrements
+            Inc => {
+                let value: Byte = self.read_operand(operand, memory).wrapping_add(1);
+                self.write_operand(operand, value, memory);
+                self.ld_register_set_status(value);
+            }
+            Dec => {
+                let value: Byte = self.read_operand(operand, memory).wrapping_sub(1);
+                self.write_operand(operand, value, memory);
+                self.ld_register_set_status(value);
+            }
+            Inx => {
+                self.x = self.x.wrapping_add(1);
+                self.ld_register_set_status(self.x);
+            }
+            Iny => {
+                self.y = self.y.wrapping_add(1);
+                self.ld_register_set_status(self.y);
+            }
+            Dex => {
+                self.x = self.x.wrapping_sub(1);
+                self.ld_register_set_status(self.x);
+            }
+            Dey => {
+                self.y = self.y.wrapping_sub(1);
+                self.ld_register_set_status(self.y);
+            }
 
-                0x26 => {
-                    println!("Instruction ROL ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    let result: Byte = self.rotate_left(cycles, value);
-                    self.write_byte(result, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Shifts
+            Asl | Lsr | Rol | Ror => {
+                let value: Byte = self.read_operand(operand, memory);
+                let result: Byte = match mnemonic {
+                    Asl => self.shift_left(value),
+                    Lsr => self.shift_right(value),
+                    Rol => self.rotate_left(value),
+                    _ => self.rotate_right(value),
+                };
+                self.write_operand(operand, result, memory);
+                self.ld_register_set_status(result);
+            }
 
-                0x36 => {
-                    println!("Instruction ROL ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    let result: Byte = self.rotate_left(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Jumps & Calls
+            Jmp => self.pc = CPU::operand_address(operand),
+            Jsr => {
+                self.push_word_to_stack(memory, self.pc);
+                self.pc = CPU::operand_address(operand);
+            }
+            Rts => self.pc = self.pop_word_from_stack(memory),
 
-                0x2E => {
-                    println!("Instruction ROL ABS");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let value = self.read_byte(cycles, abs_address as Word, memory);
-                    let result: Byte = self.rotate_left(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Branches
+            Bcc => return self.branch_on(operand, page_crossed, self.ps.get_bit(0), false),
+            Bcs => return self.branch_on(operand, page_crossed, self.ps.get_bit(0), true),
+            Beq => return self.branch_on(operand, page_crossed, self.ps.get_bit(1), true),
+            Bne => return self.branch_on(operand, page_crossed, self.ps.get_bit(1), false),
+            Bmi => return self.branch_on(operand, page_crossed, self.ps.get_bit(7), true),
+            Bpl => return self.branch_on(operand, page_crossed, self.ps.get_bit(7), false),
+            Bvc => return self.branch_on(operand, page_crossed, self.ps.get_bit(6), false),
+            Bvs => return self.branch_on(operand, page_crossed, self.ps.get_bit(6), true),
+            Bra => return self.branch_on(operand, page_crossed, true, true),
+
+            // Status Flag Changes
+            Clc => {
+                self.ps.set_bit(0, false);
+            }
+            Cld => {
+                self.ps.set_bit(3, false);
+            }
+            Cli => {
+                self.ps.set_bit(2, false);
+            }
+            Clv => {
+                self.ps.set_bit(6, false);
+            }
+            Sec => {
+                self.ps.set_bit(0, true);
+            }
+            Sed => {
+                self.ps.set_bit(3, true);
+            }
+            Sei => {
+                self.ps.set_bit(2, true);
+            }
 
-                0x3E => {
-                    println!("Instruction ROL ABSX");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x = abs_address.wrapping_add(self.X as Word);
-                    let value = self.read_byte(cycles, abs_address_plus_x, memory);
-                    let result: Byte = self.rotate_left(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, abs_address_plus_x.into(), memory);
-                    self.ld_register_set_status(result);
+            // System Functions
+            Brk => {
+                self.push_word_to_stack(memory, self.pc.wrapping_add(1));
+                self.push_ps_to_stack(memory);
+                let interrupt_vector: Word = self.interrupt_vector(memory, 0xFFFE);
+                self.pc = self.read_word(interrupt_vector, memory);
+                self.ps.set_bit(4, true); // B
+                self.ps.set_bit(2, true); // I
+                if self.variant.is_cmos() {
+                    self.ps.set_bit(3, false); // D
                 }
+            }
+            // NOPs read their operand and throw it away
+            Nop => {
+                self.read_operand(operand, memory);
+            }
+            Rti => {
+                self.pop_ps_from_stack(memory);
+                self.pc = self.pop_word_from_stack(memory);
+            }
 
-                // ROR
-                0x6A => {
-                    println!("Instruction ROR ACC");
-                    self.A = self.rotate_right(cycles, self.A);
-                    self.ld_register_set_status(self.A);
-                }
+            // Undocumented Instructions
 
-                0x66 => {
-                    println!("Instruction ROR ZP");
-                    let zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    let value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    let result: Byte = self.rotate_right(cycles, value);
-                    self.write_byte(result, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Read-modify-write combinations
+            Slo => {
+                let value: Byte = self.read_operand(operand, memory);
+                let result: Byte = self.shift_left(value);
+                self.write_operand(operand, result, memory);
+                self.a |= result;
+                self.ld_register_set_status(self.a);
+            }
+            Rla => {
+                let value: Byte = self.read_operand(operand, memory);
+                let result: Byte = self.rotate_left(value);
+                self.write_operand(operand, result, memory);
+                self.a &= result;
+                self.ld_register_set_status(self.a);
+            }
+            Sre => {
+                let value: Byte = self.read_operand(operand, memory);
+                let result: Byte = self.shift_right(value);
+                self.write_operand(operand, result, memory);
+                self.a ^= result;
+                self.ld_register_set_status(self.a);
+            }
+            Rra => {
+                let value: Byte = self.read_operand(operand, memory);
+                let result: Byte = self.rotate_right(value);
+                self.write_operand(operand, result, memory);
+                self.add_with_carry(result);
+            }
+            Dcp => {
+                let value: Byte = self.read_operand(operand, memory).wrapping_sub(1);
+                self.write_operand(operand, value, memory);
+                self.compare(self.a, value);
+            }
+            Isc => {
+                let value: Byte = self.read_operand(operand, memory).wrapping_add(1);
+                self.write_operand(operand, value, memory);
+                self.subtract_with_carry(value);
+            }
 
-                0x76 => {
-                    println!("Instruction ROR ZPX");
-                    let mut zero_page_address: Byte = self.fetch_byte(cycles, memory);
-                    zero_page_address = zero_page_address.wrapping_add(self.X);
-                    *cycles -= 1;
-                    let value = self.read_byte(cycles, zero_page_address as Word, memory);
-                    let result: Byte = self.rotate_right(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, zero_page_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // SAX stores A AND X, LAX loads A and X with the same value
+            Sax => self.write_operand(operand, self.a & self.x, memory),
+            Lax => {
+                self.a = self.read_operand(operand, memory);
+                self.x = self.a;
+                self.ld_register_set_status(self.a);
+            }
 
-                0x6E => {
-                    println!("Instruction ROR ABS");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let value = self.read_byte(cycles, abs_address as Word, memory);
-                    let result: Byte = self.rotate_right(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, abs_address.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Immediate
+            Lxa => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.a = (self.a | self.unstable.lxa_magic) & value;
+                self.x = self.a;
+                self.ld_register_set_status(self.a);
+            }
+            Ane => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.a = (self.a | self.unstable.ane_magic) & self.x & value;
+                self.ld_register_set_status(self.a);
+            }
+            Anc => {
+                self.a &= self.read_operand(operand, memory);
+                self.ld_register_set_status(self.a);
+                self.ps.set_bit(0, self.a.get_bit(7)); // C
+            }
+            Alr => {
+                self.a &= self.read_operand(operand, memory);
+                self.a = self.shift_right(self.a);
+                self.ld_register_set_status(self.a);
+            }
+            Arr => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.arr(value);
+            }
+            Sbx => {
+                let value: Byte = self.read_operand(operand, memory);
+                let a_and_x: Byte = self.a & self.x;
+                let temp: Byte = a_and_x.wrapping_sub(value);
+                self.cmp_register_set_status(value, temp, a_and_x);
+                self.x = temp;
+                self.ld_register_set_status(self.x);
+            }
 
-                0x7E => {
-                    println!("Instruction ROR ABSX");
-                    let abs_address: Word = self.fetch_word(cycles, memory);
-                    let abs_address_plus_x = abs_address.wrapping_add(self.X as Word);
-                    let value = self.read_byte(cycles, abs_address_plus_x, memory);
-                    let result: Byte = self.rotate_right(cycles, value);
-                    *cycles -= 1;
-                    self.write_byte(result, cycles, abs_address_plus_x.into(), memory);
-                    self.ld_register_set_status(result);
-                }
+            // Stores ANDed with the high byte of the address + 1
+            Sha => {
+                let address: Word = CPU::operand_address(operand);
+                self.store_and_high_byte(self.a & self.x, address, self.y, memory);
+            }
+            Shx => {
+                let address: Word = CPU::operand_address(operand);
+                self.store_and_high_byte(self.x, address, self.y, memory);
+            }
+            Shy => {
+                let address: Word = CPU::operand_address(operand);
+                self.store_and_high_byte(self.y, address, self.x, memory);
+            }
+            Tas => {
+                self.sp = self.a & self.x;
+                let address: Word = CPU::operand_address(operand);
+                self.store_and_high_byte(self.sp, address, self.y, memory);
+            }
+            Las => {
+                let value: Byte = self.read_operand(operand, memory) & self.sp;
+                self.a = value;
+                self.x = value;
+                self.sp = value;
+                self.ld_register_set_status(value);
+            }
 
-                // System Functions
-                0x00 => {
-                    println!("Instruction BRK");
-                    self.push_pc_to_stack_plus_one(cycles, memory);
-                    // self.push_pc_to_stack_plus_two(cycles, memory);
-                    self.push_ps_to_stack(cycles, memory);
-                    let interrupt_vector: Word = 0xFFFE;
-                    self.PC = self.read_word(cycles, interrupt_vector, memory);
-                    self.PS.set_bit(4, true); // B
-                    self.PS.set_bit(2, true); // I
-                }
+            // JAM (also called KIL or HLT)
+            Jam => {
+                // the CPU keeps fetching the same opcode until it's reset
+                self.pc = self.pc.wrapping_sub(1);
+                self.jammed = true;
+            }
 
-                0xEA => {
-                    println!("Instruction NOP");
-                    *cycles -= 1;
-                }
+            // 65C02
+
+            // TSB / TRB: Z tells whether A and the memory have bits in
+            // common, then the bits of A are set (or cleared) in the memory
+            Tsb | Trb => {
+                let value: Byte = self.read_operand(operand, memory);
+                self.ps.set_bit(1, self.a & value == 0); // Z
+                let result: Byte = if mnemonic == Tsb {
+                    value | self.a
+                } else {
+                    value & !self.a
+                };
+                self.write_operand(operand, result, memory);
+            }
 
-                0x40 => {
-                    println!("Instruction RTI");
-                    self.pop_ps_from_stack(cycles, memory);
-                    self.PC = self.pop_word_from_stack(cycles, memory);
-                }
+            // Wait for Interrupt / Stop the Processor
+            Wai => self.waiting = true,
+            Stp => self.jammed = true,
 
-                _ => {
-                    unimplemented!("Instruction not handled {}", ins);
-                }
+            // Rockwell bit instructions
+            Rmb(bit) | Smb(bit) => {
+                let mut value: Byte = self.read_operand(operand, memory);
+                value.set_bit(bit as usize, matches!(mnemonic, Smb(_)));
+                self.write_operand(operand, value, memory);
+            }
+            Bbr(bit) | Bbs(bit) => {
+                let value: Byte = self.read_operand(operand, memory);
+                return self.branch_on(
+                    operand,
+                    page_crossed,
+                    value.get_bit(bit as usize),
+                    matches!(mnemonic, Bbs(_)),
+                );
             }
         }
 
-        cycles_requested - *cycles
+        0
     }
 
-    fn ld_register_set_status(&mut self, x: Byte) {
-        self.PS = match x == 0 {
-            false => *self.PS.set_bit(1, false),
-            true => *self.PS.set_bit(1, true),
+    fn branch_on(
+        &mut self,
+        operand: Operand,
+        page_crossed: bool,
+        value: bool,
+        condition: bool,
+    ) -> isize {
+        let target: Word = match operand {
+            Operand::ZeroPageBranch(_, target) => target,
+            _ => CPU::operand_address(operand),
         };
+        self.branch_if(target, page_crossed, value, condition)
+    }
 
-        self.PS = match (x & 0b10000000) > 0 {
-            false => *self.PS.set_bit(7, false),
-            true => *self.PS.set_bit(7, true),
+    fn ld_register_set_status(&mut self, x: Byte) {
+        self.ps = match x == 0 {
+            false => *self.ps.set_bit(1, false),
+            true => *self.ps.set_bit(1, true),
+        };
+        self.ps = match (x & 0b10000000) > 0 {
+            false => *self.ps.set_bit(7, false),
+            true => *self.ps.set_bit(7, true),
         };
     }
 
     fn cmp_register_set_status(&mut self, operand: Byte, temp: Byte, reg: Byte) {
-        self.PS.set_bit(0, reg >= operand); // C
-        self.PS.set_bit(1, reg == operand); // Z
-        self.PS
+        self.ps.set_bit(0, reg >= operand); // C
+        self.ps.set_bit(1, reg == operand); // Z
+        self.ps
             .set_bit(7, temp & Flags::NegativeFlagBit as Byte != 0); // N
     }
 
@@ -1727,6 +1250,6 @@ impl CPU {
         if value & 0x80 > 0 {
             value |= 0xff00;
         }
-        return value;
+        value
     }
 }
@@ -0,0 +1,337 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::memory_map::RegionKind;
+use crate::trace::{nestest_line, TraceRecord, TraceSink};
+use crate::{Byte, Word};
+use crate::{Mem, CPU};
+
+const INES_MAGIC: [Byte; 4] = *b"NES\x1A";
+const HEADER_SIZE: usize = 16;
+const TRAINER_SIZE: usize = 512;
+const PRG_BANK_SIZE: usize = 0x4000;
+const CHR_BANK_SIZE: usize = 0x2000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum INesError {
+    BadMagic,
+    /// the file is shorter than its header says
+    Truncated { expected: usize, found: usize },
+    UnsupportedMapper(Byte),
+    /// NROM has 16 or 32 KiB of PRG ROM
+    UnsupportedPrgSize(usize),
+}
+
+impl fmt::Display for INesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            INesError::BadMagic => write!(f, "not an iNES file"),
+            INesError::Truncated { expected, found } => write!(
+                f,
+                "the iNES file has {} bytes, its header needs {}",
+                found, expected
+            ),
+            INesError::UnsupportedMapper(mapper) => {
+                write!(f, "mapper {} is not supported, only NROM (0) is", mapper)
+            }
+            INesError::UnsupportedPrgSize(size) => {
+                write!(f, "NROM has 16 or 32 KiB of PRG ROM, not {} bytes", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for INesError {}
+
+/// How the PPU mirrors the name tables, kept for completeness: only the CPU
+/// side of the NES is emulated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+    FourScreen,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct INesRom {
+    pub mapper: Byte,
+    pub mirroring: Mirroring,
+    /// battery backed PRG RAM at $6000-$7FFF
+    pub battery: bool,
+    pub trainer: Option<Vec<Byte>>,
+    pub prg_rom: Vec<Byte>,
+    pub chr_rom: Vec<Byte>,
+}
+
+/// The iNES header (<https://www.nesdev.org/wiki/INES>):
+///  0-3  "NES" $1A
+///  4    PRG ROM size in 16 KiB banks
+///  5    CHR ROM size in 8 KiB banks
+///  6    mirroring, battery, trainer, four screen, mapper low nibble
+///  7    mapper high nibble (NES 2.0 marks itself in bits 2-3)
+/// Old dumps have garbage like "DiskDude!" in bytes 7-15, their byte 7 is
+/// not trusted
+pub fn parse_ines(data: &[Byte]) -> Result<INesRom, INesError> {
+    if data.len() < HEADER_SIZE || data[..4] != INES_MAGIC {
+        return Err(INesError::BadMagic);
+    }
+
+    let flags6: Byte = data[6];
+    let flags7: Byte = data[7];
+    let nes2: bool = flags7 & 0x0C == 0x08;
+    let archaic: bool = !nes2 && data[12..HEADER_SIZE].iter().any(|&b| b != 0);
+    let high_nibble: Byte = if archaic { 0 } else { flags7 & 0xF0 };
+    let mapper: Byte = high_nibble | flags6 >> 4;
+
+    let mirroring = if flags6 & 0x08 != 0 {
+        Mirroring::FourScreen
+    } else if flags6 & 0x01 != 0 {
+        Mirroring::Vertical
+    } else {
+        Mirroring::Horizontal
+    };
+
+    let trainer_size: usize = if flags6 & 0x04 != 0 { TRAINER_SIZE } else { 0 };
+    let prg_size: usize = data[4] as usize * PRG_BANK_SIZE;
+    let chr_size: usize = data[5] as usize * CHR_BANK_SIZE;
+    let expected: usize = HEADER_SIZE + trainer_size + prg_size + chr_size;
+    if data.len() < expected {
+        return Err(INesError::Truncated {
+            expected,
+            found: data.len(),
+        });
+    }
+
+    let prg_start: usize = HEADER_SIZE + trainer_size;
+    let chr_start: usize = prg_start + prg_size;
+    Ok(INesRom {
+        mapper,
+        mirroring,
+        battery: flags6 & 0x02 != 0,
+        trainer: (trainer_size > 0).then(|| data[HEADER_SIZE..prg_start].to_vec()),
+        prg_rom: data[prg_start..chr_start].to_vec(),
+        chr_rom: data[chr_start..expected].to_vec(),
+    })
+}
+
+/// The memory map the CPU of an NROM cartridge sees:
+///  $0000-$07FF  2 KiB of RAM, mirrored up to $1FFF
+///  $2000-$401F  PPU, APU and I/O registers, not emulated (unmapped)
+///  $4020-$5FFF  unmapped
+///  $6000-$7FFF  PRG RAM
+///  $8000-$FFFF  PRG ROM, a 16 KiB ROM shows up twice
+pub fn nrom_mem(rom: &INesRom) -> Result<Mem, INesError> {
+    if rom.mapper != 0 {
+        return Err(INesError::UnsupportedMapper(rom.mapper));
+    }
+    let prg_size: usize = rom.prg_rom.len();
+    if prg_size != PRG_BANK_SIZE && prg_size != 2 * PRG_BANK_SIZE {
+        return Err(INesError::UnsupportedPrgSize(prg_size));
+    }
+
+    let mut mem = Mem::new();
+    mem.data = vec![0; mem.max_mem as usize];
+    mem.data[0x8000..0x8000 + prg_size].copy_from_slice(&rom.prg_rom);
+    if let Some(trainer) = &rom.trainer {
+        mem.data[0x7000..0x7000 + TRAINER_SIZE].copy_from_slice(trainer);
+    }
+
+    mem.map(
+        0x0800,
+        0x1FFF,
+        RegionKind::Mirror {
+            target: 0x0000,
+            size: 0x0800,
+        },
+    );
+    mem.map(0x2000, 0x5FFF, RegionKind::Unmapped);
+    if prg_size == PRG_BANK_SIZE {
+        mem.map(
+            0xC000,
+            0xFFFF,
+            RegionKind::Mirror {
+                target: 0x8000,
+                size: PRG_BANK_SIZE as Word,
+            },
+        );
+    }
+    mem.map(0x8000, 0xFFFF, RegionKind::Rom);
+    // the APU and I/O registers read back as $FF in nestest.log
+    mem.open_bus = 0xFF;
+
+    Ok(mem)
+}
+
+/// nestest runs without a PPU when it starts at $C000 ("automation mode").
+/// The registers are the ones after the reset sequence of the reference log
+pub fn start_automation(cpu: &mut CPU) {
+    cpu.set_pc(0xC000);
+    cpu.set_sp(0xFD);
+    cpu.set_status(0x24);
+    cpu.set_a(0);
+    cpu.set_x(0);
+    cpu.set_y(0);
+    cpu.total_cycles = 7;
+}
+
+// The columns of a nestest.log line that are compared. The PPU column and
+// the `= value` annotations of the disassembly are ignored, this emulator
+// doesn't write them
+#[derive(Debug, Clone, PartialEq)]
+struct LogFields<'a> {
+    pc: &'a str,
+    bytes: &'a str,
+    // with the `*` of the undocumented opcodes
+    mnemonic: &'a str,
+    registers: &'a str,
+    cycles: Option<&'a str>,
+}
+
+fn log_fields(line: &str) -> Option<LogFields<'_>> {
+    let registers_at: usize = line.find("A:")?;
+    // in the old logs CYC is the PPU dot followed by the scanline (SL:),
+    // only the CPU cycles of the newer ones can be compared
+    let cycles = line
+        .find("CYC:")
+        .filter(|_| !line.contains("SL:"))
+        .map(|at| line[at + 4..].split_whitespace().next().unwrap_or(""));
+
+    Some(LogFields {
+        pc: line.get(0..4)?,
+        bytes: line.get(6..14)?.trim(),
+        mnemonic: line.get(15..registers_at)?.split_whitespace().next()?,
+        registers: line.get(registers_at..registers_at + 25)?,
+        cycles,
+    })
+}
+
+/// Where our trace and the reference log part ways
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// counted from 1
+    pub line: usize,
+    pub expected: String,
+    pub actual: String,
+    /// the matching lines right before it
+    pub context: Vec<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "the trace differs from the log at line {}", self.line)?;
+        for line in &self.context {
+            writeln!(f, "           {}", line)?;
+        }
+        writeln!(f, "expected:  {}", self.expected)?;
+        write!(f, "actual:    {}", self.actual)
+    }
+}
+
+/// A trace sink that checks every instruction against the next line of a
+/// reference log, and remembers the first one that doesn't match
+pub struct LogComparison<'a> {
+    expected: std::iter::Enumerate<std::str::Lines<'a>>,
+    context: VecDeque<String>,
+    context_lines: usize,
+    pub matched: usize,
+    pub divergence: Option<Divergence>,
+    /// the log has no more lines
+    pub finished: bool,
+}
+
+impl<'a> LogComparison<'a> {
+    pub fn new(log: &'a str, context_lines: usize) -> Self {
+        LogComparison {
+            expected: log.lines().enumerate(),
+            context: VecDeque::new(),
+            context_lines,
+            matched: 0,
+            divergence: None,
+            finished: false,
+        }
+    }
+
+    pub fn compare(&mut self, actual: &str) {
+        if self.divergence.is_some() || self.finished {
+            return;
+        }
+
+        let (number, expected) = match self.expected.next() {
+            Some(line) => line,
+            None => {
+                self.finished = true;
+                return;
+            }
+        };
+
+        let expected_fields = log_fields(expected);
+        if expected_fields.is_some() && expected_fields == log_fields(actual) {
+            self.matched += 1;
+            self.context.push_back(expected.to_string());
+            if self.context.len() > self.context_lines {
+                self.context.pop_front();
+            }
+        } else {
+            self.divergence = Some(Divergence {
+                line: number + 1,
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+                context: self.context.iter().cloned().collect(),
+            });
+        }
+    }
+}
+
+impl<'a> TraceSink for LogComparison<'a> {
+    fn trace(&mut self, record: &TraceRecord) {
+        self.compare(&nestest_line(record));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NestestError {
+    Rom(INesError),
+    Divergence(Divergence),
+    /// the CPU hit a JAM opcode after line lines of the log
+    Jammed { pc: Word, line: usize },
+}
+
+impl fmt::Display for NestestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NestestError::Rom(error) => write!(f, "{}", error),
+            NestestError::Divergence(divergence) => write!(f, "{}", divergence),
+            NestestError::Jammed { pc, line } => {
+                write!(f, "the CPU jammed at ${:04X} after line {}", pc, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NestestError {}
+
+/// Runs the ROM in automation mode until every line of the log has been
+/// compared, returns the number of lines that matched
+pub fn run_nestest(rom: &INesRom, log: &str) -> Result<usize, NestestError> {
+    let mut mem = nrom_mem(rom).map_err(NestestError::Rom)?;
+    let mut cpu = CPU::new();
+    start_automation(&mut cpu);
+
+    let mut comparison = LogComparison::new(log, 5);
+    let lines: usize = log.lines().count();
+    while comparison.matched < lines {
+        if cpu.jammed {
+            return Err(NestestError::Jammed {
+                pc: cpu.pc(),
+                line: comparison.matched,
+            });
+        }
+
+        cpu.execute_traced(&mut 1, &mut mem, &mut comparison);
+        if let Some(divergence) = comparison.divergence.take() {
+            return Err(NestestError::Divergence(divergence));
+        }
+    }
+
+    Ok(comparison.matched)
+}
@@ -0,0 +1,888 @@
+use crate::Byte;
+use crate::CpuVariant;
+
+// Opcodes
+
+// Load / Store Operations
+pub const INS_LDA_IM: Byte = 0xA9;
+pub const INS_LDA_ZP: Byte = 0xA5;
+pub const INS_LDA_ZPX: Byte = 0xB5;
+pub const INS_LDA_ABS: Byte = 0xAD;
+pub const INS_LDA_ABSX: Byte = 0xBD;
+pub const INS_LDA_ABSY: Byte = 0xB9;
+pub const INS_LDA_INDX: Byte = 0xA1;
+pub const INS_LDA_INDY: Byte = 0xB1;
+
+pub const INS_LDX_IM: Byte = 0xA2;
+pub const INS_LDX_ZP: Byte = 0xA6;
+pub const INS_LDX_ZPY: Byte = 0xB6;
+pub const INS_LDX_ABS: Byte = 0xAE;
+pub const INS_LDX_ABSY: Byte = 0xBE;
+
+pub const INS_LDY_IM: Byte = 0xA0;
+pub const INS_LDY_ZP: Byte = 0xA4;
+pub const INS_LDY_ZPX: Byte = 0xB4;
+pub const INS_LDY_ABS: Byte = 0xAC;
+pub const INS_LDY_ABSX: Byte = 0xBC;
+
+pub const INS_STA_ZP: Byte = 0x85;
+pub const INS_STA_ZPX: Byte = 0x95;
+pub const INS_STA_ABS: Byte = 0x8D;
+pub const INS_STA_ABSX: Byte = 0x9D;
+pub const INS_STA_ABSY: Byte = 0x99;
+pub const INS_STA_INDX: Byte = 0x81;
+pub const INS_STA_INDY: Byte = 0x91;
+
+pub const INS_STX_ZP: Byte = 0x86;
+pub const INS_STX_ZPY: Byte = 0x96;
+pub const INS_STX_ABS: Byte = 0x8E;
+
+pub const INS_STY_ZP: Byte = 0x84;
+pub const INS_STY_ZPX: Byte = 0x94;
+pub const INS_STY_ABS: Byte = 0x8C;
+
+// Jumps And Calls
+pub const INS_JMP_ABS: Byte = 0x4C;
+pub const INS_JMP_IND: Byte = 0x6C;
+pub const INS_JSR: Byte = 0x20;
+pub const INS_RTS: Byte = 0x60;
+
+// Stack Operations
+pub const INS_TSX: Byte = 0xBA;
+pub const INS_TXS: Byte = 0x9A;
+pub const INS_PHA: Byte = 0x48;
+pub const INS_PHP: Byte = 0x08;
+pub const INS_PLA: Byte = 0x68;
+pub const INS_PLP: Byte = 0x28;
+
+// Logical Operations
+pub const INS_AND_IM: Byte = 0x29;
+pub const INS_AND_ZP: Byte = 0x25;
+pub const INS_AND_ZPX: Byte = 0x35;
+pub const INS_AND_ABS: Byte = 0x2D;
+pub const INS_AND_ABSX: Byte = 0x3D;
+pub const INS_AND_ABSY: Byte = 0x39;
+pub const INS_AND_INDX: Byte = 0x21;
+pub const INS_AND_INDY: Byte = 0x31;
+
+pub const INS_EOR_IM: Byte = 0x49;
+pub const INS_EOR_ZP: Byte = 0x45;
+pub const INS_EOR_ZPX: Byte = 0x55;
+pub const INS_EOR_ABS: Byte = 0x4D;
+pub const INS_EOR_ABSX: Byte = 0x5D;
+pub const INS_EOR_ABSY: Byte = 0x59;
+pub const INS_EOR_INDX: Byte = 0x41;
+pub const INS_EOR_INDY: Byte = 0x51;
+
+pub const INS_ORA_IM: Byte = 0x09;
+pub const INS_ORA_ZP: Byte = 0x05;
+pub const INS_ORA_ZPX: Byte = 0x15;
+pub const INS_ORA_ABS: Byte = 0x0D;
+pub const INS_ORA_ABSX: Byte = 0x1D;
+pub const INS_ORA_ABSY: Byte = 0x19;
+pub const INS_ORA_INDX: Byte = 0x01;
+pub const INS_ORA_INDY: Byte = 0x11;
+
+pub const INS_BIT_ZP: Byte = 0x24;
+pub const INS_BIT_ABS: Byte = 0x2C;
+
+// Register Transfers
+pub const INS_TAX: Byte = 0xAA;
+pub const INS_TAY: Byte = 0xA8;
+pub const INS_TXA: Byte = 0x8A;
+pub const INS_TYA: Byte = 0x98;
+
+// Increments & Decrements
+pub const INS_INC_ZP: Byte = 0xE6;
+pub const INS_INC_ZPX: Byte = 0xF6;
+pub const INS_INC_ABS: Byte = 0xEE;
+pub const INS_INC_ABSX: Byte = 0xFE;
+
+pub const INS_INX: Byte = 0xE8;
+pub const INS_INY: Byte = 0xC8;
+
+pub const INS_DEC_ZP: Byte = 0xC6;
+pub const INS_DEC_ZPX: Byte = 0xD6;
+pub const INS_DEC_ABS: Byte = 0xCE;
+pub const INS_DEC_ABSX: Byte = 0xDE;
+
+pub const INS_DEX: Byte = 0xCA;
+pub const INS_DEY: Byte = 0x88;
+
+// Branches
+pub const INS_BCC: Byte = 0x90;
+pub const INS_BCS: Byte = 0xB0;
+pub const INS_BEQ: Byte = 0xF0;
+pub const INS_BMI: Byte = 0x30;
+pub const INS_BNE: Byte = 0xD0;
+pub const INS_BPL: Byte = 0x10;
+pub const INS_BVC: Byte = 0x50;
+pub const INS_BVS: Byte = 0x70;
+
+// Status Flags Changes
+pub const INS_CLC: Byte = 0x18;
+pub const INS_CLD: Byte = 0xD8;
+pub const INS_CLI: Byte = 0x58;
+pub const INS_CLV: Byte = 0xB8;
+pub const INS_SEC: Byte = 0x38;
+pub const INS_SED: Byte = 0xF8;
+pub const INS_SEI: Byte = 0x78;
+
+// Arithmetic
+pub const INS_ADC_IM: Byte = 0x69;
+pub const INS_ADC_ZP: Byte = 0x65;
+pub const INS_ADC_ZPX: Byte = 0x75;
+pub const INS_ADC_ABS: Byte = 0x6D;
+pub const INS_ADC_ABSX: Byte = 0x7D;
+pub const INS_ADC_ABSY: Byte = 0x79;
+pub const INS_ADC_INDX: Byte = 0x61;
+pub const INS_ADC_INDY: Byte = 0x71;
+
+pub const INS_SBC_IM: Byte = 0xE9;
+pub const INS_SBC_ZP: Byte = 0xE5;
+pub const INS_SBC_ZPX: Byte = 0xF5;
+pub const INS_SBC_ABS: Byte = 0xED;
+pub const INS_SBC_ABSX: Byte = 0xFD;
+pub const INS_SBC_ABSY: Byte = 0xF9;
+pub const INS_SBC_INDX: Byte = 0xE1;
+pub const INS_SBC_INDY: Byte = 0xF1;
+
+pub const INS_CMP_IM: Byte = 0xC9;
+pub const INS_CMP_ZP: Byte = 0xC5;
+pub const INS_CMP_ZPX: Byte = 0xD5;
+pub const INS_CMP_ABS: Byte = 0xCD;
+pub const INS_CMP_ABSX: Byte = 0xDD;
+pub const INS_CMP_ABSY: Byte = 0xD9;
+pub const INS_CMP_INDX: Byte = 0xC1;
+pub const INS_CMP_INDY: Byte = 0xD1;
+
+pub const INS_CPX_IM: Byte = 0xE0;
+pub const INS_CPX_ZP: Byte = 0xE4;
+pub const INS_CPX_ABS: Byte = 0xEC;
+
+pub const INS_CPY_IM: Byte = 0xC0;
+pub const INS_CPY_ZP: Byte = 0xC4;
+pub const INS_CPY_ABS: Byte = 0xCC;
+
+// Shifts
+pub const INS_ASL_ACC: Byte = 0x0A;
+pub const INS_ASL_ZP: Byte = 0x06;
+pub const INS_ASL_ZPX: Byte = 0x16;
+pub const INS_ASL_ABS: Byte = 0x0E;
+pub const INS_ASL_ABSX: Byte = 0x1E;
+
+pub const INS_LSR_ACC: Byte = 0x4A;
+pub const INS_LSR_ZP: Byte = 0x46;
+pub const INS_LSR_ZPX: Byte = 0x56;
+pub const INS_LSR_ABS: Byte = 0x4E;
+pub const INS_LSR_ABSX: Byte = 0x5E;
+
+pub const INS_ROL_ACC: Byte = 0x2A;
+pub const INS_ROL_ZP: Byte = 0x26;
+pub const INS_ROL_ZPX: Byte = 0x36;
+pub const INS_ROL_ABS: Byte = 0x2E;
+pub const INS_ROL_ABSX: Byte = 0x3E;
+
+pub const INS_ROR_ACC: Byte = 0x6A;
+pub const INS_ROR_ZP: Byte = 0x66;
+pub const INS_ROR_ZPX: Byte = 0x76;
+pub const INS_ROR_ABS: Byte = 0x6E;
+pub const INS_ROR_ABSX: Byte = 0x7E;
+
+// System Functions
+pub const INS_BRK: Byte = 0x00;
+pub const INS_NOP: Byte = 0xEA;
+pub const INS_RTI: Byte = 0x40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressingMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndirectX,
+    IndirectY,
+    Relative,
+    /// 65C02: ($44)
+    ZeroPageIndirect,
+    /// 65C02: JMP ($4400,X)
+    AbsoluteIndexedIndirect,
+    /// Rockwell BBR / BBS: $44,$0612
+    ZeroPageRelative,
+}
+
+impl AddressingMode {
+    /// number of bytes after the opcode
+    pub fn operand_bytes(self) -> usize {
+        match self {
+            AddressingMode::Implied | AddressingMode::Accumulator => 0,
+            AddressingMode::Absolute
+            | AddressingMode::AbsoluteX
+            | AddressingMode::AbsoluteY
+            | AddressingMode::Indirect
+            | AddressingMode::AbsoluteIndexedIndirect
+            | AddressingMode::ZeroPageRelative => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mnemonic {
+    Adc,
+    And,
+    Asl,
+    Bcc,
+    Bcs,
+    Beq,
+    Bit,
+    Bmi,
+    Bne,
+    Bpl,
+    Brk,
+    Bvc,
+    Bvs,
+    Clc,
+    Cld,
+    Cli,
+    Clv,
+    Cmp,
+    Cpx,
+    Cpy,
+    Dec,
+    Dex,
+    Dey,
+    Eor,
+    Inc,
+    Inx,
+    Iny,
+    Jmp,
+    Jsr,
+    Lda,
+    Ldx,
+    Ldy,
+    Lsr,
+    Nop,
+    Ora,
+    Pha,
+    Php,
+    Pla,
+    Plp,
+    Rol,
+    Ror,
+    Rti,
+    Rts,
+    Sbc,
+    Sec,
+    Sed,
+    Sei,
+    Sta,
+    Stx,
+    Sty,
+    Tax,
+    Tay,
+    Tsx,
+    Txa,
+    Txs,
+    Tya,
+
+    // undocumented NMOS opcodes, named as in "No More Secrets"
+    // (NMOS 6510 Unintended Opcodes)
+    Alr,
+    Anc,
+    Ane,
+    Arr,
+    Dcp,
+    Isc,
+    Jam,
+    Las,
+    Lax,
+    Lxa,
+    Rla,
+    Rra,
+    Sax,
+    Sbx,
+    Sha,
+    Shx,
+    Shy,
+    Slo,
+    Sre,
+    Tas,
+
+    // 65C02
+    Bra,
+    Phx,
+    Phy,
+    Plx,
+    Ply,
+    Stp,
+    Stz,
+    Trb,
+    Tsb,
+    Wai,
+
+    // Rockwell bit instructions, with the bit number
+    Bbr(u8),
+    Bbs(u8),
+    Rmb(u8),
+    Smb(u8),
+}
+
+const BBR: [&str; 8] = [
+    "BBR0", "BBR1", "BBR2", "BBR3", "BBR4", "BBR5", "BBR6", "BBR7",
+];
+const BBS: [&str; 8] = [
+    "BBS0", "BBS1", "BBS2", "BBS3", "BBS4", "BBS5", "BBS6", "BBS7",
+];
+const RMB: [&str; 8] = [
+    "RMB0", "RMB1", "RMB2", "RMB3", "RMB4", "RMB5", "RMB6", "RMB7",
+];
+const SMB: [&str; 8] = [
+    "SMB0", "SMB1", "SMB2", "SMB3", "SMB4", "SMB5", "SMB6", "SMB7",
+];
+
+impl Mnemonic {
+    pub fn name(self) -> &'static str {
+        use Mnemonic::*;
+
+        match self {
+            Adc => "ADC",
+            And => "AND",
+            Asl => "ASL",
+            Bcc => "BCC",
+            Bcs => "BCS",
+            Beq => "BEQ",
+            Bit => "BIT",
+            Bmi => "BMI",
+            Bne => "BNE",
+            Bpl => "BPL",
+            Brk => "BRK",
+            Bvc => "BVC",
+            Bvs => "BVS",
+            Clc => "CLC",
+            Cld => "CLD",
+            Cli => "CLI",
+            Clv => "CLV",
+            Cmp => "CMP",
+            Cpx => "CPX",
+            Cpy => "CPY",
+            Dec => "DEC",
+            Dex => "DEX",
+            Dey => "DEY",
+            Eor => "EOR",
+            Inc => "INC",
+            Inx => "INX",
+            Iny => "INY",
+            Jmp => "JMP",
+            Jsr => "JSR",
+            Lda => "LDA",
+            Ldx => "LDX",
+            Ldy => "LDY",
+            Lsr => "LSR",
+            Nop => "NOP",
+            Ora => "ORA",
+            Pha => "PHA",
+            Php => "PHP",
+            Pla => "PLA",
+            Plp => "PLP",
+            Rol => "ROL",
+            Ror => "ROR",
+            Rti => "RTI",
+            Rts => "RTS",
+            Sbc => "SBC",
+            Sec => "SEC",
+            Sed => "SED",
+            Sei => "SEI",
+            Sta => "STA",
+            Stx => "STX",
+            Sty => "STY",
+            Tax => "TAX",
+            Tay => "TAY",
+            Tsx => "TSX",
+            Txa => "TXA",
+            Txs => "TXS",
+            Tya => "TYA",
+            Alr => "ALR",
+            Anc => "ANC",
+            Ane => "ANE",
+            Arr => "ARR",
+            Dcp => "DCP",
+            Isc => "ISC",
+            Jam => "JAM",
+            Las => "LAS",
+            Lax => "LAX",
+            Lxa => "LXA",
+            Rla => "RLA",
+            Rra => "RRA",
+            Sax => "SAX",
+            Sbx => "SBX",
+            Sha => "SHA",
+            Shx => "SHX",
+            Shy => "SHY",
+            Slo => "SLO",
+            Sre => "SRE",
+            Tas => "TAS",
+            Bra => "BRA",
+            Phx => "PHX",
+            Phy => "PHY",
+            Plx => "PLX",
+            Ply => "PLY",
+            Stp => "STP",
+            Stz => "STZ",
+            Trb => "TRB",
+            Tsb => "TSB",
+            Wai => "WAI",
+            Bbr(bit) => BBR[bit as usize],
+            Bbs(bit) => BBS[bit as usize],
+            Rmb(bit) => RMB[bit as usize],
+            Smb(bit) => SMB[bit as usize],
+        }
+    }
+}
+
+/// One entry of the decode tables. cycles is the base count, instructions
+/// with page_penalty take one more cycle when indexing crosses a page.
+/// Branches add their own cycles when they are taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opcode {
+    pub mnemonic: Mnemonic,
+    pub mode: AddressingMode,
+    pub cycles: u8,
+    pub page_penalty: bool,
+    /// false for the undocumented NMOS opcodes and the 65C02 NOPs
+    pub documented: bool,
+}
+
+const fn op(mnemonic: Mnemonic, mode: AddressingMode, cycles: u8) -> Opcode {
+    Opcode {
+        mnemonic,
+        mode,
+        cycles,
+        page_penalty: false,
+        documented: true,
+    }
+}
+
+// the same with the page crossing penalty
+const fn opp(mnemonic: Mnemonic, mode: AddressingMode, cycles: u8) -> Opcode {
+    Opcode {
+        mnemonic,
+        mode,
+        cycles,
+        page_penalty: true,
+        documented: true,
+    }
+}
+
+impl Opcode {
+    const fn undocumented(mut self) -> Opcode {
+        self.documented = false;
+        self
+    }
+}
+
+const JAM: Opcode = op(Mnemonic::Jam, AddressingMode::Implied, 2).undocumented();
+
+// The 151 documented opcodes, the same on every variant apart from the
+// cycles the 65C02 changes afterwards
+const fn documented_opcodes() -> [Opcode; 256] {
+    use AddressingMode::*;
+    use Mnemonic::*;
+
+    let mut t: [Opcode; 256] = [JAM; 256];
+
+    // Load / Store Operations
+    t[0xA9] = op(Lda, Immediate, 2);
+    t[0xA5] = op(Lda, ZeroPage, 3);
+    t[0xB5] = op(Lda, ZeroPageX, 4);
+    t[0xAD] = op(Lda, Absolute, 4);
+    t[0xBD] = opp(Lda, AbsoluteX, 4);
+    t[0xB9] = opp(Lda, AbsoluteY, 4);
+    t[0xA1] = op(Lda, IndirectX, 6);
+    t[0xB1] = opp(Lda, IndirectY, 5);
+
+    t[0xA2] = op(Ldx, Immediate, 2);
+    t[0xA6] = op(Ldx, ZeroPage, 3);
+    t[0xB6] = op(Ldx, ZeroPageY, 4);
+    t[0xAE] = op(Ldx, Absolute, 4);
+    t[0xBE] = opp(Ldx, AbsoluteY, 4);
+
+    t[0xA0] = op(Ldy, Immediate, 2);
+    t[0xA4] = op(Ldy, ZeroPage, 3);
+    t[0xB4] = op(Ldy, ZeroPageX, 4);
+    t[0xAC] = op(Ldy, Absolute, 4);
+    t[0xBC] = opp(Ldy, AbsoluteX, 4);
+
+    t[0x85] = op(Sta, ZeroPage, 3);
+    t[0x95] = op(Sta, ZeroPageX, 4);
+    t[0x8D] = op(Sta, Absolute, 4);
+    t[0x9D] = op(Sta, AbsoluteX, 5);
+    t[0x99] = op(Sta, AbsoluteY, 5);
+    t[0x81] = op(Sta, IndirectX, 6);
+    t[0x91] = op(Sta, IndirectY, 6);
+
+    t[0x86] = op(Stx, ZeroPage, 3);
+    t[0x96] = op(Stx, ZeroPageY, 4);
+    t[0x8E] = op(Stx, Absolute, 4);
+
+    t[0x84] = op(Sty, ZeroPage, 3);
+    t[0x94] = op(Sty, ZeroPageX, 4);
+    t[0x8C] = op(Sty, Absolute, 4);
+
+    // Register Transfers
+    t[0xAA] = op(Tax, Implied, 2);
+    t[0xA8] = op(Tay, Implied, 2);
+    t[0x8A] = op(Txa, Implied, 2);
+    t[0x98] = op(Tya, Implied, 2);
+
+    // Stack Operations
+    t[0xBA] = op(Tsx, Implied, 2);
+    t[0x9A] = op(Txs, Implied, 2);
+    t[0x48] = op(Pha, Implied, 3);
+    t[0x08] = op(Php, Implied, 3);
+    t[0x68] = op(Pla, Implied, 4);
+    t[0x28] = op(Plp, Implied, 4);
+
+    // Logical Operations
+    t[0x29] = op(And, Immediate, 2);
+    t[0x25] = op(And, ZeroPage, 3);
+    t[0x35] = op(And, ZeroPageX, 4);
+    t[0x2D] = op(And, Absolute, 4);
+    t[0x3D] = opp(And, AbsoluteX, 4);
+    t[0x39] = opp(And, AbsoluteY, 4);
+    t[0x21] = op(And, IndirectX, 6);
+    t[0x31] = opp(And, IndirectY, 5);
+
+    t[0x49] = op(Eor, Immediate, 2);
+    t[0x45] = op(Eor, ZeroPage, 3);
+    t[0x55] = op(Eor, ZeroPageX, 4);
+    t[0x4D] = op(Eor, Absolute, 4);
+    t[0x5D] = opp(Eor, AbsoluteX, 4);
+    t[0x59] = opp(Eor, AbsoluteY, 4);
+    t[0x41] = op(Eor, IndirectX, 6);
+    t[0x51] = opp(Eor, IndirectY, 5);
+
+    t[0x09] = op(Ora, Immediate, 2);
+    t[0x05] = op(Ora, ZeroPage, 3);
+    t[0x15] = op(Ora, ZeroPageX, 4);
+    t[0x0D] = op(Ora, Absolute, 4);
+    t[0x1D] = opp(Ora, AbsoluteX, 4);
+    t[0x19] = opp(Ora, AbsoluteY, 4);
+    t[0x01] = op(Ora, IndirectX, 6);
+    t[0x11] = opp(Ora, IndirectY, 5);
+
+    t[0x24] = op(Bit, ZeroPage, 3);
+    t[0x2C] = op(Bit, Absolute, 4);
+
+    // Arithmetic
+    t[0x69] = op(Adc, Immediate, 2);
+    t[0x65] = op(Adc, ZeroPage, 3);
+    t[0x75] = op(Adc, ZeroPageX, 4);
+    t[0x6D] = op(Adc, Absolute, 4);
+    t[0x7D] = opp(Adc, AbsoluteX, 4);
+    t[0x79] = opp(Adc, AbsoluteY, 4);
+    t[0x61] = op(Adc, IndirectX, 6);
+    t[0x71] = opp(Adc, IndirectY, 5);
+
+    t[0xE9] = op(Sbc, Immediate, 2);
+    t[0xE5] = op(Sbc, ZeroPage, 3);
+    t[0xF5] = op(Sbc, ZeroPageX, 4);
+    t[0xED] = op(Sbc, Absolute, 4);
+    t[0xFD] = opp(Sbc, AbsoluteX, 4);
+    t[0xF9] = opp(Sbc, AbsoluteY, 4);
+    t[0xE1] = op(Sbc, IndirectX, 6);
+    t[0xF1] = opp(Sbc, IndirectY, 5);
+
+    t[0xC9] = op(Cmp, Immediate, 2);
+    t[0xC5] = op(Cmp, ZeroPage, 3);
+    t[0xD5] = op(Cmp, ZeroPageX, 4);
+    t[0xCD] = op(Cmp, Absolute, 4);
+    t[0xDD] = opp(Cmp, AbsoluteX, 4);
+    t[0xD9] = opp(Cmp, AbsoluteY, 4);
+    t[0xC1] = op(Cmp, IndirectX, 6);
+    t[0xD1] = opp(Cmp, IndirectY, 5);
+
+    t[0xE0] = op(Cpx, Immediate, 2);
+    t[0xE4] = op(Cpx, ZeroPage, 3);
+    t[0xEC] = op(Cpx, Absolute, 4);
+
+    t[0xC0] = op(Cpy, Immediate, 2);
+    t[0xC4] = op(Cpy, ZeroPage, 3);
+    t[0xCC] = op(Cpy, Absolute, 4);
+
+    // Increments & Decrements
+    t[0xE6] = op(Inc, ZeroPage, 5);
+    t[0xF6] = op(Inc, ZeroPageX, 6);
+    t[0xEE] = op(Inc, Absolute, 6);
+    t[0xFE] = op(Inc, AbsoluteX, 7);
+    t[0xE8] = op(Inx, Implied, 2);
+    t[0xC8] = op(Iny, Implied, 2);
+
+    t[0xC6] = op(Dec, ZeroPage, 5);
+    t[0xD6] = op(Dec, ZeroPageX, 6);
+    t[0xCE] = op(Dec, Absolute, 6);
+    t[0xDE] = op(Dec, AbsoluteX, 7);
+    t[0xCA] = op(Dex, Implied, 2);
+    t[0x88] = op(Dey, Implied, 2);
+
+    // Shifts
+    t[0x0A] = op(Asl, Accumulator, 2);
+    t[0x06] = op(Asl, ZeroPage, 5);
+    t[0x16] = op(Asl, ZeroPageX, 6);
+    t[0x0E] = op(Asl, Absolute, 6);
+    t[0x1E] = op(Asl, AbsoluteX, 7);
+
+    t[0x4A] = op(Lsr, Accumulator, 2);
+    t[0x46] = op(Lsr, ZeroPage, 5);
+    t[0x56] = op(Lsr, ZeroPageX, 6);
+    t[0x4E] = op(Lsr, Absolute, 6);
+    t[0x5E] = op(Lsr, AbsoluteX, 7);
+
+    t[0x2A] = op(Rol, Accumulator, 2);
+    t[0x26] = op(Rol, ZeroPage, 5);
+    t[0x36] = op(Rol, ZeroPageX, 6);
+    t[0x2E] = op(Rol, Absolute, 6);
+    t[0x3E] = op(Rol, AbsoluteX, 7);
+
+    t[0x6A] = op(Ror, Accumulator, 2);
+    t[0x66] = op(Ror, ZeroPage, 5);
+    t[0x76] = op(Ror, ZeroPageX, 6);
+    t[0x6E] = op(Ror, Absolute, 6);
+    t[0x7E] = op(Ror, AbsoluteX, 7);
+
+    // Jumps & Calls
+    t[0x4C] = op(Jmp, Absolute, 3);
+    t[0x6C] = op(Jmp, Indirect, 5);
+    t[0x20] = op(Jsr, Absolute, 6);
+    t[0x60] = op(Rts, Implied, 6);
+
+    // Branches
+    t[0x90] = op(Bcc, Relative, 2);
+    t[0xB0] = op(Bcs, Relative, 2);
+    t[0xF0] = op(Beq, Relative, 2);
+    t[0x30] = op(Bmi, Relative, 2);
+    t[0xD0] = op(Bne, Relative, 2);
+    t[0x10] = op(Bpl, Relative, 2);
+    t[0x50] = op(Bvc, Relative, 2);
+    t[0x70] = op(Bvs, Relative, 2);
+
+    // Status Flag Changes
+    t[0x18] = op(Clc, Implied, 2);
+    t[0xD8] = op(Cld, Implied, 2);
+    t[0x58] = op(Cli, Implied, 2);
+    t[0xB8] = op(Clv, Implied, 2);
+    t[0x38] = op(Sec, Implied, 2);
+    t[0xF8] = op(Sed, Implied, 2);
+    t[0x78] = op(Sei, Implied, 2);
+
+    // System Functions
+    t[0x00] = op(Brk, Implied, 7);
+    t[0xEA] = op(Nop, Implied, 2);
+    t[0x40] = op(Rti, Implied, 6);
+
+    t
+}
+
+// read-modify-write opcodes in the layout every undocumented combination
+// uses: x7 ZP, x7+10 ZPX, xF ABS, xF+10 ABSX, xB+10 ABSY, x3 INDX, x3+10 INDY
+const fn read_modify_write(mut t: [Opcode; 256], base: usize, mnemonic: Mnemonic) -> [Opcode; 256] {
+    use AddressingMode::*;
+
+    t[base + 0x07] = op(mnemonic, ZeroPage, 5).undocumented();
+    t[base + 0x17] = op(mnemonic, ZeroPageX, 6).undocumented();
+    t[base + 0x0F] = op(mnemonic, Absolute, 6).undocumented();
+    t[base + 0x1F] = op(mnemonic, AbsoluteX, 7).undocumented();
+    t[base + 0x1B] = op(mnemonic, AbsoluteY, 7).undocumented();
+    t[base + 0x03] = op(mnemonic, IndirectX, 8).undocumented();
+    t[base + 0x13] = op(mnemonic, IndirectY, 8).undocumented();
+    t
+}
+
+const fn nmos_6502() -> [Opcode; 256] {
+    use AddressingMode::*;
+    use Mnemonic::*;
+
+    let mut t: [Opcode; 256] = documented_opcodes();
+
+    t = read_modify_write(t, 0x00, Slo);
+    t = read_modify_write(t, 0x20, Rla);
+    t = read_modify_write(t, 0x40, Sre);
+    t = read_modify_write(t, 0x60, Rra);
+    t = read_modify_write(t, 0xC0, Dcp);
+    t = read_modify_write(t, 0xE0, Isc);
+
+    t[0x87] = op(Sax, ZeroPage, 3).undocumented();
+    t[0x97] = op(Sax, ZeroPageY, 4).undocumented();
+    t[0x8F] = op(Sax, Absolute, 4).undocumented();
+    t[0x83] = op(Sax, IndirectX, 6).undocumented();
+
+    t[0xA7] = op(Lax, ZeroPage, 3).undocumented();
+    t[0xB7] = op(Lax, ZeroPageY, 4).undocumented();
+    t[0xAF] = op(Lax, Absolute, 4).undocumented();
+    t[0xBF] = opp(Lax, AbsoluteY, 4).undocumented();
+    t[0xA3] = op(Lax, IndirectX, 6).undocumented();
+    t[0xB3] = opp(Lax, IndirectY, 5).undocumented();
+
+    t[0xAB] = op(Lxa, Immediate, 2).undocumented();
+    t[0x8B] = op(Ane, Immediate, 2).undocumented();
+    t[0x0B] = op(Anc, Immediate, 2).undocumented();
+    t[0x2B] = op(Anc, Immediate, 2).undocumented();
+    t[0x4B] = op(Alr, Immediate, 2).undocumented();
+    t[0x6B] = op(Arr, Immediate, 2).undocumented();
+    t[0xCB] = op(Sbx, Immediate, 2).undocumented();
+    t[0xEB] = op(Sbc, Immediate, 2).undocumented();
+
+    t[0x9F] = op(Sha, AbsoluteY, 5).undocumented();
+    t[0x93] = op(Sha, IndirectY, 6).undocumented();
+    t[0x9E] = op(Shx, AbsoluteY, 5).undocumented();
+    t[0x9C] = op(Shy, AbsoluteX, 5).undocumented();
+    t[0x9B] = op(Tas, AbsoluteY, 5).undocumented();
+    t[0xBB] = opp(Las, AbsoluteY, 4).undocumented();
+
+    // NOPs, they read their operand and throw it away
+    t[0x1A] = op(Nop, Implied, 2).undocumented();
+    t[0x3A] = op(Nop, Implied, 2).undocumented();
+    t[0x5A] = op(Nop, Implied, 2).undocumented();
+    t[0x7A] = op(Nop, Implied, 2).undocumented();
+    t[0xDA] = op(Nop, Implied, 2).undocumented();
+    t[0xFA] = op(Nop, Implied, 2).undocumented();
+    t[0x80] = op(Nop, Immediate, 2).undocumented();
+    t[0x82] = op(Nop, Immediate, 2).undocumented();
+    t[0x89] = op(Nop, Immediate, 2).undocumented();
+    t[0xC2] = op(Nop, Immediate, 2).undocumented();
+    t[0xE2] = op(Nop, Immediate, 2).undocumented();
+    t[0x04] = op(Nop, ZeroPage, 3).undocumented();
+    t[0x44] = op(Nop, ZeroPage, 3).undocumented();
+    t[0x64] = op(Nop, ZeroPage, 3).undocumented();
+    t[0x14] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0x34] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0x54] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0x74] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0xD4] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0xF4] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0x0C] = op(Nop, Absolute, 4).undocumented();
+    t[0x1C] = opp(Nop, AbsoluteX, 4).undocumented();
+    t[0x3C] = opp(Nop, AbsoluteX, 4).undocumented();
+    t[0x5C] = opp(Nop, AbsoluteX, 4).undocumented();
+    t[0x7C] = opp(Nop, AbsoluteX, 4).undocumented();
+    t[0xDC] = opp(Nop, AbsoluteX, 4).undocumented();
+    t[0xFC] = opp(Nop, AbsoluteX, 4).undocumented();
+
+    // the 12 JAM opcodes are the default of the table
+
+    t
+}
+
+const fn rockwell_65c02() -> [Opcode; 256] {
+    use AddressingMode::*;
+    use Mnemonic::*;
+
+    let mut t: [Opcode; 256] = documented_opcodes();
+
+    // every unused opcode is a NOP, most of them take 1 byte and 1 cycle
+    let mut i: usize = 0;
+    while i < 256 {
+        if matches!(t[i].mnemonic, Jam) {
+            t[i] = op(Nop, Implied, 1).undocumented();
+        }
+        i += 1;
+    }
+    t[0x02] = op(Nop, Immediate, 2).undocumented();
+    t[0x22] = op(Nop, Immediate, 2).undocumented();
+    t[0x42] = op(Nop, Immediate, 2).undocumented();
+    t[0x62] = op(Nop, Immediate, 2).undocumented();
+    t[0x82] = op(Nop, Immediate, 2).undocumented();
+    t[0xC2] = op(Nop, Immediate, 2).undocumented();
+    t[0xE2] = op(Nop, Immediate, 2).undocumented();
+    t[0x44] = op(Nop, ZeroPage, 3).undocumented();
+    t[0x54] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0xD4] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0xF4] = op(Nop, ZeroPageX, 4).undocumented();
+    t[0x5C] = op(Nop, Absolute, 8).undocumented();
+    t[0xDC] = op(Nop, Absolute, 4).undocumented();
+    t[0xFC] = op(Nop, Absolute, 4).undocumented();
+
+    // the page boundary bug of JMP indirect is fixed, it costs a cycle
+    t[0x6C] = op(Jmp, Indirect, 6);
+    t[0x7C] = op(Jmp, AbsoluteIndexedIndirect, 6);
+
+    // shifts with X only take the extra cycle when the page changes
+    t[0x1E] = opp(Asl, AbsoluteX, 6);
+    t[0x5E] = opp(Lsr, AbsoluteX, 6);
+    t[0x3E] = opp(Rol, AbsoluteX, 6);
+    t[0x7E] = opp(Ror, AbsoluteX, 6);
+
+    t[0x80] = op(Bra, Relative, 2);
+
+    t[0xDA] = op(Phx, Implied, 3);
+    t[0x5A] = op(Phy, Implied, 3);
+    t[0xFA] = op(Plx, Implied, 4);
+    t[0x7A] = op(Ply, Implied, 4);
+
+    t[0x64] = op(Stz, ZeroPage, 3);
+    t[0x74] = op(Stz, ZeroPageX, 4);
+    t[0x9C] = op(Stz, Absolute, 4);
+    t[0x9E] = op(Stz, AbsoluteX, 5);
+
+    t[0x04] = op(Tsb, ZeroPage, 5);
+    t[0x0C] = op(Tsb, Absolute, 6);
+    t[0x14] = op(Trb, ZeroPage, 5);
+    t[0x1C] = op(Trb, Absolute, 6);
+
+    t[0x12] = op(Ora, ZeroPageIndirect, 5);
+    t[0x32] = op(And, ZeroPageIndirect, 5);
+    t[0x52] = op(Eor, ZeroPageIndirect, 5);
+    t[0x72] = op(Adc, ZeroPageIndirect, 5);
+    t[0x92] = op(Sta, ZeroPageIndirect, 5);
+    t[0xB2] = op(Lda, ZeroPageIndirect, 5);
+    t[0xD2] = op(Cmp, ZeroPageIndirect, 5);
+    t[0xF2] = op(Sbc, ZeroPageIndirect, 5);
+
+    t[0x1A] = op(Inc, Accumulator, 2);
+    t[0x3A] = op(Dec, Accumulator, 2);
+
+    t[0x89] = op(Bit, Immediate, 2);
+    t[0x34] = op(Bit, ZeroPageX, 4);
+    t[0x3C] = opp(Bit, AbsoluteX, 4);
+
+    // the bit number is in the high nibble
+    let mut bit: usize = 0;
+    while bit < 8 {
+        t[0x07 + bit * 0x10] = op(Rmb(bit as u8), ZeroPage, 5);
+        t[0x87 + bit * 0x10] = op(Smb(bit as u8), ZeroPage, 5);
+        t[0x0F + bit * 0x10] = op(Bbr(bit as u8), ZeroPageRelative, 5);
+        t[0x8F + bit * 0x10] = op(Bbs(bit as u8), ZeroPageRelative, 5);
+        bit += 1;
+    }
+
+    t
+}
+
+const fn wdc_65c02() -> [Opcode; 256] {
+    use AddressingMode::*;
+    use Mnemonic::*;
+
+    let mut t: [Opcode; 256] = rockwell_65c02();
+    t[0xCB] = op(Wai, Implied, 3);
+    t[0xDB] = op(Stp, Implied, 3);
+    t
+}
+
+pub static NMOS_6502: [Opcode; 256] = nmos_6502();
+pub static ROCKWELL_65C02: [Opcode; 256] = rockwell_65c02();
+pub static WDC_65C02: [Opcode; 256] = wdc_65c02();
+
+/// The decode table of a CPU variant
+pub fn opcode_table(variant: CpuVariant) -> &'static [Opcode; 256] {
+    match variant {
+        CpuVariant::Nmos6502 => &NMOS_6502,
+        CpuVariant::Wdc65C02 => &WDC_65C02,
+        CpuVariant::Rockwell65C02 => &ROCKWELL_65C02,
+    }
+}
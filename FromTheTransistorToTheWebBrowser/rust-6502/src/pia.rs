@@ -0,0 +1,181 @@
+use crate::bus::Bus;
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use crate::{Byte, Word};
+
+// The bits of the control registers
+pub const CONTROL_C1_IRQ_ENABLE: Byte = 0x01;
+/// 0: C1 flags falling edges, 1: rising edges
+pub const CONTROL_C1_RISING: Byte = 0x02;
+/// 0: the data address reaches the data direction register, 1: the port
+pub const CONTROL_PORT_SELECT: Byte = 0x04;
+pub const CONTROL_IRQ2_FLAG: Byte = 0x40;
+pub const CONTROL_IRQ1_FLAG: Byte = 0x80;
+
+/// The Motorola 6821 Peripheral Interface Adapter: two 8 bit ports, each
+/// with a data direction register and a control register, seen through 4
+/// addresses:
+///  0  port A, or its data direction register
+///  1  control register A
+///  2  port B, or its data direction register
+///  3  control register B
+/// A data direction bit of 1 makes the pin an output. The C1 lines flag an
+/// edge in bit 7 of the control register, reading the port clears the flag.
+/// The C2 lines (handshakes and the IRQ2 flags) are not emulated
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pia6821 {
+    pub output_a: Byte,
+    pub ddr_a: Byte,
+    pub control_a: Byte,
+    pub output_b: Byte,
+    pub ddr_b: Byte,
+    pub control_b: Byte,
+
+    /// the levels the devices wired to the ports drive on the input pins
+    pub input_a: Byte,
+    pub input_b: Byte,
+    ca1: bool,
+    cb1: bool,
+}
+
+impl Pia6821 {
+    /// after a reset every register is 0: all the pins are inputs and the
+    /// data addresses reach the data direction registers
+    pub fn new() -> Self {
+        Pia6821 {
+            output_a: 0,
+            ddr_a: 0,
+            control_a: 0,
+            output_b: 0,
+            ddr_b: 0,
+            control_b: 0,
+            input_a: 0,
+            input_b: 0,
+            ca1: false,
+            cb1: false,
+        }
+    }
+
+    /// The levels on the pins: the output register where the pin is an
+    /// output, the device where it is an input
+    pub fn port_a(&self) -> Byte {
+        (self.output_a & self.ddr_a) | (self.input_a & !self.ddr_a)
+    }
+
+    pub fn port_b(&self) -> Byte {
+        (self.output_b & self.ddr_b) | (self.input_b & !self.ddr_b)
+    }
+
+    pub fn set_ca1(&mut self, level: bool) {
+        if edge(self.control_a, self.ca1, level) {
+            self.control_a |= CONTROL_IRQ1_FLAG;
+        }
+        self.ca1 = level;
+    }
+
+    pub fn set_cb1(&mut self, level: bool) {
+        if edge(self.control_b, self.cb1, level) {
+            self.control_b |= CONTROL_IRQ1_FLAG;
+        }
+        self.cb1 = level;
+    }
+
+    fn register(&self, address: Word) -> Byte {
+        let selected = |control: Byte| control & CONTROL_PORT_SELECT != 0;
+        match address & 0x03 {
+            0 if selected(self.control_a) => self.port_a(),
+            0 => self.ddr_a,
+            1 => self.control_a,
+            2 if selected(self.control_b) => self.port_b(),
+            2 => self.ddr_b,
+            _ => self.control_b,
+        }
+    }
+}
+
+impl Default for Pia6821 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Whether going from previous to level is the edge the control register
+// waits for
+fn edge(control: Byte, previous: bool, level: bool) -> bool {
+    let rising: bool = control & CONTROL_C1_RISING != 0;
+    previous != level && level == rising
+}
+
+impl Bus for Pia6821 {
+    fn read(&mut self, address: Word) -> Byte {
+        let value: Byte = self.register(address);
+        match address & 0x03 {
+            0 if self.control_a & CONTROL_PORT_SELECT != 0 => {
+                self.control_a &= !(CONTROL_IRQ1_FLAG | CONTROL_IRQ2_FLAG)
+            }
+            2 if self.control_b & CONTROL_PORT_SELECT != 0 => {
+                self.control_b &= !(CONTROL_IRQ1_FLAG | CONTROL_IRQ2_FLAG)
+            }
+            _ => {}
+        }
+        value
+    }
+
+    // the flags of the control registers can't be written
+    fn write(&mut self, address: Word, value: Byte) {
+        let flags: Byte = CONTROL_IRQ1_FLAG | CONTROL_IRQ2_FLAG;
+        match address & 0x03 {
+            0 if self.control_a & CONTROL_PORT_SELECT != 0 => self.output_a = value,
+            0 => self.ddr_a = value,
+            1 => self.control_a = (self.control_a & flags) | (value & !flags),
+            2 if self.control_b & CONTROL_PORT_SELECT != 0 => self.output_b = value,
+            2 => self.ddr_b = value,
+            _ => self.control_b = (self.control_b & flags) | (value & !flags),
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.register(address)
+    }
+
+    fn poke(&mut self, _address: Word, _value: Byte) {}
+
+    // IRQA and IRQB are wired together here
+    fn irq(&self) -> bool {
+        let requested = |control: Byte| {
+            control & (CONTROL_IRQ1_FLAG | CONTROL_C1_IRQ_ENABLE)
+                == CONTROL_IRQ1_FLAG | CONTROL_C1_IRQ_ENABLE
+        };
+        requested(self.control_a) || requested(self.control_b)
+    }
+
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        for register in [
+            self.output_a,
+            self.ddr_a,
+            self.control_a,
+            self.output_b,
+            self.ddr_b,
+            self.control_b,
+            self.input_a,
+            self.input_b,
+        ] {
+            out.byte(register);
+        }
+        out.bool(self.ca1);
+        out.bool(self.cb1);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        self.output_a = input.byte()?;
+        self.ddr_a = input.byte()?;
+        self.control_a = input.byte()?;
+        self.output_b = input.byte()?;
+        self.ddr_b = input.byte()?;
+        self.control_b = input.byte()?;
+        self.input_a = input.byte()?;
+        self.input_b = input.byte()?;
+        self.ca1 = input.bool()?;
+        self.cb1 = input.bool()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+
+use crate::assembler::Program;
+use crate::bus::Bus;
+use crate::trace::{TraceRecord, TraceSink};
+use crate::CPU;
+use crate::{Byte, Word};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Hits {
+    pub instructions: u64,
+    pub cycles: u64,
+}
+
+impl Hits {
+    fn add(&mut self, instructions: u64, cycles: u64) {
+        self.instructions += instructions;
+        self.cycles += cycles;
+    }
+}
+
+/// What a subroutine cost: in its own instructions, and with the
+/// subroutines it calls (a recursive routine is counted once)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RoutineStats {
+    pub calls: u64,
+    pub own: Hits,
+    pub total: Hits,
+}
+
+// A subroutine being run: its entry address and the stack pointer right
+// after the call pushed its return address
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Frame {
+    routine: Word,
+    sp: u16,
+}
+
+// The first frame is the code the profile started in, it never returns
+const ROOT_SP: u16 = 0x1FF;
+
+// The mnemonic of the place where a run stopped, see finish()
+const RESUME: &str = "";
+
+// How an instruction moves the stack pointer, None when it sets it
+fn stack_change(mnemonic: &str) -> Option<i16> {
+    match mnemonic {
+        "PHA" | "PHP" | "PHX" | "PHY" => Some(-1),
+        "PLA" | "PLP" | "PLX" | "PLY" => Some(1),
+        "JSR" => Some(-2),
+        "RTS" => Some(2),
+        "RTI" => Some(3),
+        "BRK" => Some(-3),
+        "TXS" | "TAS" | "LAS" => None,
+        _ => Some(0),
+    }
+}
+
+/// Counts the instructions and cycles of every address and of every
+/// subroutine. It is a TraceSink: an instruction's cycles are known when
+/// the next one starts, so finish() has to be called for the last one.
+///
+/// The call stack follows the stack pointer instead of matching every JSR
+/// with an RTS: a subroutine returns once its return address is off the
+/// stack, however that happened (RTS, PLA PLA, a reset of SP...). Pushing an
+/// address and executing RTS to jump there stays in the current routine.
+/// An interrupt shows up as a drop of 3 in the stack pointer that the
+/// instruction doesn't explain, the handler is a routine until its RTI
+pub struct Profiler {
+    pub addresses: BTreeMap<Word, Hits>,
+    pub routines: BTreeMap<Word, RoutineStats>,
+    /// (caller, callee) with the number of calls
+    pub calls: BTreeMap<(Word, Word), u64>,
+    /// the cycles spent with each call stack, outermost routine first
+    pub stacks: BTreeMap<Vec<Word>, u64>,
+    /// labels for the reports, e.g. the symbols of the assembler
+    pub names: BTreeMap<Word, String>,
+    frames: Vec<Frame>,
+    previous: Option<TraceRecord>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler {
+            addresses: BTreeMap::new(),
+            routines: BTreeMap::new(),
+            calls: BTreeMap::new(),
+            stacks: BTreeMap::new(),
+            names: BTreeMap::new(),
+            frames: Vec::new(),
+            previous: None,
+        }
+    }
+
+    pub fn name(&mut self, address: Word, name: &str) {
+        self.names.insert(address, name.to_string());
+    }
+
+    /// Names the addresses after the labels of an assembled program, the
+    /// constants of `define` are not addresses
+    pub fn name_labels(&mut self, program: &Program) {
+        for (name, symbol) in &program.symbols {
+            if symbol.relocatable {
+                self.name(symbol.value, name);
+            }
+        }
+    }
+
+    /// Runs the CPU for cycles and accounts for all of them
+    pub fn run<M: Bus>(&mut self, cpu: &mut CPU, memory: &mut M, cycles: isize) -> isize {
+        let mut cycles: isize = cycles;
+        let executed = cpu.execute_traced(&mut cycles, memory, self);
+        self.finish(cpu);
+        executed
+    }
+
+    /// Accounts for the last instruction traced, the CPU is where it stopped.
+    /// The next run goes on from there: an interrupt taken before its first
+    /// instruction is found like the others, its cycles count at the
+    /// address the CPU stopped at
+    pub fn finish(&mut self, cpu: &CPU) {
+        self.advance(cpu.pc(), cpu.sp(), cpu.total_cycles);
+        self.previous = self.previous.map(|record| TraceRecord {
+            pc: cpu.pc(),
+            sp: cpu.sp(),
+            cycles: cpu.total_cycles,
+            mnemonic: RESUME,
+            ..record
+        });
+    }
+
+    // The previous instruction ran until cycles, the next one is at pc
+    fn advance(&mut self, pc: Word, sp: Byte, cycles: u64) {
+        if self.frames.is_empty() {
+            self.frames.push(Frame {
+                routine: pc,
+                sp: ROOT_SP,
+            });
+        }
+        let previous = match self.previous {
+            Some(previous) => previous,
+            None => return,
+        };
+
+        // interrupts taken before pc count in the previous instruction
+        let spent: u64 = cycles.saturating_sub(previous.cycles);
+        let instructions: u64 = if previous.mnemonic == RESUME { 0 } else { 1 };
+        if instructions > 0 || spent > 0 {
+            self.account(previous.pc, instructions, spent);
+        }
+
+        while self.frames.len() > 1 && self.frames.last().unwrap().sp + 2 <= sp as u16 {
+            self.frames.pop();
+        }
+
+        let expected: Option<Byte> =
+            stack_change(previous.mnemonic).map(|change| (previous.sp as i16 + change) as Byte);
+        let interrupted: bool = expected.is_some_and(|expected| sp == expected.wrapping_sub(3));
+        match previous.mnemonic {
+            "JSR" => {
+                let target: Word = previous.bytes[1] as Word | (previous.bytes[2] as Word) << 8;
+                self.call(target, previous.sp.wrapping_sub(2));
+            }
+            // unless something else interrupted, the handler is at pc
+            "BRK" if !interrupted => self.call(pc, sp),
+            _ => {}
+        }
+        if interrupted {
+            self.call(pc, sp);
+        }
+    }
+
+    fn call(&mut self, routine: Word, sp: Byte) {
+        if let Some(caller) = self.frames.last() {
+            *self.calls.entry((caller.routine, routine)).or_insert(0) += 1;
+        }
+        self.routines.entry(routine).or_default().calls += 1;
+        self.frames.push(Frame {
+            routine,
+            sp: sp as u16,
+        });
+    }
+
+    fn account(&mut self, pc: Word, instructions: u64, cycles: u64) {
+        self.addresses
+            .entry(pc)
+            .or_default()
+            .add(instructions, cycles);
+
+        let stack: Vec<Word> = self.frames.iter().map(|frame| frame.routine).collect();
+        let own: Word = stack[stack.len() - 1];
+        self.routines
+            .entry(own)
+            .or_default()
+            .own
+            .add(instructions, cycles);
+
+        let mut counted: Vec<Word> = Vec::new();
+        for &routine in &stack {
+            if !counted.contains(&routine) {
+                counted.push(routine);
+                self.routines
+                    .entry(routine)
+                    .or_default()
+                    .total
+                    .add(instructions, cycles);
+            }
+        }
+
+        *self.stacks.entry(stack).or_insert(0) += cycles;
+    }
+
+    pub fn total_cycles(&self) -> u64 {
+        self.addresses.values().map(|hits| hits.cycles).sum()
+    }
+
+    /// e.g. `$0606` or `init`, `init+3` inside a named routine
+    pub fn label(&self, address: Word) -> String {
+        match self.names.range(..=address).next_back() {
+            Some((&start, name)) if start == address => name.clone(),
+            Some((&start, name)) if address - start < 0x100 => {
+                format!("{}+{}", name, address - start)
+            }
+            _ => format!("${:04X}", address),
+        }
+    }
+
+    /// The routines and the addresses that took the most cycles:
+    ///
+    ///  routine          calls      own cycles        total cycles
+    ///  drawApple          120    1200   4.0%     1200   4.0%
+    ///  ...
+    pub fn report(&self, lines: usize) -> String {
+        let total: u64 = self.total_cycles().max(1);
+        let percent = |cycles: u64| cycles as f64 * 100.0 / total as f64;
+        let mut text = String::new();
+
+        let mut routines: Vec<(&Word, &RoutineStats)> = self.routines.iter().collect();
+        routines.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.own.cycles));
+        text.push_str(&format!(
+            "{:<20} {:>8} {:>16} {:>16}\n",
+            "routine", "calls", "own cycles", "total cycles"
+        ));
+        for (&address, stats) in routines.iter().take(lines) {
+            text.push_str(&format!(
+                "{:<20} {:>8} {:>9} {:>5.1}% {:>9} {:>5.1}%\n",
+                self.label(address),
+                stats.calls,
+                stats.own.cycles,
+                percent(stats.own.cycles),
+                stats.total.cycles,
+                percent(stats.total.cycles)
+            ));
+        }
+
+        let mut addresses: Vec<(&Word, &Hits)> = self.addresses.iter().collect();
+        addresses.sort_by_key(|(_, hits)| std::cmp::Reverse(hits.cycles));
+        text.push_str(&format!(
+            "\n{:<20} {:>8} {:>16}\n",
+            "address", "executed", "cycles"
+        ));
+        for (&address, hits) in addresses.iter().take(lines) {
+            text.push_str(&format!(
+                "{:<20} {:>8} {:>9} {:>5.1}%\n",
+                self.label(address),
+                hits.instructions,
+                hits.cycles,
+                percent(hits.cycles)
+            ));
+        }
+
+        text
+    }
+
+    /// The call stacks in the folded format of flamegraph.pl and inferno,
+    /// one `outer;inner;innermost cycles` line per stack
+    pub fn folded(&self) -> String {
+        let mut text = String::new();
+        for (stack, cycles) in &self.stacks {
+            let names: Vec<String> = stack.iter().map(|&routine| self.label(routine)).collect();
+            text.push_str(&format!("{} {}\n", names.join(";"), cycles));
+        }
+        text
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TraceSink for Profiler {
+    fn trace(&mut self, record: &TraceRecord) {
+        self.advance(record.pc, record.sp, record.cycles);
+        self.previous = Some(*record);
+    }
+}
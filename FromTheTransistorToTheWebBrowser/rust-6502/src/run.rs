@@ -0,0 +1,273 @@
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::debugger::Comparison;
+use crate::opcodes::{opcode_table, AddressingMode, Mnemonic, INS_BRK};
+use crate::trace::{NoTrace, TraceRecord, TraceSink};
+use crate::CPU;
+use crate::{Byte, Word};
+
+/// What ends a run. The budgets count from the start of the run, the other
+/// conditions are checked after every instruction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopCondition {
+    /// stop once this many cycles were executed, the last instruction may
+    /// go a few cycles over
+    Cycles(u64),
+    Instructions(u64),
+    /// the PC reached the address, the instruction there has not run yet
+    Pc(Word),
+    /// a BRK was executed, the PC is at the start of the handler
+    Brk,
+    /// a JMP or a branch to itself, how test ROMs report their result
+    SelfJump,
+    /// the byte at address (read with peek) compared with value
+    Memory {
+        address: Word,
+        comparison: Comparison,
+        value: Byte,
+    },
+    /// stop before an undocumented opcode instead of emulating it
+    Undocumented,
+}
+
+/// Why run() returned
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StopReason {
+    Cycles,
+    Instructions,
+    Pc(Word),
+    /// pc is the address of the BRK
+    Brk { pc: Word },
+    SelfJump(Word),
+    Memory { address: Word, value: Byte },
+    /// a JAM locked up the CPU (it is left at the opcode), or an undocumented
+    /// opcode is next and StopCondition::Undocumented is set
+    IllegalOpcode { pc: Word, opcode: Byte },
+    /// STP on the 65C02, only a reset restarts the CPU
+    Stopped,
+    /// WAI with no interrupt line asserted and no cycle budget, the run
+    /// would never end
+    Waiting,
+}
+
+impl StopReason {
+    /// the program did something the CPU can't go on from
+    pub fn is_error(&self) -> bool {
+        matches!(self, StopReason::IllegalOpcode { .. })
+    }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Cycles => write!(f, "cycle budget used up"),
+            StopReason::Instructions => write!(f, "instruction count reached"),
+            StopReason::Pc(address) => write!(f, "PC reached ${:04X}", address),
+            StopReason::Brk { pc } => write!(f, "BRK at ${:04X}", pc),
+            StopReason::SelfJump(address) => write!(f, "jump to itself at ${:04X}", address),
+            StopReason::Memory { address, value } => {
+                write!(f, "${:04X} is ${:02X}", address, value)
+            }
+            StopReason::IllegalOpcode { pc, opcode } => {
+                write!(f, "illegal opcode ${:02X} at ${:04X}", opcode, pc)
+            }
+            StopReason::Stopped => write!(f, "the CPU executed STP"),
+            StopReason::Waiting => write!(f, "the CPU waits for an interrupt"),
+        }
+    }
+}
+
+/// What a run executed. An interrupt sequence is not an instruction, it is
+/// counted in interrupts (BRK is an instruction)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunStats {
+    pub instructions: u64,
+    pub cycles: u64,
+    pub interrupts: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSummary {
+    pub reason: StopReason,
+    pub stats: RunStats,
+}
+
+// The instruction a step executed, what the checks after it need
+#[derive(Clone, Copy)]
+struct Executed {
+    pc: Word,
+    opcode: Byte,
+}
+
+// Keeps the last instruction for the checks and passes the records on to
+// the trace of the caller, they are only built when it is enabled
+struct LastInstruction<'a, T: TraceSink> {
+    trace: &'a mut T,
+    executed: Option<Executed>,
+}
+
+impl<'a, T: TraceSink> TraceSink for LastInstruction<'a, T> {
+    fn trace(&mut self, record: &TraceRecord) {
+        self.trace.trace(record);
+    }
+
+    fn enabled(&self) -> bool {
+        self.trace.enabled()
+    }
+
+    fn instruction(&mut self, pc: Word, opcode: Byte) {
+        self.executed = Some(Executed { pc, opcode });
+        self.trace.instruction(pc, opcode);
+    }
+}
+
+impl CPU {
+    /// Runs until one of the conditions holds. Without a budget the run can
+    /// last forever, a JAM or STP ends it whatever the conditions are
+    pub fn run<M: Bus>(&mut self, memory: &mut M, conditions: &[StopCondition]) -> RunSummary {
+        self.run_traced(memory, conditions, &mut NoTrace)
+    }
+
+    /// The same, handing every instruction to the trace sink before it runs
+    pub fn run_traced<M: Bus, T: TraceSink>(
+        &mut self,
+        memory: &mut M,
+        conditions: &[StopCondition],
+        trace: &mut T,
+    ) -> RunSummary {
+        let mut stats = RunStats::default();
+        let reason: StopReason = loop {
+            if let Some(reason) = self.stop_before(memory, conditions, &stats) {
+                break reason;
+            }
+
+            let waited: bool = self.waiting;
+            let mut last = LastInstruction {
+                trace: &mut *trace,
+                executed: None,
+            };
+            stats.cycles += self.execute_traced(&mut 1, memory, &mut last) as u64;
+            let executed: Option<Executed> = last.executed;
+            match executed {
+                Some(_) => stats.instructions += 1,
+                // a cycle of sleep in WAI
+                None if waited && self.waiting => {}
+                None => stats.interrupts += 1,
+            }
+
+            if let Some(reason) = self.stop_after(memory, conditions, executed) {
+                break reason;
+            }
+        };
+
+        RunSummary { reason, stats }
+    }
+
+    // The conditions checked before the next instruction
+    fn stop_before<M: Bus>(
+        &self,
+        memory: &M,
+        conditions: &[StopCondition],
+        stats: &RunStats,
+    ) -> Option<StopReason> {
+        if self.jammed {
+            let opcode: Byte = memory.peek(self.pc);
+            return match opcode_table(self.variant)[opcode as usize].mnemonic {
+                Mnemonic::Jam => Some(StopReason::IllegalOpcode {
+                    pc: self.pc,
+                    opcode,
+                }),
+                _ => Some(StopReason::Stopped),
+            };
+        }
+        // a waiting CPU passes the cycles to the devices one by one, one of
+        // them may raise the interrupt it waits for
+        let budget: bool = conditions
+            .iter()
+            .any(|condition| matches!(condition, StopCondition::Cycles(_)));
+        if self.waiting && !budget && !self.wakes_up(memory) {
+            return Some(StopReason::Waiting);
+        }
+
+        for condition in conditions {
+            match *condition {
+                StopCondition::Cycles(budget) if stats.cycles >= budget => {
+                    return Some(StopReason::Cycles)
+                }
+                StopCondition::Instructions(count) if stats.instructions >= count => {
+                    return Some(StopReason::Instructions)
+                }
+                StopCondition::Undocumented => {
+                    let opcode: Byte = memory.peek(self.pc);
+                    if !opcode_table(self.variant)[opcode as usize].documented {
+                        return Some(StopReason::IllegalOpcode {
+                            pc: self.pc,
+                            opcode,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // The conditions checked after an instruction (or an interrupt sequence,
+    // then executed is None)
+    fn stop_after<M: Bus>(
+        &self,
+        memory: &M,
+        conditions: &[StopCondition],
+        executed: Option<Executed>,
+    ) -> Option<StopReason> {
+        for condition in conditions {
+            match (*condition, executed) {
+                (StopCondition::Pc(address), _) if self.pc == address => {
+                    return Some(StopReason::Pc(address))
+                }
+                (StopCondition::Brk, Some(executed)) if executed.opcode == INS_BRK => {
+                    return Some(StopReason::Brk { pc: executed.pc })
+                }
+                (StopCondition::SelfJump, Some(executed))
+                    if self.pc == executed.pc && self.is_jump(executed.opcode) =>
+                {
+                    return Some(StopReason::SelfJump(executed.pc))
+                }
+                (
+                    StopCondition::Memory {
+                        address,
+                        comparison,
+                        value,
+                    },
+                    _,
+                ) => {
+                    let byte: Byte = memory.peek(address);
+                    if comparison.holds(byte as Word, value as Word) {
+                        return Some(StopReason::Memory {
+                            address,
+                            value: byte,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    // whether an interrupt ends the WAI at the next step
+    fn wakes_up<M: Bus>(&self, memory: &M) -> bool {
+        let nmi_edge: bool = memory.nmi() && !self.nmi_previous;
+        self.nmi_pending || nmi_edge || self.irq_line || memory.irq()
+    }
+
+    // JMP, JMP indirect and the branches, including BBR/BBS
+    fn is_jump(&self, opcode: Byte) -> bool {
+        let opcode = opcode_table(self.variant)[opcode as usize];
+        match opcode.mode {
+            AddressingMode::Relative | AddressingMode::ZeroPageRelative => true,
+            _ => opcode.mnemonic == Mnemonic::Jmp,
+        }
+    }
+}
@@ -0,0 +1,670 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::bus::Bus;
+use crate::debugger::Access;
+use crate::opcodes::opcode_table;
+use crate::{Byte, Word};
+use crate::{CpuVariant, Mem, CPU};
+
+// Runs the single step test vectors of the ProcessorTests project
+// (github.com/SingleStepTests/65x02): one file per opcode, `a9.json`, with
+// thousands of vectors each. A vector gives the registers and the RAM
+// before and after one instruction, and what the bus did on every cycle:
+//
+//  { "name": "a9 5c 1e",
+//    "initial": { "pc": 59082, "s": 39, "a": 57, "x": 33, "y": 174, "p": 96,
+//                 "ram": [ [59082, 169], [59083, 92] ] },
+//    "final": { ... },
+//    "cycles": [ [59082, 169, "read"], [59083, 92, "read"] ] }
+//
+// The CPU doesn't do the dummy reads and writes of the real one, so the
+// bus comparison is optional. The cycle count is always compared
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SingleStepError {
+    Io(String),
+    /// offset is counted in bytes from the start of the text
+    Json { offset: usize, message: String },
+    /// valid JSON that isn't a test vector
+    Format(String),
+}
+
+impl fmt::Display for SingleStepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SingleStepError::Io(message) => write!(f, "{}", message),
+            SingleStepError::Json { offset, message } => {
+                write!(f, "invalid JSON at byte {}: {}", offset, message)
+            }
+            SingleStepError::Format(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SingleStepError {}
+
+/// Just enough JSON for the test vectors
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// the members in the order of the text
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_json(text: &str) -> Result<Json, SingleStepError> {
+    let mut parser = JsonParser {
+        text: text.as_bytes(),
+        at: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.at < parser.text.len() {
+        return Err(parser.error("text after the value"));
+    }
+    Ok(value)
+}
+
+struct JsonParser<'a> {
+    text: &'a [u8],
+    at: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn error(&self, message: &str) -> SingleStepError {
+        SingleStepError::Json {
+            offset: self.at,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.at < self.text.len() && self.text[self.at].is_ascii_whitespace() {
+            self.at += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.text.get(self.at).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), SingleStepError> {
+        if self.peek() == Some(byte) {
+            self.at += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, SingleStepError> {
+        if self.text[self.at..].starts_with(word.as_bytes()) {
+            self.at += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("unknown keyword"))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, SingleStepError> {
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("the text ends before the value")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, SingleStepError> {
+        self.expect(b'{')?;
+        let mut members: Vec<(String, Json)> = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.at += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected the name of a member"));
+            }
+            let name = self.string()?;
+            self.expect(b':')?;
+            members.push((name, self.value()?));
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b'}') => {
+                    self.at += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, SingleStepError> {
+        self.expect(b'[')?;
+        let mut elements: Vec<Json> = Vec::new();
+        if self.peek() == Some(b']') {
+            self.at += 1;
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            elements.push(self.value()?);
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                Some(b']') => {
+                    self.at += 1;
+                    return Ok(Json::Array(elements));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SingleStepError> {
+        self.expect(b'"')?;
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            let byte: u8 = match self.text.get(self.at) {
+                Some(&byte) => byte,
+                None => return Err(self.error("the string isn't closed")),
+            };
+            self.at += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escaped: char = match self.text.get(self.at) {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\x08',
+                        Some(b'f') => '\x0C',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let code = self
+                                .text
+                                .get(self.at + 1..self.at + 5)
+                                .and_then(|digits| std::str::from_utf8(digits).ok())
+                                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                                .ok_or_else(|| self.error("bad \\u escape"))?;
+                            self.at += 4;
+                            char::from_u32(code).unwrap_or('\u{FFFD}')
+                        }
+                        _ => return Err(self.error("bad escape")),
+                    };
+                    self.at += 1;
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut buffer).as_bytes());
+                }
+                byte => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("the string isn't UTF-8"))
+    }
+
+    fn number(&mut self) -> Result<Json, SingleStepError> {
+        let start: usize = self.at;
+        while self.at < self.text.len()
+            && matches!(
+                self.text[self.at],
+                b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+            )
+        {
+            self.at += 1;
+        }
+        std::str::from_utf8(&self.text[start..self.at])
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("bad number"))
+    }
+}
+
+/// The registers and the RAM of a vector, the bytes not listed are 0
+#[derive(Debug, Clone, PartialEq)]
+pub struct State {
+    pub pc: Word,
+    pub s: Byte,
+    pub a: Byte,
+    pub x: Byte,
+    pub y: Byte,
+    pub p: Byte,
+    pub ram: Vec<(Word, Byte)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vector {
+    pub name: String,
+    pub initial: State,
+    pub expected: State,
+    /// one access a cycle
+    pub cycles: Vec<(Word, Byte, Access)>,
+}
+
+fn format_error(message: &str, name: &str) -> SingleStepError {
+    SingleStepError::Format(format!("{}: {}", name, message))
+}
+
+fn number(json: Option<&Json>, max: f64, what: &str, name: &str) -> Result<u32, SingleStepError> {
+    match json {
+        Some(&Json::Number(value)) if value >= 0.0 && value <= max && value.fract() == 0.0 => {
+            Ok(value as u32)
+        }
+        _ => Err(format_error(
+            &format!("{} is not a number up to {}", what, max),
+            name,
+        )),
+    }
+}
+
+fn array<'a>(
+    json: Option<&'a Json>,
+    what: &str,
+    name: &str,
+) -> Result<&'a [Json], SingleStepError> {
+    match json {
+        Some(Json::Array(elements)) => Ok(elements),
+        _ => Err(format_error(&format!("{} is not an array", what), name)),
+    }
+}
+
+fn parse_state(json: Option<&Json>, name: &str) -> Result<State, SingleStepError> {
+    let json: &Json = json.ok_or_else(|| format_error("a state is missing", name))?;
+    let register = |key: &str| number(json.get(key), 255.0, key, name).map(|value| value as Byte);
+
+    let mut ram: Vec<(Word, Byte)> = Vec::new();
+    for cell in array(json.get("ram"), "ram", name)? {
+        let cell: &[Json] = array(Some(cell), "a ram cell", name)?;
+        ram.push((
+            number(cell.first(), 65535.0, "an address", name)? as Word,
+            number(cell.get(1), 255.0, "a byte", name)? as Byte,
+        ));
+    }
+
+    Ok(State {
+        pc: number(json.get("pc"), 65535.0, "pc", name)? as Word,
+        s: register("s")?,
+        a: register("a")?,
+        x: register("x")?,
+        y: register("y")?,
+        p: register("p")?,
+        ram,
+    })
+}
+
+fn parse_vector(json: &Json) -> Result<Vector, SingleStepError> {
+    let name: String = match json.get("name") {
+        Some(Json::String(name)) => name.clone(),
+        _ => return Err(SingleStepError::Format("a vector has no name".to_string())),
+    };
+
+    let mut cycles: Vec<(Word, Byte, Access)> = Vec::new();
+    for cycle in array(json.get("cycles"), "cycles", &name)? {
+        let cycle: &[Json] = array(Some(cycle), "a cycle", &name)?;
+        let access: Access = match cycle.get(2) {
+            Some(Json::String(access)) if access == "read" => Access::Read,
+            Some(Json::String(access)) if access == "write" => Access::Write,
+            _ => return Err(format_error("a cycle is not a read or a write", &name)),
+        };
+        cycles.push((
+            number(cycle.first(), 65535.0, "an address", &name)? as Word,
+            number(cycle.get(1), 255.0, "a byte", &name)? as Byte,
+            access,
+        ));
+    }
+
+    Ok(Vector {
+        initial: parse_state(json.get("initial"), &name)?,
+        expected: parse_state(json.get("final"), &name)?,
+        cycles,
+        name,
+    })
+}
+
+/// The vectors of one file: an array of vectors
+pub fn parse_vectors(text: &str) -> Result<Vec<Vector>, SingleStepError> {
+    match parse_json(text)? {
+        Json::Array(vectors) => vectors.iter().map(parse_vector).collect(),
+        _ => Err(SingleStepError::Format(
+            "the vectors are not in an array".to_string(),
+        )),
+    }
+}
+
+/// How the CPU parted from a vector
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mismatch {
+    Register {
+        name: &'static str,
+        expected: Word,
+        actual: Word,
+    },
+    Memory {
+        address: Word,
+        expected: Byte,
+        actual: Byte,
+    },
+    Cycles {
+        expected: usize,
+        actual: usize,
+    },
+    /// the first access that differs, cycle is counted from 1. None when
+    /// one of them made fewer accesses
+    Bus {
+        cycle: usize,
+        expected: Option<(Word, Byte, Access)>,
+        actual: Option<(Word, Byte, Access)>,
+    },
+}
+
+fn describe_access(access: &Option<(Word, Byte, Access)>) -> String {
+    match access {
+        Some((address, value, access)) => {
+            format!("{:?} ${:02X} at ${:04X}", access, value, address)
+        }
+        None => "nothing".to_string(),
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mismatch::Register {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{}: expected ${:02X}, got ${:02X}",
+                name, expected, actual
+            ),
+            Mismatch::Memory {
+                address,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "${:04X}: expected ${:02X}, got ${:02X}",
+                address, expected, actual
+            ),
+            Mismatch::Cycles { expected, actual } => {
+                write!(f, "expected {} cycles, took {}", expected, actual)
+            }
+            Mismatch::Bus {
+                cycle,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "cycle {}: expected {}, got {}",
+                cycle,
+                describe_access(expected),
+                describe_access(actual)
+            ),
+        }
+    }
+}
+
+// Bits 4 (B) and 5 are not flags, a 6502 has no place to keep them: they
+// only exist in the status pushed on the stack, which is compared in RAM
+const FLAGS: Byte = 0b1100_1111;
+
+// Wraps the memory during the instruction to record every access
+struct BusLog<'a> {
+    memory: &'a mut Mem,
+    accesses: Vec<(Word, Byte, Access)>,
+}
+
+impl<'a> Bus for BusLog<'a> {
+    fn read(&mut self, address: Word) -> Byte {
+        let value: Byte = self.memory.read(address);
+        self.accesses.push((address, value, Access::Read));
+        value
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        self.accesses.push((address, value, Access::Write));
+        self.memory.write(address, value);
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.memory.peek(address)
+    }
+}
+
+fn blank_memory() -> Mem {
+    let mut memory = Mem::new();
+    memory.data = vec![0; memory.max_mem as usize];
+    memory
+}
+
+/// Runs the instruction of a vector, returns what didn't match
+pub fn run_vector(vector: &Vector, variant: CpuVariant, compare_bus: bool) -> Vec<Mismatch> {
+    run_in(&mut blank_memory(), vector, variant, compare_bus)
+}
+
+// The same in a blank memory, which is blank again afterwards: clearing
+// the bytes a vector used is much faster than a new 64 KiB for each one
+fn run_in(
+    memory: &mut Mem,
+    vector: &Vector,
+    variant: CpuVariant,
+    compare_bus: bool,
+) -> Vec<Mismatch> {
+    let mut cpu = CPU::new_variant(variant);
+    cpu.set_pc(vector.initial.pc);
+    cpu.set_sp(vector.initial.s);
+    cpu.set_a(vector.initial.a);
+    cpu.set_x(vector.initial.x);
+    cpu.set_y(vector.initial.y);
+    cpu.set_status(vector.initial.p);
+    for &(address, value) in &vector.initial.ram {
+        memory.data[address as usize] = value;
+    }
+
+    let mut bus = BusLog {
+        memory,
+        accesses: Vec::new(),
+    };
+    let cycles: usize = cpu.step(&mut bus) as usize;
+    let accesses = bus.accesses;
+
+    let expected = &vector.expected;
+    let mut mismatches: Vec<Mismatch> = Vec::new();
+    let registers: [(&'static str, Word, Word); 6] = [
+        ("PC", expected.pc, cpu.pc()),
+        ("S", expected.s as Word, cpu.sp() as Word),
+        ("A", expected.a as Word, cpu.a() as Word),
+        ("X", expected.x as Word, cpu.x() as Word),
+        ("Y", expected.y as Word, cpu.y() as Word),
+        (
+            "P",
+            (expected.p & FLAGS) as Word,
+            (cpu.status() & FLAGS) as Word,
+        ),
+    ];
+    for (name, expected, actual) in registers {
+        if expected != actual {
+            mismatches.push(Mismatch::Register {
+                name,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    for &(address, expected) in &expected.ram {
+        let actual: Byte = memory.data[address as usize];
+        if actual != expected {
+            mismatches.push(Mismatch::Memory {
+                address,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    if cycles != vector.cycles.len() {
+        mismatches.push(Mismatch::Cycles {
+            expected: vector.cycles.len(),
+            actual: cycles,
+        });
+    }
+
+    if compare_bus {
+        let length: usize = vector.cycles.len().max(accesses.len());
+        if let Some(cycle) =
+            (0..length).find(|&cycle| vector.cycles.get(cycle) != accesses.get(cycle))
+        {
+            mismatches.push(Mismatch::Bus {
+                cycle: cycle + 1,
+                expected: vector.cycles.get(cycle).copied(),
+                actual: accesses.get(cycle).copied(),
+            });
+        }
+    }
+
+    let ram = vector.initial.ram.iter().chain(&expected.ram);
+    let used = ram
+        .map(|&(address, _)| address)
+        .chain(accesses.iter().map(|&(address, _, _)| address));
+    for address in used {
+        memory.data[address as usize] = 0;
+    }
+
+    mismatches
+}
+
+/// The results of the vectors of one opcode, with the first one that failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpcodeReport {
+    pub opcode: Byte,
+    pub vectors: usize,
+    pub failed: usize,
+    pub first_failure: Option<(String, Vec<Mismatch>)>,
+}
+
+impl OpcodeReport {
+    pub fn passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+pub fn run_vectors(
+    opcode: Byte,
+    vectors: &[Vector],
+    variant: CpuVariant,
+    compare_bus: bool,
+) -> OpcodeReport {
+    let mut report = OpcodeReport {
+        opcode,
+        vectors: vectors.len(),
+        failed: 0,
+        first_failure: None,
+    };
+    let mut memory = blank_memory();
+    for vector in vectors {
+        let mismatches = run_in(&mut memory, vector, variant, compare_bus);
+        if !mismatches.is_empty() {
+            report.failed += 1;
+            if report.first_failure.is_none() {
+                report.first_failure = Some((vector.name.clone(), mismatches));
+            }
+        }
+    }
+    report
+}
+
+/// Runs the files `00.json` to `ff.json` that are in directory, the opcodes
+/// without a file are skipped
+pub fn run_directory<P: AsRef<Path>>(
+    directory: P,
+    variant: CpuVariant,
+    compare_bus: bool,
+) -> Result<Vec<OpcodeReport>, SingleStepError> {
+    let directory: &Path = directory.as_ref();
+    if !directory.is_dir() {
+        return Err(SingleStepError::Io(format!(
+            "{} is not a directory",
+            directory.display()
+        )));
+    }
+
+    let mut reports: Vec<OpcodeReport> = Vec::new();
+    for opcode in 0..=0xFF {
+        let path = directory.join(format!("{:02x}.json", opcode));
+        if !path.exists() {
+            continue;
+        }
+        let text = fs::read_to_string(&path)
+            .map_err(|error| SingleStepError::Io(format!("{}: {}", path.display(), error)))?;
+        let vectors = parse_vectors(&text)
+            .map_err(|error| SingleStepError::Format(format!("{}: {}", path.display(), error)))?;
+        reports.push(run_vectors(opcode, &vectors, variant, compare_bus));
+    }
+    Ok(reports)
+}
+
+/// One line per opcode, with the mismatches of the first vector that failed:
+///
+///  a9 LDA    10000 vectors  ok
+///  20 JSR    10000 vectors  10000 failed, the first is "20 55 13"
+///      $01F4: expected $12, got $13
+///  ...
+pub fn report(reports: &[OpcodeReport], variant: CpuVariant) -> String {
+    let mut text = String::new();
+    for report in reports {
+        let mnemonic: &str = opcode_table(variant)[report.opcode as usize]
+            .mnemonic
+            .name();
+        text.push_str(&format!(
+            "{:02x} {:<5} {:>6} vectors  ",
+            report.opcode, mnemonic, report.vectors
+        ));
+        match &report.first_failure {
+            None => text.push_str("ok\n"),
+            Some((name, mismatches)) => {
+                text.push_str(&format!(
+                    "{} failed, the first is {:?}\n",
+                    report.failed, name
+                ));
+                for mismatch in mismatches {
+                    text.push_str(&format!("    {}\n", mismatch));
+                }
+            }
+        }
+    }
+
+    let failed: usize = reports.iter().filter(|report| !report.passed()).count();
+    text.push_str(&format!(
+        "{} opcodes, {} passed, {} failed\n",
+        reports.len(),
+        reports.len() - failed,
+        failed
+    ));
+    text
+}
@@ -0,0 +1,248 @@
+use std::io::Write;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rust_6502::bus::Bus;
+use rust_6502::opcodes::INS_BRK;
+use rust_6502::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use rust_6502::{Byte, Word};
+use rust_6502::{Mem, CPU};
+
+use crate::terminal::{keys, RawMode};
+
+// The program below contains the entire source code of the game snake6502
+// Using 6502js Assembler ->  https://github.com/skilldrick/6502js
+// It is assembled to run at $0600, the source is in snake6502.asm
+/*
+ ___           _        __ ___  __ ___
+/ __|_ _  __ _| |_____ / /| __|/  \_  )
+\__ \ ' \/ _` | / / -_) _ \__ \ () / /
+|___/_||_\__,_|_\_\___\___/___/\__/___|
+                                         */
+
+pub const SNAKE6502: [Byte; 309] = [
+    0x20, 0x06, 0x06, 0x20, 0x38, 0x06, 0x20, 0x0d, 0x06, 0x20, 0x2a, 0x06, 0x60, 0xa9, 0x02, 0x85,
+    0x02, 0xa9, 0x04, 0x85, 0x03, 0xa9, 0x11, 0x85, 0x10, 0xa9, 0x10, 0x85, 0x12, 0xa9, 0x0f, 0x85,
+    0x14, 0xa9, 0x04, 0x85, 0x11, 0x85, 0x13, 0x85, 0x15, 0x60, 0xa5, 0xfe, 0x85, 0x00, 0xa5, 0xfe,
+    0x29, 0x03, 0x18, 0x69, 0x02, 0x85, 0x01, 0x60, 0x20, 0x4d, 0x06, 0x20, 0x8d, 0x06, 0x20, 0xc3,
+    0x06, 0x20, 0x19, 0x07, 0x20, 0x20, 0x07, 0x20, 0x2d, 0x07, 0x4c, 0x38, 0x06, 0xa5, 0xff, 0xc9,
+    0x77, 0xf0, 0x0d, 0xc9, 0x64, 0xf0, 0x14, 0xc9, 0x73, 0xf0, 0x1b, 0xc9, 0x61, 0xf0, 0x22, 0x60,
+    0xa9, 0x04, 0x24, 0x02, 0xd0, 0x26, 0xa9, 0x01, 0x85, 0x02, 0x60, 0xa9, 0x08, 0x24, 0x02, 0xd0,
+    0x1b, 0xa9, 0x02, 0x85, 0x02, 0x60, 0xa9, 0x01, 0x24, 0x02, 0xd0, 0x10, 0xa9, 0x04, 0x85, 0x02,
+    0x60, 0xa9, 0x02, 0x24, 0x02, 0xd0, 0x05, 0xa9, 0x08, 0x85, 0x02, 0x60, 0x60, 0x20, 0x94, 0x06,
+    0x20, 0xa8, 0x06, 0x60, 0xa5, 0x00, 0xc5, 0x10, 0xd0, 0x0d, 0xa5, 0x01, 0xc5, 0x11, 0xd0, 0x07,
+    0xe6, 0x03, 0xe6, 0x03, 0x20, 0x2a, 0x06, 0x60, 0xa2, 0x02, 0xb5, 0x10, 0xc5, 0x10, 0xd0, 0x06,
+    0xb5, 0x11, 0xc5, 0x11, 0xf0, 0x09, 0xe8, 0xe8, 0xe4, 0x03, 0xf0, 0x06, 0x4c, 0xaa, 0x06, 0x4c,
+    0x35, 0x07, 0x60, 0xa6, 0x03, 0xca, 0x8a, 0xb5, 0x10, 0x95, 0x12, 0xca, 0x10, 0xf9, 0xa5, 0x02,
+    0x4a, 0xb0, 0x09, 0x4a, 0xb0, 0x19, 0x4a, 0xb0, 0x1f, 0x4a, 0xb0, 0x2f, 0xa5, 0x10, 0x38, 0xe9,
+    0x20, 0x85, 0x10, 0x90, 0x01, 0x60, 0xc6, 0x11, 0xa9, 0x01, 0xc5, 0x11, 0xf0, 0x28, 0x60, 0xe6,
+    0x10, 0xa9, 0x1f, 0x24, 0x10, 0xf0, 0x1f, 0x60, 0xa5, 0x10, 0x18, 0x69, 0x20, 0x85, 0x10, 0xb0,
+    0x01, 0x60, 0xe6, 0x11, 0xa9, 0x06, 0xc5, 0x11, 0xf0, 0x0c, 0x60, 0xc6, 0x10, 0xa5, 0x10, 0x29,
+    0x1f, 0xc9, 0x1f, 0xf0, 0x01, 0x60, 0x4c, 0x35, 0x07, 0xa0, 0x00, 0xa5, 0xfe, 0x91, 0x00, 0x60,
+    0xa6, 0x03, 0xa9, 0x00, 0x81, 0x10, 0xa2, 0x00, 0xa9, 0x01, 0x81, 0x10, 0x60, 0xa2, 0x00, 0xea,
+    0xea, 0xca, 0xd0, 0xfb, 0x60,
+];
+
+// The memory map of the 6502js simulator (https://github.com/skilldrick/6502js)
+// that snake6502 was written for
+pub const SYS_RANDOM: Word = 0x00FE; // a new random byte on every read
+pub const SYS_LAST_KEY: Word = 0x00FF; // ASCII code of the last key pressed
+pub const SCREEN_START: Word = 0x0200; // 32x32 pixels, one byte each
+pub const SCREEN_END: Word = 0x05FF;
+pub const SCREEN_WIDTH: usize = 32;
+pub const PROGRAM_START: Word = 0x0600;
+
+// The 16 colours of 6502js, a pixel uses the low nibble of its byte
+pub const PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00), // black
+    (0xFF, 0xFF, 0xFF), // white
+    (0x88, 0x00, 0x00), // red
+    (0xAA, 0xFF, 0xEE), // cyan
+    (0xCC, 0x44, 0xCC), // purple
+    (0x00, 0xCC, 0x55), // green
+    (0x00, 0x00, 0xAA), // blue
+    (0xEE, 0xEE, 0x77), // yellow
+    (0xDD, 0x88, 0x55), // orange
+    (0x66, 0x44, 0x00), // brown
+    (0xFF, 0x77, 0x77), // light red
+    (0x33, 0x33, 0x33), // dark grey
+    (0x77, 0x77, 0x77), // grey
+    (0xAA, 0xFF, 0x66), // light green
+    (0x00, 0x88, 0xFF), // light blue
+    (0xBB, 0xBB, 0xBB), // light grey
+];
+
+// 64 KiB of RAM with the random number generator at $FE. The last key is
+// plain memory at $FF, the front-end writes it with press_key
+pub struct SnakeBus {
+    pub mem: Mem,
+    // xorshift32 state, never 0
+    seed: u32,
+}
+
+impl SnakeBus {
+    pub fn new(seed: u32) -> Self {
+        let mem = Mem::blank();
+        SnakeBus {
+            mem,
+            seed: seed.max(1),
+        }
+    }
+
+    fn next_random(&mut self) -> Byte {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as Byte
+    }
+
+    // WASD steer the snake, the game compares lower case ASCII codes.
+    // Returns false for the other keys, they are not passed to the game
+    pub fn press_key(&mut self, key: Byte) -> bool {
+        let key: Byte = key.to_ascii_lowercase();
+        if !b"wasd".contains(&key) {
+            return false;
+        }
+
+        self.mem.poke(SYS_LAST_KEY, key);
+        true
+    }
+
+    pub fn screen(&self) -> &[Byte] {
+        &self.mem.data()[SCREEN_START as usize..=SCREEN_END as usize]
+    }
+
+    // The screen as ANSI true colour text, 32 columns by 16 lines. Every
+    // character is an upper half block: the foreground is the pixel of the
+    // even row and the background the one of the odd row below it
+    pub fn render(&self) -> String {
+        let screen = self.screen();
+        let mut text = String::new();
+
+        for rows in screen.chunks(SCREEN_WIDTH * 2) {
+            let (top, bottom) = rows.split_at(SCREEN_WIDTH);
+            for (&upper, &lower) in top.iter().zip(bottom.iter()) {
+                let (fr, fg, fb) = PALETTE[(upper & 0x0F) as usize];
+                let (br, bg, bb) = PALETTE[(lower & 0x0F) as usize];
+                text.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                    fr, fg, fb, br, bg, bb
+                ));
+            }
+            // raw mode doesn't turn \n into \r\n
+            text.push_str("\x1b[0m\r\n");
+        }
+
+        text
+    }
+}
+
+impl Bus for SnakeBus {
+    fn read(&mut self, address: Word) -> Byte {
+        if address == SYS_RANDOM {
+            let random: Byte = self.next_random();
+            self.mem.poke(SYS_RANDOM, random);
+        }
+        self.mem.peek(address)
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        self.mem.poke(address, value);
+    }
+
+    // the last random byte, peeking doesn't draw a new one
+    fn peek(&self, address: Word) -> Byte {
+        self.mem.peek(address)
+    }
+
+    // the random generator too, a resumed game draws the same apples
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        self.mem.save_state(out);
+        out.u32(self.seed);
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        self.mem.load_state(input)?;
+        self.seed = input.u32()?.max(1);
+        Ok(())
+    }
+}
+
+// Copies snake6502 to $0600 and points the CPU at it. SNAKE6502 is the
+// bare code, without the load address that load_prg expects
+pub fn load_snake<M: Bus>(cpu: &mut CPU, memory: &mut M) {
+    cpu.power_on(memory);
+    for (i, &byte) in SNAKE6502.iter().enumerate() {
+        memory.write(PROGRAM_START.wrapping_add(i as Word), byte);
+    }
+    cpu.set_pc(PROGRAM_START);
+}
+
+// Puts the terminal in raw mode (keys arrive without Enter and aren't
+// echoed) until it is dropped. stty saves us a terminal library
+// Plays snake6502 in the terminal at about rate instructions per second,
+// until the game is over or Q / Ctrl-C is pressed
+pub fn play(rate: u32) {
+    const FRAMES_PER_SECOND: u32 = 30;
+    const CTRL_C: Byte = 0x03;
+
+    let seed: u32 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.subsec_nanos())
+        .unwrap_or(1);
+    let mut bus = SnakeBus::new(seed);
+    let mut cpu = CPU::new();
+    load_snake(&mut cpu, &mut bus);
+
+    let instructions_per_frame: u32 = (rate / FRAMES_PER_SECOND).max(1);
+    let frame = Duration::from_secs(1) / FRAMES_PER_SECOND;
+
+    let raw_mode = RawMode::enable();
+    print!("\x1b[?25l\x1b[2J"); // hide the cursor, clear the screen
+    let keys = keys();
+    let mut shown: Vec<Byte> = Vec::new();
+    let mut game_over = false;
+
+    'frames: loop {
+        let started = Instant::now();
+
+        for key in keys.try_iter() {
+            if key == CTRL_C || key.eq_ignore_ascii_case(&b'q') {
+                break 'frames;
+            }
+            bus.press_key(key);
+        }
+
+        for _ in 0..instructions_per_frame {
+            // the game ends with the BRK at the gameOver label
+            if bus.peek(cpu.pc()) == INS_BRK {
+                game_over = true;
+                break;
+            }
+            cpu.step(&mut bus);
+        }
+
+        if bus.screen() != shown.as_slice() {
+            shown = bus.screen().to_vec();
+            print!("\x1b[H{}", bus.render());
+            print!("W A S D to steer, Q to quit\r\n");
+            let _ = std::io::stdout().flush();
+        }
+
+        if game_over {
+            break;
+        }
+
+        if let Some(rest) = frame.checked_sub(started.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+
+    print!("\x1b[?25h"); // show the cursor
+    let _ = std::io::stdout().flush();
+    drop(raw_mode);
+    if game_over {
+        // snakeLength ($03) counts the bytes of the segment addresses
+        println!("Game over, {} segments long", bus.peek(0x03) / 2);
+    }
+}
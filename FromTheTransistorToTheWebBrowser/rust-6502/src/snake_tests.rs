@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod snake_tests {
+
+    use crate::snake::*;
+    use rust_6502::assembler::assemble;
+    use rust_6502::bus::Bus;
+    use rust_6502::disasm::{decode, disassemble};
+    use rust_6502::snapshot::{load_snapshot, save_snapshot};
+    use rust_6502::{Byte, Mem, CPU};
+
+    fn setup(bus: &mut SnakeBus) -> CPU {
+        let mut cpu = CPU::new();
+        load_snake(&mut cpu, bus);
+        cpu
+    }
+
+    fn run(cpu: &mut CPU, bus: &mut SnakeBus, instructions: usize) {
+        for _ in 0..instructions {
+            cpu.step(bus);
+        }
+    }
+
+    #[test]
+    fn every_read_of_fe_gives_a_new_random_byte() {
+        let mut bus = SnakeBus::new(42);
+
+        // when:
+        let randoms: Vec<Byte> = (0..16).map(|_| bus.read(SYS_RANDOM)).collect();
+
+        // then:
+        let mut distinct = randoms.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert!(distinct.len() > 8);
+        assert_eq!(bus.peek(SYS_RANDOM), randoms[15]);
+        assert_eq!(bus.peek(SYS_RANDOM), randoms[15]);
+    }
+
+    #[test]
+    fn wasd_keys_are_stored_at_ff_in_lower_case() {
+        let mut bus = SnakeBus::new(1);
+
+        // then:
+        assert!(bus.press_key(b'w'));
+        assert_eq!(bus.peek(SYS_LAST_KEY), 0x77);
+        assert!(bus.press_key(b'D'));
+        assert_eq!(bus.peek(SYS_LAST_KEY), 0x64);
+        assert!(!bus.press_key(b'x'));
+        assert_eq!(bus.peek(SYS_LAST_KEY), 0x64);
+    }
+
+    #[test]
+    fn the_screen_is_rendered_with_two_pixels_per_character() {
+        let mut bus = SnakeBus::new(1);
+
+        // given:
+        bus.write(SCREEN_START, 0x01); // white, top left
+        bus.write(SCREEN_START + 32, 0x12); // red, below it
+
+        // when:
+        let text = bus.render();
+
+        // then:
+        let lines: Vec<&str> = text.split("\r\n").filter(|l| !l.is_empty()).collect();
+        assert_eq!(lines.len(), 16);
+        assert!(lines[0].starts_with("\x1b[38;2;255;255;255m\x1b[48;2;136;0;0m\u{2580}"));
+        assert_eq!(lines[1].matches('\u{2580}').count(), 32);
+    }
+
+    #[test]
+    fn the_game_draws_the_snake_and_the_apple() {
+        let mut bus = SnakeBus::new(7);
+
+        // given:
+        let mut cpu = setup(&mut bus);
+
+        // when:
+        run(&mut cpu, &mut bus, 2000);
+
+        // then:
+        let pixels = bus.screen().iter().filter(|&&pixel| pixel != 0).count();
+        assert!(pixels >= 3, "{} pixels drawn", pixels);
+        assert_eq!(bus.peek(0x02), 2); // moving right
+    }
+
+    #[test]
+    fn the_keys_steer_the_snake() {
+        let mut bus = SnakeBus::new(7);
+
+        // given:
+        let mut cpu = setup(&mut bus);
+        run(&mut cpu, &mut bus, 2000);
+
+        // when:
+        bus.press_key(b's');
+        run(&mut cpu, &mut bus, 2000);
+
+        // then:
+        assert_eq!(bus.peek(0x02), 4); // moving down
+    }
+
+    #[test]
+    fn snake6502_assembles_to_the_embedded_image() {
+        // when:
+        let program = assemble(include_str!("snake6502.asm")).unwrap();
+
+        // then:
+        assert_eq!(program.origin, 0x0600);
+        assert_eq!(program.bytes, SNAKE6502.to_vec());
+        assert_eq!(program.symbol("loop"), Some(0x0638));
+        assert_eq!(program.symbol("gameOver"), Some(0x0735));
+        assert_eq!(program.symbol("sysRandom"), Some(0xFE));
+    }
+
+    #[test]
+    fn the_snake6502_image_disassembles_from_start_to_end() {
+        let mut mem = Mem::blank();
+        mem.load(0x0600, &SNAKE6502);
+
+        // when:
+        let instructions = disassemble(&mem, 0x0600, 0x0600 + SNAKE6502.len() as u16 - 1);
+
+        // then:
+        let line_at = |address: u16| {
+            instructions
+                .iter()
+                .find(|i| i.address == address)
+                .map(|i| i.text())
+                .unwrap()
+        };
+        assert_eq!(line_at(0x0600), "JSR $0606");
+        assert_eq!(line_at(0x060D), "LDA #$02");
+        assert_eq!(line_at(0x0651), "BEQ $0660");
+        assert_eq!(line_at(0x06BF), "JMP $0735");
+        assert_eq!(line_at(0x06CC), "BPL $06C7");
+        assert_eq!(line_at(0x071D), "STA ($00),Y");
+        assert_eq!(line_at(0x0724), "STA ($10,X)");
+        assert_eq!(line_at(0x0732), "BNE $072F");
+        assert!(instructions.iter().all(|i| decode(i.bytes[0]).is_some()));
+        assert_eq!(instructions.last().unwrap().next_address(), 0x0735);
+    }
+
+    #[test]
+    fn a_resumed_snake_game_draws_the_same_random_numbers() {
+        let mut bus = SnakeBus::new(1234);
+        let cpu = CPU::new();
+
+        // given:
+        bus.read(0x00FE);
+        let snapshot = save_snapshot(&cpu, &bus);
+        let expected: Vec<Byte> = (0..8).map(|_| bus.read(0x00FE)).collect();
+
+        // when:
+        let mut resumed = SnakeBus::new(1);
+        load_snapshot(&snapshot, &mut CPU::new(), &mut resumed).unwrap();
+
+        // then:
+        let actual: Vec<Byte> = (0..8).map(|_| resumed.read(0x00FE)).collect();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::bus::Bus;
+use crate::{Byte, Word};
+use crate::{CpuVariant, UnstableConstants, CPU};
+
+/// A snapshot file starts with the magic and the version of the format,
+/// followed by the CPU and then by whatever the bus saves. Numbers are
+/// little endian like on the 6502. Bump the version whenever the layout
+/// changes, old snapshots are rejected instead of being misread
+pub const SNAPSHOT_MAGIC: [Byte; 4] = *b"S502";
+pub const SNAPSHOT_VERSION: Word = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    /// not a snapshot at all
+    BadMagic,
+    /// written by another version of the format
+    UnsupportedVersion(Word),
+    /// the data ends in the middle of the snapshot
+    Truncated,
+    /// the data doesn't fit the CPU or the bus it is loaded into
+    Invalid(String),
+    Io(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::BadMagic => write!(f, "not a snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot version {} is not supported, expected version {}",
+                version, SNAPSHOT_VERSION
+            ),
+            SnapshotError::Truncated => write!(f, "the snapshot is truncated"),
+            SnapshotError::Invalid(message) => write!(f, "invalid snapshot: {}", message),
+            SnapshotError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+#[derive(Debug, Default)]
+pub struct SnapshotWriter {
+    pub data: Vec<Byte>,
+}
+
+impl SnapshotWriter {
+    pub fn new() -> Self {
+        SnapshotWriter { data: Vec::new() }
+    }
+
+    pub fn byte(&mut self, value: Byte) {
+        self.data.push(value);
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.data.push(value as Byte);
+    }
+
+    pub fn word(&mut self, value: Word) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// a block of bytes with its length in front
+    pub fn bytes(&mut self, values: &[Byte]) {
+        self.u32(values.len() as u32);
+        self.data.extend_from_slice(values);
+    }
+}
+
+pub struct SnapshotReader<'a> {
+    data: &'a [Byte],
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub fn new(data: &'a [Byte]) -> Self {
+        SnapshotReader { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [Byte], SnapshotError> {
+        if self.data.len() < length {
+            return Err(SnapshotError::Truncated);
+        }
+        let (taken, rest) = self.data.split_at(length);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    pub fn byte(&mut self) -> Result<Byte, SnapshotError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn bool(&mut self) -> Result<bool, SnapshotError> {
+        match self.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(SnapshotError::Invalid(format!(
+                "{:02X} is not a boolean",
+                value
+            ))),
+        }
+    }
+
+    pub fn word(&mut self) -> Result<Word, SnapshotError> {
+        let bytes = self.take(2)?;
+        Ok(Word::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, SnapshotError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, SnapshotError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [Byte], SnapshotError> {
+        let length = self.u32()? as usize;
+        self.take(length)
+    }
+}
+
+fn variant_to_byte(variant: CpuVariant) -> Byte {
+    match variant {
+        CpuVariant::Nmos6502 => 0,
+        CpuVariant::Wdc65C02 => 1,
+        CpuVariant::Rockwell65C02 => 2,
+    }
+}
+
+fn variant_from_byte(value: Byte) -> Result<CpuVariant, SnapshotError> {
+    match value {
+        0 => Ok(CpuVariant::Nmos6502),
+        1 => Ok(CpuVariant::Wdc65C02),
+        2 => Ok(CpuVariant::Rockwell65C02),
+        _ => Err(SnapshotError::Invalid(format!(
+            "unknown CPU variant {}",
+            value
+        ))),
+    }
+}
+
+fn save_cpu(cpu: &CPU, out: &mut SnapshotWriter) {
+    out.word(cpu.pc());
+    out.byte(cpu.sp());
+    out.byte(cpu.a());
+    out.byte(cpu.x());
+    out.byte(cpu.y());
+    out.byte(cpu.status());
+    out.bool(cpu.irq_line);
+    out.bool(cpu.nmi_line);
+    out.bool(cpu.nmi_pending);
+    out.bool(cpu.nmi_previous);
+    out.byte(variant_to_byte(cpu.variant));
+    out.bool(cpu.jammed);
+    out.bool(cpu.waiting);
+    out.byte(cpu.unstable.ane_magic);
+    out.byte(cpu.unstable.lxa_magic);
+    out.u64(cpu.total_cycles);
+}
+
+fn load_cpu(input: &mut SnapshotReader) -> Result<CPU, SnapshotError> {
+    let mut cpu = CPU::new();
+    cpu.set_pc(input.word()?);
+    cpu.set_sp(input.byte()?);
+    cpu.set_a(input.byte()?);
+    cpu.set_x(input.byte()?);
+    cpu.set_y(input.byte()?);
+    cpu.set_status(input.byte()?);
+    cpu.irq_line = input.bool()?;
+    cpu.nmi_line = input.bool()?;
+    cpu.nmi_pending = input.bool()?;
+    cpu.nmi_previous = input.bool()?;
+    cpu.variant = variant_from_byte(input.byte()?)?;
+    cpu.jammed = input.bool()?;
+    cpu.waiting = input.bool()?;
+    cpu.unstable = UnstableConstants {
+        ane_magic: input.byte()?,
+        lxa_magic: input.byte()?,
+    };
+    cpu.total_cycles = input.u64()?;
+    Ok(cpu)
+}
+
+/// The registers, flags, cycle counter and the state of the bus: the memory
+/// and the devices attached to it
+pub fn save_snapshot<M: Bus>(cpu: &CPU, memory: &M) -> Vec<Byte> {
+    let mut out = SnapshotWriter::new();
+    out.data.extend_from_slice(&SNAPSHOT_MAGIC);
+    out.word(SNAPSHOT_VERSION);
+    save_cpu(cpu, &mut out);
+    memory.save_state(&mut out);
+    out.data
+}
+
+/// Restores a snapshot into a bus built like the one it was saved from.
+/// The CPU is only changed when the whole snapshot could be read, the bus
+/// can be left half loaded by an invalid snapshot
+pub fn load_snapshot<M: Bus>(
+    data: &[Byte],
+    cpu: &mut CPU,
+    memory: &mut M,
+) -> Result<(), SnapshotError> {
+    let mut input = SnapshotReader::new(data);
+
+    let magic = input
+        .take(SNAPSHOT_MAGIC.len())
+        .map_err(|_| SnapshotError::BadMagic)?;
+    if magic != SNAPSHOT_MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let version: Word = input.word()?;
+    if version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    let loaded: CPU = load_cpu(&mut input)?;
+    memory.load_state(&mut input)?;
+    if !input.is_empty() {
+        return Err(SnapshotError::Invalid(
+            "unexpected data after the bus state".to_string(),
+        ));
+    }
+
+    *cpu = loaded;
+    Ok(())
+}
+
+pub fn save_snapshot_file<M: Bus, P: AsRef<Path>>(
+    path: P,
+    cpu: &CPU,
+    memory: &M,
+) -> Result<(), SnapshotError> {
+    fs::write(&path, save_snapshot(cpu, memory))
+        .map_err(|error| SnapshotError::Io(format!("{}: {}", path.as_ref().display(), error)))
+}
+
+pub fn load_snapshot_file<M: Bus, P: AsRef<Path>>(
+    path: P,
+    cpu: &mut CPU,
+    memory: &mut M,
+) -> Result<(), SnapshotError> {
+    let data = fs::read(&path)
+        .map_err(|error| SnapshotError::Io(format!("{}: {}", path.as_ref().display(), error)))?;
+    load_snapshot(&data, cpu, memory)
+}
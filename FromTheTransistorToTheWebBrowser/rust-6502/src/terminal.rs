@@ -0,0 +1,170 @@
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rust_6502::acia::{SerialBus, SerialPort};
+use rust_6502::apple1::Apple1Bus;
+use rust_6502::{Byte, CPU};
+
+// Puts the terminal in raw mode (every key arrives right away, nothing is
+// echoed) until it is dropped
+pub struct RawMode;
+
+impl RawMode {
+    pub fn enable() -> Self {
+        stty(&["raw", "-echo"]);
+        RawMode
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        stty(&["-raw", "echo"]);
+    }
+}
+
+fn stty(args: &[&str]) {
+    // without a terminal (e.g. piped input) the programs still run
+    let _ = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .status();
+}
+
+// Reads stdin on its own thread so the emulation never blocks on it
+pub fn keys() -> Receiver<Byte> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // unbuffered on purpose, every key has to arrive right away
+        #[allow(clippy::unbuffered_bytes)]
+        for key in std::io::stdin().bytes() {
+            match key {
+                Ok(key) if sender.send(key).is_ok() => {}
+                _ => break,
+            }
+        }
+    });
+    receiver
+}
+
+// Ctrl-] quits, like telnet: Ctrl-C belongs to the program (BASIC stops
+// with it)
+pub const QUIT_KEY: Byte = 0x1D;
+
+// The terminal the emulator runs in, put it in raw mode (RawMode)
+// so every key is sent right away
+pub struct StdioSerial {
+    keys: Receiver<Byte>,
+    quit: bool,
+}
+
+impl StdioSerial {
+    pub fn new() -> Self {
+        StdioSerial {
+            keys: keys(),
+            quit: false,
+        }
+    }
+}
+
+impl Default for StdioSerial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SerialPort for StdioSerial {
+    fn receive(&mut self) -> Option<Byte> {
+        match self.keys.try_recv() {
+            Ok(QUIT_KEY) => {
+                self.quit = true;
+                None
+            }
+            Ok(key) => Some(key),
+            Err(_) => None,
+        }
+    }
+
+    // a serial line doesn't stop the emulation, write errors are ignored
+    fn transmit(&mut self, value: Byte) {
+        let mut out = std::io::stdout();
+        let _ = out.write_all(&[value]);
+        let _ = out.flush();
+    }
+
+    fn closed(&self) -> bool {
+        self.quit
+    }
+}
+
+// Boots an Apple-1 with 8 KiB of RAM into Wozmon in the terminal, at about
+// the 1 MHz of the real one. paste is typed in first, e.g. a program in the
+// `0280: A9 01 ...` format of Wozmon. Ctrl-C quits
+pub fn run_apple1(paste: &str) {
+    const FRAMES_PER_SECOND: u32 = 60;
+    const CYCLES_PER_FRAME: isize = 1_000_000 / FRAMES_PER_SECOND as isize;
+    const CTRL_C: Byte = 0x03;
+
+    let mut bus = Apple1Bus::new(0x2000);
+    let mut cpu = CPU::new();
+    cpu.power_on(&mut bus);
+    bus.type_text(paste);
+
+    let frame = Duration::from_secs(1) / FRAMES_PER_SECOND;
+    let raw_mode = RawMode::enable();
+    let keys = keys();
+    print!("Apple-1 with Wozmon, Ctrl-C to quit\r\n");
+
+    'frames: loop {
+        let started = Instant::now();
+
+        for key in keys.try_iter() {
+            if key == CTRL_C {
+                break 'frames;
+            }
+            bus.press_key(key);
+        }
+
+        let mut cycles: isize = CYCLES_PER_FRAME;
+        cpu.execute(&mut cycles, &mut bus);
+
+        let output = bus.take_output();
+        if !output.is_empty() {
+            print!("{}", output.replace('\n', "\r\n"));
+            let _ = std::io::stdout().flush();
+        }
+
+        if let Some(rest) = frame.checked_sub(started.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+
+    drop(raw_mode);
+    println!();
+}
+
+// Runs the serial computer of acia.rs at 1 MHz until the port is closed (or
+// forever, a pty is never closed: the emulator stops with Ctrl-C in its own
+// terminal)
+pub fn run_serial_bus<S: SerialPort>(bus: &mut SerialBus<S>) {
+    const FRAMES_PER_SECOND: u32 = 60;
+    const CYCLES_PER_FRAME: isize = 1_000_000 / FRAMES_PER_SECOND as isize;
+
+    let mut cpu = CPU::new();
+    cpu.power_on(bus);
+    let frame = Duration::from_secs(1) / FRAMES_PER_SECOND;
+
+    while !bus.acia.port.closed() {
+        let started = Instant::now();
+
+        let mut cycles: isize = CYCLES_PER_FRAME;
+        cpu.execute(&mut cycles, bus);
+
+        if let Some(rest) = frame.checked_sub(started.elapsed()) {
+            thread::sleep(rest);
+        }
+    }
+}
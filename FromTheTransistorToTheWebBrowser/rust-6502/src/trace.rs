@@ -0,0 +1,203 @@
+use std::io::Write;
+
+use crate::disasm::format_operand;
+use crate::opcodes::AddressingMode;
+use crate::{Byte, Word};
+
+/// The state of the CPU right before it executes an instruction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TraceRecord {
+    pub pc: Word,
+    /// the opcode and its operand bytes, the first length of them are used
+    pub bytes: [Byte; 3],
+    pub length: usize,
+    pub mnemonic: &'static str,
+    pub mode: AddressingMode,
+    pub documented: bool,
+    pub a: Byte,
+    pub x: Byte,
+    pub y: Byte,
+    pub sp: Byte,
+    pub ps: Byte,
+    /// cycles executed since the reset
+    pub cycles: u64,
+}
+
+impl TraceRecord {
+    /// e.g. `LDA ($44),Y`
+    pub fn text(&self) -> String {
+        let (operand, _) = format_operand(
+            self.mnemonic,
+            self.mode,
+            self.pc,
+            &self.bytes[..self.length],
+        );
+        if operand.is_empty() {
+            self.mnemonic.to_string()
+        } else {
+            format!("{} {}", self.mnemonic, operand)
+        }
+    }
+}
+
+/// Receives every instruction the CPU executes, see CPU::execute_traced
+pub trait TraceSink {
+    fn trace(&mut self, record: &TraceRecord);
+
+    /// The CPU only builds the records when the sink wants them
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    /// Called before every instruction, enabled or not, with its address
+    /// and opcode. Cheaper than a record for sinks that only need those
+    fn instruction(&mut self, _pc: Word, _opcode: Byte) {}
+}
+
+/// The default sink, it is disabled so tracing costs nothing
+pub struct NoTrace;
+
+impl TraceSink for NoTrace {
+    fn trace(&mut self, _record: &TraceRecord) {}
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+/// A line in the format of nestest.log, the log of the nestest ROM:
+///
+///  C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7
+///
+/// Undocumented opcodes get a `*` before the mnemonic. B and U are not real
+/// flags, P shows them the way nestest does: U set and B clear. The PPU
+/// column and the `= value` annotations of the memory operands are left out
+pub fn nestest_line(record: &TraceRecord) -> String {
+    let bytes: Vec<String> = record.bytes[..record.length]
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    let marker: char = if record.documented { ' ' } else { '*' };
+    // nestest.log knows ISC by its other name
+    let text: String = match record.mnemonic {
+        "ISC" => record.text().replacen("ISC", "ISB", 1),
+        _ => record.text(),
+    };
+    let p: Byte = (record.ps | 0b00100000) & !0b00010000;
+
+    format!(
+        "{:04X}  {:<8} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+        record.pc,
+        bytes.join(" "),
+        marker,
+        text,
+        record.a,
+        record.x,
+        record.y,
+        p,
+        record.sp,
+        record.cycles
+    )
+}
+
+/// Writes a nestest line for every instruction
+pub struct NestestLog<W: Write> {
+    pub out: W,
+}
+
+impl<W: Write> NestestLog<W> {
+    pub fn new(out: W) -> Self {
+        NestestLog { out }
+    }
+}
+
+impl<W: Write> TraceSink for NestestLog<W> {
+    fn trace(&mut self, record: &TraceRecord) {
+        // a trace must not stop the emulation, write errors are ignored
+        let _ = writeln!(self.out, "{}", nestest_line(record));
+    }
+}
+
+/// One instruction in the ring buffer, plain bytes without strings. Decode
+/// `bytes[0]` with the opcode tables to get the mnemonic back
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TraceEntry {
+    pub cycles: u64,
+    pub pc: Word,
+    pub bytes: [Byte; 3],
+    pub a: Byte,
+    pub x: Byte,
+    pub y: Byte,
+    pub sp: Byte,
+    pub ps: Byte,
+}
+
+/// Keeps the last capacity instructions, handy to find out how the CPU got
+/// somewhere after a crash or a JAM
+pub struct TraceRing {
+    entries: Vec<TraceEntry>,
+    // where the next entry goes
+    next: usize,
+    full: bool,
+}
+
+impl TraceRing {
+    pub fn new(capacity: usize) -> Self {
+        TraceRing {
+            entries: vec![TraceEntry::default(); capacity.max(1)],
+            next: 0,
+            full: false,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        if self.full {
+            self.entries.len()
+        } else {
+            self.next
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.next = 0;
+        self.full = false;
+    }
+
+    /// the entries from the oldest to the newest
+    pub fn entries(&self) -> Vec<TraceEntry> {
+        if self.full {
+            let (newest, oldest) = self.entries.split_at(self.next);
+            oldest.iter().chain(newest.iter()).copied().collect()
+        } else {
+            self.entries[..self.next].to_vec()
+        }
+    }
+}
+
+impl TraceSink for TraceRing {
+    fn trace(&mut self, record: &TraceRecord) {
+        self.entries[self.next] = TraceEntry {
+            cycles: record.cycles,
+            pc: record.pc,
+            bytes: record.bytes,
+            a: record.a,
+            x: record.x,
+            y: record.y,
+            sp: record.sp,
+            ps: record.ps,
+        };
+        self.next += 1;
+        if self.next == self.entries.len() {
+            self.next = 0;
+            self.full = true;
+        }
+    }
+}
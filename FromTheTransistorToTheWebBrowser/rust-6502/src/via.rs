@@ -0,0 +1,519 @@
+use crate::bus::Bus;
+use crate::snapshot::{SnapshotError, SnapshotReader, SnapshotWriter};
+use crate::{Byte, Word};
+
+// The registers, at these offsets from the base address of the VIA
+pub const ORB: Word = 0x0; // port B
+pub const ORA: Word = 0x1; // port A, with the handshake
+pub const DDRB: Word = 0x2;
+pub const DDRA: Word = 0x3;
+pub const T1CL: Word = 0x4; // timer 1 counter, writes go to the latch
+pub const T1CH: Word = 0x5;
+pub const T1LL: Word = 0x6; // timer 1 latch
+pub const T1LH: Word = 0x7;
+pub const T2CL: Word = 0x8; // timer 2 counter, writes go to the latch
+pub const T2CH: Word = 0x9;
+pub const SR: Word = 0xA;
+pub const ACR: Word = 0xB;
+pub const PCR: Word = 0xC;
+pub const IFR: Word = 0xD;
+pub const IER: Word = 0xE;
+pub const ORA_NO_HANDSHAKE: Word = 0xF;
+
+// The bits of the interrupt flag and enable registers
+pub const IRQ_CA2: Byte = 0x01;
+pub const IRQ_CA1: Byte = 0x02;
+pub const IRQ_SR: Byte = 0x04;
+pub const IRQ_CB2: Byte = 0x08;
+pub const IRQ_CB1: Byte = 0x10;
+pub const IRQ_T2: Byte = 0x20;
+pub const IRQ_T1: Byte = 0x40;
+/// IFR: any enabled flag is set. IER: writing 1 sets the bits written as 1,
+/// writing 0 clears them
+pub const IRQ_ANY: Byte = 0x80;
+
+// The bits of the auxiliary control register
+pub const ACR_PA_LATCH: Byte = 0x01;
+pub const ACR_PB_LATCH: Byte = 0x02;
+/// bits 2-4, see ShiftMode
+pub const ACR_SHIFT_MASK: Byte = 0x1C;
+/// 0: timer 2 is a one-shot timer, 1: it counts the pulses on PB6
+pub const ACR_T2_PULSES: Byte = 0x20;
+/// 0: timer 1 is a one-shot timer, 1: it runs free, reloaded from its latch
+pub const ACR_T1_FREE_RUN: Byte = 0x40;
+/// timer 1 drives PB7: low until the timeout in one-shot mode, inverted at
+/// every timeout when running free
+pub const ACR_T1_PB7: Byte = 0x80;
+
+// The bits of the peripheral control register
+/// 0: the C1 line flags falling edges, 1: rising edges
+pub const PCR_CA1_RISING: Byte = 0x01;
+pub const PCR_CB1_RISING: Byte = 0x10;
+
+/// What the shift register does, bits 2-4 of the ACR. The bits go out on
+/// CB2 (bit 7 first, they come back in bit 0 so the byte is kept) or come in
+/// from it, clocked by timer 2 (a bit every 2 * (T2 latch low + 2) cycles),
+/// by the clock (a bit every 2 cycles) or by the rising edges on CB1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShiftMode {
+    Disabled,
+    InT2,
+    InClock,
+    InExternal,
+    /// shifts out forever at the rate of timer 2, without interrupts
+    OutFreeRunning,
+    OutT2,
+    OutClock,
+    OutExternal,
+}
+
+impl ShiftMode {
+    fn from_acr(acr: Byte) -> ShiftMode {
+        match (acr & ACR_SHIFT_MASK) >> 2 {
+            0 => ShiftMode::Disabled,
+            1 => ShiftMode::InT2,
+            2 => ShiftMode::InClock,
+            3 => ShiftMode::InExternal,
+            4 => ShiftMode::OutFreeRunning,
+            5 => ShiftMode::OutT2,
+            6 => ShiftMode::OutClock,
+            _ => ShiftMode::OutExternal,
+        }
+    }
+
+    fn shifts_out(self) -> bool {
+        matches!(
+            self,
+            ShiftMode::OutFreeRunning
+                | ShiftMode::OutT2
+                | ShiftMode::OutClock
+                | ShiftMode::OutExternal
+        )
+    }
+
+    fn external(self) -> bool {
+        matches!(self, ShiftMode::InExternal | ShiftMode::OutExternal)
+    }
+}
+
+/// The MOS 6522 Versatile Interface Adapter: two 8 bit ports with their
+/// data direction registers, two 16 bit timers, a shift register and the
+/// interrupt registers, seen through the 16 addresses above. Map it at any
+/// base address of an AddressDecoder, it counts the cycles the CPU hands to
+/// tick() and pulls IRQ while an enabled flag is set.
+///
+/// Timer 1 flags its timeout N + 1 cycles after N is written to T1CH, and
+/// every N + 2 cycles when running free. Timer 2 flags it once, N + 1
+/// cycles after the write to T2CH, or after N pulses on PB6. Both keep
+/// counting down after the timeout. The CA2/CB2 handshakes are not emulated,
+/// CB2 is only the data line of the shift register
+#[derive(Debug, Clone, PartialEq)]
+pub struct Via6522 {
+    pub output_a: Byte,
+    pub ddr_a: Byte,
+    pub output_b: Byte,
+    pub ddr_b: Byte,
+    /// the levels the devices wired to the ports drive on the input pins
+    pub input_a: Byte,
+    pub input_b: Byte,
+
+    pub t1_counter: Word,
+    pub t1_latch: Word,
+    pub t2_counter: Word,
+    pub t2_latch_low: Byte,
+    pub shift: Byte,
+    pub acr: Byte,
+    pub pcr: Byte,
+    pub ifr: Byte,
+    pub ier: Byte,
+
+    /// the level of CB2: driven by the VIA when shifting out, by the device
+    /// when shifting in
+    pub cb2: bool,
+
+    // the ports at the last active C1 edge, when the ACR latches them
+    latch_a: Byte,
+    latch_b: Byte,
+    // the timers flag their next timeout (timer 1 always does when running
+    // free)
+    t1_armed: bool,
+    t2_armed: bool,
+    // timer 1 went through 0, it takes the latch at the next cycle
+    t1_reload: bool,
+    pb7: bool,
+    // the bits shifted since the shift register was last read or written,
+    // and the cycles since the last one
+    shift_bits: u8,
+    shift_cycles: u32,
+    ca1: bool,
+    cb1: bool,
+    pb6: bool,
+}
+
+impl Via6522 {
+    /// after a reset the ports are inputs, the timers and the shift register
+    /// are stopped and the interrupts are disabled. The counters and latches
+    /// are not reset on a real chip, they start at 0 here
+    pub fn new() -> Self {
+        Via6522 {
+            output_a: 0,
+            ddr_a: 0,
+            output_b: 0,
+            ddr_b: 0,
+            input_a: 0,
+            input_b: 0,
+            t1_counter: 0,
+            t1_latch: 0,
+            t2_counter: 0,
+            t2_latch_low: 0,
+            shift: 0,
+            acr: 0,
+            pcr: 0,
+            ifr: 0,
+            ier: 0,
+            cb2: false,
+            latch_a: 0,
+            latch_b: 0,
+            t1_armed: false,
+            t2_armed: false,
+            t1_reload: false,
+            pb7: true,
+            shift_bits: 8,
+            shift_cycles: 0,
+            ca1: false,
+            cb1: false,
+            pb6: false,
+        }
+    }
+
+    /// The levels on the pins: the output register where the pin is an
+    /// output, the device where it is an input. Timer 1 can take over PB7
+    pub fn port_a(&self) -> Byte {
+        (self.output_a & self.ddr_a) | (self.input_a & !self.ddr_a)
+    }
+
+    pub fn port_b(&self) -> Byte {
+        let port: Byte = (self.output_b & self.ddr_b) | (self.input_b & !self.ddr_b);
+        if self.acr & ACR_T1_PB7 != 0 {
+            (port & 0x7F) | if self.pb7 { 0x80 } else { 0 }
+        } else {
+            port
+        }
+    }
+
+    pub fn set_ca1(&mut self, level: bool) {
+        if self.ca1 != level && level == (self.pcr & PCR_CA1_RISING != 0) {
+            self.ifr |= IRQ_CA1;
+            self.latch_a = self.port_a();
+        }
+        self.ca1 = level;
+    }
+
+    /// CB1 is also the clock of the shift register in the external modes
+    pub fn set_cb1(&mut self, level: bool) {
+        if self.cb1 != level && level == (self.pcr & PCR_CB1_RISING != 0) {
+            self.ifr |= IRQ_CB1;
+            self.latch_b = self.port_b();
+        }
+        if !self.cb1 && level && self.shift_mode().external() {
+            self.shift_bit();
+        }
+        self.cb1 = level;
+    }
+
+    /// Timer 2 counts the falling edges on PB6 when the ACR says so
+    pub fn set_pb6(&mut self, level: bool) {
+        if self.pb6 && !level && self.acr & ACR_T2_PULSES != 0 {
+            self.t2_counter = self.t2_counter.wrapping_sub(1);
+            if self.t2_counter == 0 && self.t2_armed {
+                self.ifr |= IRQ_T2;
+                self.t2_armed = false;
+            }
+        }
+        self.pb6 = level;
+    }
+
+    pub fn shift_mode(&self) -> ShiftMode {
+        ShiftMode::from_acr(self.acr)
+    }
+
+    // One cycle of the clock
+    fn clock(&mut self) {
+        if self.t1_reload {
+            self.t1_counter = self.t1_latch;
+            self.t1_reload = false;
+        } else if self.t1_counter == 0 {
+            self.timer1_timeout();
+        } else {
+            self.t1_counter -= 1;
+        }
+
+        if self.acr & ACR_T2_PULSES == 0 {
+            if self.t2_counter == 0 && self.t2_armed {
+                self.ifr |= IRQ_T2;
+                self.t2_armed = false;
+            }
+            self.t2_counter = self.t2_counter.wrapping_sub(1);
+        }
+
+        let period: u32 = match self.shift_mode() {
+            ShiftMode::InClock | ShiftMode::OutClock => 2,
+            ShiftMode::InT2 | ShiftMode::OutT2 | ShiftMode::OutFreeRunning => {
+                2 * (self.t2_latch_low as u32 + 2)
+            }
+            ShiftMode::Disabled | ShiftMode::InExternal | ShiftMode::OutExternal => return,
+        };
+        self.shift_cycles += 1;
+        if self.shift_cycles >= period {
+            self.shift_cycles = 0;
+            self.shift_bit();
+        }
+    }
+
+    fn timer1_timeout(&mut self) {
+        let free_running: bool = self.acr & ACR_T1_FREE_RUN != 0;
+        if self.t1_armed {
+            self.ifr |= IRQ_T1;
+            if free_running {
+                self.pb7 = !self.pb7;
+            } else {
+                self.pb7 = true;
+                self.t1_armed = false;
+            }
+        }
+        self.t1_counter = 0xFFFF;
+        self.t1_reload = free_running;
+    }
+
+    // Shifts a bit out to CB2 or in from CB2, the shift register flags the
+    // eighth one and stops, unless it runs free
+    fn shift_bit(&mut self) {
+        let mode: ShiftMode = self.shift_mode();
+        if mode == ShiftMode::Disabled
+            || (self.shift_bits >= 8 && mode != ShiftMode::OutFreeRunning)
+        {
+            return;
+        }
+
+        if mode.shifts_out() {
+            self.cb2 = self.shift & 0x80 != 0;
+            self.shift = self.shift.rotate_left(1);
+        } else {
+            self.shift = (self.shift << 1) | self.cb2 as Byte;
+        }
+
+        if mode != ShiftMode::OutFreeRunning {
+            self.shift_bits += 1;
+            if self.shift_bits == 8 {
+                self.ifr |= IRQ_SR;
+            }
+        }
+    }
+
+    // Reading or writing the shift register starts 8 more bits
+    fn restart_shift(&mut self) {
+        self.ifr &= !IRQ_SR;
+        self.shift_bits = 0;
+        self.shift_cycles = 0;
+    }
+
+    fn input_a(&self) -> Byte {
+        if self.acr & ACR_PA_LATCH != 0 {
+            self.latch_a
+        } else {
+            self.port_a()
+        }
+    }
+
+    // the output bits read the output register, not the pins
+    fn input_b(&self) -> Byte {
+        let pins: Byte = if self.acr & ACR_PB_LATCH != 0 {
+            self.latch_b
+        } else {
+            self.port_b()
+        };
+        let outputs: Byte = if self.acr & ACR_T1_PB7 != 0 {
+            self.ddr_b & 0x7F
+        } else {
+            self.ddr_b
+        };
+        (self.output_b & outputs) | (pins & !outputs)
+    }
+
+    fn register(&self, address: Word) -> Byte {
+        match address & 0x0F {
+            ORB => self.input_b(),
+            ORA | ORA_NO_HANDSHAKE => self.input_a(),
+            DDRB => self.ddr_b,
+            DDRA => self.ddr_a,
+            T1CL => self.t1_counter as Byte,
+            T1CH => (self.t1_counter >> 8) as Byte,
+            T1LL => self.t1_latch as Byte,
+            T1LH => (self.t1_latch >> 8) as Byte,
+            T2CL => self.t2_counter as Byte,
+            T2CH => (self.t2_counter >> 8) as Byte,
+            SR => self.shift,
+            ACR => self.acr,
+            PCR => self.pcr,
+            IFR if self.ifr & self.ier & 0x7F != 0 => self.ifr | IRQ_ANY,
+            IFR => self.ifr,
+            _ => self.ier | IRQ_ANY,
+        }
+    }
+}
+
+impl Default for Via6522 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus for Via6522 {
+    fn read(&mut self, address: Word) -> Byte {
+        let value: Byte = self.register(address);
+        match address & 0x0F {
+            ORB => self.ifr &= !(IRQ_CB1 | IRQ_CB2),
+            ORA => self.ifr &= !(IRQ_CA1 | IRQ_CA2),
+            T1CL => self.ifr &= !IRQ_T1,
+            T2CL => self.ifr &= !IRQ_T2,
+            SR => self.restart_shift(),
+            _ => {}
+        }
+        value
+    }
+
+    fn write(&mut self, address: Word, value: Byte) {
+        match address & 0x0F {
+            ORB => {
+                self.output_b = value;
+                self.ifr &= !(IRQ_CB1 | IRQ_CB2);
+            }
+            ORA => {
+                self.output_a = value;
+                self.ifr &= !(IRQ_CA1 | IRQ_CA2);
+            }
+            ORA_NO_HANDSHAKE => self.output_a = value,
+            DDRB => self.ddr_b = value,
+            DDRA => self.ddr_a = value,
+            T1CL | T1LL => self.t1_latch = (self.t1_latch & 0xFF00) | value as Word,
+            T1LH => {
+                self.t1_latch = (self.t1_latch & 0x00FF) | (value as Word) << 8;
+                self.ifr &= !IRQ_T1;
+            }
+            // starts timer 1, PB7 goes low until the timeout
+            T1CH => {
+                self.t1_latch = (self.t1_latch & 0x00FF) | (value as Word) << 8;
+                self.t1_counter = self.t1_latch;
+                self.t1_reload = false;
+                self.t1_armed = true;
+                self.pb7 = false;
+                self.ifr &= !IRQ_T1;
+            }
+            T2CL => self.t2_latch_low = value,
+            T2CH => {
+                self.t2_counter = (value as Word) << 8 | self.t2_latch_low as Word;
+                self.t2_armed = true;
+                self.ifr &= !IRQ_T2;
+            }
+            SR => {
+                self.shift = value;
+                self.restart_shift();
+            }
+            ACR => self.acr = value,
+            PCR => self.pcr = value,
+            IFR => self.ifr &= !(value & 0x7F),
+            _ => {
+                if value & IRQ_ANY != 0 {
+                    self.ier |= value & 0x7F;
+                } else {
+                    self.ier &= !value;
+                }
+            }
+        }
+    }
+
+    fn peek(&self, address: Word) -> Byte {
+        self.register(address)
+    }
+
+    fn poke(&mut self, _address: Word, _value: Byte) {}
+
+    fn irq(&self) -> bool {
+        self.ifr & self.ier & 0x7F != 0
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        for _ in 0..cycles {
+            self.clock();
+        }
+    }
+
+    fn save_state(&self, out: &mut SnapshotWriter) {
+        for register in [
+            self.output_a,
+            self.ddr_a,
+            self.output_b,
+            self.ddr_b,
+            self.input_a,
+            self.input_b,
+            self.t2_latch_low,
+            self.shift,
+            self.acr,
+            self.pcr,
+            self.ifr,
+            self.ier,
+            self.latch_a,
+            self.latch_b,
+            self.shift_bits,
+        ] {
+            out.byte(register);
+        }
+        out.word(self.t1_counter);
+        out.word(self.t1_latch);
+        out.word(self.t2_counter);
+        out.u32(self.shift_cycles);
+        for line in [
+            self.cb2,
+            self.t1_armed,
+            self.t2_armed,
+            self.t1_reload,
+            self.pb7,
+            self.ca1,
+            self.cb1,
+            self.pb6,
+        ] {
+            out.bool(line);
+        }
+    }
+
+    fn load_state(&mut self, input: &mut SnapshotReader) -> Result<(), SnapshotError> {
+        self.output_a = input.byte()?;
+        self.ddr_a = input.byte()?;
+        self.output_b = input.byte()?;
+        self.ddr_b = input.byte()?;
+        self.input_a = input.byte()?;
+        self.input_b = input.byte()?;
+        self.t2_latch_low = input.byte()?;
+        self.shift = input.byte()?;
+        self.acr = input.byte()?;
+        self.pcr = input.byte()?;
+        self.ifr = input.byte()?;
+        self.ier = input.byte()?;
+        self.latch_a = input.byte()?;
+        self.latch_b = input.byte()?;
+        self.shift_bits = input.byte()?;
+        self.t1_counter = input.word()?;
+        self.t1_latch = input.word()?;
+        self.t2_counter = input.word()?;
+        self.shift_cycles = input.u32()?;
+        self.cb2 = input.bool()?;
+        self.t1_armed = input.bool()?;
+        self.t2_armed = input.bool()?;
+        self.t1_reload = input.bool()?;
+        self.pb7 = input.bool()?;
+        self.ca1 = input.bool()?;
+        self.cb1 = input.bool()?;
+        self.pb6 = input.bool()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+; Wozmon, the monitor of the Apple-1 by Steve Wozniak (1976), in the 256
+; bytes of ROM at $FF00. It reads lines from the keyboard of the PIA and
+; understands three kinds of commands (hex numbers, no $):
+;
+;   FF00          examine a byte
+;   FF00.FF0F     examine a range
+;   0280: A9 01   deposit bytes
+;   0280R         run from an address
+
+define XAML     $24     ; last "opened" location, low byte
+define XAMH     $25     ; last "opened" location, high byte
+define STL      $26     ; store address, low byte
+define STH      $27     ; store address, high byte
+define L        $28     ; hex value parsing, low byte
+define H        $29     ; hex value parsing, high byte
+define YSAV     $2A     ; used to see if a hex value was given
+define MODE     $2B     ; $00 = examine, $7F = store, $AE = block examine
+
+define IN       $0200   ; the input buffer
+
+define KBD      $D010   ; PIA port A data: the keyboard
+define KBDCR    $D011   ; PIA port A control
+define DSP      $D012   ; PIA port B data: the display
+define DSPCR    $D013   ; PIA port B control
+
+        .org $FF00
+
+RESET:      CLD             ; clear decimal arithmetic mode
+            CLI
+            LDY #$7F        ; mask for the DSP data direction register
+            STY DSP         ; set it up
+            LDA #$A7        ; KBD and DSP control register mask
+            STA KBDCR       ; enable interrupts, set CA1, CB1 for
+            STA DSPCR       ; positive edge sense / output mode
+NOTCR:      CMP #$DF        ; "_"?
+            BEQ BACKSPACE   ; yes
+            CMP #$9B        ; ESC?
+            BEQ ESCAPE      ; yes
+            INY             ; advance the text index
+            BPL NEXTCHAR    ; auto ESC if > 127
+ESCAPE:     LDA #$DC        ; "\"
+            JSR ECHO        ; output it
+GETLINE:    LDA #$8D        ; CR
+            JSR ECHO        ; output it
+            LDY #$01        ; initialize the text index
+BACKSPACE:  DEY             ; back up the text index
+            BMI GETLINE     ; beyond the start of the line, reinitialize
+NEXTCHAR:   LDA KBDCR       ; key ready?
+            BPL NEXTCHAR    ; loop until ready
+            LDA KBD         ; load the character, B7 should be 1
+            STA IN,Y        ; add it to the text buffer
+            JSR ECHO        ; display it
+            CMP #$8D        ; CR?
+            BNE NOTCR       ; no
+            LDY #$FF        ; reset the text index
+            LDA #$00        ; for XAM mode
+            TAX             ; 0 -> X
+SETSTOR:    ASL             ; leaves $7B if setting STOR mode
+SETMODE:    STA MODE        ; $00 = XAM, $7B = STOR, $AE = BLOK XAM
+BLSKIP:     INY             ; advance the text index
+NEXTITEM:   LDA IN,Y        ; get a character
+            CMP #$8D        ; CR?
+            BEQ GETLINE     ; yes, done with this line
+            CMP #$AE        ; "."?
+            BCC BLSKIP      ; skip delimiter
+            BEQ SETMODE     ; set BLOCK XAM mode
+            CMP #$BA        ; ":"?
+            BEQ SETSTOR     ; yes, set STOR mode
+            CMP #$D2        ; "R"?
+            BEQ RUN         ; yes, run the user program
+            STX L           ; $00 -> L
+            STX H           ;    and H
+            STY YSAV        ; save Y for comparison
+NEXTHEX:    LDA IN,Y        ; get a character for hex test
+            EOR #$B0        ; map digits to $0-9
+            CMP #$0A        ; digit?
+            BCC DIG         ; yes
+            ADC #$88        ; map letters "A"-"F" to $FA-FF
+            CMP #$FA        ; hex letter?
+            BCC NOTHEX      ; no, character not hex
+DIG:        ASL
+            ASL             ; hex digit to the MSD of A
+            ASL
+            ASL
+            LDX #$04        ; shift count
+HEXSHIFT:   ASL             ; hex digit left, MSB to carry
+            ROL L           ; rotate into the LSD
+            ROL H           ; rotate into the MSD
+            DEX             ; done 4 shifts?
+            BNE HEXSHIFT    ; no, loop
+            INY             ; advance the text index
+            BNE NEXTHEX     ; always taken, check the next character
+NOTHEX:     CPY YSAV        ; check if L, H empty (no hex digits)
+            BEQ ESCAPE      ; yes, generate ESC sequence
+            BIT MODE        ; test the MODE byte
+            BVC NOTSTOR     ; B6 = 0 for STOR, 1 for XAM and BLOCK XAM
+            LDA L           ; LSD of the hex data
+            STA (STL,X)     ; store at the current store index
+            INC STL         ; increment the store index
+            BNE NEXTITEM    ; get the next item (no carry)
+            INC STH         ; add carry to the store index high order
+TONEXTITEM: JMP NEXTITEM    ; get the next command item
+RUN:        JMP (XAML)      ; run at the current XAM index
+NOTSTOR:    BMI XAMNEXT     ; B7 = 0 for XAM, 1 for BLOCK XAM
+            LDX #$02        ; byte count
+SETADR:     LDA L-1,X       ; copy the hex data to
+            STA STL-1,X     ;  the store index
+            STA XAML-1,X    ;  and to the XAM index
+            DEX             ; next of 2 bytes
+            BNE SETADR      ; loop unless X = 0
+NXTPRNT:    BNE PRDATA      ; NE means no address to print
+            LDA #$8D        ; CR
+            JSR ECHO        ; output it
+            LDA XAMH        ; examine index high order byte
+            JSR PRBYTE      ; output it in hex format
+            LDA XAML        ; low order examine index byte
+            JSR PRBYTE      ; output it in hex format
+            LDA #$BA        ; ":"
+            JSR ECHO        ; output it
+PRDATA:     LDA #$A0        ; blank
+            JSR ECHO        ; output it
+            LDA (XAML,X)    ; get the data byte at the examine index
+            JSR PRBYTE      ; output it in hex format
+XAMNEXT:    STX MODE        ; 0 -> MODE (XAM mode)
+            LDA XAML
+            CMP L           ; compare the examine index to the hex data
+            LDA XAMH
+            SBC H
+            BCS TONEXTITEM  ; not less, no more data to output
+            INC XAML
+            BNE MOD8CHK     ; increment the examine index
+            INC XAMH
+MOD8CHK:    LDA XAML        ; check the low order examine index byte
+            AND #$07        ;  for MOD 8 = 0
+            BPL NXTPRNT     ; always taken
+PRBYTE:     PHA             ; save A for the LSD
+            LSR
+            LSR
+            LSR             ; MSD to the LSD position
+            LSR
+            JSR PRHEX       ; output the hex digit
+            PLA             ; restore A
+PRHEX:      AND #$0F        ; mask the LSD for the hex print
+            ORA #$B0        ; add "0"
+            CMP #$BA        ; digit?
+            BCC ECHO        ; yes, output it
+            ADC #$06        ; add the offset for a letter
+ECHO:       BIT DSP         ; DA bit (B7) cleared yet?
+            BMI ECHO        ; no, wait for the display
+            STA DSP         ; output the character
+            RTS             ; return
+
+            BRK             ; unused
+            BRK             ; unused
+
+            .word $0F00     ; NMI
+            .word RESET     ; RESET
+            .word $0000     ; IRQ
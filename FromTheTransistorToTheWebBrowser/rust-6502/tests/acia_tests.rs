@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod acia_tests {
+
+    use rust_6502::acia::*;
+    use rust_6502::bus::Bus;
+    use rust_6502::{Byte, CPU};
+
+    // 9600 baud, 8 data bits, no parity, 1 stop bit: 10 bits of 104 cycles
+    // at 1 MHz
+    const CONTROL_9600_8N1: Byte = 0x1E;
+    const FRAME: u64 = 1_000_000 * 10 / 9600;
+
+    fn acia(input: &str, command: Byte) -> Acia6551<BufferedSerial> {
+        let mut acia = Acia6551::new(BufferedSerial::new(input));
+        acia.write(CONTROL, CONTROL_9600_8N1);
+        acia.write(COMMAND, command);
+        acia
+    }
+
+    #[test]
+    fn a_reset_leaves_the_transmit_register_empty_and_the_interrupts_off() {
+        let mut acia = acia("", 0xE5);
+
+        // when: a programmed reset
+        acia.write(STATUS, 0x00);
+
+        // then: the parity bits stay, a byte takes a bit more
+        assert_eq!(acia.read(STATUS), STATUS_TDRE);
+        assert_eq!(acia.read(COMMAND), 0xE0 | COMMAND_RX_IRQ_DISABLE);
+        assert_eq!(acia.read(CONTROL), CONTROL_9600_8N1);
+        assert_eq!(acia.frame_cycles(), 1_000_000 * 11 / 9600);
+    }
+
+    #[test]
+    fn the_bytes_are_sent_at_the_baud_rate() {
+        let mut acia = acia("", 0x0B);
+
+        // when:
+        acia.write(DATA, b'A');
+        let first_status = acia.peek(STATUS);
+        acia.write(DATA, b'B');
+        acia.tick(FRAME - 1);
+
+        // then: A is on the line, B waits
+        assert_eq!(first_status & STATUS_TDRE, STATUS_TDRE);
+        assert_eq!(acia.peek(STATUS) & STATUS_TDRE, 0);
+        assert!(acia.port.output.is_empty());
+
+        // when:
+        acia.tick(1);
+
+        // then:
+        assert_eq!(acia.port.output, b"A");
+        assert_eq!(acia.peek(STATUS) & STATUS_TDRE, STATUS_TDRE);
+
+        // when:
+        acia.tick(FRAME);
+
+        // then:
+        assert_eq!(acia.port.output, b"AB");
+    }
+
+    #[test]
+    fn a_received_byte_interrupts_until_the_status_is_read() {
+        // DTR, receiver interrupt on, transmitter on without its interrupt
+        let mut acia = acia("HI", 0x09);
+
+        // when:
+        acia.tick(1);
+
+        // then:
+        assert!(acia.irq());
+        assert_eq!(acia.read(STATUS), STATUS_IRQ | STATUS_TDRE | STATUS_RDRF);
+        assert!(!acia.irq());
+        assert_eq!(acia.read(DATA), b'H');
+        assert_eq!(acia.peek(STATUS) & STATUS_RDRF, 0);
+
+        // when: the next byte takes a frame
+        acia.tick(FRAME - 1);
+
+        // then:
+        assert_eq!(acia.peek(STATUS) & STATUS_RDRF, 0);
+
+        // when:
+        acia.tick(1);
+
+        // then:
+        assert_eq!(acia.read(DATA), b'I');
+    }
+
+    #[test]
+    fn the_transmitter_interrupts_when_its_register_is_free_again() {
+        // DTR, receiver interrupt off, transmitter interrupt on
+        let mut acia = acia("", 0x07);
+
+        // when:
+        acia.write(DATA, b'A');
+
+        // then:
+        assert!(acia.irq());
+        assert_eq!(acia.read(STATUS) & STATUS_IRQ, STATUS_IRQ);
+        assert!(!acia.irq());
+
+        // when:
+        acia.write(DATA, b'B');
+        acia.tick(FRAME);
+
+        // then:
+        assert!(acia.irq());
+        assert_eq!(acia.port.output, b"A");
+    }
+
+    #[test]
+    fn the_receiver_waits_for_dtr() {
+        let mut acia = acia("X", 0x02);
+
+        // when:
+        acia.tick(FRAME);
+
+        // then:
+        assert_eq!(acia.peek(STATUS) & STATUS_RDRF, 0);
+        assert_eq!(acia.port.input.len(), 1);
+    }
+
+    // A ROM at $FF00 that echoes what it receives, polling the status:
+    //
+    //  FF00  LDA #$1E / STA $8003    9600 8N1
+    //  FF05  LDA #$0B / STA $8002    DTR, no interrupts
+    //  FF0A  LDA $8001 / AND #$08 / BEQ $FF0A
+    //  FF11  LDA $8000 / TAX
+    //  FF15  LDA $8001 / AND #$10 / BEQ $FF15
+    //  FF1C  STX $8000
+    //  FF1F  JMP $FF0A
+    #[test]
+    fn a_program_echoes_the_terminal() {
+        let mut rom: Vec<Byte> = vec![0; 0x100];
+        rom[..0x22].copy_from_slice(&[
+            0xA9, 0x1E, 0x8D, 0x03, 0x80, 0xA9, 0x0B, 0x8D, 0x02, 0x80, 0xAD, 0x01, 0x80, 0x29,
+            0x08, 0xF0, 0xF9, 0xAD, 0x00, 0x80, 0xAA, 0xAD, 0x01, 0x80, 0x29, 0x10, 0xF0, 0xF9,
+            0x8E, 0x00, 0x80, 0x4C, 0x0A, 0xFF,
+        ]);
+        rom[0xFC] = 0x00;
+        rom[0xFD] = 0xFF;
+        let mut bus = SerialBus::new(rom, BufferedSerial::new("HELLO\r"));
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        cpu.execute(&mut 20_000, &mut bus);
+
+        // then:
+        assert_eq!(bus.acia.port.output, b"HELLO\r");
+        assert!(bus.acia.port.input.is_empty());
+    }
+}
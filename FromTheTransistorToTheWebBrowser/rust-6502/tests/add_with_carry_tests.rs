@@ -1,14 +1,12 @@
 #[cfg(test)]
 mod add_with_carry_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Byte;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     struct ADCTestData {
         carry: bool,
@@ -29,9 +27,18 @@ mod add_with_carry_tests {
     }
 
     fn verify_unmodified_flags(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2)); // I
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3)); // D
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4)); // B
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        ); // I
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        ); // D
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        ); // B
     }
 
     fn test_adc_or_sbc_absolute(test: ADCTestData, operation: EOperation) {
@@ -41,22 +48,22 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_ABS
+            INS_ADC_ABS
         } else {
-            cpu.INS_SBC_ABS
+            INS_SBC_ABS
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -65,11 +72,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -80,23 +87,23 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.X = 0x10;
-        cpu.A = test.a;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_x(0x10);
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_ABSX
+            INS_ADC_ABSX
         } else {
-            cpu.INS_SBC_ABSX
+            INS_SBC_ABSX
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 0x10] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -105,11 +112,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -120,23 +127,23 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.Y = 0x10;
-        cpu.A = test.a;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_y(0x10);
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_ABSY
+            INS_ADC_ABSY
         } else {
-            cpu.INS_SBC_ABSY
+            INS_SBC_ABSY
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 0x10] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -145,11 +152,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -160,20 +167,20 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_IM
+            INS_ADC_IM
         } else {
-            cpu.INS_SBC_IM
+            INS_SBC_IM
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, test.operand);
         let mut expected_cycles = 2;
         let cpu_copy = cpu;
 
@@ -182,11 +189,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -197,21 +204,21 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_ZP
+            INS_ADC_ZP
         } else {
-            cpu.INS_SBC_ZP
+            INS_SBC_ZP
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, test.operand);
         let mut expected_cycles = 3;
         let cpu_copy = cpu;
 
@@ -220,11 +227,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -235,22 +242,22 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.X = 0x10;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_x(0x10);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_ZPX
+            INS_ADC_ZPX
         } else {
-            cpu.INS_SBC_ZPX
+            INS_SBC_ZPX
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042 + 0x10] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -259,11 +266,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -274,24 +281,24 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.X = 0x04;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_x(0x04);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_INDX
+            INS_ADC_INDX
         } else {
-            cpu.INS_SBC_INDX
+            INS_SBC_INDX
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x02;
-        mem.Data[0x0006] = 0x00; // 0x2 + 0x4
-        mem.Data[0x0007] = 0x80;
-        mem.Data[0x8000] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x02);
+        mem.poke(0x0006, 0x00); // 0x2 + 0x4
+        mem.poke(0x0007, 0x80);
+        mem.poke(0x8000, test.operand);
         let mut expected_cycles = 6;
         let cpu_copy = cpu;
 
@@ -300,11 +307,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -315,24 +322,24 @@ mod add_with_carry_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, test.carry); // C
-        cpu.A = test.a;
-        cpu.Y = 0x04;
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(6, !test.expect_v); // V
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_a(test.a);
+        cpu.set_y(0x04);
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
         let opcode = if operation == EOperation::Add {
-            cpu.INS_ADC_INDY
+            INS_ADC_INDY
         } else {
-            cpu.INS_SBC_INDY
+            INS_SBC_INDY
         };
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x02;
-        mem.Data[0x0002] = 0x00; // 0x2 + 0x4
-        mem.Data[0x0003] = 0x80;
-        mem.Data[0x8000 + 0x04] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x02);
+        mem.poke(0x0002, 0x00); // 0x2 + 0x4
+        mem.poke(0x0003, 0x80);
+        mem.poke(0x8000 + 0x04, test.operand);
         let mut expected_cycles = 5;
         let cpu_copy = cpu;
 
@@ -341,11 +348,11 @@ mod add_with_carry_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(cpu.A, test.answer);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(6), test.expect_v); // V
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.answer);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), test.expect_v); // V
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
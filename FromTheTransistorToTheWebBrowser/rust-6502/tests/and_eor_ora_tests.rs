@@ -1,14 +1,12 @@
 #[cfg(test)]
 mod and_eor_ora_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Byte;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     enum ELogicalOp {
         And,
@@ -25,11 +23,26 @@ mod and_eor_ora_tests {
     }
 
     fn verify_unmodified_flags_from_logical_op_ins(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(0), cpu_copy.PS.get_bit(0));
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2));
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3));
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4));
-        assert_eq!(cpu.PS.get_bit(6), cpu_copy.PS.get_bit(6));
+        assert_eq!(
+            cpu.flag(Flags::CarryFlagBit),
+            cpu_copy.flag(Flags::CarryFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            cpu_copy.flag(Flags::OverflowFlagBit)
+        );
     }
 
     fn test_logical_op_inmediate(logical_op: ELogicalOp) {
@@ -42,20 +55,20 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
+        cpu.set_a(0xCC);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_IM;
+                mem.poke(0xFFFC, INS_AND_IM);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_IM;
+                mem.poke(0xFFFC, INS_EOR_IM);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_IM;
+                mem.poke(0xFFFC, INS_ORA_IM);
             }
         }
 
-        mem.Data[0xFFFD] = 0x84;
+        mem.poke(0xFFFD, 0x84);
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
@@ -63,10 +76,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x84, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 2);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), expected_negative);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative);
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -80,21 +93,21 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
+        cpu.set_a(0xCC);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ZP;
+                mem.poke(0xFFFC, INS_AND_ZP);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ZP;
+                mem.poke(0xFFFC, INS_EOR_ZP);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ZP;
+                mem.poke(0xFFFC, INS_ORA_ZP);
             }
         }
 
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0x37;
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
@@ -102,10 +115,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 3);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), expected_negative);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative);
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -119,22 +132,22 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        cpu.X = 5;
+        cpu.set_a(0xCC);
+        cpu.set_x(5);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ZPX;
+                mem.poke(0xFFFC, INS_AND_ZPX);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ZPX;
+                mem.poke(0xFFFC, INS_EOR_ZPX);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ZPX;
+                mem.poke(0xFFFC, INS_ORA_ZPX);
             }
         }
 
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0047] = 0x37;
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0047, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
@@ -142,10 +155,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), expected_negative);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative);
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -159,24 +172,24 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ABS;
+                mem.poke(0xFFFC, INS_AND_ABS);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ABS;
+                mem.poke(0xFFFC, INS_EOR_ABS);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ABS;
+                mem.poke(0xFFFC, INS_ORA_ABS);
             }
         }
 
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44;
-        mem.Data[0x4480] = 0x37;
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44);
+        mem.poke(0x4480, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
@@ -184,10 +197,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -201,25 +214,25 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
-        cpu.X = 1;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
+        cpu.set_x(1);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ABSX;
+                mem.poke(0xFFFC, INS_AND_ABSX);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ABSX;
+                mem.poke(0xFFFC, INS_EOR_ABSX);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ABSX;
+                mem.poke(0xFFFC, INS_ORA_ABSX);
             }
         }
 
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44;
-        mem.Data[0x4481] = 0x37;
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44);
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
@@ -227,10 +240,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -244,25 +257,25 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
-        cpu.Y = 1;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
+        cpu.set_y(1);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ABSY;
+                mem.poke(0xFFFC, INS_AND_ABSY);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ABSY;
+                mem.poke(0xFFFC, INS_EOR_ABSY);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ABSY;
+                mem.poke(0xFFFC, INS_ORA_ABSY);
             }
         }
 
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44;
-        mem.Data[0x4481] = 0x37;
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44);
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
@@ -270,10 +283,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -287,23 +300,23 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        cpu.Y = 0xFF;
+        cpu.set_a(0xCC);
+        cpu.set_y(0xFF);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ABSY;
+                mem.poke(0xFFFC, INS_AND_ABSY);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ABSY;
+                mem.poke(0xFFFC, INS_EOR_ABSY);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ABSY;
+                mem.poke(0xFFFC, INS_ORA_ABSY);
             }
         }
 
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44;
-        mem.Data[0x4501] = 0x37;
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44);
+        mem.poke(0x4501, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
@@ -311,10 +324,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -328,23 +341,23 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        cpu.X = 0xFF;
+        cpu.set_a(0xCC);
+        cpu.set_x(0xFF);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ABSX;
+                mem.poke(0xFFFC, INS_AND_ABSX);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_ABSX;
+                mem.poke(0xFFFC, INS_EOR_ABSX);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ABSX;
+                mem.poke(0xFFFC, INS_ORA_ABSX);
             }
         }
 
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44;
-        mem.Data[0x4501] = 0x37;
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44);
+        mem.poke(0x4501, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
@@ -352,10 +365,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -369,26 +382,26 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
-        cpu.X = 0x04;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
+        cpu.set_x(0x04);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_INDX;
+                mem.poke(0xFFFC, INS_AND_INDX);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_INDX;
+                mem.poke(0xFFFC, INS_EOR_INDX);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_INDX;
+                mem.poke(0xFFFC, INS_ORA_INDX);
             }
         }
 
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0006] = 0x00;
-        mem.Data[0x0007] = 0x80;
-        mem.Data[0x8000] = 0x37;
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0006, 0x00);
+        mem.poke(0x0007, 0x80);
+        mem.poke(0x8000, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 6, &mut mem);
@@ -396,10 +409,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 6);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -413,26 +426,26 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
-        cpu.Y = 0x04;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
+        cpu.set_y(0x04);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_INDY;
+                mem.poke(0xFFFC, INS_AND_INDY);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_INDY;
+                mem.poke(0xFFFC, INS_EOR_INDY);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_INDY;
+                mem.poke(0xFFFC, INS_ORA_INDY);
             }
         }
 
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0002] = 0x00;
-        mem.Data[0x0003] = 0x80;
-        mem.Data[0x8004] = 0x37;
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0002, 0x00);
+        mem.poke(0x0003, 0x80);
+        mem.poke(0x8004, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
@@ -440,10 +453,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -457,26 +470,26 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Z
-        cpu.PS.set_bit(7, true); // N
-        cpu.A = 0xCC;
-        cpu.Y = 0xFF;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+        cpu.set_a(0xCC);
+        cpu.set_y(0xFF);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_INDY;
+                mem.poke(0xFFFC, INS_AND_INDY);
             }
             ELogicalOp::Eor => {
-                mem.Data[0xFFFC] = cpu.INS_EOR_INDY;
+                mem.poke(0xFFFC, INS_EOR_INDY);
             }
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_INDY;
+                mem.poke(0xFFFC, INS_ORA_INDY);
             }
         }
 
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0002] = 0x02;
-        mem.Data[0x0003] = 0x80;
-        mem.Data[0x8101] = 0x37;
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0002, 0x02);
+        mem.poke(0x0003, 0x80);
+        mem.poke(0x8101, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 6, &mut mem);
@@ -484,10 +497,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 6);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -501,20 +514,20 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        cpu.X = 0xFF;
+        cpu.set_a(0xCC);
+        cpu.set_x(0xFF);
         match logical_op {
             ELogicalOp::And => {
-                mem.Data[0xFFFC] = cpu.INS_AND_ZPX;
+                mem.poke(0xFFFC, INS_AND_ZPX);
             }
-            ELogicalOp::Eor => mem.Data[0xFFFC] = cpu.INS_EOR_ZPX,
+            ELogicalOp::Eor => mem.poke(0xFFFC, INS_EOR_ZPX),
             ELogicalOp::Or => {
-                mem.Data[0xFFFC] = cpu.INS_ORA_ZPX;
+                mem.poke(0xFFFC, INS_ORA_ZPX);
             }
         }
 
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x007F] = 0x37;
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x007F, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
@@ -522,10 +535,10 @@ mod and_eor_ora_tests {
         // then:
         let expected_result: Byte = do_logical_op(0xCC, 0x37, logical_op);
         let expected_negative: bool = (expected_result & 0b10000000) > 0;
-        assert_eq!(cpu.A, expected_result);
+        assert_eq!(cpu.a(), expected_result);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false); // Z
-        assert_eq!(cpu.PS.get_bit(7), expected_negative); // N
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), expected_negative); // N
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -554,17 +567,17 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        mem.Data[0xFFFC] = cpu.INS_EOR_IM;
-        mem.Data[0xFFFD] = cpu.A;
+        cpu.set_a(0xCC);
+        mem.poke(0xFFFC, INS_EOR_IM);
+        mem.poke(0xFFFD, cpu.a());
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
 
         // then:
         assert_eq!(cycles_used, 2);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_logical_op_ins(cpu, cpu_copy);
     }
 
@@ -729,22 +742,22 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(6, false);
-        cpu.PS.set_bit(7, false);
-        cpu.A = 0xCC;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0xCC;
+        cpu.set_flag(Flags::OverflowFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_a(0xCC);
+        mem.poke(0xFFFC, INS_BIT_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0xCC);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0xCC);
+        assert_eq!(cpu.a(), 0xCC);
         assert_eq!(cycles_used, 3);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(6), true);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
     }
 
     #[test]
@@ -758,22 +771,22 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(6, true);
-        cpu.PS.set_bit(7, true);
-        cpu.A = 0xCC;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0x33;
+        cpu.set_flag(Flags::OverflowFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_a(0xCC);
+        mem.poke(0xFFFC, INS_BIT_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0x33);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0xCC);
+        assert_eq!(cpu.a(), 0xCC);
         assert_eq!(cycles_used, 3);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(6), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
     }
 
     #[test]
@@ -787,22 +800,22 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(6, false);
-        cpu.PS.set_bit(7, false);
-        cpu.A = 0x33;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0xCC;
+        cpu.set_flag(Flags::OverflowFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_a(0x33);
+        mem.poke(0xFFFC, INS_BIT_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0xCC);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x33);
+        assert_eq!(cpu.a(), 0x33);
         assert_eq!(cycles_used, 3);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(6), true);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
     }
 
     #[test]
@@ -816,21 +829,21 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ABS;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0xCC;
+        cpu.set_a(0xCC);
+        mem.poke(0xFFFC, INS_BIT_ABS);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0xCC);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0xCC);
+        assert_eq!(cpu.a(), 0xCC);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(6), true);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
     }
 
     #[test]
@@ -844,21 +857,21 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0xCC;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ABS;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0x33;
+        cpu.set_a(0xCC);
+        mem.poke(0xFFFC, INS_BIT_ABS);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0x33);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0xCC);
+        assert_eq!(cpu.a(), 0xCC);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(6), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
     }
 
     #[test]
@@ -872,20 +885,20 @@ mod and_eor_ora_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.A = 0x33;
-        mem.Data[0xFFFC] = cpu.INS_BIT_ABS;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0xCC;
+        cpu.set_a(0x33);
+        mem.poke(0xFFFC, INS_BIT_ABS);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0xCC);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x33);
+        assert_eq!(cpu.a(), 0x33);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(6), true);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::OverflowFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
     }
 }
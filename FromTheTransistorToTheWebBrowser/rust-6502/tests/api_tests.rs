@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod api_tests {
+
+    use rust_6502::{AddressDecoder, Bus, Flags, Ram, Rom, CPU};
+
+    #[test]
+    fn registers_and_flags_are_read_and_set_with_the_accessors() {
+        let mut cpu = CPU::new();
+
+        // when:
+        cpu.set_pc(0x1234);
+        cpu.set_sp(0xF0);
+        cpu.set_a(0x01);
+        cpu.set_x(0x02);
+        cpu.set_y(0x03);
+        cpu.set_status(0x00);
+        cpu.set_flag(Flags::CarryFlagBit, true);
+        cpu.set_flag(Flags::DecimalModeFlagBit, true);
+        cpu.set_flag(Flags::CarryFlagBit, false);
+
+        // then:
+        assert_eq!(cpu.pc(), 0x1234);
+        assert_eq!(cpu.sp(), 0xF0);
+        assert_eq!((cpu.a(), cpu.x(), cpu.y()), (0x01, 0x02, 0x03));
+        assert_eq!(cpu.status(), 0b0000_1000);
+        assert!(cpu.flag(Flags::DecimalModeFlagBit));
+        assert!(!cpu.flag(Flags::CarryFlagBit));
+    }
+
+    #[test]
+    fn a_machine_is_built_from_devices_and_run() {
+        // LDX #$05; DEX; BNE -3; STX $10; JAM, with the reset vector at $F000
+        let mut rom: Vec<u8> = vec![0; 0x1000];
+        rom[..8].copy_from_slice(&[0xA2, 0x05, 0xCA, 0xD0, 0xFD, 0x86, 0x10, 0x02]);
+        rom[0x0FFC] = 0x00;
+        rom[0x0FFD] = 0xF0;
+
+        let mut bus = AddressDecoder::new();
+        bus.map(0x0000, 0x07FF, Box::new(Ram::new(0x0800)));
+        bus.map(0xF000, 0xFFFF, Box::new(Rom::new(rom)));
+        let mut cpu = CPU::new();
+
+        // given:
+        bus.write(0x0010, 0xFF);
+        cpu.power_on(&mut bus);
+
+        // when:
+        cpu.execute(&mut 1000, &mut bus);
+
+        // then:
+        assert!(cpu.is_jammed());
+        assert_eq!(cpu.pc(), 0xF007);
+        assert_eq!(bus.peek(0x0010), 0x00);
+        assert!(cpu.flag(Flags::ZeroFlagBit));
+        // a jammed CPU takes the cycles that are left
+        assert_eq!(cpu.cycles(), 1000);
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod apple1_tests {
+
+    use rust_6502::apple1::*;
+    use rust_6502::assembler::assemble;
+    use rust_6502::bus::Bus;
+    use rust_6502::CPU;
+
+    fn boot() -> (CPU, Apple1Bus) {
+        let mut bus = Apple1Bus::new(0x1000);
+        let mut cpu = CPU::new();
+        cpu.power_on(&mut bus);
+        (cpu, bus)
+    }
+
+    fn run(cpu: &mut CPU, bus: &mut Apple1Bus, cycles: isize) -> String {
+        let mut cycles: isize = cycles;
+        cpu.execute(&mut cycles, bus);
+        bus.take_output()
+    }
+
+    #[test]
+    fn wozmon_asm_assembles_to_the_rom() {
+        // when:
+        let program = assemble(include_str!("../src/wozmon.asm")).unwrap();
+
+        // then:
+        assert_eq!(program.origin, WOZMON_START);
+        assert_eq!(program.bytes, WOZMON.to_vec());
+    }
+
+    #[test]
+    fn wozmon_greets_with_a_backslash() {
+        let (mut cpu, mut bus) = boot();
+
+        // when:
+        let output = run(&mut cpu, &mut bus, 10_000);
+
+        // then:
+        assert_eq!(cpu.pc() & 0xFF00, 0xFF00);
+        assert_eq!(output, "\\\n");
+    }
+
+    #[test]
+    fn wozmon_examines_memory() {
+        let (mut cpu, mut bus) = boot();
+        run(&mut cpu, &mut bus, 10_000);
+
+        // when:
+        bus.type_text("ff00.ff07\n");
+        let output = run(&mut cpu, &mut bus, 100_000);
+
+        // then:
+        assert_eq!(output, "FF00.FF07\n\nFF00: D8 58 A0 7F 8C 12 D0 A9\n");
+    }
+
+    #[test]
+    fn a_program_deposited_with_wozmon_runs() {
+        let (mut cpu, mut bus) = boot();
+        run(&mut cpu, &mut bus, 10_000);
+
+        // given: LDA #"A"; JSR ECHO; JMP GETLINE
+        bus.type_text("0280: A9 C1 20 EF FF 4C 1F FF\n");
+
+        // when:
+        bus.type_text("280R\n");
+        let output = run(&mut cpu, &mut bus, 200_000);
+
+        // then:
+        assert_eq!(bus.peek(0x0281), 0xC1);
+        assert!(output.ends_with("280R\n\n0280: A9A\n"));
+    }
+
+    #[test]
+    fn the_keyboard_has_no_lower_case_and_rubs_out_with_underscore() {
+        let mut bus = Apple1Bus::new(0x2000);
+
+        // given:
+        bus.write(0xD011, 0xA7);
+
+        // when:
+        bus.type_text("a\x08");
+
+        // then:
+        assert_eq!(bus.read(0xD011) & 0x80, 0x80);
+        assert_eq!(bus.read(0xD010), 0xC1);
+        assert_eq!(bus.read(0xD010), 0xDF);
+        assert_eq!(bus.read(0xD011) & 0x80, 0);
+    }
+
+    #[test]
+    fn the_display_is_upper_case_with_40_columns() {
+        let mut bus = Apple1Bus::new(0x1000);
+
+        // given:
+        bus.write(DSP, 0x7F);
+        bus.write(DSPCR, 0xA7);
+
+        // when:
+        for _ in 0..41 {
+            bus.write(DSP, b'a');
+        }
+        bus.write(DSP, 0x07);
+        bus.write(DSP, 0x8D);
+
+        // then:
+        assert_eq!(bus.take_output(), format!("{}\nA\n", "A".repeat(40)));
+    }
+}
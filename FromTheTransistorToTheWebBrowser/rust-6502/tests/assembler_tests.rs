@@ -0,0 +1,262 @@
+#[cfg(test)]
+mod assembler_tests {
+
+    use rust_6502::assembler::*;
+    use rust_6502::bus::Bus;
+    use rust_6502::disasm::{decode, disassemble_instruction};
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    fn bytes_of(source: &str) -> Vec<Byte> {
+        assemble(source).unwrap().bytes
+    }
+
+    #[test]
+    fn every_addressing_mode_is_assembled() {
+        // when:
+        let bytes = bytes_of(
+            "
+            nop
+            asl
+            asl a
+            lda #$10
+            lda $44
+            lda $44,x
+            ldx $44,y
+            lda $4400
+            lda $4400,x
+            lda $4400,y
+            jmp ($4400)
+            lda ($44,x)
+            lda ($44),y
+            ",
+        );
+
+        // then:
+        assert_eq!(
+            bytes,
+            vec![
+                0xEA, 0x0A, 0x0A, 0xA9, 0x10, 0xA5, 0x44, 0xB5, 0x44, 0xB6, 0x44, 0xAD, 0x00, 0x44,
+                0xBD, 0x00, 0x44, 0xB9, 0x00, 0x44, 0x6C, 0x00, 0x44, 0xA1, 0x44, 0xB1, 0x44,
+            ]
+        );
+    }
+
+    #[test]
+    fn every_documented_opcode_assembles_back_from_its_disassembly() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        for opcode in 0..=0xFF {
+            if decode(opcode).is_none() {
+                continue;
+            }
+
+            // given:
+            mem.poke(0x0600, opcode);
+            mem.poke(0x0601, 0x34);
+            mem.poke(0x0602, 0x12);
+            let instruction = disassemble_instruction(&mem, 0x0600);
+
+            // when:
+            let bytes = bytes_of(&instruction.text());
+
+            // then:
+            assert_eq!(bytes, instruction.bytes, "{}", instruction.text());
+        }
+    }
+
+    #[test]
+    fn known_small_addresses_use_zero_page_and_forward_references_absolute() {
+        // when:
+        let program = assemble(
+            "
+            define counter $10
+            inc counter
+            inc $0010
+            inc later
+            later:
+            ",
+        )
+        .unwrap();
+
+        // then:
+        assert_eq!(
+            program.bytes,
+            vec![0xE6, 0x10, 0xEE, 0x10, 0x00, 0xEE, 0x08, 0x06]
+        );
+    }
+
+    #[test]
+    fn defines_and_the_low_and_high_byte_operators() {
+        // when:
+        let bytes = bytes_of(
+            "
+            define screen $0200
+            WIDTH = 32
+            lda #<screen
+            ldx #>screen
+            ldy #WIDTH-1
+            lda screen+WIDTH
+            ",
+        );
+
+        // then:
+        assert_eq!(
+            bytes,
+            vec![0xA9, 0x00, 0xA2, 0x02, 0xA0, 0x1F, 0xAD, 0x20, 0x02]
+        );
+    }
+
+    #[test]
+    fn data_directives_and_org() {
+        // when:
+        let program = assemble(
+            "
+            .org $1000
+            start:
+              .byte 1, $02, %11, 'A', \"HI\"
+              .word start, $1234
+            .org $1010
+            end: dcb $FF
+            ",
+        )
+        .unwrap();
+
+        // then:
+        assert_eq!(program.origin, 0x1000);
+        assert_eq!(
+            program.bytes,
+            vec![
+                0x01, 0x02, 0x03, 0x41, 0x48, 0x49, 0x00, 0x10, 0x34, 0x12, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0xFF,
+            ]
+        );
+        assert_eq!(program.symbol("end"), Some(0x1010));
+    }
+
+    #[test]
+    fn branches_are_assembled_relative_to_the_next_instruction() {
+        // when:
+        let bytes = bytes_of(
+            "
+            back:
+              dex
+              bne back
+              beq forward
+              nop
+            forward:
+              bcc *
+            ",
+        );
+
+        // then:
+        assert_eq!(bytes, vec![0xCA, 0xD0, 0xFD, 0xF0, 0x01, 0xEA, 0x90, 0xFE]);
+    }
+
+    #[test]
+    fn errors_report_the_line_number() {
+        let far_branch = format!("start:\n.byte {}\nbne start", vec!["0"; 200].join(","));
+
+        let cases: Vec<(&str, usize, &str)> = vec![
+            ("nop\n  lda missing", 2, "undefined symbol 'missing'"),
+            ("nop\nfoo #1", 2, "unknown instruction 'foo'"),
+            ("a:\na:", 2, "symbol 'a' is already defined"),
+            (
+                "stx $1234,x",
+                1,
+                "STX does not support this addressing mode",
+            ),
+            ("lda #$1234", 1, "value $1234 does not fit in a byte"),
+            (
+                &far_branch,
+                3,
+                "branch to $0600 is out of range (-202 bytes)",
+            ),
+            (
+                ".org $1000\nnop\n.org $0800",
+                3,
+                ".org $0800 moves backwards",
+            ),
+        ];
+
+        for (source, line, message) in cases {
+            // when:
+            let error = assemble(source).unwrap_err();
+
+            // then:
+            assert_eq!(
+                error,
+                AssemblerError {
+                    line,
+                    message: message.to_string()
+                },
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn a_program_can_be_relocated() {
+        let program = assemble(
+            "
+            define port $D012
+            start:
+              lda #<table
+              ldx #>table
+              jsr start
+              sta port
+            table:
+              .word table
+            ",
+        )
+        .unwrap();
+
+        // when:
+        let moved = program.relocate(0x80F8);
+
+        // then:
+        assert_eq!(moved.origin, 0x80F8);
+        assert_eq!(moved.symbol("start"), Some(0x80F8));
+        assert_eq!(moved.symbol("table"), Some(0x8102));
+        assert_eq!(moved.symbol("port"), Some(0xD012));
+        assert_eq!(
+            moved.bytes,
+            vec![0xA9, 0x02, 0xA2, 0x81, 0x20, 0xF8, 0x80, 0x8D, 0x12, 0xD0, 0x02, 0x81]
+        );
+    }
+
+    #[test]
+    fn an_assembled_program_runs_on_the_cpu() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        let program = assemble(
+            "
+            .org $1000
+              ldx #5
+              lda #0
+              clc
+            loop:
+              adc #3
+              dex
+              bne loop
+              sta $0200
+            ",
+        )
+        .unwrap();
+        program.load(&mut mem);
+        cpu.set_pc(0x1000);
+
+        // when:
+        cpu.execute(&mut (2 + 2 + 2 + 5 * (2 + 2 + 3) - 1 + 4), &mut mem);
+
+        // then:
+        assert_eq!(mem.peek(0x0200), 15);
+    }
+}
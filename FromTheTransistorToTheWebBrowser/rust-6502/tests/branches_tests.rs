@@ -2,15 +2,13 @@
 
 mod branches_tests {
 
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
     // type SByte = c_schar;
-    use bit_field::BitField;
-    type Word = c_ushort;
 
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn beq_can_branch_forward_when_zero_is_set() {
@@ -21,20 +19,20 @@ mod branches_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        // cpu.Y = 0x42;
-        // cpu.X = 0x42;
-        mem.Data[0xFF00] = cpu.INS_BEQ;
-        mem.Data[0xFF01] = 0x01;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        // cpu.set_y(0x42);
+        // cpu.set_x(0x42);
+        mem.poke(0xFF00, INS_BEQ);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 3; // 2 or 3 or 5
+        let mut expected_cycles = 3; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -46,20 +44,20 @@ mod branches_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        // cpu.Y = 0x42;
-        // cpu.X = 0x42;
-        mem.Data[0xFF00] = cpu.INS_BEQ;
-        mem.Data[0xFF01] = 0x01;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        // cpu.set_y(0x42);
+        // cpu.set_x(0x42);
+        mem.poke(0xFF00, INS_BEQ);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 2; // 2 or 3 or 5
+        let mut expected_cycles = 2; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.PC, 0xFF02);
-        // assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF02);
+        // assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -71,20 +69,20 @@ mod branches_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFEFD);
         cpu_copy.reset_vector(&mut mem, 0xEFFD);
-        cpu.PS.set_bit(1, true);
-        // cpu.Y = 0x42;
-        // cpu.X = 0x42;
-        mem.Data[0xFEFD] = cpu.INS_BEQ;
-        mem.Data[0xFEFE] = 0x1;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        // cpu.set_y(0x42);
+        // cpu.set_x(0x42);
+        mem.poke(0xFEFD, INS_BEQ);
+        mem.poke(0xFEFE, 0x1);
 
-        let mut expected_cycles = 5; // 2 or 3 or 5
+        let mut expected_cycles = 4; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
-        assert_eq!(actual_cycles, 5);
-        assert_eq!(cpu.PC, 0xFF00);
-        // assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(actual_cycles, 4);
+        assert_eq!(cpu.pc(), 0xFF00);
+        // assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     // TODO: check this test
@@ -102,13 +100,13 @@ mod branches_tests {
         cpu.reset_vector(&mut mem, 0xFFCC);
         // cpu_copy.reset_vector(&mut mem, 0xFFCC);
 
-        cpu.PS.set_bit(1, true);
-        // cpu_copy.PS.set_bit(1, true);
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        // cpu_copy.set_flag(Flags::ZeroFlagBit, true);
 
-        mem.Data[0xFFCC] = 0xA9;
-        mem.Data[0xFFCC + 1] = 0x00;
-        mem.Data[0xFFCC + 2] = 0xF0;
-        mem.Data[0xFFCC + 3] = 0xFC;
+        mem.poke(0xFFCC, 0xA9);
+        mem.poke(0xFFCC + 1, 0x00);
+        mem.poke(0xFFCC + 2, 0xF0);
+        mem.poke(0xFFCC + 3, 0xFC);
 
         let cpu_copy = cpu;
 
@@ -118,8 +116,8 @@ mod branches_tests {
 
         // then:
         assert_eq!(actual_cycles, 2 + 3);
-        assert_eq!(cpu.PC, 0xFFCC);
-        // assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFFCC);
+        // assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -131,19 +129,19 @@ mod branches_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        cpu_copy.PS.set_bit(1, false);
-        mem.Data[0xFF00] = cpu.INS_BNE;
-        mem.Data[0xFF01] = 0x01;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        cpu_copy.set_flag(Flags::ZeroFlagBit, false);
+        mem.poke(0xFF00, INS_BNE);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 3; // 2 or 3 or 5
+        let mut expected_cycles = 3; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -156,20 +154,20 @@ mod branches_tests {
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, true);
-        cpu_copy.PS.set_bit(0, true);
+        cpu.set_flag(Flags::CarryFlagBit, true);
+        cpu_copy.set_flag(Flags::CarryFlagBit, true);
 
-        mem.Data[0xFF00] = cpu.INS_BCS;
-        mem.Data[0xFF01] = 0x01;
+        mem.poke(0xFF00, INS_BCS);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 3; // 2 or 3 or 5
+        let mut expected_cycles = 3; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -182,20 +180,20 @@ mod branches_tests {
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, false);
-        cpu_copy.PS.set_bit(0, false);
+        cpu.set_flag(Flags::CarryFlagBit, false);
+        cpu_copy.set_flag(Flags::CarryFlagBit, false);
 
-        mem.Data[0xFF00] = cpu.INS_BCC;
-        mem.Data[0xFF01] = 0x01;
+        mem.poke(0xFF00, INS_BCC);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 3; // 2 or 3 or 5
+        let mut expected_cycles = 3; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -208,19 +206,19 @@ mod branches_tests {
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(7, true);
-        cpu_copy.PS.set_bit(7, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu_copy.set_flag(Flags::NegativeFlagBit, true);
 
-        mem.Data[0xFF00] = cpu.INS_BMI;
-        mem.Data[0xFF01] = 0x01;
+        mem.poke(0xFF00, INS_BMI);
+        mem.poke(0xFF01, 0x01);
 
-        let mut expected_cycles = 3; // 2 or 3 or 5
+        let mut expected_cycles = 3; // 2 or 3 or 4
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 }
@@ -0,0 +1,229 @@
+#[cfg(test)]
+mod bus_tests {
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rust_6502::bus::{AddressDecoder, Bus, IoPort, Ram, Rom};
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
+
+    // 32 KiB of RAM at 0x0000 and an 8 KiB ROM at 0xE000 with the program
+    // at its start and the reset vector pointing to it
+    fn machine_with_rom(program: &[Byte]) -> AddressDecoder {
+        let mut rom: Vec<Byte> = vec![0; 0x2000];
+        rom[..program.len()].copy_from_slice(program);
+        rom[0x1FFC] = 0x00; // reset vector 0xE000
+        rom[0x1FFD] = 0xE0;
+
+        let mut decoder = AddressDecoder::new();
+        decoder.map(0x0000, 0x7FFF, Box::new(Ram::new(0x8000)));
+        decoder.map(0xE000, 0xFFFF, Box::new(Rom::new(rom)));
+        decoder
+    }
+
+    #[test]
+    fn mem_is_a_bus() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        // when:
+        mem.write(0x1234, 0x42);
+
+        // then:
+        assert_eq!(mem.peek(0x1234), 0x42);
+        assert_eq!(mem.read(0x1234), 0x42);
+        assert_eq!(mem.peek(0x1234), 0x42);
+    }
+
+    #[test]
+    fn power_on_loads_the_pc_from_the_reset_vector() {
+        let mut bus = machine_with_rom(&[]);
+        let mut cpu = CPU::new();
+
+        // when:
+        cpu.power_on(&mut bus);
+
+        // then:
+        assert_eq!(cpu.pc(), 0xE000);
+        assert_eq!(cpu.sp(), 0xFF);
+    }
+
+    #[test]
+    fn the_cpu_can_run_a_program_from_rom_and_store_into_ram() {
+        // LDA #$42, STA $0200
+        let mut bus = machine_with_rom(&[0xA9, 0x42, 0x8D, 0x00, 0x02]);
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (2 + 4), &mut bus);
+
+        // then:
+        assert_eq!(cycles_used, 2 + 4);
+        assert_eq!(bus.peek(0x0200), 0x42);
+        assert_eq!(cpu.pc(), 0xE005);
+    }
+
+    #[test]
+    fn writes_to_rom_are_ignored() {
+        // LDA #$42, STA $E000
+        let mut bus = machine_with_rom(&[0xA9, 0x42, 0x8D, 0x00, 0xE0]);
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        cpu.execute(&mut (2 + 4), &mut bus);
+
+        // then:
+        assert_eq!(bus.peek(0xE000), 0xA9);
+    }
+
+    #[test]
+    fn devices_see_addresses_relative_to_their_region() {
+        let writes: Rc<RefCell<Vec<(Word, Byte)>>> = Rc::new(RefCell::new(Vec::new()));
+        let log = Rc::clone(&writes);
+
+        // LDA #$41, STA $D012
+        let mut bus = machine_with_rom(&[0xA9, 0x41, 0x8D, 0x12, 0xD0]);
+        bus.map(
+            0xD010,
+            0xD013,
+            Box::new(IoPort::new(
+                Box::new(|_| 0),
+                Box::new(move |address, value| log.borrow_mut().push((address, value))),
+            )),
+        );
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        cpu.execute(&mut (2 + 4), &mut bus);
+
+        // then:
+        assert_eq!(*writes.borrow(), vec![(0x0002, 0x41)]);
+    }
+
+    #[test]
+    fn the_cpu_can_read_a_keyboard_port() {
+        let key: Rc<RefCell<Byte>> = Rc::new(RefCell::new(0x77));
+        let port_key = Rc::clone(&key);
+
+        // LDA $00FF
+        let mut bus = machine_with_rom(&[0xAD, 0xFF, 0x00]);
+        let mut decoder = AddressDecoder::new();
+        decoder.map(
+            0x00FF,
+            0x00FF,
+            Box::new(IoPort::new(
+                Box::new(move |_| *port_key.borrow()),
+                Box::new(|_, _| {}),
+            )),
+        );
+        decoder.map(0x0000, 0xFFFF, Box::new(bus));
+        bus = decoder;
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 4, &mut bus);
+
+        // then:
+        assert_eq!(cycles_used, 4);
+        assert_eq!(cpu.a(), 0x77);
+    }
+
+    #[test]
+    fn small_devices_are_mirrored_over_their_region() {
+        let mut bus = AddressDecoder::new();
+        bus.map(0x0000, 0x1FFF, Box::new(Ram::new(0x0800)));
+
+        // when:
+        bus.write(0x0801, 0x42);
+
+        // then:
+        assert_eq!(bus.read(0x0001), 0x42);
+        assert_eq!(bus.read(0x1001), 0x42);
+        assert_eq!(bus.read(0x1801), 0x42);
+    }
+
+    #[test]
+    fn unmapped_addresses_read_the_unmapped_value() {
+        let mut bus = AddressDecoder::new();
+        bus.unmapped_value = 0xEA;
+
+        // when:
+        bus.write(0x4000, 0x42);
+
+        // then:
+        assert_eq!(bus.read(0x4000), 0xEA);
+        assert_eq!(bus.peek(0x4000), 0xEA);
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_ram_cannot_be_empty() {
+        Ram::new(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_rom_cannot_be_empty() {
+        Rom::new(vec![]);
+    }
+
+    // counts the fetches, everything reads 0xEA (NOP)
+    struct FetchCounter {
+        fetches: Rc<RefCell<Vec<Word>>>,
+    }
+
+    impl Bus for FetchCounter {
+        fn read(&mut self, _address: Word) -> Byte {
+            0xEA
+        }
+
+        fn write(&mut self, _address: Word, _value: Byte) {}
+
+        fn peek(&self, _address: Word) -> Byte {
+            0xEA
+        }
+
+        fn fetch(&mut self, address: Word) -> Byte {
+            self.fetches.borrow_mut().push(address);
+            0xEA
+        }
+    }
+
+    #[test]
+    fn fetches_reach_the_device() {
+        let fetches = Rc::new(RefCell::new(Vec::new()));
+        let mut bus = AddressDecoder::new();
+        bus.map(
+            0x8000,
+            0xFFFF,
+            Box::new(FetchCounter {
+                fetches: Rc::clone(&fetches),
+            }),
+        );
+
+        // when:
+        let opcode = bus.fetch(0x8002);
+        bus.read(0x8003);
+
+        // then:
+        assert_eq!(opcode, 0xEA);
+        assert_eq!(*fetches.borrow(), vec![0x0002]);
+    }
+}
@@ -1,14 +1,12 @@
 #[cfg(test)]
 mod compare_register_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Byte;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     struct CMPTestData {
         register_value: Byte,
@@ -76,6 +74,22 @@ mod compare_register_tests {
         Y,
     }
 
+    fn register(cpu: &CPU, register: &ERegister) -> Byte {
+        match register {
+            ERegister::A => cpu.a(),
+            ERegister::X => cpu.x(),
+            ERegister::Y => cpu.y(),
+        }
+    }
+
+    fn set_register(cpu: &mut CPU, register: &ERegister, value: Byte) {
+        match register {
+            ERegister::A => cpu.set_a(value),
+            ERegister::X => cpu.set_x(value),
+            ERegister::Y => cpu.set_y(value),
+        }
+    }
+
     fn compare_inmediate(test: CMPTestData, register_to_compare: ERegister) {
         let mut mem = Mem::new();
         let mut cpu = CPU::new();
@@ -83,35 +97,19 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
-
-        let mut register: *mut Byte = &mut cpu.A;
-
-        let mut opcode: Byte = cpu.INS_CMP_IM;
-
-        match register_to_compare {
-            ERegister::X => {
-                register = &mut cpu.X;
-                opcode = cpu.INS_CPX_IM;
-            }
-            ERegister::Y => {
-                register = &mut cpu.Y;
-                opcode = cpu.INS_CPY_IM;
-            }
-            ERegister::A => {
-                register = &mut cpu.A;
-                opcode = cpu.INS_CMP_IM;
-            }
-        }
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        unsafe {
-            *register = test.register_value;
-        }
+        let opcode: Byte = match register_to_compare {
+            ERegister::X => INS_CPX_IM,
+            ERegister::Y => INS_CPY_IM,
+            ERegister::A => INS_CMP_IM,
+        };
+        set_register(&mut cpu, &register_to_compare, test.register_value);
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, test.operand);
         let mut expected_cycles = 2;
         let cpu_copy = cpu;
 
@@ -120,12 +118,10 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        unsafe {
-            assert_eq!(*register, test.register_value);
-        }
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(register(&cpu, &register_to_compare), test.register_value);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -136,36 +132,20 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
-
-        let mut register: *mut Byte = &mut cpu.A;
-
-        let mut opcode: Byte = cpu.INS_CMP_ZP;
-
-        match register_to_compare {
-            ERegister::X => {
-                register = &mut cpu.X;
-                opcode = cpu.INS_CPX_ZP;
-            }
-            ERegister::Y => {
-                register = &mut cpu.Y;
-                opcode = cpu.INS_CPY_ZP;
-            }
-            ERegister::A => {
-                register = &mut cpu.A;
-                opcode = cpu.INS_CMP_ZP;
-            }
-        }
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        unsafe {
-            *register = test.register_value;
-        }
+        let opcode: Byte = match register_to_compare {
+            ERegister::X => INS_CPX_ZP,
+            ERegister::Y => INS_CPY_ZP,
+            ERegister::A => INS_CMP_ZP,
+        };
+        set_register(&mut cpu, &register_to_compare, test.register_value);
 
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042] = test.operand;
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, test.operand);
         let mut expected_cycles = 3;
         let cpu_copy = cpu;
 
@@ -174,12 +154,10 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        unsafe {
-            assert_eq!(*register, test.register_value);
-        }
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(register(&cpu, &register_to_compare), test.register_value);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -190,16 +168,16 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        cpu.A = test.register_value;
-        cpu.X = 4;
+        cpu.set_a(test.register_value);
+        cpu.set_x(4);
 
-        mem.Data[0xFF00] = cpu.INS_CMP_ZPX;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042 + 0x4] = test.operand;
+        mem.poke(0xFF00, INS_CMP_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x4, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -208,11 +186,11 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.register_value);
-        assert_eq!(cpu.X, 4);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.register_value);
+        assert_eq!(cpu.x(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -223,37 +201,21 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
-
-        let mut register: *mut Byte = &mut cpu.A;
-
-        let mut opcode: Byte = cpu.INS_CMP_ABS;
-
-        match register_to_compare {
-            ERegister::X => {
-                register = &mut cpu.X;
-                opcode = cpu.INS_CPX_ABS;
-            }
-            ERegister::Y => {
-                register = &mut cpu.Y;
-                opcode = cpu.INS_CPY_ABS;
-            }
-            ERegister::A => {
-                register = &mut cpu.A;
-                opcode = cpu.INS_CMP_ABS;
-            }
-        }
-
-        unsafe {
-            *register = test.register_value;
-        }
-
-        mem.Data[0xFF00] = opcode;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = test.operand;
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
+
+        let opcode: Byte = match register_to_compare {
+            ERegister::X => INS_CPX_ABS,
+            ERegister::Y => INS_CPY_ABS,
+            ERegister::A => INS_CMP_ABS,
+        };
+        set_register(&mut cpu, &register_to_compare, test.register_value);
+
+        mem.poke(0xFF00, opcode);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -262,12 +224,10 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        unsafe {
-            assert_eq!(*register, test.register_value);
-        }
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(register(&cpu, &register_to_compare), test.register_value);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -278,17 +238,17 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        cpu.A = test.register_value;
-        cpu.X = 4;
+        cpu.set_a(test.register_value);
+        cpu.set_x(4);
 
-        mem.Data[0xFF00] = cpu.INS_CMP_ABSX;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 4] = test.operand;
+        mem.poke(0xFF00, INS_CMP_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 4, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -297,11 +257,11 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.register_value);
-        assert_eq!(cpu.X, 4);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.register_value);
+        assert_eq!(cpu.x(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -312,17 +272,17 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        cpu.A = test.register_value;
-        cpu.Y = 4;
+        cpu.set_a(test.register_value);
+        cpu.set_y(4);
 
-        mem.Data[0xFF00] = cpu.INS_CMP_ABSY;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 4] = test.operand;
+        mem.poke(0xFF00, INS_CMP_ABSY);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 4, test.operand);
         let mut expected_cycles = 4;
         let cpu_copy = cpu;
 
@@ -331,11 +291,11 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, test.register_value);
-        assert_eq!(cpu.Y, 4);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.register_value);
+        assert_eq!(cpu.y(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -346,18 +306,18 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        cpu.A = test.register_value;
-        cpu.X = 4;
+        cpu.set_a(test.register_value);
+        cpu.set_x(4);
 
-        mem.Data[0xFF00] = cpu.INS_CMP_INDX;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x42 + 4] = 0x00;
-        mem.Data[0x42 + 5] = 0x80;
-        mem.Data[0x8000] = test.operand;
+        mem.poke(0xFF00, INS_CMP_INDX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x42 + 4, 0x00);
+        mem.poke(0x42 + 5, 0x80);
+        mem.poke(0x8000, test.operand);
         let mut expected_cycles = 6;
         let cpu_copy = cpu;
 
@@ -366,11 +326,11 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(cpu.A, test.register_value);
-        assert_eq!(cpu.X, 4);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.register_value);
+        assert_eq!(cpu.x(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -381,18 +341,18 @@ mod compare_register_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(0, !test.expect_c); // C
-        cpu.PS.set_bit(1, !test.expect_z); // Z
-        cpu.PS.set_bit(7, !test.expect_n); // N
+        cpu.set_flag(Flags::CarryFlagBit, !test.expect_c); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
 
-        cpu.A = test.register_value;
-        cpu.Y = 4;
+        cpu.set_a(test.register_value);
+        cpu.set_y(4);
 
-        mem.Data[0xFF00] = cpu.INS_CMP_INDY;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x42] = 0x00;
-        mem.Data[0x43] = 0x80;
-        mem.Data[0x8000 + 4] = test.operand;
+        mem.poke(0xFF00, INS_CMP_INDY);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x42, 0x00);
+        mem.poke(0x43, 0x80);
+        mem.poke(0x8000 + 4, test.operand);
         let mut expected_cycles = 5;
         let cpu_copy = cpu;
 
@@ -401,11 +361,11 @@ mod compare_register_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(cpu.A, test.register_value);
-        assert_eq!(cpu.Y, 4);
-        assert_eq!(cpu.PS.get_bit(0), test.expect_c); // C
-        assert_eq!(cpu.PS.get_bit(1), test.expect_z); // Z
-        assert_eq!(cpu.PS.get_bit(7), test.expect_n); // N
+        assert_eq!(cpu.a(), test.register_value);
+        assert_eq!(cpu.y(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), test.expect_n); // N
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -788,9 +748,21 @@ mod compare_register_tests {
     }
 
     fn verify_unmodified_flags(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2)); // I
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3)); // D
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4)); // B
-        assert_eq!(cpu.PS.get_bit(6), cpu_copy.PS.get_bit(6)); // V
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        ); // I
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        ); // D
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        ); // B
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            cpu_copy.flag(Flags::OverflowFlagBit)
+        ); // V
     }
 }
@@ -0,0 +1,469 @@
+#[cfg(test)]
+mod cpu_variants_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::Byte;
+    use rust_6502::CpuVariant;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    fn setup(variant: CpuVariant, mem: &mut Mem, program: &[Byte]) -> CPU {
+        let mut cpu = CPU::new_variant(variant);
+        cpu.reset_vector(mem, 0xFF00);
+        mem.load(0xFF00, program);
+        cpu
+    }
+
+    #[test]
+    fn the_default_variant_is_the_nmos_6502() {
+        // when:
+        let cpu = CPU::new();
+
+        // then:
+        assert_eq!(cpu.variant(), CpuVariant::Nmos6502);
+        assert!(!cpu.variant().is_cmos());
+        assert!(CpuVariant::Rockwell65C02.is_cmos());
+    }
+
+    #[test]
+    fn jmp_indirect_at_the_end_of_a_page_wraps_on_the_nmos_6502() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Nmos6502, &mut mem, &[0x6C, 0xFF, 0x30]);
+        mem.poke(0x30FF, 0x80);
+        mem.poke(0x3000, 0x50);
+        mem.poke(0x3100, 0x40);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(cpu.pc(), 0x5080);
+    }
+
+    #[test]
+    fn jmp_indirect_at_the_end_of_a_page_is_fixed_on_the_65c02() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0x6C, 0xFF, 0x30]);
+        mem.poke(0x30FF, 0x80);
+        mem.poke(0x3000, 0x50);
+        mem.poke(0x3100, 0x40);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(cpu.pc(), 0x4080);
+    }
+
+    #[test]
+    fn jmp_absolute_indexed_indirect() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0x7C, 0x00, 0x30]);
+        cpu.set_x(0x04);
+        mem.poke(0x3004, 0x34);
+        mem.poke(0x3005, 0x12);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(cpu.pc(), 0x1234);
+    }
+
+    #[test]
+    fn bra_always_branches() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Rockwell65C02, &mut mem, &[0x80, 0x10]);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 3, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 3);
+        assert_eq!(cpu.pc(), 0xFF12);
+    }
+
+    #[test]
+    fn phx_and_ply_move_x_to_y_through_the_stack() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0xDA, 0x7A]);
+        cpu.set_x(0x80);
+        cpu.set_y(0x00);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (3 + 4), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 3 + 4);
+        assert_eq!(cpu.y(), 0x80);
+        assert_eq!(cpu.sp(), 0xFF);
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+    }
+
+    #[test]
+    fn stz_stores_zero() {
+        let mut mem = Mem::new();
+
+        // given:
+        // STZ $42, STZ $3000,X
+        let mut cpu = setup(
+            CpuVariant::Wdc65C02,
+            &mut mem,
+            &[0x64, 0x42, 0x9E, 0x00, 0x30],
+        );
+        cpu.set_x(0x01);
+        mem.poke(0x0042, 0xFF);
+        mem.poke(0x3001, 0xFF);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (3 + 5), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 3 + 5);
+        assert_eq!(mem.peek(0x0042), 0x00);
+        assert_eq!(mem.peek(0x3001), 0x00);
+    }
+
+    #[test]
+    fn tsb_and_trb_test_and_change_the_bits_of_a() {
+        let mut mem = Mem::new();
+
+        // given:
+        // TSB $42, TRB $3000
+        let mut cpu = setup(
+            CpuVariant::Wdc65C02,
+            &mut mem,
+            &[0x04, 0x42, 0x1C, 0x00, 0x30],
+        );
+        cpu.set_a(0x0F);
+        mem.poke(0x0042, 0xF0);
+        mem.poke(0x3000, 0xFF);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+        let z_after_tsb = cpu.flag(Flags::ZeroFlagBit);
+        let cycles_used = cycles_used + cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5 + 6);
+        assert_eq!(mem.peek(0x0042), 0xFF);
+        assert!(z_after_tsb);
+        assert_eq!(mem.peek(0x3000), 0xF0);
+        assert!(!cpu.flag(Flags::ZeroFlagBit)); // Z
+    }
+
+    #[test]
+    fn zero_page_indirect_loads_and_stores() {
+        let mut mem = Mem::new();
+
+        // given:
+        // LDA ($10), ADC ($10), STA ($12)
+        let mut cpu = setup(
+            CpuVariant::Wdc65C02,
+            &mut mem,
+            &[0xB2, 0x10, 0x72, 0x10, 0x92, 0x12],
+        );
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        mem.poke(0x0010, 0x00);
+        mem.poke(0x0011, 0x30);
+        mem.poke(0x0012, 0x00);
+        mem.poke(0x0013, 0x40);
+        mem.poke(0x3000, 0x21);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (5 + 5 + 5), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5 + 5 + 5);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(mem.peek(0x4000), 0x42);
+    }
+
+    #[test]
+    fn inc_a_and_dec_a() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0x1A, 0x3A, 0x3A]);
+        cpu.set_a(0xFF);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+        let a_after_inc = cpu.a();
+        let z_after_inc = cpu.flag(Flags::ZeroFlagBit);
+        cpu.execute(&mut (2 + 2), &mut mem);
+
+        // then:
+        assert_eq!(a_after_inc, 0x00);
+        assert!(z_after_inc);
+        assert_eq!(cpu.a(), 0xFE);
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+    }
+
+    #[test]
+    fn bit_immediate_only_changes_z() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0x89, 0xC0]);
+        cpu.set_a(0x01);
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+        cpu.set_flag(Flags::OverflowFlagBit, false); // V
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert!(cpu.flag(Flags::ZeroFlagBit)); // Z
+        assert!(!cpu.flag(Flags::NegativeFlagBit)); // N
+        assert!(!cpu.flag(Flags::OverflowFlagBit)); // V
+    }
+
+    #[test]
+    fn decimal_adc_flags_come_from_the_bcd_result_on_the_65c02() {
+        let mut mem = Mem::new();
+
+        for (variant, z, cycles) in [
+            (CpuVariant::Nmos6502, false, 2),
+            (CpuVariant::Wdc65C02, true, 3),
+        ] {
+            // given:
+            let mut cpu = setup(variant, &mut mem, &[0x69, 0x01]);
+            cpu.set_flag(Flags::DecimalModeFlagBit, true); // D
+            cpu.set_flag(Flags::CarryFlagBit, false); // C
+            cpu.set_a(0x99);
+            let mut budget = cycles;
+
+            // when:
+            let cycles_used = cpu.execute(&mut budget, &mut mem);
+
+            // then:
+            assert_eq!(cycles_used, cycles);
+            assert_eq!(cpu.a(), 0x00);
+            assert!(cpu.flag(Flags::CarryFlagBit)); // C
+            assert_eq!(cpu.flag(Flags::ZeroFlagBit), z, "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn decimal_sbc_on_the_65c02() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0xE9, 0x01]);
+        cpu.set_flag(Flags::DecimalModeFlagBit, true); // D
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_a(0x00);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 3, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 3);
+        assert_eq!(cpu.a(), 0x99);
+        assert!(!cpu.flag(Flags::CarryFlagBit)); // C
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+    }
+
+    #[test]
+    fn brk_clears_decimal_mode_on_the_65c02_only() {
+        let mut mem = Mem::new();
+
+        for (variant, d) in [(CpuVariant::Nmos6502, true), (CpuVariant::Wdc65C02, false)] {
+            // given:
+            let mut cpu = setup(variant, &mut mem, &[0x00]);
+            cpu.set_flag(Flags::DecimalModeFlagBit, true); // D
+            mem.poke(0xFFFE, 0x00);
+            mem.poke(0xFFFF, 0x80);
+
+            // when:
+            cpu.execute(&mut 7, &mut mem);
+
+            // then:
+            assert_eq!(cpu.pc(), 0x8000);
+            assert_eq!(cpu.flag(Flags::DecimalModeFlagBit), d, "{:?}", variant);
+        }
+    }
+
+    #[test]
+    fn rmb_and_smb_change_one_bit_of_a_zero_page_byte() {
+        let mut mem = Mem::new();
+
+        // given:
+        // RMB0 $42, SMB7 $43
+        let mut cpu = setup(
+            CpuVariant::Rockwell65C02,
+            &mut mem,
+            &[0x07, 0x42, 0xF7, 0x43],
+        );
+        mem.poke(0x0042, 0xFF);
+        mem.poke(0x0043, 0x00);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (5 + 5), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5 + 5);
+        assert_eq!(mem.peek(0x0042), 0xFE);
+        assert_eq!(mem.peek(0x0043), 0x80);
+    }
+
+    #[test]
+    fn bbr_and_bbs_branch_on_one_bit_of_a_zero_page_byte() {
+        let mut mem = Mem::new();
+
+        // given:
+        // BBR1 $42,+$10 (not taken), BBS1 $42,+$10 (taken)
+        let mut cpu = setup(
+            CpuVariant::Rockwell65C02,
+            &mut mem,
+            &[0x1F, 0x42, 0x10, 0x9F, 0x42, 0x10],
+        );
+        mem.poke(0x0042, 0x02);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+        let pc_not_taken = cpu.pc();
+        let cycles_used = cycles_used + cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5 + 6);
+        assert_eq!(pc_not_taken, 0xFF03);
+        assert_eq!(cpu.pc(), 0xFF16);
+    }
+
+    #[test]
+    fn wai_sleeps_until_an_interrupt() {
+        let mut mem = Mem::new();
+
+        // given:
+        // WAI with the I flag set, then LDA #$42
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0xCB, 0xA9, 0x42]);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, true); // I
+
+        // when:
+        let cycles_used = cpu.execute(&mut 100, &mut mem);
+        let waiting = cpu.is_waiting();
+        cpu.set_irq(true);
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 100);
+        assert!(waiting);
+        assert!(!cpu.is_waiting());
+        // the IRQ is masked, so the CPU carries on after the WAI
+        assert_eq!(cpu.a(), 0x42);
+    }
+
+    #[test]
+    fn stp_stops_the_cpu_until_it_is_reset() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Wdc65C02, &mut mem, &[0xDB, 0xA9, 0x42]);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 100, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 100);
+        assert!(cpu.is_jammed());
+        assert_eq!(cpu.a(), 0x00);
+    }
+
+    #[test]
+    fn the_rockwell_65c02_has_no_wai_and_stp() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(CpuVariant::Rockwell65C02, &mut mem, &[0xCB, 0xDB, 0xEA]);
+
+        // when:
+        let cycles_used = cpu.execute(&mut (1 + 1 + 2), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 1 + 1 + 2);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert!(!cpu.is_waiting());
+        assert!(!cpu.is_jammed());
+    }
+
+    #[test]
+    fn unused_65c02_opcodes_are_nops() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        // (opcode, bytes, cycles)
+        let nops: [(Byte, u16, isize); 8] = [
+            (0x03, 1, 1),
+            (0xFB, 1, 1),
+            (0x02, 2, 2),
+            (0xE2, 2, 2),
+            (0x44, 2, 3),
+            (0xF4, 2, 4),
+            (0x5C, 3, 8),
+            (0xDC, 3, 4),
+        ];
+
+        for (opcode, bytes, cycles) in nops {
+            // given:
+            let mut cpu = CPU::new_variant(CpuVariant::Wdc65C02);
+            cpu.set_pc(0x0200);
+            cpu.set_sp(0xFF);
+            mem.poke(0x0200, opcode);
+            let cpu_copy = cpu;
+
+            // when:
+            let cycles_used = cpu.step(&mut mem);
+
+            // then:
+            assert_eq!(cycles_used, cycles, "opcode {:02X}", opcode);
+            assert_eq!(cpu.pc(), 0x0200 + bytes, "opcode {:02X}", opcode);
+            assert_eq!(cpu.a(), cpu_copy.a());
+            assert_eq!(cpu.status(), cpu_copy.status());
+        }
+    }
+
+    #[test]
+    fn no_opcode_jams_the_65c02() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        for opcode in 0..=0xFF {
+            // given:
+            let mut cpu = CPU::new_variant(CpuVariant::Rockwell65C02);
+            cpu.set_pc(0x0200);
+            cpu.set_sp(0xFF);
+            mem.poke(0x0200, opcode);
+            mem.poke(0x0201, 0x40);
+            mem.poke(0x0202, 0x30);
+            // keep INC and DEC away from the edges, whatever they address
+            for address in [0x0040, 0x0041, 0x0101, 0x3040] {
+                mem.poke(address, 0x01);
+            }
+
+            // when:
+            cpu.step(&mut mem);
+
+            // then:
+            assert!(!cpu.is_jammed(), "opcode {:02X}", opcode);
+        }
+    }
+}
@@ -0,0 +1,395 @@
+#[cfg(test)]
+mod debugger_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::debugger::*;
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    //  1000  LDX #$00
+    //  1002  INX          <- loop
+    //  1003  STX $0200
+    //  1006  CPX #$05
+    //  1008  BNE loop
+    //  100A  JSR $1020
+    //  100D  NOP
+    //  100E  JMP $100E
+    //
+    //  1020  LDA $0200
+    //  1023  JSR $1030
+    //  1026  RTS
+    //
+    //  1030  INY
+    //  1031  RTS
+    fn setup(cpu: &mut CPU, mem: &mut Mem) {
+        cpu.reset_vector(mem, 0x1000);
+
+        let main: [Byte; 17] = [
+            0xA2, 0x00, 0xE8, 0x8E, 0x00, 0x02, 0xE0, 0x05, 0xD0, 0xF8, 0x20, 0x20, 0x10, 0xEA,
+            0x4C, 0x0E, 0x10,
+        ];
+        let sub: [Byte; 7] = [0xAD, 0x00, 0x02, 0x20, 0x30, 0x10, 0x60];
+        let nested_sub: [Byte; 2] = [0xC8, 0x60];
+
+        mem.load(0x1000, &main);
+        mem.load(0x1020, &sub);
+        mem.load(0x1030, &nested_sub);
+    }
+
+    #[test]
+    fn run_stops_at_a_breakpoint_before_executing_it() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_breakpoint(0x100A);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::Breakpoint(0x100A));
+        assert_eq!(cpu.pc(), 0x100A);
+        assert_eq!(cpu.x(), 5);
+    }
+
+    #[test]
+    fn run_can_continue_from_a_breakpoint() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_breakpoint(0x1002);
+
+        // when:
+        let first = debugger.run(&mut cpu, &mut mem, 1000);
+        let x_at_first = cpu.x();
+        let second = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(first, StopReason::Breakpoint(0x1002));
+        assert_eq!(second, StopReason::Breakpoint(0x1002));
+        assert_eq!(x_at_first, 0);
+        assert_eq!(cpu.x(), 1);
+    }
+
+    #[test]
+    fn removed_breakpoints_do_not_stop_the_cpu() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_breakpoint(0x1002);
+        debugger.add_breakpoint(0x100D);
+        debugger.remove_breakpoint(0x1002);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::Breakpoint(0x100D));
+    }
+
+    #[test]
+    fn a_write_watchpoint_stops_after_the_instruction_that_writes() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_watchpoint(0x0200, 0x0200, Access::Write);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(
+            reason,
+            StopReason::Watchpoint {
+                pc: 0x1003,
+                address: 0x0200,
+                value: 0x01,
+                access: Access::Write,
+            }
+        );
+        assert_eq!(cpu.pc(), 0x1006);
+    }
+
+    #[test]
+    fn a_read_watchpoint_ignores_writes() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_watchpoint(0x0200, 0x0200, Access::Read);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(
+            reason,
+            StopReason::Watchpoint {
+                pc: 0x1020,
+                address: 0x0200,
+                value: 0x05,
+                access: Access::Read,
+            }
+        );
+    }
+
+    #[test]
+    fn a_watchpoint_can_cover_a_range_of_addresses() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_watchpoint(0x0100, 0x01FF, Access::ReadWrite);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(
+            reason,
+            StopReason::Watchpoint {
+                pc: 0x100A,
+                address: 0x01FF,
+                value: 0x10,
+                access: Access::Write,
+            }
+        );
+    }
+
+    #[test]
+    fn instruction_fetches_do_not_trigger_read_watchpoints() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_watchpoint(0x1000, 0x10FF, Access::Read);
+        debugger.add_breakpoint(0x100D);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::Breakpoint(0x100D));
+    }
+
+    #[test]
+    fn a_condition_without_address_is_checked_before_every_instruction() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        let condition = Condition {
+            register: Register::X,
+            comparison: Comparison::Equal,
+            value: 3,
+        };
+        debugger.add_condition(None, condition);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(
+            reason,
+            StopReason::Condition {
+                address: 0x1003,
+                condition
+            }
+        );
+        assert_eq!(cpu.x(), 3);
+    }
+
+    #[test]
+    fn a_condition_with_address_only_stops_at_that_address() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        let condition = Condition {
+            register: Register::X,
+            comparison: Comparison::GreaterOrEqual,
+            value: 4,
+        };
+        debugger.add_condition(Some(0x1006), condition);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(
+            reason,
+            StopReason::Condition {
+                address: 0x1006,
+                condition
+            }
+        );
+        assert_eq!(cpu.x(), 4);
+    }
+
+    #[test]
+    fn step_executes_a_single_instruction() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.add_breakpoint(0x1002);
+
+        // when:
+        let reason = debugger.step(&mut cpu, &mut mem);
+
+        // then:
+        assert_eq!(reason, StopReason::StepComplete);
+        assert_eq!(cpu.pc(), 0x1002);
+    }
+
+    #[test]
+    fn step_over_runs_a_whole_subroutine_as_one_step() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_pc(0x100A);
+        let cpu_copy = cpu;
+
+        // when:
+        let reason = debugger.step_over(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::StepComplete);
+        assert_eq!(cpu.pc(), 0x100D);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.y(), 1);
+    }
+
+    #[test]
+    fn step_over_is_a_step_when_the_instruction_is_not_a_jsr() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+
+        // when:
+        let reason = debugger.step_over(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::StepComplete);
+        assert_eq!(cpu.pc(), 0x1002);
+    }
+
+    #[test]
+    fn step_over_stops_at_a_breakpoint_inside_the_subroutine() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_pc(0x100A);
+        debugger.add_breakpoint(0x1030);
+
+        // when:
+        let reason = debugger.step_over(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(reason, StopReason::Breakpoint(0x1030));
+    }
+
+    #[test]
+    fn step_out_runs_until_the_subroutine_returns() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_pc(0x100A);
+        let cpu_copy = cpu;
+        debugger.step(&mut cpu, &mut mem);
+        let pc_in_subroutine = cpu.pc();
+
+        // when:
+        let reason = debugger.step_out(&mut cpu, &mut mem, 1000);
+
+        // then:
+        assert_eq!(pc_in_subroutine, 0x1020);
+        assert_eq!(reason, StopReason::StepComplete);
+        assert_eq!(cpu.pc(), 0x100D);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.y(), 1); // the nested subroutine ran
+    }
+
+    #[test]
+    fn run_gives_up_after_the_maximum_number_of_instructions() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+
+        // when:
+        let reason = debugger.run(&mut cpu, &mut mem, 100);
+
+        // then:
+        assert_eq!(reason, StopReason::InstructionLimit);
+        assert_eq!(cpu.pc(), 0x100E);
+    }
+
+    #[test]
+    fn a_recording_debugger_steps_back_to_the_last_write() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut debugger = Debugger::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        debugger.record(100);
+        debugger.add_breakpoint(0x100D);
+        debugger.run(&mut cpu, &mut mem, 1000);
+
+        // when:
+        let pc = debugger.back_to_write(&mut cpu, &mut mem, 0x0200);
+
+        // then:
+        assert_eq!(pc, Some(0x1003));
+        assert_eq!(cpu.x(), 5);
+        assert_eq!(mem.peek(0x0200), 4);
+
+        // when:
+        let undone = debugger.step_back(&mut cpu, &mut mem, 2);
+
+        // then:
+        assert_eq!(undone, 2);
+        assert_eq!(cpu.pc(), 0x1008);
+        assert_eq!(cpu.x(), 4);
+    }
+}
@@ -0,0 +1,502 @@
+#[cfg(test)]
+mod decimal_mode_tests {
+
+    use bit_field::BitField;
+
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
+
+    struct BCDTestData {
+        carry: bool,
+        a: Byte,
+        operand: Byte,
+        answer: Byte,
+
+        expect_c: bool,
+        expect_z: bool,
+        expect_n: bool,
+        expect_v: bool,
+    }
+
+    #[derive(PartialEq, Clone, Copy)]
+    enum EOperation {
+        Add,
+        Subtract,
+    }
+
+    #[derive(Clone, Copy)]
+    enum EAddressing {
+        Inmediate,
+        ZeroPage,
+        ZeroPageX,
+        Absolute,
+        AbsoluteX,
+        AbsoluteY,
+        IndirectX,
+        IndirectY,
+    }
+
+    fn to_bcd(value: u32) -> Byte {
+        (((value / 10) << 4) | (value % 10)) as Byte
+    }
+
+    // The flags of a decimal ADC on the NMOS 6502, after Appendix A of
+    // "Decimal Mode" by Bruce Clark: A and C are the decimal sum, Z comes
+    // from the binary sum, N and V from the signed sum before the high nibble
+    // is adjusted
+    fn expected_adc(a: u32, operand: u32, carry: bool) -> BCDTestData {
+        let c = if carry { 1 } else { 0 };
+        let bcd_a = to_bcd(a);
+        let bcd_operand = to_bcd(operand);
+        let sum = a + operand + c;
+
+        let mut low = (bcd_a & 0x0F) as i32 + (bcd_operand & 0x0F) as i32 + c as i32;
+        if low >= 0x0A {
+            low = ((low + 0x06) & 0x0F) + 0x10;
+        }
+        let signed = (bcd_a & 0xF0) as i8 as i32 + (bcd_operand & 0xF0) as i8 as i32 + low;
+
+        BCDTestData {
+            carry,
+            a: bcd_a,
+            operand: bcd_operand,
+            answer: to_bcd(sum % 100),
+            expect_c: sum >= 100,
+            expect_z: (bcd_a as u32 + bcd_operand as u32 + c) & 0xFF == 0,
+            expect_n: (signed as Byte).get_bit(7),
+            expect_v: !(-128..=127).contains(&signed),
+        }
+    }
+
+    // Every flag of a decimal SBC is the same as in binary mode
+    fn expected_sbc(a: u32, operand: u32, carry: bool) -> BCDTestData {
+        let borrow = if carry { 0 } else { 1 };
+        let bcd_a = to_bcd(a);
+        let bcd_operand = to_bcd(operand);
+        let difference = a as i32 - operand as i32 - borrow;
+        let binary = (bcd_a as i32 - bcd_operand as i32 - borrow) as Byte;
+        let signed = bcd_a as i8 as i32 - bcd_operand as i8 as i32 - borrow;
+
+        BCDTestData {
+            carry,
+            a: bcd_a,
+            operand: bcd_operand,
+            answer: to_bcd(((difference + 100) % 100) as u32),
+            expect_c: difference >= 0,
+            expect_z: binary == 0,
+            expect_n: binary.get_bit(7),
+            expect_v: !(-128..=127).contains(&signed),
+        }
+    }
+
+    fn opcode(operation: EOperation, addressing: EAddressing) -> Byte {
+        let add = operation == EOperation::Add;
+        match addressing {
+            EAddressing::Inmediate => {
+                if add {
+                    INS_ADC_IM
+                } else {
+                    INS_SBC_IM
+                }
+            }
+            EAddressing::ZeroPage => {
+                if add {
+                    INS_ADC_ZP
+                } else {
+                    INS_SBC_ZP
+                }
+            }
+            EAddressing::ZeroPageX => {
+                if add {
+                    INS_ADC_ZPX
+                } else {
+                    INS_SBC_ZPX
+                }
+            }
+            EAddressing::Absolute => {
+                if add {
+                    INS_ADC_ABS
+                } else {
+                    INS_SBC_ABS
+                }
+            }
+            EAddressing::AbsoluteX => {
+                if add {
+                    INS_ADC_ABSX
+                } else {
+                    INS_SBC_ABSX
+                }
+            }
+            EAddressing::AbsoluteY => {
+                if add {
+                    INS_ADC_ABSY
+                } else {
+                    INS_SBC_ABSY
+                }
+            }
+            EAddressing::IndirectX => {
+                if add {
+                    INS_ADC_INDX
+                } else {
+                    INS_SBC_INDX
+                }
+            }
+            EAddressing::IndirectY => {
+                if add {
+                    INS_ADC_INDY
+                } else {
+                    INS_SBC_INDY
+                }
+            }
+        }
+    }
+
+    // writes the instruction at 0xFF00 and returns the address of the operand
+    // and the cycles the instruction takes
+    fn setup_instruction(
+        cpu: &mut CPU,
+        mem: &mut Mem,
+        operation: EOperation,
+        addressing: EAddressing,
+    ) -> (Word, isize) {
+        cpu.set_x(0x10);
+        cpu.set_y(0x04);
+        mem.poke(0xFF00, opcode(operation, addressing));
+
+        match addressing {
+            EAddressing::Inmediate => (0xFF01, 2),
+            EAddressing::ZeroPage => {
+                mem.poke(0xFF01, 0x42);
+                (0x0042, 3)
+            }
+            EAddressing::ZeroPageX => {
+                mem.poke(0xFF01, 0x42);
+                (0x0042 + 0x10, 4)
+            }
+            EAddressing::Absolute => {
+                mem.poke(0xFF01, 0x00);
+                mem.poke(0xFF02, 0x80);
+                (0x8000, 4)
+            }
+            EAddressing::AbsoluteX => {
+                mem.poke(0xFF01, 0x00);
+                mem.poke(0xFF02, 0x80);
+                (0x8000 + 0x10, 4)
+            }
+            EAddressing::AbsoluteY => {
+                mem.poke(0xFF01, 0x00);
+                mem.poke(0xFF02, 0x80);
+                (0x8000 + 0x04, 4)
+            }
+            EAddressing::IndirectX => {
+                mem.poke(0xFF01, 0x02);
+                mem.poke(0x0012, 0x00); // 0x2 + 0x10
+                mem.poke(0x0013, 0x80);
+                (0x8000, 6)
+            }
+            EAddressing::IndirectY => {
+                mem.poke(0xFF01, 0x02);
+                mem.poke(0x0002, 0x00);
+                mem.poke(0x0003, 0x80);
+                (0x8000 + 0x04, 5)
+            }
+        }
+    }
+
+    fn verify_decimal_instruction(
+        cpu: &mut CPU,
+        mem: &mut Mem,
+        test: &BCDTestData,
+        operand_address: Word,
+        cycles: isize,
+    ) {
+        // given:
+        cpu.set_pc(0xFF00);
+        cpu.set_a(test.a);
+        cpu.set_flag(Flags::CarryFlagBit, test.carry); // C
+        cpu.set_flag(Flags::ZeroFlagBit, !test.expect_z); // Z
+        cpu.set_flag(Flags::DecimalModeFlagBit, true); // D
+        cpu.set_flag(Flags::OverflowFlagBit, !test.expect_v); // V
+        cpu.set_flag(Flags::NegativeFlagBit, !test.expect_n); // N
+        mem.poke(operand_address, test.operand);
+        let cpu_copy = *cpu;
+        let mut expected_cycles = cycles;
+
+        // when:
+        let actual_cycles = cpu.execute(&mut expected_cycles, mem);
+
+        // then:
+        let case = format!(
+            "A: {:02X} operand: {:02X} carry: {}",
+            test.a, test.operand, test.carry
+        );
+        assert_eq!(actual_cycles, cycles, "{}", case);
+        assert_eq!(cpu.a(), test.answer, "{}", case);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), test.expect_c, "C {}", case);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), test.expect_z, "Z {}", case);
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            test.expect_v,
+            "V {}",
+            case
+        );
+        assert_eq!(
+            cpu.flag(Flags::NegativeFlagBit),
+            test.expect_n,
+            "N {}",
+            case
+        );
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        ); // I
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        ); // D
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        ); // B
+    }
+
+    // runs every pair of valid BCD operands, with and without carry
+    fn test_all_bcd_operands(operation: EOperation, addressing: EAddressing) {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        let (operand_address, cycles) =
+            setup_instruction(&mut cpu, &mut mem, operation, addressing);
+
+        for a in 0..100 {
+            for operand in 0..100 {
+                for &carry in [false, true].iter() {
+                    let test = if operation == EOperation::Add {
+                        expected_adc(a, operand, carry)
+                    } else {
+                        expected_sbc(a, operand, carry)
+                    };
+
+                    verify_decimal_instruction(&mut cpu, &mut mem, &test, operand_address, cycles);
+                }
+            }
+        }
+    }
+
+    fn test_single_case(test: BCDTestData, operation: EOperation) {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        let (operand_address, cycles) =
+            setup_instruction(&mut cpu, &mut mem, operation, EAddressing::Inmediate);
+        verify_decimal_instruction(&mut cpu, &mut mem, &test, operand_address, cycles);
+    }
+
+    #[test]
+    fn adc_decimal_99_plus_01_wraps_to_00_with_carry_but_zero_is_not_set() {
+        let test = BCDTestData {
+            carry: false,
+            a: 0x99,
+            operand: 0x01,
+            answer: 0x00,
+            expect_c: true,
+            expect_n: true,
+            expect_v: false,
+            expect_z: false,
+        };
+
+        test_single_case(test, EOperation::Add);
+    }
+
+    #[test]
+    fn adc_decimal_79_plus_00_with_carry_sets_the_overflow_flag() {
+        let test = BCDTestData {
+            carry: true,
+            a: 0x79,
+            operand: 0x00,
+            answer: 0x80,
+            expect_c: false,
+            expect_n: true,
+            expect_v: true,
+            expect_z: false,
+        };
+
+        test_single_case(test, EOperation::Add);
+    }
+
+    #[test]
+    fn adc_decimal_can_add_two_numbers() {
+        let test = BCDTestData {
+            carry: false,
+            a: 0x15,
+            operand: 0x27,
+            answer: 0x42,
+            expect_c: false,
+            expect_n: false,
+            expect_v: false,
+            expect_z: false,
+        };
+
+        test_single_case(test, EOperation::Add);
+    }
+
+    // (carry, A, operand, answer, C, Z, N, V)
+    type AdcResult = (bool, Byte, Byte, Byte, bool, bool, bool, bool);
+
+    // Known NMOS results, the valid ones from the examples and the invalid
+    // ones from the invalid BCD section of
+    // http://www.6502.org/tutorials/decimal_mode.html
+    const NMOS_ADC_RESULTS: [AdcResult; 12] = [
+        (false, 0x00, 0x00, 0x00, false, true, false, false),
+        (false, 0x99, 0x01, 0x00, true, false, true, false),
+        (true, 0x79, 0x00, 0x80, false, false, true, true),
+        (false, 0x50, 0x50, 0x00, true, false, true, true),
+        (false, 0x24, 0x56, 0x80, false, false, true, true),
+        (true, 0x58, 0x46, 0x05, true, false, true, true),
+        (false, 0x81, 0x92, 0x73, true, false, false, true),
+        // invalid BCD
+        (false, 0x0F, 0x01, 0x16, false, false, false, false),
+        (false, 0x1A, 0x00, 0x20, false, false, false, false),
+        (false, 0x0A, 0x0A, 0x1A, false, false, false, false),
+        (false, 0xAA, 0xAA, 0xBA, true, false, false, true),
+        (true, 0xFF, 0xFF, 0x55, true, false, true, false),
+    ];
+
+    #[test]
+    fn adc_decimal_gives_the_known_nmos_results() {
+        for &(carry, a, operand, answer, expect_c, expect_z, expect_n, expect_v) in
+            NMOS_ADC_RESULTS.iter()
+        {
+            let test = BCDTestData {
+                carry,
+                a,
+                operand,
+                answer,
+                expect_c,
+                expect_z,
+                expect_n,
+                expect_v,
+            };
+
+            test_single_case(test, EOperation::Add);
+        }
+    }
+
+    #[test]
+    fn sbc_decimal_00_minus_01_wraps_to_99_and_clears_the_carry() {
+        let test = BCDTestData {
+            carry: true,
+            a: 0x00,
+            operand: 0x01,
+            answer: 0x99,
+            expect_c: false,
+            expect_n: true,
+            expect_v: false,
+            expect_z: false,
+        };
+
+        test_single_case(test, EOperation::Subtract);
+    }
+
+    #[test]
+    fn sbc_decimal_can_subtract_with_a_borrow() {
+        let test = BCDTestData {
+            carry: false,
+            a: 0x42,
+            operand: 0x15,
+            answer: 0x26,
+            expect_c: true,
+            expect_n: false,
+            expect_v: false,
+            expect_z: false,
+        };
+
+        test_single_case(test, EOperation::Subtract);
+    }
+
+    #[test]
+    fn adc_decimal_inmediate_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::Inmediate);
+    }
+
+    #[test]
+    fn adc_decimal_zero_page_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::ZeroPage);
+    }
+
+    #[test]
+    fn adc_decimal_zero_page_x_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::ZeroPageX);
+    }
+
+    #[test]
+    fn adc_decimal_absolute_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::Absolute);
+    }
+
+    #[test]
+    fn adc_decimal_absolute_x_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::AbsoluteX);
+    }
+
+    #[test]
+    fn adc_decimal_absolute_y_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::AbsoluteY);
+    }
+
+    #[test]
+    fn adc_decimal_indirect_x_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::IndirectX);
+    }
+
+    #[test]
+    fn adc_decimal_indirect_y_all_operands() {
+        test_all_bcd_operands(EOperation::Add, EAddressing::IndirectY);
+    }
+
+    #[test]
+    fn sbc_decimal_inmediate_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::Inmediate);
+    }
+
+    #[test]
+    fn sbc_decimal_zero_page_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::ZeroPage);
+    }
+
+    #[test]
+    fn sbc_decimal_zero_page_x_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::ZeroPageX);
+    }
+
+    #[test]
+    fn sbc_decimal_absolute_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::Absolute);
+    }
+
+    #[test]
+    fn sbc_decimal_absolute_x_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::AbsoluteX);
+    }
+
+    #[test]
+    fn sbc_decimal_absolute_y_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::AbsoluteY);
+    }
+
+    #[test]
+    fn sbc_decimal_indirect_x_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::IndirectX);
+    }
+
+    #[test]
+    fn sbc_decimal_indirect_y_all_operands() {
+        test_all_bcd_operands(EOperation::Subtract, EAddressing::IndirectY);
+    }
+}
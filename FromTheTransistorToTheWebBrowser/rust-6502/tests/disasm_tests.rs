@@ -0,0 +1,225 @@
+#[cfg(test)]
+mod disasm_tests {
+
+    use rust_6502::bus::{AddressDecoder, IoPort, Ram};
+    use rust_6502::disasm::*;
+    use rust_6502::CpuVariant;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
+
+    fn mem_with(address: Word, bytes: &[Byte]) -> Mem {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+        mem.load(address, bytes);
+        mem
+    }
+
+    #[test]
+    fn all_151_documented_opcodes_are_decoded() {
+        // when:
+        let documented = (0..=0xFF)
+            .filter(|&opcode| decode(opcode).is_some())
+            .count();
+
+        // then:
+        assert_eq!(documented, 151);
+        assert_eq!(decode(0xB1), Some(("LDA", AddressingMode::IndirectY)));
+        assert_eq!(decode(0x02), None);
+    }
+
+    #[test]
+    fn operands_use_the_6502_syntax_of_each_addressing_mode() {
+        let program: [Byte; 29] = [
+            0xEA, // NOP
+            0x0A, // ASL A
+            0xA9, 0x10, // LDA #$10
+            0xA5, 0x44, // LDA $44
+            0xB5, 0x44, // LDA $44,X
+            0xB6, 0x44, // LDX $44,Y
+            0xAD, 0x00, 0x44, // LDA $4400
+            0xBD, 0x00, 0x44, // LDA $4400,X
+            0xB9, 0x00, 0x44, // LDA $4400,Y
+            0x6C, 0x00, 0x44, // JMP ($4400)
+            0xA1, 0x44, // LDA ($44,X)
+            0xB1, 0x44, // LDA ($44),Y
+            0xD0, 0x00, // BNE $101C
+            0x60, // RTS
+        ];
+        let mem = mem_with(0x1000, &program);
+
+        // when:
+        let instructions = disassemble(&mem, 0x1000, 0x1000 + program.len() as u16 - 1);
+
+        // then:
+        let text: Vec<String> = instructions.iter().map(|i| i.text()).collect();
+        assert_eq!(
+            text,
+            vec![
+                "NOP",
+                "ASL A",
+                "LDA #$10",
+                "LDA $44",
+                "LDA $44,X",
+                "LDX $44,Y",
+                "LDA $4400",
+                "LDA $4400,X",
+                "LDA $4400,Y",
+                "JMP ($4400)",
+                "LDA ($44,X)",
+                "LDA ($44),Y",
+                "BNE $101C",
+                "RTS",
+            ]
+        );
+    }
+
+    #[test]
+    fn an_instruction_record_has_the_address_bytes_and_operand() {
+        let mem = mem_with(0x2000, &[0xB1, 0x44]);
+
+        // when:
+        let instruction = disassemble_instruction(&mem, 0x2000);
+
+        // then:
+        assert_eq!(instruction.address, 0x2000);
+        assert_eq!(instruction.bytes, vec![0xB1, 0x44]);
+        assert_eq!(instruction.mnemonic, "LDA");
+        assert_eq!(instruction.mode, AddressingMode::IndirectY);
+        assert_eq!(instruction.operand, "($44),Y");
+        assert_eq!(instruction.target, None);
+        assert_eq!(instruction.next_address(), 0x2002);
+        assert_eq!(instruction.to_string(), "2000  B1 44     LDA ($44),Y");
+    }
+
+    #[test]
+    fn branch_targets_are_resolved_to_absolute_addresses() {
+        // BEQ +$10, BNE -$04
+        let mem = mem_with(0x3000, &[0xF0, 0x10, 0xD0, 0xFC]);
+
+        // when:
+        let forward = disassemble_instruction(&mem, 0x3000);
+        let backward = disassemble_instruction(&mem, 0x3002);
+
+        // then:
+        assert_eq!(forward.operand, "$3012");
+        assert_eq!(forward.target, Some(0x3012));
+        assert_eq!(backward.operand, "$3000");
+        assert_eq!(backward.target, Some(0x3000));
+    }
+
+    #[test]
+    fn jsr_and_jmp_absolute_have_a_target_but_jmp_indirect_does_not() {
+        // JSR $1234, JMP $5678, JMP ($9ABC)
+        let mem = mem_with(
+            0x3000,
+            &[0x20, 0x34, 0x12, 0x4C, 0x78, 0x56, 0x6C, 0xBC, 0x9A],
+        );
+
+        // when:
+        let instructions = disassemble(&mem, 0x3000, 0x3008);
+
+        // then:
+        assert_eq!(instructions[0].target, Some(0x1234));
+        assert_eq!(instructions[1].target, Some(0x5678));
+        assert_eq!(instructions[2].target, None);
+    }
+
+    #[test]
+    fn undocumented_opcodes_are_decoded() {
+        // LAX ($44),Y, JAM, NOP $44,X
+        let mem = mem_with(0x4000, &[0xB3, 0x44, 0x02, 0x34, 0x44]);
+
+        // when:
+        let instructions = disassemble(&mem, 0x4000, 0x4004);
+
+        // then:
+        let text: Vec<String> = instructions.iter().map(|i| i.text()).collect();
+        assert_eq!(text, vec!["LAX ($44),Y", "JAM", "NOP $44,X"]);
+        assert_eq!(
+            (0..=0xFF)
+                .filter(|&opcode| decode_undocumented(opcode).is_some())
+                .count(),
+            105
+        );
+    }
+
+    #[test]
+    fn the_65c02_opcodes_are_decoded_for_the_65c02() {
+        // BRA $4004, LDA ($44), JMP ($1234,X), BBS7 $44,$4006, WAI, NOP
+        let mem = mem_with(
+            0x4000,
+            &[
+                0x80, 0x02, 0xB2, 0x44, 0x7C, 0x34, 0x12, 0xFF, 0x44, 0xF6, 0xCB, 0x02, 0x00,
+            ],
+        );
+
+        // when:
+        let mut address: u16 = 0x4000;
+        let mut text: Vec<String> = Vec::new();
+        for _ in 0..6 {
+            let instruction = disassemble_instruction_for(CpuVariant::Wdc65C02, &mem, address);
+            address = instruction.next_address();
+            text.push(instruction.text());
+        }
+
+        // then:
+        assert_eq!(
+            text,
+            vec![
+                "BRA $4004",
+                "LDA ($44)",
+                "JMP ($1234,X)",
+                "BBS7 $44,$4000",
+                "WAI",
+                "NOP #$00"
+            ]
+        );
+        assert_eq!(disassemble_instruction(&mem, 0x4002).text(), "JAM");
+    }
+
+    #[test]
+    fn the_listing_labels_jump_targets() {
+        //  0600  LDX #$03
+        //  0602  DEX
+        //  0603  BNE $0602
+        //  0605  JMP $0600
+        let mem = mem_with(0x0600, &[0xA2, 0x03, 0xCA, 0xD0, 0xFD, 0x4C, 0x00, 0x06]);
+
+        // when:
+        let text = listing(&disassemble(&mem, 0x0600, 0x0607));
+
+        // then:
+        assert_eq!(
+            text,
+            "L0600:\n\
+             0600  A2 03     LDX #$03\n\
+             L0602:\n\
+             0602  CA        DEX\n\
+             0603  D0 FD     BNE $0602       ; L0602\n\
+             0605  4C 00 06  JMP $0600       ; L0600\n"
+        );
+    }
+
+    #[test]
+    fn disassembling_does_not_read_from_devices() {
+        let mut bus = AddressDecoder::new();
+        bus.map(
+            0xD000,
+            0xD0FF,
+            Box::new(IoPort::new(
+                Box::new(|_| panic!("the device was read")),
+                Box::new(|_, _| {}),
+            )),
+        );
+        bus.map(0x0000, 0xFFFF, Box::new(Ram::new(0x10000)));
+
+        // when:
+        let instructions = disassemble(&bus, 0xD000, 0xD003);
+
+        // then:
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(instructions[0].text(), "BRK");
+    }
+}
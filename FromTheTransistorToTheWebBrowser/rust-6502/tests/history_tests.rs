@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod history_tests {
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use rust_6502::bus::{AddressDecoder, Bus, IoPort, Ram};
+    use rust_6502::history::*;
+    use rust_6502::memory_map::RegionKind;
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    //  1000  LDX #$00
+    //  1002  INX          <- loop
+    //  1003  STX $0200
+    //  1006  JSR $1020
+    //  1009  JMP $1002
+    //
+    //  1020  INC $0201
+    //  1023  RTS
+    fn setup(cpu: &mut CPU, mem: &mut Mem) {
+        cpu.reset_vector(mem, 0x1000);
+
+        let main: [Byte; 12] = [
+            0xA2, 0x00, 0xE8, 0x8E, 0x00, 0x02, 0x20, 0x20, 0x10, 0x4C, 0x02, 0x10,
+        ];
+        let sub: [Byte; 4] = [0xEE, 0x01, 0x02, 0x60];
+
+        mem.load(0x1000, &main);
+        mem.load(0x1020, &sub);
+    }
+
+    fn run(history: &mut History, cpu: &mut CPU, mem: &mut Mem, instructions: usize) {
+        for _ in 0..instructions {
+            history.step(cpu, mem);
+        }
+    }
+
+    #[test]
+    fn stepping_back_restores_the_registers_and_the_memory() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        run(&mut history, &mut cpu, &mut mem, 2);
+        let cpu_copy = cpu;
+        let mem_copy = mem.data().to_vec();
+
+        // when:
+        run(&mut history, &mut cpu, &mut mem, 20);
+        let undone = history.step_back_n(&mut cpu, &mut mem, 20);
+
+        // then:
+        assert_eq!(undone, 20);
+        assert_eq!(history.len(), 2);
+        assert_eq!(cpu.pc(), cpu_copy.pc());
+        assert_eq!(cpu.x(), cpu_copy.x());
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.status(), cpu_copy.status());
+        assert_eq!(cpu.cycles(), cpu_copy.cycles());
+        assert!(mem.data() == mem_copy);
+    }
+
+    #[test]
+    fn a_jsr_is_undone_with_the_return_address_it_pushed() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        mem.poke(0x01FE, 0x55);
+        mem.poke(0x01FF, 0x66);
+        run(&mut history, &mut cpu, &mut mem, 4);
+
+        // when:
+        history.step_back(&mut cpu, &mut mem);
+
+        // then:
+        assert_eq!(cpu.pc(), 0x1006);
+        assert_eq!(cpu.sp(), 0xFF);
+        assert_eq!(mem.peek(0x01FE), 0x55);
+        assert_eq!(mem.peek(0x01FF), 0x66);
+    }
+
+    #[test]
+    fn the_oldest_instructions_are_forgotten() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(5);
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        run(&mut history, &mut cpu, &mut mem, 12);
+
+        // when:
+        let undone = history.step_back_n(&mut cpu, &mut mem, 100);
+
+        // then:
+        assert_eq!(undone, 5);
+        assert!(history.is_empty());
+        assert!(!history.step_back(&mut cpu, &mut mem));
+        assert_eq!(cpu.pc(), 0x1002); // the 8th instruction
+    }
+
+    #[test]
+    fn running_back_to_a_write_stops_before_the_instruction_that_wrote() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given: the STX of X = 3 was 4 instructions back
+        setup(&mut cpu, &mut mem);
+        run(&mut history, &mut cpu, &mut mem, 18);
+
+        // when:
+        let pc = history.back_to_write(&mut cpu, &mut mem, 0x0200);
+
+        // then:
+        assert_eq!(pc, Some(0x1003));
+        assert_eq!(cpu.x(), 3);
+        assert_eq!(mem.peek(0x0200), 2);
+        assert_eq!(history.len(), 14);
+    }
+
+    #[test]
+    fn nothing_is_undone_without_a_write_to_the_address() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        run(&mut history, &mut cpu, &mut mem, 10);
+        let pc = cpu.pc();
+
+        // when:
+        let found = history.back_to_write(&mut cpu, &mut mem, 0x0300);
+
+        // then:
+        assert_eq!(found, None);
+        assert_eq!(cpu.pc(), pc);
+        assert_eq!(history.len(), 10);
+    }
+
+    // LDA #$42; STA $F000; STA $0300 at $1000
+    const STORES: [Byte; 8] = [0xA9, 0x42, 0x8D, 0x00, 0xF0, 0x8D, 0x00, 0x03];
+
+    #[test]
+    fn stepping_back_does_not_flag_the_rom_in_strict_mode() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given:
+        cpu.reset_vector(&mut mem, 0x1000);
+        mem.load(0x1000, &STORES);
+        mem.map(0xF000, 0xFFFF, RegionKind::Rom);
+        mem.strict = true;
+        run(&mut history, &mut cpu, &mut mem, 3);
+        let violations = mem.take_violations();
+
+        // when:
+        let undone = history.step_back_n(&mut cpu, &mut mem, 3);
+
+        // then:
+        assert_eq!(undone, 3);
+        assert_eq!(violations.len(), 1);
+        assert!(mem.take_violations().is_empty());
+        assert_eq!(mem.peek(0x0300), 0x00);
+        assert_eq!(cpu.pc(), 0x1000);
+    }
+
+    #[test]
+    fn stepping_back_leaves_the_devices_alone() {
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        let port_writes = Rc::clone(&writes);
+        let mut bus = AddressDecoder::new();
+        bus.map(0x0000, 0x1FFF, Box::new(Ram::new(0x2000)));
+        bus.map(
+            0xF000,
+            0xF000,
+            Box::new(IoPort::new(
+                Box::new(|_| 0),
+                Box::new(move |_, value| port_writes.borrow_mut().push(value)),
+            )),
+        );
+        let mut cpu = CPU::new();
+        let mut history = History::new(100);
+
+        // given:
+        for (i, &byte) in STORES.iter().enumerate() {
+            bus.write(0x1000 + i as u16, byte);
+        }
+        cpu.set_pc(0x1000);
+        for _ in 0..3 {
+            history.step(&mut cpu, &mut bus);
+        }
+
+        // when:
+        history.step_back_n(&mut cpu, &mut bus, 3);
+
+        // then: the port was written once, the RAM is back
+        assert_eq!(*writes.borrow(), vec![0x42]);
+        assert_eq!(bus.peek(0x0300), 0x00);
+    }
+}
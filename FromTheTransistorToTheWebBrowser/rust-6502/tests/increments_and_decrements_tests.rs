@@ -1,14 +1,11 @@
 #[cfg(test)]
 mod increments_and_decrements_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn incx_can_increment_a_zero_value() {
@@ -19,8 +16,8 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.X = 0x0;
-        mem.Data[0xFF00] = cpu.INS_INX;
+        cpu.set_x(0x0);
+        mem.poke(0xFF00, INS_INX);
 
         let mut expected_cycles = 2;
 
@@ -28,9 +25,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x01);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x01);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -43,10 +40,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0xFF;
-        mem.Data[0xFF00] = cpu.INS_INX;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0xFF);
+        mem.poke(0xFF00, INS_INX);
 
         let mut expected_cycles = 2;
 
@@ -54,9 +51,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x00);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x00);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -69,10 +66,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.X = 0b10000010;
-        mem.Data[0xFF00] = cpu.INS_INX;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_x(0b10000010);
+        mem.poke(0xFF00, INS_INX);
 
         let mut expected_cycles = 2;
 
@@ -80,9 +77,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0b10000011);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0b10000011);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -95,8 +92,8 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.Y = 0x0;
-        mem.Data[0xFF00] = cpu.INS_INY;
+        cpu.set_y(0x0);
+        mem.poke(0xFF00, INS_INY);
 
         let mut expected_cycles = 2;
 
@@ -104,9 +101,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0x01);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.y(), 0x01);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -119,10 +116,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        cpu.PS.set_bit(7, true);
-        cpu.Y = 0xFF;
-        mem.Data[0xFF00] = cpu.INS_INY;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_y(0xFF);
+        mem.poke(0xFF00, INS_INY);
 
         let mut expected_cycles = 2;
 
@@ -130,9 +127,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0x00);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.y(), 0x00);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -145,10 +142,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.Y = 0b10000010;
-        mem.Data[0xFF00] = cpu.INS_INY;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_y(0b10000010);
+        mem.poke(0xFF00, INS_INY);
 
         let mut expected_cycles = 2;
 
@@ -156,9 +153,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0b10000011);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.y(), 0b10000011);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -171,8 +168,8 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.X = 0x00;
-        mem.Data[0xFF00] = cpu.INS_DEX;
+        cpu.set_x(0x00);
+        mem.poke(0xFF00, INS_DEX);
 
         let mut expected_cycles = 2;
 
@@ -180,9 +177,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0xFF);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0xFF);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -195,10 +192,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.X = 0xFF;
-        mem.Data[0xFF00] = cpu.INS_DEX;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_x(0xFF);
+        mem.poke(0xFF00, INS_DEX);
 
         let mut expected_cycles = 2;
 
@@ -206,9 +203,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0xFE);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0xFE);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -221,10 +218,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.X = 0b10000011;
-        mem.Data[0xFF00] = cpu.INS_DEX;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_x(0b10000011);
+        mem.poke(0xFF00, INS_DEX);
 
         let mut expected_cycles = 2;
 
@@ -232,9 +229,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0b10000010);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0b10000010);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -247,8 +244,8 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.Y = 0x0;
-        mem.Data[0xFF00] = cpu.INS_DEY;
+        cpu.set_y(0x0);
+        mem.poke(0xFF00, INS_DEY);
 
         let mut expected_cycles = 2;
 
@@ -256,9 +253,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0xFF);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.y(), 0xFF);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -271,10 +268,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.Y = 0xFF;
-        mem.Data[0xFF00] = cpu.INS_DEY;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_y(0xFF);
+        mem.poke(0xFF00, INS_DEY);
 
         let mut expected_cycles = 2;
 
@@ -282,9 +279,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0xFE);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.y(), 0xFE);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -297,10 +294,10 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, false);
-        cpu.Y = 0b10000011;
-        mem.Data[0xFF00] = cpu.INS_DEY;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_y(0b10000011);
+        mem.poke(0xFF00, INS_DEY);
 
         let mut expected_cycles = 2;
 
@@ -308,9 +305,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0b10000010);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.y(), 0b10000010);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -323,11 +320,11 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        mem.Data[0xFF00] = cpu.INS_DEC_ZP;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        mem.poke(0xFF00, INS_DEC_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0x57);
 
         let mut expected_cycles = 5;
 
@@ -335,9 +332,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(mem.Data[0x0042], 0x56);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x0042), 0x56);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -350,12 +347,12 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x10;
-        mem.Data[0xFF00] = cpu.INS_DEC_ZPX;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042 + 0x10] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x10);
+        mem.poke(0xFF00, INS_DEC_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 0x57);
 
         let mut expected_cycles = 6;
 
@@ -363,9 +360,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x0042 + 0x10], 0x56);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x0042 + 0x10), 0x56);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -378,12 +375,12 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        mem.Data[0xFF00] = cpu.INS_DEC_ABS;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        mem.poke(0xFF00, INS_DEC_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 0x57);
 
         let mut expected_cycles = 6;
 
@@ -391,9 +388,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000], 0x56);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x8000), 0x56);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -406,23 +403,23 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x10;
-        mem.Data[0xFF00] = cpu.INS_DEC_ABSX;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 0x10] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x10);
+        mem.poke(0xFF00, INS_DEC_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 0x57);
 
-        let mut expected_cycles = 6;
+        let mut expected_cycles = 7;
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
-        assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000 + 0x10], 0x56);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(actual_cycles, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 0x56);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -435,11 +432,11 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        mem.Data[0xFF00] = cpu.INS_INC_ZP;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        mem.poke(0xFF00, INS_INC_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0x57);
 
         let mut expected_cycles = 5;
 
@@ -447,9 +444,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(mem.Data[0x0042], 0x58);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x0042), 0x58);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -462,12 +459,12 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x10;
-        mem.Data[0xFF00] = cpu.INS_INC_ZPX;
-        mem.Data[0xFF01] = 0x42;
-        mem.Data[0x0042 + 0x10] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x10);
+        mem.poke(0xFF00, INS_INC_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 0x57);
 
         let mut expected_cycles = 6;
 
@@ -475,9 +472,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x0042 + 0x10], 0x58);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x0042 + 0x10), 0x58);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -490,12 +487,12 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        mem.Data[0xFF00] = cpu.INS_INC_ABS;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        mem.poke(0xFF00, INS_INC_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 0x57);
 
         let mut expected_cycles = 6;
 
@@ -503,9 +500,9 @@ mod increments_and_decrements_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000], 0x58);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(mem.peek(0x8000), 0x58);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -518,31 +515,46 @@ mod increments_and_decrements_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x10;
-        mem.Data[0xFF00] = cpu.INS_INC_ABSX;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000 + 0x10] = 0x57;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x10);
+        mem.poke(0xFF00, INS_INC_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 0x57);
 
-        let mut expected_cycles = 6;
+        let mut expected_cycles = 7;
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
-        assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000 + 0x10], 0x58);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(actual_cycles, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 0x58);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
     fn verify_unmodified_flags(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(0), cpu_copy.PS.get_bit(0));
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2));
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3));
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4));
-        assert_eq!(cpu.PS.get_bit(6), cpu_copy.PS.get_bit(6));
+        assert_eq!(
+            cpu.flag(Flags::CarryFlagBit),
+            cpu_copy.flag(Flags::CarryFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            cpu_copy.flag(Flags::OverflowFlagBit)
+        );
     }
 }
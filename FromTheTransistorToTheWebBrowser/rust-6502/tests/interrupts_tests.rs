@@ -0,0 +1,299 @@
+#[cfg(test)]
+mod interrupts_tests {
+
+    use bit_field::BitField;
+
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
+
+    // Mem that asserts an interrupt line as soon as the CPU writes to the
+    // stack, to raise an interrupt in the middle of BRK
+    struct LineOnStackWrite {
+        mem: Mem,
+        assert_nmi: bool,
+        assert_irq: bool,
+        nmi: bool,
+        irq: bool,
+    }
+
+    impl Bus for LineOnStackWrite {
+        fn read(&mut self, address: Word) -> Byte {
+            self.mem.read(address)
+        }
+
+        fn write(&mut self, address: Word, value: Byte) {
+            if (0x0100..=0x01FF).contains(&address) {
+                self.nmi |= self.assert_nmi;
+                self.irq |= self.assert_irq;
+            }
+            self.mem.write(address, value);
+        }
+
+        fn peek(&self, address: Word) -> Byte {
+            self.mem.peek(address)
+        }
+
+        fn irq(&self) -> bool {
+            self.irq
+        }
+
+        fn nmi(&self) -> bool {
+            self.nmi
+        }
+    }
+
+    // NOPs at 0xFF00, the IRQ handler at 0x8000 and the NMI handler at 0x9000
+    fn setup(cpu: &mut CPU, mem: &mut Mem) {
+        cpu.reset_vector(mem, 0xFF00);
+        for address in 0xFF00..0xFF10 {
+            mem.poke(address, INS_NOP);
+        }
+        mem.poke(0xFFFA, 0x00);
+        mem.poke(0xFFFB, 0x90);
+        mem.poke(0xFFFE, 0x00);
+        mem.poke(0xFFFF, 0x80);
+        for address in 0x8000..0x8010 {
+            mem.poke(address, INS_NOP);
+        }
+        for address in 0x9000..0x9010 {
+            mem.poke(address, INS_NOP);
+        }
+    }
+
+    fn stack_byte(cpu: &CPU, mem: &Mem, offset: u16) -> Byte {
+        mem.data()[(0x100 | cpu.sp() as u16) as usize + offset as usize]
+    }
+
+    #[test]
+    fn irq_is_ignored_when_the_interrupt_disable_flag_is_set() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, true); // I
+        cpu.set_irq(true);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.pc(), 0xFF01);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+    }
+
+    #[test]
+    fn irq_pushes_pc_and_ps_with_break_cleared_and_jumps_to_the_irq_vector() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, false); // I
+        cpu.set_irq(true);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(cpu.pc(), 0x8000);
+        assert_eq!(cpu.sp(), cpu_copy.sp() - 3);
+        assert_eq!(stack_byte(&cpu, &mem, 3), 0xFF); // PC hi
+        assert_eq!(stack_byte(&cpu, &mem, 2), 0x00); // PC lo
+        let pushed_ps: Byte = stack_byte(&cpu, &mem, 1);
+        assert!(!pushed_ps.get_bit(4)); // B
+        assert!(pushed_ps.get_bit(5)); // U
+        assert!(cpu.flag(Flags::InterruptDisableFlagBit)); // I
+    }
+
+    #[test]
+    fn irq_is_level_triggered_and_is_serviced_again_after_rti() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        mem.poke(0x8000, INS_RTI);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, false); // I
+        cpu.set_irq(true);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut (7 + 6 + 7), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7 + 6 + 7);
+        assert_eq!(cpu.pc(), 0x8000);
+        assert_eq!(cpu.sp(), cpu_copy.sp() - 3);
+    }
+
+    #[test]
+    fn nmi_is_serviced_even_when_interrupts_are_disabled() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, true); // I
+        cpu.set_nmi(true);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(cpu.pc(), 0x9000);
+        assert_eq!(cpu.sp(), cpu_copy.sp() - 3);
+        assert!(!stack_byte(&cpu, &mem, 1).get_bit(4)); // B
+    }
+
+    #[test]
+    fn nmi_is_edge_triggered_and_is_serviced_only_once() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        mem.poke(0x9000, INS_RTI);
+        cpu.set_nmi(true);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut (7 + 6 + 2), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7 + 6 + 2);
+        assert_eq!(cpu.pc(), 0xFF01);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+    }
+
+    #[test]
+    fn a_new_nmi_needs_the_line_to_be_released_first() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        mem.poke(0x9000, INS_RTI);
+        cpu.set_nmi(true);
+        cpu.execute(&mut (7 + 6), &mut mem);
+
+        // when:
+        cpu.set_nmi(false);
+        cpu.set_nmi(true);
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(cpu.pc(), 0x9000);
+    }
+
+    #[test]
+    fn nmi_can_interrupt_an_irq_handler_and_both_return() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem);
+        mem.poke(0x8001, INS_RTI);
+        mem.poke(0x9000, INS_RTI);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, false); // I
+        let cpu_copy = cpu;
+
+        // when:
+        cpu.set_irq(true);
+        cpu.execute(&mut 7, &mut mem); // IRQ
+        cpu.set_irq(false);
+        cpu.execute(&mut 2, &mut mem); // NOP in the IRQ handler
+        cpu.set_nmi(true);
+        let cycles_nmi = cpu.execute(&mut 7, &mut mem);
+        let sp_in_nmi = cpu.sp();
+        let pc_in_nmi = cpu.pc();
+        cpu.execute(&mut 6, &mut mem); // RTI from the NMI handler
+        let pc_after_nmi = cpu.pc();
+        cpu.execute(&mut 6, &mut mem); // RTI from the IRQ handler
+
+        // then:
+        assert_eq!(cycles_nmi, 7);
+        assert_eq!(pc_in_nmi, 0x9000);
+        assert_eq!(sp_in_nmi, cpu_copy.sp() - 6);
+        assert_eq!(pc_after_nmi, 0x8001);
+        assert_eq!(cpu.pc(), 0xFF00);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert!(!cpu.flag(Flags::InterruptDisableFlagBit)); // I
+    }
+
+    #[test]
+    fn nmi_during_brk_hijacks_the_brk_and_keeps_the_break_flag() {
+        let mut bus = LineOnStackWrite {
+            mem: Mem::new(),
+            assert_nmi: true,
+            assert_irq: false,
+            nmi: false,
+            irq: false,
+        };
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut bus.mem);
+        bus.mem.poke(0xFF00, INS_BRK);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut bus);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(cpu.pc(), 0x9000);
+        assert_eq!(cpu.sp(), cpu_copy.sp() - 3);
+        let pushed_ps: Byte = bus.mem.data()[(0x100 | cpu.sp() as u16) as usize + 1];
+        assert_eq!(
+            pushed_ps & Flags::BreakFlagBit as Byte,
+            Flags::BreakFlagBit as Byte
+        );
+        assert!(!cpu.nmi_pending());
+    }
+
+    #[test]
+    fn irq_during_brk_is_not_serviced_until_the_handler_returns() {
+        let mut bus = LineOnStackWrite {
+            mem: Mem::new(),
+            assert_nmi: false,
+            assert_irq: true,
+            nmi: false,
+            irq: false,
+        };
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut bus.mem);
+        bus.mem.poke(0xFF00, INS_BRK);
+        bus.mem.poke(0x8000, INS_RTI);
+        cpu.set_flag(Flags::InterruptDisableFlagBit, false); // I
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_brk = cpu.execute(&mut 7, &mut bus);
+        let sp_in_handler = cpu.sp();
+        let pushed_ps: Byte = bus.mem.data()[(0x100 | cpu.sp() as u16) as usize + 1];
+        let cycles_rti_and_irq = cpu.execute(&mut (6 + 7), &mut bus);
+
+        // then:
+        assert_eq!(cycles_brk, 7);
+        assert_eq!(sp_in_handler, cpu_copy.sp() - 3);
+        assert!(pushed_ps.get_bit(4)); // B, the BRK is not lost
+        assert_eq!(cycles_rti_and_irq, 6 + 7);
+        assert_eq!(cpu.pc(), 0x8000);
+        assert!(!bus.mem.data()[(0x100 | cpu.sp() as u16) as usize + 1].get_bit(4));
+        // B
+    }
+}
@@ -2,13 +2,10 @@
 
 mod jumps_and_calls_tests {
 
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn can_jump_to_subroutine_and_jump_back_again() {
@@ -19,12 +16,12 @@ mod jumps_and_calls_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_JSR;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = cpu.INS_RTS;
-        mem.Data[0xFF03] = cpu.INS_LDA_IM;
-        mem.Data[0xFF04] = 0x42;
+        mem.poke(0xFF00, INS_JSR);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, INS_RTS);
+        mem.poke(0xFF03, INS_LDA_IM);
+        mem.poke(0xFF04, 0x42);
 
         let mut expected_cycles = 6 + 6 + 2;
 
@@ -32,8 +29,8 @@ mod jumps_and_calls_tests {
 
         // then:
         assert_eq!(actual_cycles, 6 + 6 + 2);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.SP, cpu_copy.SP);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
     }
 
     #[test]
@@ -45,9 +42,9 @@ mod jumps_and_calls_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_JSR;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
+        mem.poke(0xFF00, INS_JSR);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
 
         let mut expected_cycles = 6;
 
@@ -55,9 +52,9 @@ mod jumps_and_calls_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_ne!(cpu.SP, cpu_copy.SP);
-        assert_eq!(cpu.PC, 0x8000);
-        assert_eq!(cpu.PS, cpu.PS);
+        assert_ne!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.pc(), 0x8000);
+        assert_eq!(cpu.status(), cpu.status());
     }
 
     #[test]
@@ -69,10 +66,10 @@ mod jumps_and_calls_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_JSR;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = cpu.INS_RTS;
+        mem.poke(0xFF00, INS_JSR);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, INS_RTS);
 
         let mut expected_cycles = 6 + 6;
 
@@ -80,8 +77,8 @@ mod jumps_and_calls_tests {
 
         // then:
         assert_eq!(actual_cycles, 6 + 6);
-        assert_eq!(cpu.PC, 0xFF03);
-        assert_eq!(cpu.PS, cpu.PS);
+        assert_eq!(cpu.pc(), 0xFF03);
+        assert_eq!(cpu.status(), cpu.status());
     }
 
     #[test]
@@ -93,9 +90,9 @@ mod jumps_and_calls_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_JMP_ABS;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
+        mem.poke(0xFF00, INS_JMP_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
 
         let mut expected_cycles = 3;
 
@@ -103,9 +100,9 @@ mod jumps_and_calls_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.SP, cpu_copy.SP);
-        assert_eq!(cpu.PC, 0x8000);
-        assert_eq!(cpu.PS, cpu.PS);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.pc(), 0x8000);
+        assert_eq!(cpu.status(), cpu.status());
     }
 
     #[test]
@@ -117,11 +114,11 @@ mod jumps_and_calls_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_JMP_IND;
-        mem.Data[0xFF01] = 0x00;
-        mem.Data[0xFF02] = 0x80;
-        mem.Data[0x8000] = 0x00;
-        mem.Data[0x8001] = 0x90;
+        mem.poke(0xFF00, INS_JMP_IND);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 0x00);
+        mem.poke(0x8001, 0x90);
 
         let mut expected_cycles = 5;
 
@@ -129,9 +126,9 @@ mod jumps_and_calls_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(cpu.SP, cpu_copy.SP);
-        assert_eq!(cpu.PC, 0x9000);
-        assert_eq!(cpu.PS, cpu.PS);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(cpu.pc(), 0x9000);
+        assert_eq!(cpu.status(), cpu.status());
     }
 
     // fn verify_unmodified_flags_from_store(cpu: CPU, cpu_copy: CPU) {
@@ -1,14 +1,11 @@
 #[cfg(test)]
 mod load_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    use crate::Mem;
-    use crate::CPU;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn test_ldainmediate_can_load_value_into_a_register() {
@@ -20,15 +17,15 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDA_IM;
-        mem.Data[0xFFFD] = 0x84;
+        mem.poke(0xFFFC, INS_LDA_IM);
+        mem.poke(0xFFFD, 0x84);
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
         assert_eq!(cycles_used, 2);
 
         // then:
-        assert_eq!(cpu.A, 0x84);
+        assert_eq!(cpu.a(), 0x84);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -41,15 +38,15 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDX_IM;
-        mem.Data[0xFFFD] = 0x84;
+        mem.poke(0xFFFC, INS_LDX_IM);
+        mem.poke(0xFFFD, 0x84);
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
         assert_eq!(cycles_used, 2);
 
         // then:
-        assert_eq!(cpu.X, 0x84);
+        assert_eq!(cpu.x(), 0x84);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -62,15 +59,15 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDY_IM;
-        mem.Data[0xFFFD] = 0x84;
+        mem.poke(0xFFFC, INS_LDY_IM);
+        mem.poke(0xFFFD, 0x84);
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
         assert_eq!(cycles_used, 2);
 
         // then:
-        assert_eq!(cpu.Y, 0x84);
+        assert_eq!(cpu.y(), 0x84);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -82,18 +79,18 @@ mod load_tests {
         let mut cpu_copy = CPU::new();
 
         // given:
-        cpu.A = 0x29;
+        cpu.set_a(0x29);
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDA_IM;
-        mem.Data[0xFFFD] = 0x0;
+        mem.poke(0xFFFC, INS_LDA_IM);
+        mem.poke(0xFFFD, 0x0);
 
         // when:
         let cycles_used = cpu.execute(&mut 2, &mut mem);
 
         // then:
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -107,16 +104,16 @@ mod load_tests {
         // given
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDA_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0x37;
+        mem.poke(0xFFFC, INS_LDA_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
         assert_eq!(cycles_used, 3);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -129,16 +126,16 @@ mod load_tests {
         // given
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDX_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0x37;
+        mem.poke(0xFFFC, INS_LDX_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
         assert_eq!(cycles_used, 3);
 
         // then:
-        assert_eq!(cpu.X, 0x37);
+        assert_eq!(cpu.x(), 0x37);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -151,16 +148,16 @@ mod load_tests {
         // given
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDY_ZP;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0042] = 0x37;
+        mem.poke(0xFFFC, INS_LDY_ZP);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0042, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 3, &mut mem);
         assert_eq!(cycles_used, 3);
 
         // then:
-        assert_eq!(cpu.Y, 0x37);
+        assert_eq!(cpu.y(), 0x37);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -174,18 +171,18 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.X = 5;
+        cpu.set_x(5);
 
         // start - inline a little program
-        mem.Data[0xFFFC] = cpu.INS_LDA_ZPX;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0047] = 0x37;
+        mem.poke(0xFFFC, INS_LDA_ZPX);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0047, 0x37);
         // end - inline a little program
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 4);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
@@ -199,18 +196,18 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.Y = 5;
+        cpu.set_y(5);
 
         // start - inline a little program
-        mem.Data[0xFFFC] = cpu.INS_LDX_ZPY;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0047] = 0x37;
+        mem.poke(0xFFFC, INS_LDX_ZPY);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0047, 0x37);
         // end - inline a little program
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
         // then:
-        assert_eq!(cpu.X, 0x37);
+        assert_eq!(cpu.x(), 0x37);
         assert_eq!(cycles_used, 4);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
@@ -224,18 +221,18 @@ mod load_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.X = 5;
+        cpu.set_x(5);
 
         // start - inline a little program
-        mem.Data[0xFFFC] = cpu.INS_LDY_ZPX;
-        mem.Data[0xFFFD] = 0x42;
-        mem.Data[0x0047] = 0x37;
+        mem.poke(0xFFFC, INS_LDY_ZPX);
+        mem.poke(0xFFFD, 0x42);
+        mem.poke(0x0047, 0x37);
         // end - inline a little program
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
         // then:
-        assert_eq!(cpu.Y, 0x37);
+        assert_eq!(cpu.y(), 0x37);
         assert_eq!(cycles_used, 4);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
@@ -248,11 +245,11 @@ mod load_tests {
 
         // given:
         cpu.reset(&mut mem);
-        cpu.X = 0xFF;
+        cpu.set_x(0xFF);
 
-        mem.Data[0xFFFC] = cpu.INS_LDA_ZPX;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x007F] = 0x37;
+        mem.poke(0xFFFC, INS_LDA_ZPX);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x007F, 0x37);
 
         // when:
         let mut cpu_copy = CPU::new();
@@ -261,7 +258,7 @@ mod load_tests {
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 4);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
@@ -289,8 +286,8 @@ mod load_tests {
     //         // given:
     //         cpu.reset(&mut mem);
     //         cpu_copy.reset(&mut mem);
-    //         mem.Data[0xFFFC] = cpu.INS_LDA_IM;
-    //         mem.Data[0xFFFD] = 0x84;
+    //         mem.poke(0xFFFC, INS_LDA_IM);
+    //         mem.poke(0xFFFD, 0x84);
     //
     //         // when:
     //         let cycles_used = cpu.execute(&mut 1, &mut mem);
@@ -309,8 +306,8 @@ mod load_tests {
     //         // given:
     //         cpu.reset(&mut mem);
     //         cpu_copy.reset(&mut mem);
-    //         mem.Data[0xFFFC] = 0x0;
-    //         mem.Data[0xFFFD] = 0x0;
+    //         mem.poke(0xFFFC, 0x0);
+    //         mem.poke(0xFFFD, 0x0);
     //
     //         // when:
     //         let cycles_used = cpu.execute(&mut 3, &mut mem);
@@ -326,16 +323,16 @@ mod load_tests {
 
         // given:
         cpu.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDA_ABS;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4480] = 0x37;
+        mem.poke(0xFFFC, INS_LDA_ABS);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4480, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 4);
     }
 
@@ -346,16 +343,16 @@ mod load_tests {
 
         // given:
         cpu.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDX_ABS;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4480] = 0x37;
+        mem.poke(0xFFFC, INS_LDX_ABS);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4480, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.X, 0x37);
+        assert_eq!(cpu.x(), 0x37);
         assert_eq!(cycles_used, 4);
     }
 
@@ -366,16 +363,16 @@ mod load_tests {
 
         // given:
         cpu.reset(&mut mem);
-        mem.Data[0xFFFC] = cpu.INS_LDY_ABS;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4480] = 0x37;
+        mem.poke(0xFFFC, INS_LDY_ABS);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4480, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.Y, 0x37);
+        assert_eq!(cpu.y(), 0x37);
         assert_eq!(cycles_used, 4);
     }
 
@@ -389,20 +386,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.X = 1;
-        mem.Data[0xFFFC] = cpu.INS_LDA_ABSX;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4481] = 0x37;
+        cpu.set_x(1);
+        mem.poke(0xFFFC, INS_LDA_ABSX);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -416,20 +413,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 1;
-        mem.Data[0xFFFC] = cpu.INS_LDX_ABSY;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4481] = 0x37;
+        cpu.set_y(1);
+        mem.poke(0xFFFC, INS_LDX_ABSY);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.X, 0x37);
+        assert_eq!(cpu.x(), 0x37);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -443,20 +440,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 0xFF;
-        mem.Data[0xFFFC] = cpu.INS_LDX_ABSY;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44; // 0x4402
-        mem.Data[0x4501] = 0x37; // 0x4402 + 0xFF crosses page boundary!
+        cpu.set_y(0xFF);
+        mem.poke(0xFFFC, INS_LDX_ABSY);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44); // 0x4402
+        mem.poke(0x4501, 0x37); // 0x4402 + 0xFF crosses page boundary!
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
 
         // then:
-        assert_eq!(cpu.X, 0x37);
+        assert_eq!(cpu.x(), 0x37);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -470,20 +467,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.X = 1;
-        mem.Data[0xFFFC] = cpu.INS_LDY_ABSX;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4481] = 0x37;
+        cpu.set_x(1);
+        mem.poke(0xFFFC, INS_LDY_ABSX);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.Y, 0x37);
+        assert_eq!(cpu.y(), 0x37);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -497,22 +494,22 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.X = 0xFF;
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        mem.Data[0xFFFC] = cpu.INS_LDY_ABSX;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44; // 0x4402
-        mem.Data[0x4501] = 0x37; // 0x4402 + 0xFF crosses page boundary!
+        cpu.set_x(0xFF);
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        mem.poke(0xFFFC, INS_LDY_ABSX);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44); // 0x4402
+        mem.poke(0x4501, 0x37); // 0x4402 + 0xFF crosses page boundary!
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
 
         // then:
-        assert_eq!(cpu.Y, 0x37);
+        assert_eq!(cpu.y(), 0x37);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -526,20 +523,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.X = 0xFF;
-        mem.Data[0xFFFC] = cpu.INS_LDA_ABSX;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44; // 0x4402
-        mem.Data[0x4501] = 0x37; // 0x4402 + 0xFF crosses page boundary!
+        cpu.set_x(0xFF);
+        mem.poke(0xFFFC, INS_LDA_ABSX);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44); // 0x4402
+        mem.poke(0x4501, 0x37); // 0x4402 + 0xFF crosses page boundary!
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -553,20 +550,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 1;
-        mem.Data[0xFFFC] = cpu.INS_LDA_ABSY;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0xFFFE] = 0x44; // 0x4480
-        mem.Data[0x4481] = 0x37;
+        cpu.set_y(1);
+        mem.poke(0xFFFC, INS_LDA_ABSY);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0xFFFE, 0x44); // 0x4480
+        mem.poke(0x4481, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 4, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 4);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -580,20 +577,20 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 0xFF;
-        mem.Data[0xFFFC] = cpu.INS_LDA_ABSY;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0xFFFE] = 0x44; // 0x4402
-        mem.Data[0x4501] = 0x37; // 0x4402 + 0xFF crosses page boundary!
+        cpu.set_y(0xFF);
+        mem.poke(0xFFFC, INS_LDA_ABSY);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0xFFFE, 0x44); // 0x4402
+        mem.poke(0x4501, 0x37); // 0x4402 + 0xFF crosses page boundary!
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -607,21 +604,21 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.X = 0x04;
-        mem.Data[0xFFFC] = cpu.INS_LDA_INDX;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0006] = 0x00; // 0x02 + 0x04
-        mem.Data[0x0007] = 0x80;
-        mem.Data[0x8000] = 0x37;
+        cpu.set_x(0x04);
+        mem.poke(0xFFFC, INS_LDA_INDX);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0006, 0x00); // 0x02 + 0x04
+        mem.poke(0x0007, 0x80);
+        mem.poke(0x8000, 0x37);
 
         // when:
         let cycles_used = cpu.execute(&mut 6, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 6);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -635,21 +632,21 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 0x04;
-        mem.Data[0xFFFC] = cpu.INS_LDA_INDY;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0002] = 0x00;
-        mem.Data[0x0003] = 0x80;
-        mem.Data[0x8004] = 0x37; //0x8000 + 0x4
+        cpu.set_y(0x04);
+        mem.poke(0xFFFC, INS_LDA_INDY);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0002, 0x00);
+        mem.poke(0x0003, 0x80);
+        mem.poke(0x8004, 0x37); //0x8000 + 0x4
 
         // when:
         let cycles_used = cpu.execute(&mut 5, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 5);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -663,21 +660,21 @@ mod load_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.Y = 0xFF;
-        mem.Data[0xFFFC] = cpu.INS_LDA_INDY;
-        mem.Data[0xFFFD] = 0x02;
-        mem.Data[0x0002] = 0x02;
-        mem.Data[0x0003] = 0x80;
-        mem.Data[0x8101] = 0x37; //0x8002 + 0xFF
+        cpu.set_y(0xFF);
+        mem.poke(0xFFFC, INS_LDA_INDY);
+        mem.poke(0xFFFD, 0x02);
+        mem.poke(0x0002, 0x02);
+        mem.poke(0x0003, 0x80);
+        mem.poke(0x8101, 0x37); //0x8002 + 0xFF
 
         // when:
         let cycles_used = cpu.execute(&mut 6, &mut mem);
 
         // then:
-        assert_eq!(cpu.A, 0x37);
+        assert_eq!(cpu.a(), 0x37);
         assert_eq!(cycles_used, 6);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags_from_lda(cpu, cpu_copy);
     }
 
@@ -690,8 +687,8 @@ mod load_tests {
     //         // given:
     //         cpu.reset(&mut mem);
     //         cpu_copy.reset(&mut mem);
-    //         mem.Data[0xFFFC] = cpu.INS_LDA_IM;
-    //         mem.Data[0xFFFD] = 0x84;
+    //         mem.poke(0xFFFC, INS_LDA_IM);
+    //         mem.poke(0xFFFD, 0x84);
     //
     //         // when:
     //         let cycles_used = cpu.execute(&mut 2, &mut mem);
@@ -703,10 +700,25 @@ mod load_tests {
     //     }
 
     fn verify_unmodified_flags_from_lda(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(0), cpu_copy.PS.get_bit(0));
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2));
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3));
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4));
-        assert_eq!(cpu.PS.get_bit(6), cpu_copy.PS.get_bit(6));
+        assert_eq!(
+            cpu.flag(Flags::CarryFlagBit),
+            cpu_copy.flag(Flags::CarryFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            cpu_copy.flag(Flags::OverflowFlagBit)
+        );
     }
 }
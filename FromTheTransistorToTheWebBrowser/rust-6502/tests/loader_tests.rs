@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod loader_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::loader::*;
+    use rust_6502::Byte;
+    use rust_6502::{Mem, CPU};
+
+    // LDA #$01; STA $0200; JMP $0605 at $0600
+    const PROGRAM: [Byte; 8] = [0xA9, 0x01, 0x8D, 0x00, 0x02, 0x4C, 0x05, 0x06];
+
+    const INTEL_HEX: &str = ":05060000A9018D0002BC
+:030605004C05069B
+:0400000500000600F1
+:00000001FF
+";
+
+    const S_RECORD: &str = "S00600004844521B
+S1080600A9018D0002B8
+S10606054C050697
+S9030600F6
+";
+
+    fn memory() -> Mem {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset(&mut mem);
+        mem
+    }
+
+    #[test]
+    fn a_raw_binary_is_loaded_at_the_given_address() {
+        let mut mem = memory();
+
+        // when:
+        let image = parse_raw(&PROGRAM, 0x0600).unwrap();
+        image.write_to(&mut mem);
+
+        // then:
+        assert_eq!(&mem.data()[0x0600..0x0608], &PROGRAM);
+        assert_eq!(image.entry(), Some(0x0600));
+        assert_eq!(image.len(), 8);
+    }
+
+    #[test]
+    fn an_image_that_goes_past_ffff_is_rejected() {
+        // then:
+        assert_eq!(
+            parse_raw(&PROGRAM, 0xFFFC),
+            Err(LoaderError::AddressOverflow {
+                address: 0xFFFC,
+                length: 8
+            })
+        );
+        assert!(parse_raw(&PROGRAM, 0xFFF8).is_ok());
+        assert!(matches!(
+            parse_intel_hex(":03FFFE00010203FA\n"),
+            Err(LoaderError::AddressOverflow { .. })
+        ));
+        assert!(matches!(
+            parse_intel_hex(":020000040001F9\n:05060000A9018D0002BC\n"),
+            Err(LoaderError::AddressOverflow { .. })
+        ));
+        assert!(matches!(
+            parse_srecord("S205010000EA0F\n"),
+            Err(LoaderError::AddressOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn a_prg_file_starts_with_its_load_address() {
+        let mut mem = memory();
+        let mut cpu = CPU::new();
+
+        // given:
+        let mut prg: Vec<Byte> = vec![0x00, 0x06];
+        prg.extend_from_slice(&PROGRAM);
+
+        // when:
+        let start_address = cpu.load_prg(&prg, &mut mem).unwrap();
+
+        // then:
+        assert_eq!(start_address, 0x0600);
+        assert_eq!(&mem.data()[0x0600..0x0608], &PROGRAM);
+        assert_eq!(parse_prg(&[0x00]), Err(LoaderError::MissingLoadAddress));
+    }
+
+    #[test]
+    fn intel_hex_records_are_loaded_with_their_start_address() {
+        let mut mem = memory();
+
+        // when:
+        let image = parse_intel_hex(INTEL_HEX).unwrap();
+        image.write_to(&mut mem);
+
+        // then:
+        assert_eq!(image.segments.len(), 1);
+        assert_eq!(&mem.data()[0x0600..0x0608], &PROGRAM);
+        assert_eq!(image.start, Some(0x0600));
+    }
+
+    #[test]
+    fn an_intel_hex_record_with_a_wrong_checksum_is_rejected() {
+        // given:
+        let text = INTEL_HEX.replace("069B", "069C");
+
+        // when:
+        let error = parse_intel_hex(&text).unwrap_err();
+
+        // then:
+        assert_eq!(
+            error,
+            LoaderError::Checksum {
+                line: 2,
+                expected: 0x9B,
+                found: 0x9C
+            }
+        );
+        assert_eq!(error.to_string(), "line 2: checksum is 9C, expected 9B");
+    }
+
+    #[test]
+    fn broken_intel_hex_records_are_rejected() {
+        // then:
+        assert!(matches!(
+            parse_intel_hex("05060000A9018D0002BC"),
+            Err(LoaderError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_intel_hex(":06060000A9018D0002BC"),
+            Err(LoaderError::Syntax { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_intel_hex(":0506000GA9018D0002BC"),
+            Err(LoaderError::Syntax { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn intel_hex_records_that_overlap_are_rejected() {
+        // given: 2 bytes at $0607, the second record ends there
+        let text = INTEL_HEX.replace(
+            ":0400000500000600F1",
+            ":02060700AABB8C\n:0400000500000600F1",
+        );
+
+        // when:
+        let error = parse_intel_hex(&text).unwrap_err();
+
+        // then:
+        assert_eq!(error, LoaderError::Overlap { address: 0x0607 });
+        assert_eq!(error.to_string(), "$0607 is loaded more than once");
+    }
+
+    #[test]
+    fn s_records_are_loaded_with_their_start_address() {
+        let mut mem = memory();
+
+        // when:
+        let image = parse_srecord(S_RECORD).unwrap();
+        image.write_to(&mut mem);
+
+        // then:
+        assert_eq!(&mem.data()[0x0600..0x0608], &PROGRAM);
+        assert_eq!(image.start, Some(0x0600));
+    }
+
+    #[test]
+    fn an_s_record_with_a_wrong_checksum_is_rejected() {
+        // given:
+        let text = S_RECORD.replace("0002B8", "0002B9");
+
+        // then:
+        assert_eq!(
+            parse_srecord(&text),
+            Err(LoaderError::Checksum {
+                line: 2,
+                expected: 0xB8,
+                found: 0xB9
+            })
+        );
+    }
+
+    #[test]
+    fn s_records_that_overlap_are_rejected() {
+        // given: a NOP over the operand of the LDA
+        let text = S_RECORD.replace("S9030600F6", "S1040601EA0A\nS9030600F6");
+
+        // then:
+        assert_eq!(
+            parse_srecord(&text),
+            Err(LoaderError::Overlap { address: 0x0601 })
+        );
+    }
+
+    #[test]
+    fn the_reset_vector_can_point_to_the_program() {
+        let mut mem = memory();
+        let mut cpu = CPU::new();
+
+        // given:
+        let image = parse(Format::SRecord, S_RECORD.as_bytes()).unwrap();
+        image.write_to(&mut mem);
+
+        // when:
+        let entry = image.set_reset_vector(&mut mem);
+        cpu.power_on(&mut mem);
+        cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(entry, Some(0x0600));
+        assert_eq!((mem.peek(0xFFFC), mem.peek(0xFFFD)), (0x00, 0x06));
+        assert_eq!(mem.read(0x0200), 0x01);
+    }
+
+    #[test]
+    fn files_are_read_in_the_format_of_their_extension() {
+        let path = std::env::temp_dir().join(format!("rust-6502-{}.hex", std::process::id()));
+
+        // given:
+        std::fs::write(&path, INTEL_HEX).unwrap();
+
+        // when:
+        let format = Format::from_path(&path);
+        let image = read_file(&path, Format::IntelHex);
+        let _ = std::fs::remove_file(&path);
+
+        // then:
+        assert_eq!(format, Some(Format::IntelHex));
+        assert_eq!(image, parse_intel_hex(INTEL_HEX));
+        assert_eq!(Format::from_path("game.PRG"), Some(Format::Prg));
+        assert_eq!(Format::from_path("rom.s19"), Some(Format::SRecord));
+        assert_eq!(Format::from_path("rom.bin"), None);
+        assert!(matches!(
+            read_file("/nonexistent/rom.hex", Format::IntelHex),
+            Err(LoaderError::Io(_))
+        ));
+    }
+}
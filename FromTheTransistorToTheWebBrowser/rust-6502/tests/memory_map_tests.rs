@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod memory_map_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::memory_map::*;
+    use rust_6502::opcodes::*;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    fn memory() -> Mem {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset(&mut mem);
+        mem
+    }
+
+    #[test]
+    fn writes_to_rom_are_ignored() {
+        let mut mem = memory();
+
+        // given:
+        mem.poke(0xE000, 0x42);
+        mem.map(0xE000, 0xFFFF, RegionKind::Rom);
+
+        // when:
+        mem.write(0xE000, 0x00);
+
+        // then:
+        assert_eq!(mem.read(0xE000), 0x42);
+        assert!(mem.take_violations().is_empty());
+    }
+
+    #[test]
+    fn strict_mode_records_the_writes_to_rom() {
+        let mut mem = memory();
+        let mut cpu = CPU::new();
+
+        // given:
+        mem.map(0xE000, 0xFFFF, RegionKind::Rom);
+        mem.strict = true;
+        cpu.reset_vector(&mut mem, 0x0400);
+        mem.poke(0x0400, INS_STA_ABS);
+        mem.poke(0x0401, 0x34);
+        mem.poke(0x0402, 0xF2);
+        cpu.set_a(0x99);
+
+        // when:
+        cpu.step(&mut mem);
+
+        // then:
+        assert_eq!(mem.peek(0xF234), 0x00);
+        assert_eq!(
+            mem.take_violations(),
+            vec![Violation::RomWrite {
+                address: 0xF234,
+                value: 0x99
+            }]
+        );
+        assert!(mem.take_violations().is_empty());
+    }
+
+    #[test]
+    fn a_mirror_aliases_its_target() {
+        let mut mem = memory();
+
+        // given: the 2 KiB of RAM of the NES repeated up to $1FFF
+        mem.map(
+            0x0800,
+            0x1FFF,
+            RegionKind::Mirror {
+                target: 0x0000,
+                size: 0x0800,
+            },
+        );
+
+        // when:
+        mem.write(0x0012, 0x34);
+        mem.write(0x1813, 0x56);
+
+        // then:
+        assert_eq!(mem.read(0x0812), 0x34);
+        assert_eq!(mem.read(0x1012), 0x34);
+        assert_eq!(mem.peek(0x1812), 0x34);
+        assert_eq!(mem.read(0x0013), 0x56);
+        assert_eq!(mem.data()[0x1813], 0x00);
+    }
+
+    #[test]
+    fn a_mirror_of_rom_is_read_only() {
+        let mut mem = memory();
+
+        // given:
+        mem.map(0xC000, 0xFFFF, RegionKind::Rom);
+        mem.map(
+            0x8000,
+            0xBFFF,
+            RegionKind::Mirror {
+                target: 0xC000,
+                size: 0x4000,
+            },
+        );
+        mem.poke(0xC000, 0x4C);
+
+        // when:
+        mem.write(0x8000, 0x00);
+
+        // then:
+        assert_eq!(mem.read(0x8000), 0x4C);
+    }
+
+    #[test]
+    fn unmapped_reads_return_the_open_bus_value() {
+        let mut mem = memory();
+
+        // given: a byte stored before the range is unmapped
+        mem.poke(0x6000, 0x11);
+        mem.map(0x6000, 0x7FFF, RegionKind::Unmapped);
+        mem.open_bus = 0x60;
+        mem.strict = true;
+
+        // when:
+        let value = mem.read(0x6000);
+        mem.write(0x7FFF, 0x22);
+
+        // then:
+        assert_eq!(value, 0x60);
+        assert_eq!(mem.peek(0x7FFF), 0x60);
+        assert_eq!(mem.data()[0x7FFF], 0x00);
+        assert_eq!(
+            mem.take_violations(),
+            vec![
+                Violation::UnmappedRead { address: 0x6000 },
+                Violation::UnmappedWrite {
+                    address: 0x7FFF,
+                    value: 0x22
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn memory_that_was_never_reset_is_unmapped() {
+        let mut mem = Mem::new();
+
+        // given:
+        mem.strict = true;
+        mem.open_bus = 0xFF;
+
+        // when:
+        let value = mem.read(0x1234);
+
+        // then:
+        assert_eq!(value, 0xFF);
+        assert_eq!(
+            mem.take_violations(),
+            vec![Violation::UnmappedRead { address: 0x1234 }]
+        );
+    }
+
+    #[test]
+    fn the_first_region_declared_wins() {
+        let mut mem = memory();
+
+        // given:
+        mem.map(0xF000, 0xF0FF, RegionKind::Ram);
+        mem.map(0xE000, 0xFFFF, RegionKind::Rom);
+
+        // when:
+        mem.write(0xF010, 0x01);
+        mem.write(0xF110, 0x01);
+
+        // then:
+        assert_eq!(mem.read(0xF010), 0x01);
+        assert_eq!(mem.read(0xF110), 0x00);
+        assert_eq!(
+            mem.region(0xF110).map(|region| region.kind),
+            Some(RegionKind::Rom)
+        );
+        assert_eq!(mem.region(0x1000), None);
+    }
+
+    #[test]
+    fn write_word_wraps_around_at_ffff() {
+        let mut mem = memory();
+
+        // when:
+        mem.write_word(0xFFFF, 0x1234);
+
+        // then:
+        assert_eq!(mem.peek(0xFFFF), 0x34);
+        assert_eq!(mem.peek(0x0000), 0x12);
+    }
+}
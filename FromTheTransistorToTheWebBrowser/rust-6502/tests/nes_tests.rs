@@ -0,0 +1,178 @@
+#[cfg(test)]
+mod nes_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::nes::*;
+    use rust_6502::Byte;
+
+    // An iNES file with one 16 KiB PRG bank holding program at its start
+    // ($C000 once mirrored) and one 8 KiB CHR bank
+    fn ines(program: &[Byte], flags6: Byte, flags7: Byte) -> Vec<Byte> {
+        let mut data: Vec<Byte> = vec![0x4E, 0x45, 0x53, 0x1A, 1, 1, flags6, flags7];
+        data.resize(16, 0);
+        let mut prg: Vec<Byte> = vec![0xEA; 0x4000];
+        prg[..program.len()].copy_from_slice(program);
+        data.extend_from_slice(&prg);
+        data.extend_from_slice(&[0x55; 0x2000]);
+        data
+    }
+
+    // LDX #$05; STX $00; loop: INX; LAX $00; ISC $00; JMP loop
+    const PROGRAM: [Byte; 12] = [
+        0xA2, 0x05, 0x86, 0x00, 0xE8, 0xA7, 0x00, 0xE7, 0x00, 0x4C, 0x04, 0xC0,
+    ];
+
+    // the way nestest.log shows it, with the PPU column, the values of the
+    // memory operands and ISB for ISC
+    const LOG: &str = "\
+C000  A2 05     LDX #$05                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+C002  86 00     STX $00 = 00                    A:00 X:05 Y:00 P:24 SP:FD PPU:  0, 27 CYC:9
+C004  E8        INX                             A:00 X:05 Y:00 P:24 SP:FD PPU:  0, 36 CYC:12
+C005  A7 00    *LAX $00 = 05                    A:00 X:06 Y:00 P:24 SP:FD PPU:  0, 42 CYC:14
+C007  E7 00    *ISB $00 = 05                    A:05 X:05 Y:00 P:24 SP:FD PPU:  0, 51 CYC:17
+C009  4C 04 C0  JMP $C004                       A:FE X:05 Y:00 P:A4 SP:FD PPU:  0, 66 CYC:22
+C004  E8        INX                             A:FE X:05 Y:00 P:A4 SP:FD PPU:  0, 75 CYC:25
+";
+
+    #[test]
+    fn the_ines_header_is_parsed() {
+        // when:
+        let rom = parse_ines(&ines(&PROGRAM, 0b0001_0011, 0b0010_0000)).unwrap();
+
+        // then:
+        assert_eq!(rom.mapper, 0x21);
+        assert_eq!(rom.mirroring, Mirroring::Vertical);
+        assert!(rom.battery);
+        assert_eq!(rom.trainer, None);
+        assert_eq!(rom.prg_rom.len(), 0x4000);
+        assert_eq!(&rom.prg_rom[..2], &[0xA2, 0x05]);
+        assert_eq!(rom.chr_rom, vec![0x55; 0x2000]);
+    }
+
+    #[test]
+    fn the_trainer_comes_before_the_prg_rom() {
+        // given:
+        let mut data = ines(&PROGRAM, 0b0000_0100, 0);
+        let trainer: Vec<Byte> = vec![0x77; 512];
+        data.splice(16..16, trainer.iter().copied());
+
+        // when:
+        let rom = parse_ines(&data).unwrap();
+        let mem = nrom_mem(&rom).unwrap();
+
+        // then:
+        assert_eq!(rom.trainer, Some(trainer));
+        assert_eq!(&rom.prg_rom[..2], &[0xA2, 0x05]);
+        assert_eq!(mem.peek(0x7000), 0x77);
+    }
+
+    #[test]
+    fn old_headers_with_garbage_in_them_only_use_the_low_mapper_nibble() {
+        // given:
+        let mut data = ines(&PROGRAM, 0b0001_0000, 0x44);
+        data[8..16].copy_from_slice(b"iskDude!");
+
+        // then:
+        assert_eq!(parse_ines(&data).unwrap().mapper, 1);
+    }
+
+    #[test]
+    fn broken_ines_files_are_rejected() {
+        // given:
+        let data = ines(&PROGRAM, 0, 0);
+
+        // then:
+        assert_eq!(parse_ines(b"NES"), Err(INesError::BadMagic));
+        assert_eq!(parse_ines(&data[1..]), Err(INesError::BadMagic));
+        assert_eq!(
+            parse_ines(&data[..0x3000]),
+            Err(INesError::Truncated {
+                expected: 16 + 0x4000 + 0x2000,
+                found: 0x3000
+            })
+        );
+    }
+
+    #[test]
+    fn only_nrom_is_supported() {
+        // given:
+        let rom = parse_ines(&ines(&PROGRAM, 0b0001_0000, 0)).unwrap();
+
+        // then:
+        assert_eq!(nrom_mem(&rom).err(), Some(INesError::UnsupportedMapper(1)));
+    }
+
+    #[test]
+    fn nrom_mirrors_the_ram_and_a_16k_prg_rom() {
+        // given:
+        let rom = parse_ines(&ines(&PROGRAM, 0, 0)).unwrap();
+        let mut mem = nrom_mem(&rom).unwrap();
+
+        // when:
+        mem.write(0x0801, 0x42);
+        mem.write(0xC000, 0x00);
+
+        // then:
+        assert_eq!(mem.read(0x0001), 0x42);
+        assert_eq!(mem.read(0x1801), 0x42);
+        assert_eq!(mem.read(0x8000), 0xA2);
+        assert_eq!(mem.read(0xC000), 0xA2);
+        assert_eq!(mem.read(0x4015), 0xFF);
+    }
+
+    #[test]
+    fn the_trace_is_compared_with_the_log() {
+        // given:
+        let rom = parse_ines(&ines(&PROGRAM, 0, 0)).unwrap();
+
+        // when:
+        let result = run_nestest(&rom, LOG);
+
+        // then:
+        assert_eq!(result, Ok(7));
+    }
+
+    #[test]
+    fn the_first_divergence_is_reported_with_the_lines_before_it() {
+        // given:
+        let rom = parse_ines(&ines(&PROGRAM, 0, 0)).unwrap();
+        let log = LOG.replace("A:00 X:06", "A:00 X:07");
+
+        // when:
+        let error = run_nestest(&rom, &log).unwrap_err();
+
+        // then:
+        let divergence = match error {
+            NestestError::Divergence(divergence) => divergence,
+            error => panic!("unexpected error {:?}", error),
+        };
+        assert_eq!(divergence.line, 4);
+        assert_eq!(divergence.context.len(), 3);
+        assert!(divergence.context[2].starts_with("C004  E8"));
+        assert!(divergence.expected.contains("X:07"));
+        assert!(divergence.actual.starts_with("C005  A7 00    *LAX $00"));
+        assert!(divergence.actual.contains("X:06"));
+        assert!(divergence
+            .to_string()
+            .starts_with("the trace differs from the log at line 4\n"));
+    }
+
+    #[test]
+    fn a_jam_stops_the_run() {
+        // given:
+        let rom = parse_ines(&ines(&[0x02], 0, 0)).unwrap();
+        let log = "\
+C000  02       *JAM                             A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7
+C001  EA        NOP                             A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:9
+";
+
+        // then:
+        assert_eq!(
+            run_nestest(&rom, log),
+            Err(NestestError::Jammed {
+                pc: 0xC000,
+                line: 1
+            })
+        );
+    }
+}
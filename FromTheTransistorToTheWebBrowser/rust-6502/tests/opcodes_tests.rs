@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod opcodes_tests {
+
+    use rust_6502::opcodes::*;
+    use rust_6502::CpuVariant;
+
+    #[test]
+    fn the_nmos_table_has_151_documented_opcodes_and_12_jams() {
+        // when:
+        let documented = NMOS_6502.iter().filter(|o| o.documented).count();
+        let jams = NMOS_6502
+            .iter()
+            .filter(|o| o.mnemonic == Mnemonic::Jam)
+            .count();
+
+        // then:
+        assert_eq!(documented, 151);
+        assert_eq!(jams, 12);
+    }
+
+    #[test]
+    fn entries_describe_the_opcode_constants() {
+        // then:
+        let lda_absx = NMOS_6502[INS_LDA_ABSX as usize];
+        assert_eq!(lda_absx.mnemonic, Mnemonic::Lda);
+        assert_eq!(lda_absx.mode, AddressingMode::AbsoluteX);
+        assert_eq!(lda_absx.cycles, 4);
+        assert!(lda_absx.page_penalty);
+
+        let sta_indy = NMOS_6502[INS_STA_INDY as usize];
+        assert_eq!(sta_indy.mnemonic, Mnemonic::Sta);
+        assert_eq!(sta_indy.mode, AddressingMode::IndirectY);
+        assert_eq!(sta_indy.cycles, 6);
+        assert!(!sta_indy.page_penalty);
+
+        assert_eq!(NMOS_6502[INS_JSR as usize].cycles, 6);
+        assert_eq!(NMOS_6502[INS_BRK as usize].cycles, 7);
+        assert_eq!(
+            NMOS_6502[INS_ROL_ACC as usize].mode,
+            AddressingMode::Accumulator
+        );
+    }
+
+    #[test]
+    fn the_documented_opcodes_decode_the_same_on_every_variant() {
+        for (opcode, nmos) in NMOS_6502.iter().enumerate() {
+            if !nmos.documented {
+                continue;
+            }
+
+            // then:
+            for variant in [CpuVariant::Wdc65C02, CpuVariant::Rockwell65C02] {
+                let cmos = opcode_table(variant)[opcode];
+                assert_eq!((cmos.mnemonic, cmos.mode), (nmos.mnemonic, nmos.mode));
+            }
+        }
+    }
+
+    #[test]
+    fn the_65c02_tables_have_no_jam() {
+        // then:
+        for variant in [CpuVariant::Wdc65C02, CpuVariant::Rockwell65C02] {
+            assert!(opcode_table(variant)
+                .iter()
+                .all(|o| o.mnemonic != Mnemonic::Jam));
+        }
+        assert_eq!(WDC_65C02[0xCB].mnemonic, Mnemonic::Wai);
+        assert_eq!(ROCKWELL_65C02[0xCB].mnemonic, Mnemonic::Nop);
+        assert_eq!(WDC_65C02[0x9F].mnemonic, Mnemonic::Bbs(1));
+        assert_eq!(WDC_65C02[0x9F].mnemonic.name(), "BBS1");
+    }
+
+    #[test]
+    fn operand_bytes_follow_the_addressing_mode() {
+        // then:
+        assert_eq!(AddressingMode::Implied.operand_bytes(), 0);
+        assert_eq!(AddressingMode::ZeroPageIndirect.operand_bytes(), 1);
+        assert_eq!(AddressingMode::Relative.operand_bytes(), 1);
+        assert_eq!(AddressingMode::AbsoluteIndexedIndirect.operand_bytes(), 2);
+        assert_eq!(AddressingMode::ZeroPageRelative.operand_bytes(), 2);
+    }
+}
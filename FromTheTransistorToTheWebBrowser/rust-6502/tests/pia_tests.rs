@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod pia_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::pia::*;
+
+    #[test]
+    fn the_control_register_selects_the_port_or_its_direction() {
+        let mut pia = Pia6821::new();
+
+        // when:
+        pia.write(0, 0x0F);
+        pia.write(1, CONTROL_PORT_SELECT);
+        pia.write(0, 0x55);
+        pia.input_a = 0xA0;
+
+        // then:
+        assert_eq!(pia.ddr_a, 0x0F);
+        assert_eq!(pia.output_a, 0x55);
+        assert_eq!(pia.read(0), 0xA5);
+
+        // when:
+        pia.write(1, 0x00);
+
+        // then:
+        assert_eq!(pia.read(0), 0x0F);
+    }
+
+    #[test]
+    fn the_active_edge_of_c1_sets_the_flag_until_the_port_is_read() {
+        let mut pia = Pia6821::new();
+
+        // given:
+        pia.write(3, CONTROL_PORT_SELECT | CONTROL_C1_RISING);
+
+        // when:
+        pia.set_cb1(true);
+
+        // then:
+        assert_eq!(pia.peek(3) & CONTROL_IRQ1_FLAG, CONTROL_IRQ1_FLAG);
+
+        // when:
+        pia.write(3, CONTROL_PORT_SELECT | CONTROL_C1_RISING);
+        pia.set_cb1(false);
+
+        // then:
+        assert_eq!(pia.read(3) & CONTROL_IRQ1_FLAG, CONTROL_IRQ1_FLAG);
+
+        // when:
+        pia.read(2);
+
+        // then:
+        assert_eq!(pia.read(3) & CONTROL_IRQ1_FLAG, 0);
+    }
+
+    #[test]
+    fn c1_falls_by_default() {
+        let mut pia = Pia6821::new();
+
+        // when:
+        pia.set_ca1(true);
+
+        // then:
+        assert_eq!(pia.control_a, 0);
+
+        // when:
+        pia.set_ca1(false);
+
+        // then:
+        assert_eq!(pia.control_a, CONTROL_IRQ1_FLAG);
+    }
+
+    #[test]
+    fn the_flag_raises_irq_when_it_is_enabled() {
+        let mut pia = Pia6821::new();
+
+        // given:
+        pia.write(1, CONTROL_C1_RISING);
+        pia.set_ca1(true);
+
+        // then:
+        assert!(!pia.irq());
+
+        // when:
+        pia.write(1, CONTROL_C1_RISING | CONTROL_C1_IRQ_ENABLE);
+
+        // then:
+        assert!(pia.irq());
+    }
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod profiler_tests {
+
+    use rust_6502::assembler::{assemble, Program};
+    use rust_6502::profiler::*;
+    use rust_6502::{Mem, CPU};
+
+    fn setup(source: &str) -> (CPU, Mem, Program, Profiler) {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset(&mut mem);
+
+        let program = assemble(source).unwrap();
+        program.load(&mut mem);
+        cpu.set_pc(program.origin);
+
+        let mut profiler = Profiler::new();
+        profiler.name_labels(&program);
+        (cpu, mem, program, profiler)
+    }
+
+    // runs until the PC reaches the label end
+    fn run_to_end(cpu: &mut CPU, mem: &mut Mem, program: &Program, profiler: &mut Profiler) {
+        let end = program.symbol("end").unwrap();
+        while cpu.pc() != end {
+            cpu.execute_traced(&mut 1, mem, profiler);
+        }
+        profiler.finish(cpu);
+    }
+
+    #[test]
+    fn every_address_gets_its_instructions_and_cycles() {
+        let (mut cpu, mut mem, program, mut profiler) = setup(
+            "
+main:   LDX #$03
+loop:   DEX
+        BNE loop
+end:    JMP end
+",
+        );
+
+        // when:
+        run_to_end(&mut cpu, &mut mem, &program, &mut profiler);
+
+        // then:
+        let hits = |label: &str| profiler.addresses[&program.symbol(label).unwrap()];
+        assert_eq!(
+            hits("main"),
+            Hits {
+                instructions: 1,
+                cycles: 2
+            }
+        );
+        assert_eq!(
+            hits("loop"),
+            Hits {
+                instructions: 3,
+                cycles: 6
+            }
+        );
+        // taken twice, then not taken
+        assert_eq!(
+            profiler.addresses[&0x0603],
+            Hits {
+                instructions: 3,
+                cycles: 8
+            }
+        );
+        assert_eq!(profiler.total_cycles(), 16);
+    }
+
+    #[test]
+    fn subroutines_are_counted_with_and_without_their_callees() {
+        let (mut cpu, mut mem, program, mut profiler) = setup(
+            "
+main:   JSR sub
+        JSR sub
+end:    JMP end
+sub:    JSR inner
+        RTS
+inner:  NOP
+        RTS
+",
+        );
+        let address = |label: &str| program.symbol(label).unwrap();
+
+        // when:
+        run_to_end(&mut cpu, &mut mem, &program, &mut profiler);
+
+        // then:
+        let sub = profiler.routines[&address("sub")];
+        let inner = profiler.routines[&address("inner")];
+        assert_eq!(sub.calls, 2);
+        assert_eq!(inner.calls, 2);
+        assert_eq!(
+            inner.own,
+            Hits {
+                instructions: 4,
+                cycles: 16
+            }
+        );
+        assert_eq!(sub.own.cycles, 24);
+        assert_eq!(sub.total.cycles, 40);
+        assert_eq!(profiler.routines[&address("main")].total.cycles, 52);
+        assert_eq!(profiler.calls[&(address("main"), address("sub"))], 2);
+        assert_eq!(profiler.calls[&(address("sub"), address("inner"))], 2);
+        assert_eq!(
+            profiler.folded(),
+            "main 12\nmain;sub 24\nmain;sub;inner 16\n"
+        );
+    }
+
+    #[test]
+    fn rts_as_a_jump_and_popped_return_addresses_are_handled() {
+        // RTS returns to the address on the stack, it doesn't add 1
+        let (mut cpu, mut mem, program, mut profiler) = setup(
+            "
+main:   LDA #>jumped
+        PHA
+        LDA #<jumped
+        PHA
+        RTS
+jumped: JSR popper
+back:   NOP
+end:    JMP end
+popper: PLA
+        PLA
+        JMP back
+",
+        );
+        let address = |label: &str| program.symbol(label).unwrap();
+
+        // when:
+        run_to_end(&mut cpu, &mut mem, &program, &mut profiler);
+
+        // then:
+        assert!(!profiler.routines.contains_key(&address("jumped")));
+        let popper = profiler.routines[&address("popper")];
+        assert_eq!(popper.calls, 1);
+        assert_eq!(popper.own.instructions, 2);
+        assert_eq!(
+            profiler.folded(),
+            format!("main {}\nmain;popper 8\n", profiler.total_cycles() - 8)
+        );
+    }
+
+    #[test]
+    fn an_interrupt_handler_is_a_routine_until_its_rti() {
+        let (mut cpu, mut mem, program, mut profiler) = setup(
+            "
+main:   CLI
+loop:   JMP loop
+handler: INX
+        SEI
+        RTI
+",
+        );
+        let handler = program.symbol("handler").unwrap();
+        mem.write_word(0xFFFE, handler);
+
+        // when:
+        profiler.run(&mut cpu, &mut mem, 20);
+        cpu.set_irq(true);
+        profiler.run(&mut cpu, &mut mem, 10);
+        cpu.set_irq(false);
+        profiler.run(&mut cpu, &mut mem, 30);
+
+        // then:
+        assert_eq!(cpu.x(), 1);
+        assert_eq!(profiler.routines[&handler].calls, 1);
+        assert_eq!(profiler.routines[&handler].own.instructions, 3);
+        assert_eq!(profiler.total_cycles(), cpu.cycles());
+        assert_eq!(
+            profiler.calls[&(program.symbol("main").unwrap(), handler)],
+            1
+        );
+    }
+
+    #[test]
+    fn the_report_lists_the_hot_spots_first() {
+        let (mut cpu, mut mem, program, mut profiler) = setup(
+            "
+main:   JSR slow
+end:    JMP end
+slow:   LDY #$10
+wait:   DEY
+        BNE wait
+        RTS
+",
+        );
+
+        // when:
+        run_to_end(&mut cpu, &mut mem, &program, &mut profiler);
+        let report = profiler.report(2);
+
+        // then:
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("routine"));
+        assert!(lines[1].starts_with("slow "));
+        assert!(lines[2].starts_with("main "));
+        assert!(lines[5].starts_with("wait+1 "));
+        assert!(lines[6].starts_with("wait "));
+        assert_eq!(lines.len(), 7);
+    }
+}
@@ -0,0 +1,146 @@
+#[cfg(test)]
+mod run_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::debugger::Comparison;
+    use rust_6502::run::*;
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    //  1000  LDX #$00
+    //  1002  INX          <- loop
+    //  1003  STX $0200
+    //  1006  CPX #$05
+    //  1008  BNE $1002
+    //  100A  the end of the program
+    fn setup(cpu: &mut CPU, mem: &mut Mem, end: &[Byte]) {
+        cpu.reset_vector(mem, 0x1000);
+
+        let main: [Byte; 10] = [0xA2, 0x00, 0xE8, 0x8E, 0x00, 0x02, 0xE0, 0x05, 0xD0, 0xF8];
+        mem.load(0x1000, &main);
+        mem.load(0x100A, end);
+    }
+
+    #[test]
+    fn the_budgets_stop_the_run_and_the_statistics_count_what_ran() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x4C, 0x0A, 0x10]);
+
+        // when:
+        let by_count = cpu.run(&mut mem, &[StopCondition::Instructions(3)]);
+        let by_cycles = cpu.run(&mut mem, &[StopCondition::Cycles(10)]);
+
+        // then: LDX, INX and STX
+        assert_eq!(by_count.reason, StopReason::Instructions);
+        assert_eq!(by_count.stats.instructions, 3);
+        assert_eq!(by_count.stats.cycles, 2 + 2 + 4);
+        // CPX, BNE taken, INX, STX: the budget was 10, the STX went over it
+        assert_eq!(by_cycles.reason, StopReason::Cycles);
+        assert_eq!(by_cycles.stats.instructions, 4);
+        assert_eq!(by_cycles.stats.cycles, 2 + 3 + 2 + 4);
+        assert_eq!(cpu.cycles(), 8 + 11);
+    }
+
+    #[test]
+    fn the_pc_a_memory_value_and_a_jump_to_itself_stop_the_run() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        let three = StopCondition::Memory {
+            address: 0x0200,
+            comparison: Comparison::Equal,
+            value: 3,
+        };
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x4C, 0x0A, 0x10]);
+
+        // when:
+        let memory = cpu.run(&mut mem, &[three, StopCondition::SelfJump]);
+        let pc = cpu.run(&mut mem, &[StopCondition::Pc(0x100A)]);
+        let jump = cpu.run(&mut mem, &[StopCondition::SelfJump]);
+
+        // then:
+        assert_eq!(
+            memory.reason,
+            StopReason::Memory {
+                address: 0x0200,
+                value: 3
+            }
+        );
+        assert_eq!(cpu.x(), 5);
+        assert_eq!(pc.reason, StopReason::Pc(0x100A));
+        assert_eq!(jump.reason, StopReason::SelfJump(0x100A));
+        assert_eq!(jump.stats.instructions, 1);
+        assert_eq!(cpu.pc(), 0x100A);
+    }
+
+    #[test]
+    fn a_brk_stops_the_run_in_its_handler() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x00]);
+        mem.poke(0xFFFE, 0x00);
+        mem.poke(0xFFFF, 0x20);
+
+        // when:
+        let summary = cpu.run(&mut mem, &[StopCondition::Brk]);
+
+        // then:
+        assert_eq!(summary.reason, StopReason::Brk { pc: 0x100A });
+        assert!(!summary.reason.is_error());
+        assert_eq!(cpu.pc(), 0x2000);
+        assert_eq!(summary.stats.interrupts, 0);
+    }
+
+    #[test]
+    fn a_jam_is_reported_as_an_illegal_opcode() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given: no condition, the JAM ends the run
+        setup(&mut cpu, &mut mem, &[0x02]);
+
+        // when:
+        let summary = cpu.run(&mut mem, &[]);
+        let again = cpu.run(&mut mem, &[]);
+
+        // then:
+        let jam = StopReason::IllegalOpcode {
+            pc: 0x100A,
+            opcode: 0x02,
+        };
+        assert_eq!(summary.reason, jam);
+        assert!(summary.reason.is_error());
+        assert_eq!(again.reason, jam);
+        assert_eq!(again.stats.cycles, 0);
+    }
+
+    #[test]
+    fn undocumented_opcodes_stop_the_run_before_they_execute() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given: LAX $10
+        setup(&mut cpu, &mut mem, &[0xA7, 0x10]);
+
+        // when:
+        let summary = cpu.run(&mut mem, &[StopCondition::Undocumented]);
+
+        // then:
+        assert_eq!(
+            summary.reason,
+            StopReason::IllegalOpcode {
+                pc: 0x100A,
+                opcode: 0xA7
+            }
+        );
+        assert_eq!(cpu.pc(), 0x100A);
+        assert_eq!(cpu.x(), 5);
+    }
+}
@@ -0,0 +1,930 @@
+#[cfg(test)]
+mod shifts_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    // type Byte = c_uchar;
+    // type Word = c_ushort;
+
+    #[test]
+    fn test_asl_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(1);
+
+        mem.poke(0xFF00, INS_ASL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 2);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_asl_can_shift_a_negative_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_a(0b11000010);
+
+        mem.poke(0xFF00, INS_ASL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0b10000100);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_asl_zp_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_ASL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 2);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_asl_zp_can_shift_a_negative_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ASL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0b11000010);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0b10000100);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_asl_zpx_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_ASL_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x0042 + 0x10), 2);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_asl_zpx_can_shift_a_negative_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_ASL_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 0b11000010);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x0042 + 0x10), 0b10000100);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_asl_abs_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_ASL_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x8000), 2);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_asl_abs_can_shift_a_negative_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ASL_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 0b11000010);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x8000), 0b10000100);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_asl_absx_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_ASL_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 2);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_asl_absx_can_shift_a_negative_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_ASL_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 0b11000010);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 0b10000100);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    // ---------- LSR ---------
+
+    #[test]
+    fn test_lsr_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(1);
+
+        mem.poke(0xFF00, INS_LSR_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_can_shift_a_zero_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(8);
+
+        mem.poke(0xFF00, INS_LSR_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_zp_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_LSR_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_zp_can_shift_a_zero_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_LSR_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 8);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_zpx_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_LSR_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x0042 + 0x10), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_zpx_can_shift_a_zero_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_LSR_ZPX);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042 + 0x10, 8);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x0042 + 0x10), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_abs_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_LSR_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x8000), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_abs_can_shift_a_zero_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_LSR_ABS);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000, 8);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(mem.peek(0x8000), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_absx_can_shift_the_value_of_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_LSR_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_lsr_absx_can_shift_a_zero_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_x(0x10);
+
+        mem.poke(0xFF00, INS_LSR_ABSX);
+        mem.poke(0xFF01, 0x00);
+        mem.poke(0xFF02, 0x80);
+        mem.poke(0x8000 + 0x10, 8);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x8000 + 0x10), 4);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    // ---------- ROL ---------
+
+    #[test]
+    fn test_rol_can_shift_a_bit_out_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(0);
+
+        mem.poke(0xFF00, INS_ROL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 1);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_can_shift_a_bit_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(0b10000000);
+
+        mem.poke(0xFF00, INS_ROL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_can_shift_zero_with_no_carry() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        cpu.set_a(0);
+
+        mem.poke(0xFF00, INS_ROL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_can_shift_a_value_that_result_in_neg_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_a(0b01110011);
+
+        mem.poke(0xFF00, INS_ROL_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0b11100111);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    // -------- Zero Page ---------
+
+    #[test]
+    fn test_rol_zp_can_shift_a_bit_out_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_ROL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 1);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_zp_can_shift_a_bit_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_ROL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0b10000000);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_zp_can_shift_zero_with_no_carry() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, true); // N
+
+        mem.poke(0xFF00, INS_ROL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_rol_zp_can_shift_a_value_that_result_in_neg_value() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ROL_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0b01110011);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0b11100111);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    // ---------- ROR ---------
+
+    #[test]
+    fn test_ror_can_shift_the_carry_flag_into_the_operand() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_a(0);
+
+        mem.poke(0xFF00, INS_ROR_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_ror_can_shift_a_value_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_a(1);
+
+        mem.poke(0xFF00, INS_ROR_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_ror_can_rotate_a_number() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        cpu.set_a(0b01101101);
+
+        mem.poke(0xFF00, INS_ROR_ACC);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2);
+        assert_eq!(cpu.a(), 0b10110110);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    // -------- Zero Page ---------
+
+    #[test]
+    fn test_ror_zp_can_shift_the_carry_flag_into_the_operand() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ROR_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0b10000000);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), false); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+
+    #[test]
+    fn test_ror_zp_can_shift_a_value_into_the_carry_flag() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ROR_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 1);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false); // N
+    }
+
+    #[test]
+    fn test_ror_zp_can_rotate_a_number() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0xFF00);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Z
+        cpu.set_flag(Flags::NegativeFlagBit, false); // N
+
+        mem.poke(0xFF00, INS_ROR_ZP);
+        mem.poke(0xFF01, 0x42);
+        mem.poke(0x0042, 0b01101101);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0b10110110);
+        assert_eq!(cpu.flag(Flags::CarryFlagBit), true); // C
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false); // Z
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true); // N
+    }
+}
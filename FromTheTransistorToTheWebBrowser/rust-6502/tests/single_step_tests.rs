@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod single_step_tests {
+
+    use rust_6502::debugger::Access;
+    use rust_6502::single_step::*;
+    use rust_6502::CpuVariant;
+
+    // LDA #$80 at $1000
+    const LDA: &str = r#"[
+      { "name": "a9 80 00",
+        "initial": { "pc": 4096, "s": 253, "a": 0, "x": 0, "y": 0, "p": 36,
+                     "ram": [ [4096, 169], [4097, 128] ] },
+        "final": { "pc": 4098, "s": 253, "a": 128, "x": 0, "y": 0, "p": 164,
+                   "ram": [ [4096, 169], [4097, 128] ] },
+        "cycles": [ [4096, 169, "read"], [4097, 128, "read"] ] }
+    ]"#;
+
+    // INX at $2000, the second cycle reads the next byte and throws it away
+    const INX: &str = r#"[
+      { "name": "e8 12 34",
+        "initial": { "pc": 8192, "s": 253, "a": 0, "x": 65, "y": 0, "p": 36,
+                     "ram": [ [8192, 232], [8193, 18] ] },
+        "final": { "pc": 8193, "s": 253, "a": 0, "x": 66, "y": 0, "p": 36,
+                   "ram": [ [8192, 232], [8193, 18] ] },
+        "cycles": [ [8192, 232, "read"], [8193, 18, "read"] ] }
+    ]"#;
+
+    #[test]
+    fn json_values_are_parsed() {
+        // when:
+        let json = parse_json(r#" { "a": [1, -2.5e1, true, null], "b\"A": "x\ny" } "#);
+
+        // then:
+        assert_eq!(
+            json,
+            Ok(Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-25.0),
+                        Json::Bool(true),
+                        Json::Null
+                    ])
+                ),
+                ("b\"A".to_string(), Json::String("x\ny".to_string())),
+            ]))
+        );
+        assert_eq!(
+            parse_json("[1, 2"),
+            Err(SingleStepError::Json {
+                offset: 5,
+                message: "expected ',' or ']'".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn a_vector_is_read_with_its_states_and_cycles() {
+        // when:
+        let vectors = parse_vectors(LDA).unwrap();
+
+        // then:
+        assert_eq!(vectors.len(), 1);
+        assert_eq!(vectors[0].name, "a9 80 00");
+        assert_eq!(vectors[0].initial.pc, 0x1000);
+        assert_eq!(vectors[0].initial.ram, vec![(0x1000, 0xA9), (0x1001, 0x80)]);
+        assert_eq!(vectors[0].expected.a, 0x80);
+        assert_eq!(vectors[0].cycles[1], (0x1001, 0x80, Access::Read));
+        assert!(matches!(
+            parse_vectors(r#"[ { "name": "x", "initial": {} } ]"#),
+            Err(SingleStepError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn a_vector_the_cpu_follows_passes_with_its_bus_accesses() {
+        let vectors = parse_vectors(LDA).unwrap();
+
+        // when:
+        let mismatches = run_vector(&vectors[0], CpuVariant::Nmos6502, true);
+
+        // then:
+        assert_eq!(mismatches, vec![]);
+    }
+
+    #[test]
+    fn the_registers_memory_and_cycles_that_differ_are_reported() {
+        let mut vectors = parse_vectors(LDA).unwrap();
+
+        // given:
+        vectors[0].expected.a = 0x81;
+        vectors[0].expected.ram.push((0x0200, 0x01));
+        vectors[0].cycles.push((0x1002, 0x00, Access::Read));
+
+        // when:
+        let mismatches = run_vector(&vectors[0], CpuVariant::Nmos6502, false);
+
+        // then:
+        assert_eq!(
+            mismatches,
+            vec![
+                Mismatch::Register {
+                    name: "A",
+                    expected: 0x81,
+                    actual: 0x80
+                },
+                Mismatch::Memory {
+                    address: 0x0200,
+                    expected: 0x01,
+                    actual: 0x00
+                },
+                Mismatch::Cycles {
+                    expected: 3,
+                    actual: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn the_bus_comparison_finds_the_dummy_reads_the_cpu_skips() {
+        let vectors = parse_vectors(INX).unwrap();
+
+        // when:
+        let without_bus = run_vector(&vectors[0], CpuVariant::Nmos6502, false);
+        let with_bus = run_vector(&vectors[0], CpuVariant::Nmos6502, true);
+
+        // then:
+        assert_eq!(without_bus, vec![]);
+        assert_eq!(
+            with_bus,
+            vec![Mismatch::Bus {
+                cycle: 2,
+                expected: Some((0x2001, 0x12, Access::Read)),
+                actual: None
+            }]
+        );
+    }
+
+    #[test]
+    fn a_directory_is_run_and_reported_per_opcode() {
+        let directory =
+            std::env::temp_dir().join(format!("rust-6502-single-step-{}", std::process::id()));
+
+        // given:
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("a9.json"), LDA).unwrap();
+        std::fs::write(directory.join("e8.json"), INX.replace("66", "67")).unwrap();
+
+        // when:
+        let reports = run_directory(&directory, CpuVariant::Nmos6502, false);
+        let _ = std::fs::remove_dir_all(&directory);
+
+        // then:
+        let reports = reports.unwrap();
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].passed());
+        assert_eq!(reports[1].opcode, 0xE8);
+        assert_eq!(reports[1].failed, 1);
+        assert_eq!(
+            report(&reports, CpuVariant::Nmos6502),
+            "a9 LDA        1 vectors  ok\n\
+             e8 INX        1 vectors  1 failed, the first is \"e8 12 34\"\n    \
+             X: expected $43, got $42\n\
+             2 opcodes, 1 passed, 1 failed\n"
+        );
+    }
+}
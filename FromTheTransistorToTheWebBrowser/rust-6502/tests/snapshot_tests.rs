@@ -0,0 +1,194 @@
+#[cfg(test)]
+mod snapshot_tests {
+
+    use rust_6502::bus::{AddressDecoder, Bus, Ram, Rom};
+    use rust_6502::snapshot::*;
+    use rust_6502::Byte;
+    use rust_6502::{CpuVariant, Mem, CPU};
+
+    // Fills $0200-$023F with 1, 2, 3... then loops forever:
+    //  LDX #$00; loop: INX; TXA; STA $01FF,X; CPX #$40; BNE loop; JMP *
+    const PROGRAM: [Byte; 15] = [
+        0xA2, 0x00, 0xE8, 0x8A, 0x9D, 0xFF, 0x01, 0xE0, 0x40, 0xD0, 0xF7, 0x4C, 0x0B, 0x04, 0xEA,
+    ];
+
+    fn setup(mem: &mut Mem) -> CPU {
+        let mut cpu = CPU::new();
+        cpu.reset_vector(mem, 0x0400);
+        mem.load(0x0400, &PROGRAM);
+        cpu
+    }
+
+    fn run(cpu: &mut CPU, memory: &mut impl Bus, instructions: usize) {
+        for _ in 0..instructions {
+            cpu.step(memory);
+        }
+    }
+
+    #[test]
+    fn a_program_resumes_from_a_snapshot_taken_mid_run() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(&mut mem);
+        run(&mut cpu, &mut mem, 100);
+        let snapshot = save_snapshot(&cpu, &mem);
+
+        // when:
+        let mut resumed_mem = Mem::new();
+        let mut resumed_cpu = CPU::new();
+        load_snapshot(&snapshot, &mut resumed_cpu, &mut resumed_mem).unwrap();
+        run(&mut cpu, &mut mem, 300);
+        run(&mut resumed_cpu, &mut resumed_mem, 300);
+
+        // then:
+        assert_eq!(resumed_mem.data(), mem.data());
+        assert_eq!(resumed_mem.peek(0x023F), 0x40);
+        assert_eq!(
+            (
+                resumed_cpu.pc(),
+                resumed_cpu.a(),
+                resumed_cpu.x(),
+                resumed_cpu.status()
+            ),
+            (cpu.pc(), cpu.a(), cpu.x(), cpu.status())
+        );
+        assert_eq!(resumed_cpu.cycles(), cpu.cycles());
+    }
+
+    #[test]
+    fn a_machine_reset_twice_saves_64k_that_load_into_a_new_machine() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(&mut mem);
+        cpu.reset_vector(&mut mem, 0x0400);
+        run(&mut cpu, &mut mem, 10);
+
+        // when:
+        let snapshot = save_snapshot(&cpu, &mem);
+        let mut loaded_mem = Mem::new();
+        let mut loaded_cpu = CPU::new();
+        let result = load_snapshot(&snapshot, &mut loaded_cpu, &mut loaded_mem);
+
+        // then:
+        assert_eq!(result, Ok(()));
+        assert_eq!(mem.data().len(), 0x10000);
+        assert_eq!(loaded_mem.data(), mem.data());
+        assert_eq!(loaded_cpu.pc(), cpu.pc());
+    }
+
+    #[test]
+    fn a_snapshot_survives_a_round_trip_through_a_file() {
+        let mut mem = Mem::new();
+        let path = std::env::temp_dir().join(format!("rust-6502-{}.snapshot", std::process::id()));
+
+        // given:
+        let mut cpu = CPU::new_variant(CpuVariant::Wdc65C02);
+        cpu.reset_vector(&mut mem, 0x0400);
+        mem.load(0x0400, &PROGRAM);
+        cpu.set_irq(true);
+        run(&mut cpu, &mut mem, 10);
+
+        // when:
+        save_snapshot_file(&path, &cpu, &mem).unwrap();
+        let mut loaded_mem = Mem::new();
+        let mut loaded_cpu = CPU::new();
+        let result = load_snapshot_file(&path, &mut loaded_cpu, &mut loaded_mem);
+        let _ = std::fs::remove_file(&path);
+
+        // then:
+        assert_eq!(result, Ok(()));
+        assert_eq!(loaded_mem.data(), mem.data());
+        assert_eq!(loaded_cpu.variant(), CpuVariant::Wdc65C02);
+        assert!(loaded_cpu.irq_line());
+        assert_eq!(
+            save_snapshot(&loaded_cpu, &loaded_mem),
+            save_snapshot(&cpu, &mem)
+        );
+    }
+
+    #[test]
+    fn the_snapshot_starts_with_the_magic_the_version_and_the_registers() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(&mut mem);
+        cpu.set_a(0x11);
+        cpu.set_x(0x22);
+        cpu.set_y(0x33);
+
+        // when:
+        let snapshot = save_snapshot(&cpu, &mem);
+
+        // then:
+        assert_eq!(&snapshot[..4], b"S502");
+        assert_eq!(&snapshot[4..6], &[0x01, 0x00]);
+        assert_eq!(&snapshot[6..12], &[0x00, 0x04, 0xFF, 0x11, 0x22, 0x33]);
+    }
+
+    #[test]
+    fn a_snapshot_of_another_version_is_rejected() {
+        let mut mem = Mem::new();
+        let cpu = setup(&mut mem);
+
+        // given:
+        let mut snapshot = save_snapshot(&cpu, &mem);
+        snapshot[4] = 0x02;
+
+        // when:
+        let mut loaded_cpu = CPU::new();
+        let error = load_snapshot(&snapshot, &mut loaded_cpu, &mut Mem::new()).unwrap_err();
+
+        // then:
+        assert_eq!(error, SnapshotError::UnsupportedVersion(2));
+        assert_eq!(
+            error.to_string(),
+            "snapshot version 2 is not supported, expected version 1"
+        );
+        assert_eq!(loaded_cpu.pc(), 0);
+    }
+
+    #[test]
+    fn broken_snapshots_are_rejected() {
+        let mut mem = Mem::new();
+        let cpu = setup(&mut mem);
+        let snapshot = save_snapshot(&cpu, &mem);
+        let mut cpu = CPU::new();
+
+        // then:
+        assert_eq!(
+            load_snapshot(b"PNG!", &mut cpu, &mut Mem::new()),
+            Err(SnapshotError::BadMagic)
+        );
+        assert_eq!(
+            load_snapshot(&snapshot[..100], &mut cpu, &mut Mem::new()),
+            Err(SnapshotError::Truncated)
+        );
+        assert!(matches!(
+            load_snapshot(&snapshot, &mut cpu, &mut Ram::new(0x100)),
+            Err(SnapshotError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn the_devices_of_an_address_decoder_are_restored() {
+        let mut decoder = AddressDecoder::new();
+        decoder.map(0x0000, 0x7FFF, Box::new(Ram::new(0x8000)));
+        decoder.map(0xE000, 0xFFFF, Box::new(Rom::new(vec![0xEA; 0x2000])));
+        let cpu = CPU::new();
+
+        // given:
+        decoder.write(0x1234, 0x42);
+        let snapshot = save_snapshot(&cpu, &decoder);
+        decoder.write(0x1234, 0x00);
+
+        // when:
+        let mut loaded_cpu = CPU::new();
+        load_snapshot(&snapshot, &mut loaded_cpu, &mut decoder).unwrap();
+
+        // then:
+        assert_eq!(decoder.peek(0x1234), 0x42);
+        assert_eq!(decoder.peek(0xE000), 0xEA);
+    }
+}
@@ -2,15 +2,12 @@
 
 mod stack_and_operations_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Flags;
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
 
     #[test]
     fn tsx_can_transfer_the_stack_pointer_to_the_x_register() {
@@ -21,11 +18,11 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x00;
-        cpu.SP = 0x01;
-        mem.Data[0xFF00] = cpu.INS_TSX;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x00);
+        cpu.set_sp(0x01);
+        mem.poke(0xFF00, INS_TSX);
 
         let mut expected_cycles = 2;
 
@@ -33,9 +30,9 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x01);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x01);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
     }
 
     #[test]
@@ -47,11 +44,11 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, true);
-        cpu.PS.set_bit(7, true);
-        cpu.X = 0x00;
-        cpu.SP = 0x00;
-        mem.Data[0xFF00] = cpu.INS_TSX;
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_x(0x00);
+        cpu.set_sp(0x00);
+        mem.poke(0xFF00, INS_TSX);
 
         let mut expected_cycles = 2;
 
@@ -59,9 +56,9 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x00);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x00);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
     }
 
     #[test]
@@ -73,11 +70,11 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        cpu.PS.set_bit(7, false);
-        cpu.X = 0x00;
-        cpu.SP = 0b10000000;
-        mem.Data[0xFF00] = cpu.INS_TSX;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_x(0x00);
+        cpu.set_sp(0b10000000);
+        mem.poke(0xFF00, INS_TSX);
 
         let mut expected_cycles = 2;
 
@@ -85,9 +82,9 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0b10000000);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
     }
 
     #[test]
@@ -99,9 +96,9 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.X = 0xFF;
-        cpu.SP = 0;
-        mem.Data[0xFF00] = cpu.INS_TXS;
+        cpu.set_x(0xFF);
+        cpu.set_sp(0);
+        mem.poke(0xFF00, INS_TXS);
 
         let mut expected_cycles = 2;
 
@@ -109,7 +106,7 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.SP, 0xFF);
+        assert_eq!(cpu.sp(), 0xFF);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -122,8 +119,8 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.A = 0x42;
-        mem.Data[0xFF00] = cpu.INS_PHA;
+        cpu.set_a(0x42);
+        mem.poke(0xFF00, INS_PHA);
 
         let mut expected_cycles = 3;
 
@@ -131,7 +128,10 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(mem.Data[cpu.sp_to_address() as usize + 1 as usize], cpu.A);
+        assert_eq!(
+            mem.data()[0x100 + cpu.sp() as usize + 1],
+            cpu.a()
+        );
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -144,10 +144,10 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.A = 0x00;
-        cpu.SP = 0xFE;
-        mem.Data[0x01FF] = 0x42;
-        mem.Data[0xFF00] = cpu.INS_PLA;
+        cpu.set_a(0x00);
+        cpu.set_sp(0xFE);
+        mem.poke(0x01FF, 0x42);
+        mem.poke(0xFF00, INS_PLA);
 
         let mut expected_cycles = 4;
 
@@ -155,8 +155,8 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.SP, 0xFF);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.sp(), 0xFF);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -169,12 +169,12 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(1, false);
-        cpu.PS.set_bit(7, true);
-        cpu.A = 0x42;
-        cpu.SP = 0xFE;
-        mem.Data[0x01FF] = 0x00;
-        mem.Data[0xFF00] = cpu.INS_PLA;
+        cpu.set_flag(Flags::ZeroFlagBit, false);
+        cpu.set_flag(Flags::NegativeFlagBit, true);
+        cpu.set_a(0x42);
+        cpu.set_sp(0xFE);
+        mem.poke(0x01FF, 0x00);
+        mem.poke(0xFF00, INS_PLA);
 
         let mut expected_cycles = 4;
 
@@ -182,10 +182,10 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, 0x00);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
-        assert_eq!(cpu.SP, 0xFF);
+        assert_eq!(cpu.a(), 0x00);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
+        assert_eq!(cpu.sp(), 0xFF);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -198,12 +198,12 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS.set_bit(7, false);
-        cpu.PS.set_bit(1, true);
-        cpu.A = 0x42;
-        cpu.SP = 0xFE;
-        mem.Data[0x01FF] = 0b10000001;
-        mem.Data[0xFF00] = cpu.INS_PLA;
+        cpu.set_flag(Flags::NegativeFlagBit, false);
+        cpu.set_flag(Flags::ZeroFlagBit, true);
+        cpu.set_a(0x42);
+        cpu.set_sp(0xFE);
+        mem.poke(0x01FF, 0b10000001);
+        mem.poke(0xFF00, INS_PLA);
 
         let mut expected_cycles = 4;
 
@@ -211,10 +211,10 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.A, 0b10000001);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
-        assert_eq!(cpu.SP, 0xFF);
+        assert_eq!(cpu.a(), 0b10000001);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
+        assert_eq!(cpu.sp(), 0xFF);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -227,8 +227,8 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS = 0xCC;
-        mem.Data[0xFF00] = cpu.INS_PHP;
+        cpu.set_status(0xCC);
+        mem.poke(0xFF00, INS_PHP);
 
         let mut expected_cycles = 3;
         cpu_copy = cpu;
@@ -238,11 +238,11 @@ mod stack_and_operations_tests {
         // then:
         assert_eq!(actual_cycles, 3);
         assert_eq!(
-            mem.Data[cpu.sp_to_address() as usize + 1 as usize],
+            mem.data()[0x100 + cpu.sp() as usize + 1],
             0xCC | Flags::UnusedFlagBit as u8 | Flags::BreakFlagBit as u8
         );
-        assert_eq!(cpu.PS, cpu_copy.PS);
-        assert_eq!(cpu.SP, 0xFE);
+        assert_eq!(cpu.status(), cpu_copy.status());
+        assert_eq!(cpu.sp(), 0xFE);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -253,8 +253,8 @@ mod stack_and_operations_tests {
 
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
-        cpu.PS = 0x0;
-        mem.Data[0xFF00] = cpu.INS_PHP;
+        cpu.set_status(0x0);
+        mem.poke(0xFF00, INS_PHP);
 
         let mut expected_cycles = 3;
 
@@ -262,7 +262,7 @@ mod stack_and_operations_tests {
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
-        let add_ps_on_stack: Word = cpu.sp_to_address() + 1;
+        let add_ps_on_stack: Word = 0x100 + cpu.sp() as Word + 1;
         assert_eq!(actual_cycles, 3);
         // https://wiki.nesdev.com/w/index.php/Status_flags
         //Two interrupts (/IRQ and /NMI) and two instructions (PHP and BRK) push
@@ -272,7 +272,7 @@ mod stack_and_operations_tests {
         // and place where the B flag actually exists: not in the status register
         // itself, but in bit 4 of the copy that is written to the stack.
         let flags_on_stack: Byte = Flags::UnusedFlagBit as u8 | Flags::BreakFlagBit as u8;
-        assert_eq!(mem.Data[add_ps_on_stack as usize], flags_on_stack);
+        assert_eq!(mem.peek(add_ps_on_stack), flags_on_stack);
     }
 
     #[test]
@@ -284,10 +284,13 @@ mod stack_and_operations_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
         cpu_copy.reset_vector(&mut mem, 0xFF00);
-        cpu.PS = 0;
-        cpu.SP = 0xFE;
-        mem.Data[0x01FF] = 0x42 | Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8;
-        mem.Data[0xFF00] = cpu.INS_PLP;
+        cpu.set_status(0);
+        cpu.set_sp(0xFE);
+        mem.poke(
+            0x01FF,
+            0x42 | Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8,
+        );
+        mem.poke(0xFF00, INS_PLP);
 
         let mut expected_cycles = 4;
 
@@ -295,7 +298,7 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.PS, 0x42);
+        assert_eq!(cpu.status(), 0x42);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
@@ -306,10 +309,13 @@ mod stack_and_operations_tests {
 
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
-        cpu.PS = 0;
-        cpu.SP = 0xFE;
-        mem.Data[0x01FF] = Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8;
-        mem.Data[0xFF00] = cpu.INS_PLP;
+        cpu.set_status(0);
+        cpu.set_sp(0xFE);
+        mem.poke(
+            0x01FF,
+            Flags::BreakFlagBit as u8 | Flags::UnusedFlagBit as u8,
+        );
+        mem.poke(0xFF00, INS_PLP);
 
         let mut expected_cycles = 4;
 
@@ -317,17 +323,17 @@ mod stack_and_operations_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(cpu.PS, 0);
+        assert_eq!(cpu.status(), 0);
         // verify_unmodified_flags_from_store(cpu, cpu_copy);
     }
 
     // fn verify_unmodified_flags_from_store(cpu: CPU, cpu_copy: CPU) {
     //     assert_eq!(cpu.C, cpu_copy.C);
-    //     assert_eq!(cpu.PS.1, cpu_copy.Z);
+    //     assert_eq!(cpu.status().1, cpu_copy.Z);
     //     assert_eq!(cpu.I, cpu_copy.I);
     //     assert_eq!(cpu.D, cpu_copy.D);
     //     assert_eq!(cpu.B, cpu_copy.B);
     //     assert_eq!(cpu.V, cpu_copy.V);
-    //     assert_eq!(cpu.PS.7, cpu_copy.N);
+    //     assert_eq!(cpu.status().7, cpu_copy.N);
     // }
 }
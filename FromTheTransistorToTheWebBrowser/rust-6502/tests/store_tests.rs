@@ -1,13 +1,11 @@
 #[cfg(test)]
 mod store_tests {
 
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     fn test_sta_zp(opcode_to_test: Byte) {
         let mut mem = Mem::new();
@@ -17,19 +15,19 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x0080] = 0x00;
+        cpu.set_a(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x0080, 0x00);
 
         let mut expected_cycles = 3;
 
         let actual_cycles = cpu.execute(&mut expected_cycles, &mut mem);
 
         // then:
-        assert_eq!(mem.Data[0x0080], 0x2F);
+        assert_eq!(mem.peek(0x0080), 0x2F);
         assert_eq!(actual_cycles, 3);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_sta_zpx(opcode_to_test: Byte) {
@@ -40,11 +38,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x42;
-        cpu.X = 0x0F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x008F] = 0x00;
+        cpu.set_a(0x42);
+        cpu.set_x(0x0F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x008F, 0x00);
 
         let mut expected_cycles = 4;
 
@@ -52,8 +50,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(mem.Data[0x008F], 0x42);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x008F), 0x42);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_sty_zpx(opcode_to_test: Byte) {
@@ -64,11 +62,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.Y = 0x42;
-        cpu.X = 0x0F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x008F] = 0x00;
+        cpu.set_y(0x42);
+        cpu.set_x(0x0F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x008F, 0x00);
 
         let mut expected_cycles = 4;
 
@@ -76,8 +74,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(mem.Data[0x008F], 0x42);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x008F), 0x42);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_stx_zp(opcode_to_test: Byte) {
@@ -88,10 +86,10 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.X = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x0080] = 0x00;
+        cpu.set_x(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x0080, 0x00);
 
         let mut expected_cycles = 3;
 
@@ -99,8 +97,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(mem.Data[0x0080], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x0080), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_sty_zp(opcode_to_test: Byte) {
@@ -111,10 +109,10 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.Y = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x80;
-        mem.Data[0x0080] = 0x00;
+        cpu.set_y(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x80);
+        mem.poke(0x0080, 0x00);
 
         let mut expected_cycles = 3;
 
@@ -122,8 +120,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 3);
-        assert_eq!(mem.Data[0x0080], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x0080), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_sta_abs(opcode_to_test: Byte) {
@@ -134,11 +132,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0x00;
+        cpu.set_a(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0x00);
 
         let mut expected_cycles = 4;
 
@@ -146,8 +144,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(mem.Data[0x8000], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x8000), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_stx_abs(opcode_to_test: Byte) {
@@ -158,11 +156,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.X = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0x00;
+        cpu.set_x(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0x00);
 
         let mut expected_cycles = 4;
 
@@ -170,8 +168,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(mem.Data[0x8000], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x8000), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     fn test_sty_abs(opcode_to_test: Byte) {
@@ -182,11 +180,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.Y = 0x2F;
-        mem.Data[0xFFFC] = opcode_to_test;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x80;
-        mem.Data[0x8000] = 0x00;
+        cpu.set_y(0x2F);
+        mem.poke(0xFFFC, opcode_to_test);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x80);
+        mem.poke(0x8000, 0x00);
 
         let mut expected_cycles = 4;
 
@@ -194,56 +192,48 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 4);
-        assert_eq!(mem.Data[0x8000], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x8000), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
     fn test_sta_zp_can_store_the_a_register_into_memory() {
-        let cpu = CPU::new();
-        test_sta_zp(cpu.INS_STA_ZP);
+        test_sta_zp(INS_STA_ZP);
     }
 
     #[test]
     fn test_sta_zpx_can_store_the_a_register_into_memory() {
-        let cpu = CPU::new();
-        test_sta_zpx(cpu.INS_STA_ZPX);
+        test_sta_zpx(INS_STA_ZPX);
     }
 
     #[test]
     fn test_stx_zp_can_store_the_x_register_into_memory() {
-        let cpu = CPU::new();
-        test_stx_zp(cpu.INS_STX_ZP);
+        test_stx_zp(INS_STX_ZP);
     }
 
     #[test]
     fn test_sty_zp_can_store_the_y_register_into_memory() {
-        let cpu = CPU::new();
-        test_sty_zp(cpu.INS_STY_ZP);
+        test_sty_zp(INS_STY_ZP);
     }
 
     #[test]
     fn test_sty_zpx_can_store_the_y_register_into_memory() {
-        let cpu = CPU::new();
-        test_sty_zpx(cpu.INS_STY_ZPX);
+        test_sty_zpx(INS_STY_ZPX);
     }
 
     #[test]
     fn test_sta_abs_can_store_the_a_register_into_memory() {
-        let cpu = CPU::new();
-        test_sta_abs(cpu.INS_STA_ABS);
+        test_sta_abs(INS_STA_ABS);
     }
 
     #[test]
     fn test_stx_abs_can_store_the_x_register_into_memory() {
-        let cpu = CPU::new();
-        test_stx_abs(cpu.INS_STX_ABS);
+        test_stx_abs(INS_STX_ABS);
     }
 
     #[test]
     fn test_sty_abs_can_store_the_y_register_into_memory() {
-        let cpu = CPU::new();
-        test_sty_abs(cpu.INS_STY_ABS);
+        test_sty_abs(INS_STY_ABS);
     }
 
     #[test]
@@ -255,11 +245,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x2F;
-        cpu.X = 0x92;
-        mem.Data[0xFFFC] = cpu.INS_STA_ABSX;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x20;
+        cpu.set_a(0x2F);
+        cpu.set_x(0x92);
+        mem.poke(0xFFFC, INS_STA_ABSX);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x20);
 
         let mut expected_cycles = 5;
 
@@ -267,8 +257,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(mem.Data[0x2092], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x2092), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -280,11 +270,11 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x2F;
-        cpu.Y = 0x92;
-        mem.Data[0xFFFC] = cpu.INS_STA_ABSY;
-        mem.Data[0xFFFD] = 0x00;
-        mem.Data[0xFFFE] = 0x20;
+        cpu.set_a(0x2F);
+        cpu.set_y(0x92);
+        mem.poke(0xFFFC, INS_STA_ABSY);
+        mem.poke(0xFFFD, 0x00);
+        mem.poke(0xFFFE, 0x20);
 
         let mut expected_cycles = 5;
 
@@ -292,8 +282,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 5);
-        assert_eq!(mem.Data[0x2092], 0x2F);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x2092), 0x2F);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -305,13 +295,13 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x42;
-        cpu.X = 0x0F;
-        mem.Data[0xFFFC] = cpu.INS_STA_INDX;
-        mem.Data[0xFFFD] = 0x20;
-        mem.Data[0x002F] = 0x00;
-        mem.Data[0x0030] = 0x80;
-        mem.Data[0x8000] = 0x00;
+        cpu.set_a(0x42);
+        cpu.set_x(0x0F);
+        mem.poke(0xFFFC, INS_STA_INDX);
+        mem.poke(0xFFFD, 0x20);
+        mem.poke(0x002F, 0x00);
+        mem.poke(0x0030, 0x80);
+        mem.poke(0x8000, 0x00);
 
         let mut expected_cycles = 6;
 
@@ -319,8 +309,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000], 0x42);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x8000), 0x42);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     #[test]
@@ -332,13 +322,13 @@ mod store_tests {
         // given:
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
-        cpu.A = 0x42;
-        cpu.Y = 0x0F;
-        mem.Data[0xFFFC] = cpu.INS_STA_INDY;
-        mem.Data[0xFFFD] = 0x20;
-        mem.Data[0x0020] = 0x00;
-        mem.Data[0x0021] = 0x80;
-        mem.Data[0x8000 + 0x0F] = 0x00;
+        cpu.set_a(0x42);
+        cpu.set_y(0x0F);
+        mem.poke(0xFFFC, INS_STA_INDY);
+        mem.poke(0xFFFD, 0x20);
+        mem.poke(0x0020, 0x00);
+        mem.poke(0x0021, 0x80);
+        mem.poke(0x8000 + 0x0F, 0x00);
 
         let mut expected_cycles = 6;
 
@@ -346,8 +336,8 @@ mod store_tests {
 
         // then:
         assert_eq!(actual_cycles, 6);
-        assert_eq!(mem.Data[0x8000 + 0x0F], 0x42);
-        assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(mem.peek(0x8000 + 0x0F), 0x42);
+        assert_eq!(cpu.status(), cpu_copy.status());
     }
 
     // fn verify_unmodified_flags_from_store(cpu: CPU, cpu_copy: CPU) {
@@ -1,12 +1,13 @@
 #[cfg(test)]
 mod system_functions_tests {
 
-    use bit_field::BitField;
     // use std::os::raw::*;
 
-    use crate::Flags;
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn test_nop_will_do_nothing_but_consume_a_cycle() {
@@ -15,7 +16,7 @@ mod system_functions_tests {
 
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_NOP;
+        mem.poke(0xFF00, INS_NOP);
         let cpu_copy = cpu;
 
         // when:
@@ -23,12 +24,12 @@ mod system_functions_tests {
 
         // then:
         assert_eq!(cycles_used, 2);
-        assert_eq!(cpu.PS, cpu_copy.PS);
-        assert_eq!(cpu.PC, 0xFF01);
-        assert_eq!(cpu.A, cpu_copy.A);
-        assert_eq!(cpu.X, cpu_copy.X);
-        assert_eq!(cpu.Y, cpu_copy.Y);
-        assert_eq!(cpu.SP, cpu_copy.SP);
+        assert_eq!(cpu.status(), cpu_copy.status());
+        assert_eq!(cpu.pc(), 0xFF01);
+        assert_eq!(cpu.a(), cpu_copy.a());
+        assert_eq!(cpu.x(), cpu_copy.x());
+        assert_eq!(cpu.y(), cpu_copy.y());
+        assert_eq!(cpu.sp(), cpu_copy.sp());
     }
 
     #[test]
@@ -38,16 +39,16 @@ mod system_functions_tests {
 
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_BRK;
-        mem.Data[0xFFFE] = 0x00;
-        mem.Data[0xFFFF] = 0x80;
+        mem.poke(0xFF00, INS_BRK);
+        mem.poke(0xFFFE, 0x00);
+        mem.poke(0xFFFF, 0x80);
 
         // when:
         let cycles_used = cpu.execute(&mut 7, &mut mem);
 
         // then:
         assert_eq!(cycles_used, 7);
-        assert_eq!(cpu.PC, 0x8000);
+        assert_eq!(cpu.pc(), 0x8000);
     }
 
     #[test]
@@ -57,16 +58,16 @@ mod system_functions_tests {
 
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
-        mem.Data[0xFF00] = cpu.INS_BRK;
-        mem.Data[0xFFFE] = 0x00;
-        mem.Data[0xFFFF] = 0x90;
+        mem.poke(0xFF00, INS_BRK);
+        mem.poke(0xFFFE, 0x00);
+        mem.poke(0xFFFF, 0x90);
 
         // when:
         let cycles_used = cpu.execute(&mut 7, &mut mem);
 
         // then:
         assert_eq!(cycles_used, 7);
-        assert_eq!(cpu.PC, 0x9000);
+        assert_eq!(cpu.pc(), 0x9000);
     }
 
     #[test]
@@ -77,16 +78,16 @@ mod system_functions_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(4, false); // B
+        cpu.set_flag(Flags::BreakFlagBit, false); // B
 
-        mem.Data[0xFF00] = cpu.INS_BRK;
+        mem.poke(0xFF00, INS_BRK);
 
         // when:
         let cycles_used = cpu.execute(&mut 7, &mut mem);
 
         // then:
         assert_eq!(cycles_used, 7);
-        assert_eq!(cpu.PS.get_bit(4), true);
+        assert_eq!(cpu.flag(Flags::BreakFlagBit), true);
     }
 
     #[test]
@@ -97,9 +98,9 @@ mod system_functions_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        cpu.PS.set_bit(4, false); // B
+        cpu.set_flag(Flags::BreakFlagBit, false); // B
 
-        mem.Data[0xFF00] = cpu.INS_BRK;
+        mem.poke(0xFF00, INS_BRK);
         let cpu_copy = cpu;
 
         // when:
@@ -107,7 +108,7 @@ mod system_functions_tests {
 
         // then:
         assert_eq!(cycles_used, 7);
-        assert_eq!(cpu.SP, cpu_copy.SP - 3);
+        assert_eq!(cpu.sp(), cpu_copy.sp() - 3);
     }
 
     #[test]
@@ -118,9 +119,9 @@ mod system_functions_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        mem.Data[0xFF00] = cpu.INS_BRK;
+        mem.poke(0xFF00, INS_BRK);
         let cpu_copy = cpu;
-        let old_sp: u16 = cpu_copy.SP as u16;
+        let old_sp: u16 = cpu_copy.sp() as u16;
         let x = 0x100 | old_sp;
 
         // when:
@@ -128,21 +129,21 @@ mod system_functions_tests {
 
         // then:
         assert_eq!(cycles_used, 7);
-        dbg!(mem.Data[((x | old_sp) - 0) as usize]);
-        assert_eq!(mem.Data[((x | old_sp) - 0) as usize], 0xFF);
+        dbg!(mem.peek((x | old_sp) - 0));
+        assert_eq!(mem.peek((x | old_sp) - 0), 0xFF);
         // https://www.c64-wiki.com/wiki/BRK
         // Note that since BRK increments the program counter by
         // 2 instead of 1, it is advisable to use a NOP after it
         // to avoid issues
-        assert_eq!(mem.Data[((x | old_sp) - 1) as usize], 0x02);
+        assert_eq!(mem.peek((x | old_sp) - 1), 0x02);
         assert_eq!(
-            mem.Data[((x | old_sp) - 2) as usize],
-            cpu_copy.PS | Flags::UnusedFlagBit as u8 | Flags::BreakFlagBit as u8
+            mem.peek((x | old_sp) - 2),
+            cpu_copy.status() | Flags::UnusedFlagBit as u8 | Flags::BreakFlagBit as u8
         );
         // https://wiki.nesdev.com/w/index.php/Status_flags
         // Instruction	|Bits 5 and 4	| Side effects after pushing
         // BRK			|	11			| I is set to 1
-        assert_eq!(cpu.PS.get_bit(2), true);
+        assert_eq!(cpu.flag(Flags::InterruptDisableFlagBit), true);
     }
 
     #[test]
@@ -153,10 +154,10 @@ mod system_functions_tests {
         // given:
         cpu.reset_vector(&mut mem, 0xFF00);
 
-        mem.Data[0xFF00] = cpu.INS_BRK;
-        mem.Data[0xFFFE] = 0x00;
-        mem.Data[0xFFFF] = 0x80;
-        mem.Data[0x8000] = cpu.INS_RTI;
+        mem.poke(0xFF00, INS_BRK);
+        mem.poke(0xFFFE, 0x00);
+        mem.poke(0xFFFF, 0x80);
+        mem.poke(0x8000, INS_RTI);
         let cpu_copy = cpu;
 
         // when:
@@ -166,8 +167,8 @@ mod system_functions_tests {
         // then:
         assert_eq!(cycles_used_brk, 7);
         assert_eq!(cycles_used_rti, 6);
-        assert_eq!(cpu.SP, cpu_copy.SP);
-        assert_eq!(0xFF02, cpu.PC);
-        // assert_eq!(cpu.PS, cpu_copy.PS);
+        assert_eq!(cpu.sp(), cpu_copy.sp());
+        assert_eq!(0xFF02, cpu.pc());
+        // assert_eq!(cpu.status(), cpu_copy.status());
     }
 }
@@ -0,0 +1,302 @@
+#[cfg(test)]
+mod timing_tests {
+
+    use bit_field::BitField;
+
+    use rust_6502::bus::Bus;
+    use rust_6502::Byte;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    // The published timing of the 151 documented opcodes
+    // (http://www.6502.org/tutorials/6502opcodes.html): opcode, cycles and
+    // whether crossing a page while indexing takes one more cycle.
+    // Branches take 2 cycles, 3 when taken and 4 when taken to another page
+    const TIMING: [(Byte, isize, bool); 151] = [
+        // ADC
+        (0x69, 2, false),
+        (0x65, 3, false),
+        (0x75, 4, false),
+        (0x6D, 4, false),
+        (0x7D, 4, true),
+        (0x79, 4, true),
+        (0x61, 6, false),
+        (0x71, 5, true),
+        // AND
+        (0x29, 2, false),
+        (0x25, 3, false),
+        (0x35, 4, false),
+        (0x2D, 4, false),
+        (0x3D, 4, true),
+        (0x39, 4, true),
+        (0x21, 6, false),
+        (0x31, 5, true),
+        // ASL
+        (0x0A, 2, false),
+        (0x06, 5, false),
+        (0x16, 6, false),
+        (0x0E, 6, false),
+        (0x1E, 7, false),
+        // Branches
+        (0x90, 2, false),
+        (0xB0, 2, false),
+        (0xF0, 2, false),
+        (0x30, 2, false),
+        (0xD0, 2, false),
+        (0x10, 2, false),
+        (0x50, 2, false),
+        (0x70, 2, false),
+        // BIT
+        (0x24, 3, false),
+        (0x2C, 4, false),
+        // BRK
+        (0x00, 7, false),
+        // Flags
+        (0x18, 2, false),
+        (0xD8, 2, false),
+        (0x58, 2, false),
+        (0xB8, 2, false),
+        (0x38, 2, false),
+        (0xF8, 2, false),
+        (0x78, 2, false),
+        // CMP
+        (0xC9, 2, false),
+        (0xC5, 3, false),
+        (0xD5, 4, false),
+        (0xCD, 4, false),
+        (0xDD, 4, true),
+        (0xD9, 4, true),
+        (0xC1, 6, false),
+        (0xD1, 5, true),
+        // CPX
+        (0xE0, 2, false),
+        (0xE4, 3, false),
+        (0xEC, 4, false),
+        // CPY
+        (0xC0, 2, false),
+        (0xC4, 3, false),
+        (0xCC, 4, false),
+        // DEC
+        (0xC6, 5, false),
+        (0xD6, 6, false),
+        (0xCE, 6, false),
+        (0xDE, 7, false),
+        // EOR
+        (0x49, 2, false),
+        (0x45, 3, false),
+        (0x55, 4, false),
+        (0x4D, 4, false),
+        (0x5D, 4, true),
+        (0x59, 4, true),
+        (0x41, 6, false),
+        (0x51, 5, true),
+        // INC
+        (0xE6, 5, false),
+        (0xF6, 6, false),
+        (0xEE, 6, false),
+        (0xFE, 7, false),
+        // JMP
+        (0x4C, 3, false),
+        (0x6C, 5, false),
+        // JSR
+        (0x20, 6, false),
+        // LDA
+        (0xA9, 2, false),
+        (0xA5, 3, false),
+        (0xB5, 4, false),
+        (0xAD, 4, false),
+        (0xBD, 4, true),
+        (0xB9, 4, true),
+        (0xA1, 6, false),
+        (0xB1, 5, true),
+        // LDX
+        (0xA2, 2, false),
+        (0xA6, 3, false),
+        (0xB6, 4, false),
+        (0xAE, 4, false),
+        (0xBE, 4, true),
+        // LDY
+        (0xA0, 2, false),
+        (0xA4, 3, false),
+        (0xB4, 4, false),
+        (0xAC, 4, false),
+        (0xBC, 4, true),
+        // LSR
+        (0x4A, 2, false),
+        (0x46, 5, false),
+        (0x56, 6, false),
+        (0x4E, 6, false),
+        (0x5E, 7, false),
+        // NOP
+        (0xEA, 2, false),
+        // ORA
+        (0x09, 2, false),
+        (0x05, 3, false),
+        (0x15, 4, false),
+        (0x0D, 4, false),
+        (0x1D, 4, true),
+        (0x19, 4, true),
+        (0x01, 6, false),
+        (0x11, 5, true),
+        // Registers
+        (0xAA, 2, false),
+        (0x8A, 2, false),
+        (0xCA, 2, false),
+        (0xE8, 2, false),
+        (0xA8, 2, false),
+        (0x98, 2, false),
+        (0x88, 2, false),
+        (0xC8, 2, false),
+        // ROL
+        (0x2A, 2, false),
+        (0x26, 5, false),
+        (0x36, 6, false),
+        (0x2E, 6, false),
+        (0x3E, 7, false),
+        // ROR
+        (0x6A, 2, false),
+        (0x66, 5, false),
+        (0x76, 6, false),
+        (0x6E, 6, false),
+        (0x7E, 7, false),
+        // RTI
+        (0x40, 6, false),
+        // RTS
+        (0x60, 6, false),
+        // SBC
+        (0xE9, 2, false),
+        (0xE5, 3, false),
+        (0xF5, 4, false),
+        (0xED, 4, false),
+        (0xFD, 4, true),
+        (0xF9, 4, true),
+        (0xE1, 6, false),
+        (0xF1, 5, true),
+        // STA
+        (0x85, 3, false),
+        (0x95, 4, false),
+        (0x8D, 4, false),
+        (0x9D, 5, false),
+        (0x99, 5, false),
+        (0x81, 6, false),
+        (0x91, 6, false),
+        // Stack
+        (0x9A, 2, false),
+        (0xBA, 2, false),
+        (0x48, 3, false),
+        (0x68, 4, false),
+        (0x08, 3, false),
+        (0x28, 4, false),
+        // STX
+        (0x86, 3, false),
+        (0x96, 4, false),
+        (0x8E, 4, false),
+        // STY
+        (0x84, 3, false),
+        (0x94, 4, false),
+        (0x8C, 4, false),
+    ];
+
+    fn is_branch(opcode: Byte) -> bool {
+        opcode & 0x1F == 0x10
+    }
+
+    // Runs one instruction at 0x0400 with its operand bytes set to $10 $30:
+    // zero page $10, absolute $3010 and a zero page pointer to $3010.
+    // Indexing with 0xFF crosses a page, with 0x01 it doesn't
+    fn cycles_of(opcode: Byte, index: Byte) -> isize {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.reset_vector(&mut mem, 0x0400);
+        cpu.set_x(index);
+        cpu.set_y(index);
+        mem.poke(0x0400, opcode);
+        mem.poke(0x0401, 0x10);
+        mem.poke(0x0402, 0x30);
+        mem.poke(0x0010, 0x10);
+        mem.poke(0x0011, 0x30);
+
+        // when:
+        cpu.step(&mut mem)
+    }
+
+    #[test]
+    fn the_timing_table_covers_the_151_documented_opcodes() {
+        // then:
+        let mut opcodes: Vec<Byte> = TIMING.iter().map(|&(opcode, _, _)| opcode).collect();
+        opcodes.sort();
+        opcodes.dedup();
+        assert_eq!(opcodes.len(), 151);
+    }
+
+    #[test]
+    fn every_documented_opcode_takes_the_published_cycles() {
+        for &(opcode, cycles, _) in TIMING.iter().filter(|(o, _, _)| !is_branch(*o)) {
+            // then:
+            assert_eq!(
+                cycles_of(opcode, 0x01),
+                cycles,
+                "opcode {:02X} without a page crossing",
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn indexed_reads_take_one_more_cycle_when_the_page_changes() {
+        for &(opcode, cycles, page_penalty) in TIMING.iter().filter(|(o, _, _)| !is_branch(*o)) {
+            // when:
+            let expected: isize = if page_penalty { cycles + 1 } else { cycles };
+
+            // then:
+            assert_eq!(
+                cycles_of(opcode, 0xFF),
+                expected,
+                "opcode {:02X} crossing a page",
+                opcode
+            );
+        }
+    }
+
+    #[test]
+    fn branches_take_one_more_cycle_when_taken_and_another_one_to_a_new_page() {
+        // opcode, status flag tested and the value that takes the branch
+        let branches: [(Byte, usize, bool); 8] = [
+            (0x90, 0, false), // BCC
+            (0xB0, 0, true),  // BCS
+            (0xF0, 1, true),  // BEQ
+            (0xD0, 1, false), // BNE
+            (0x30, 7, true),  // BMI
+            (0x10, 7, false), // BPL
+            (0x50, 6, false), // BVC
+            (0x70, 6, true),  // BVS
+        ];
+
+        for (opcode, bit, taken_on) in branches {
+            for (taken, offset, expected) in [(false, 0x10, 2), (true, 0x10, 3), (true, 0xF0, 4)] {
+                let mut mem = Mem::new();
+                let mut cpu = CPU::new();
+
+                // given:
+                cpu.reset_vector(&mut mem, 0x0408);
+                let mut status = cpu.status();
+                status.set_bit(bit, taken == taken_on);
+                cpu.set_status(status);
+                mem.poke(0x0408, opcode);
+                mem.poke(0x0409, offset);
+
+                // when:
+                let actual_cycles = cpu.step(&mut mem);
+
+                // then:
+                assert_eq!(
+                    actual_cycles, expected,
+                    "opcode {:02X} taken: {} offset: {:02X}",
+                    opcode, taken, offset
+                );
+            }
+        }
+    }
+}
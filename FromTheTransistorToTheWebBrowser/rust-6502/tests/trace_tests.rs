@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod trace_tests {
+
+    use rust_6502::opcodes::AddressingMode;
+    use rust_6502::trace::*;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+    use rust_6502::{Byte, Word};
+
+    fn record(pc: Word, bytes: [Byte; 3], length: usize, mnemonic: &'static str) -> TraceRecord {
+        TraceRecord {
+            pc,
+            bytes,
+            length,
+            mnemonic,
+            mode: AddressingMode::Absolute,
+            documented: true,
+            a: 0x00,
+            x: 0x00,
+            y: 0x00,
+            sp: 0xFD,
+            ps: 0x24,
+            cycles: 7,
+        }
+    }
+
+    // LDA #$01, LDX #$02, NOP, NOP at 0x0400
+    fn setup(mem: &mut Mem) -> CPU {
+        let mut cpu = CPU::new();
+        cpu.reset_vector(mem, 0x0400);
+        mem.load(0x0400, &[0xA9, 0x01, 0xA2, 0x02, 0xEA, 0xEA]);
+        cpu
+    }
+
+    #[test]
+    fn a_record_is_formatted_like_the_nestest_log() {
+        // given:
+        let record = record(0xC000, [0x4C, 0xF5, 0xC5], 3, "JMP");
+
+        // then:
+        assert_eq!(
+            nestest_line(&record),
+            "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7"
+        );
+    }
+
+    #[test]
+    fn undocumented_opcodes_are_marked_with_a_star() {
+        // given:
+        let mut record = record(0xC000, [0xA7, 0x33, 0x00], 2, "LAX");
+        record.mode = AddressingMode::ZeroPage;
+        record.documented = false;
+        record.ps = 0xB4;
+
+        // then:
+        assert_eq!(
+            nestest_line(&record),
+            "C000  A7 33    *LAX $33                         A:00 X:00 Y:00 P:A4 SP:FD CYC:7"
+        );
+    }
+
+    #[test]
+    fn the_sink_gets_the_state_before_every_instruction() {
+        let mut mem = Mem::new();
+        let mut ring = TraceRing::new(8);
+
+        // given:
+        let mut cpu = setup(&mut mem);
+
+        // when:
+        let actual_cycles = cpu.execute_traced(&mut 6, &mut mem, &mut ring);
+
+        // then:
+        let entries = ring.entries();
+        assert_eq!(actual_cycles, 6);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(
+            (entries[0].pc, entries[0].cycles, entries[0].a),
+            (0x0400, 0, 0x00)
+        );
+        assert_eq!(
+            (entries[1].pc, entries[1].cycles, entries[1].a),
+            (0x0402, 2, 0x01)
+        );
+        assert_eq!(
+            (entries[2].pc, entries[2].cycles, entries[2].x),
+            (0x0404, 4, 0x02)
+        );
+        assert_eq!(entries[1].bytes, [0xA2, 0x02, 0x00]);
+        assert_eq!(cpu.cycles(), 6);
+    }
+
+    #[test]
+    fn the_ring_buffer_keeps_the_last_instructions() {
+        let mut mem = Mem::new();
+        let mut ring = TraceRing::new(2);
+
+        // given:
+        let mut cpu = setup(&mut mem);
+
+        // when:
+        cpu.execute_traced(&mut 8, &mut mem, &mut ring);
+
+        // then:
+        let pcs: Vec<Word> = ring.entries().iter().map(|entry| entry.pc).collect();
+        assert_eq!(ring.len(), 2);
+        assert_eq!(pcs, vec![0x0404, 0x0405]);
+
+        // when:
+        ring.clear();
+
+        // then:
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn the_nestest_log_writes_a_line_per_instruction() {
+        let mut mem = Mem::new();
+        let mut log = NestestLog::new(Vec::new());
+
+        // given:
+        let mut cpu = setup(&mut mem);
+
+        // when:
+        cpu.execute_traced(&mut 4, &mut mem, &mut log);
+
+        // then:
+        let text = String::from_utf8(log.out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0400  A9 01     LDA #$01"));
+        assert!(lines[1].starts_with("0402  A2 02     LDX #$02"));
+        assert!(lines[1].ends_with("A:01 X:00 Y:00 P:65 SP:FF CYC:2"));
+    }
+
+    #[test]
+    fn executing_without_a_sink_still_counts_the_cycles() {
+        let mut mem = Mem::new();
+
+        // given:
+        let mut cpu = setup(&mut mem);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+        cpu.step(&mut mem);
+
+        // then:
+        assert_eq!(cpu.cycles(), 4);
+        assert!(!NoTrace.enabled());
+    }
+
+    // a disabled sink that keeps the addresses and opcodes
+    struct Opcodes {
+        seen: Vec<(Word, Byte)>,
+    }
+
+    impl TraceSink for Opcodes {
+        fn trace(&mut self, _record: &TraceRecord) {
+            panic!("no record is built for a disabled sink");
+        }
+
+        fn enabled(&self) -> bool {
+            false
+        }
+
+        fn instruction(&mut self, pc: Word, opcode: Byte) {
+            self.seen.push((pc, opcode));
+        }
+    }
+
+    #[test]
+    fn a_disabled_sink_still_gets_the_opcodes() {
+        let mut mem = Mem::new();
+        let mut opcodes = Opcodes { seen: Vec::new() };
+
+        // given:
+        let mut cpu = setup(&mut mem);
+
+        // when:
+        cpu.execute_traced(&mut 6, &mut mem, &mut opcodes);
+
+        // then:
+        assert_eq!(
+            opcodes.seen,
+            vec![(0x0400, 0xA9), (0x0402, 0xA2), (0x0404, 0xEA)]
+        );
+    }
+}
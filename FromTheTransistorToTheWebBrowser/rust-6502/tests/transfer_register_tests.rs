@@ -1,14 +1,11 @@
 #[cfg(test)]
 mod transfer_register_tests {
 
-    use bit_field::BitField;
-    use std::os::raw::*;
-
-    type Byte = c_uchar;
-    type Word = c_ushort;
-
-    use crate::Mem;
-    use crate::CPU;
+    use rust_6502::bus::Bus;
+    use rust_6502::opcodes::*;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
 
     #[test]
     fn tax_of_can_transfer_non_negative_non_zero_value() {
@@ -20,12 +17,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.A = 0x42;
-        cpu.X = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_a(0x42);
+        cpu.set_x(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAX;
+        mem.poke(0xFFFC, INS_TAX);
 
         let mut expected_cycles = 2;
 
@@ -34,10 +31,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.X, 0x42);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.x(), 0x42);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -51,12 +48,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, false); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.A = 0x0;
-        cpu.X = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_a(0x0);
+        cpu.set_x(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAX;
+        mem.poke(0xFFFC, INS_TAX);
 
         let mut expected_cycles = 2;
 
@@ -65,10 +62,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0x0);
-        assert_eq!(cpu.X, 0x0);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.a(), 0x0);
+        assert_eq!(cpu.x(), 0x0);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -82,12 +79,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, false); // Negative Flag
-        cpu.A = 0b10000000;
-        cpu.X = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, false); // Negative Flag
+        cpu.set_a(0b10000000);
+        cpu.set_x(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAX;
+        mem.poke(0xFFFC, INS_TAX);
 
         let mut expected_cycles = 2;
 
@@ -96,10 +93,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0b10000000);
-        assert_eq!(cpu.X, 0b10000000);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.a(), 0b10000000);
+        assert_eq!(cpu.x(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -113,12 +110,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.A = 0x42;
-        cpu.Y = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_a(0x42);
+        cpu.set_y(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAY;
+        mem.poke(0xFFFC, INS_TAY);
 
         let mut expected_cycles = 2;
 
@@ -127,10 +124,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.Y, 0x42);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.y(), 0x42);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -144,12 +141,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, false); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.A = 0x0;
-        cpu.Y = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_a(0x0);
+        cpu.set_y(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAY;
+        mem.poke(0xFFFC, INS_TAY);
 
         let mut expected_cycles = 2;
 
@@ -158,10 +155,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0x0);
-        assert_eq!(cpu.Y, 0x0);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.a(), 0x0);
+        assert_eq!(cpu.y(), 0x0);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -175,12 +172,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, false); // Negative Flag
-        cpu.A = 0b10000000;
-        cpu.Y = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, false); // Negative Flag
+        cpu.set_a(0b10000000);
+        cpu.set_y(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TAY;
+        mem.poke(0xFFFC, INS_TAY);
 
         let mut expected_cycles = 2;
 
@@ -189,10 +186,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.A, 0b10000000);
-        assert_eq!(cpu.Y, 0b10000000);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.a(), 0b10000000);
+        assert_eq!(cpu.y(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -206,12 +203,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.X = 0x42;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_x(0x42);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TXA;
+        mem.poke(0xFFFC, INS_TXA);
 
         let mut expected_cycles = 2;
 
@@ -220,10 +217,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x42);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x42);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -237,12 +234,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, false); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.X = 0x0;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_x(0x0);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TXA;
+        mem.poke(0xFFFC, INS_TXA);
 
         let mut expected_cycles = 2;
 
@@ -251,10 +248,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0x0);
-        assert_eq!(cpu.A, 0x0);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.x(), 0x0);
+        assert_eq!(cpu.a(), 0x0);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -268,12 +265,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, false); // Negative Flag
-        cpu.X = 0b10000000;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, false); // Negative Flag
+        cpu.set_x(0b10000000);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TXA;
+        mem.poke(0xFFFC, INS_TXA);
 
         let mut expected_cycles = 2;
 
@@ -282,10 +279,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.X, 0b10000000);
-        assert_eq!(cpu.A, 0b10000000);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.x(), 0b10000000);
+        assert_eq!(cpu.a(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -299,12 +296,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.Y = 0x42;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_y(0x42);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TYA;
+        mem.poke(0xFFFC, INS_TYA);
 
         let mut expected_cycles = 2;
 
@@ -313,10 +310,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0x42);
-        assert_eq!(cpu.A, 0x42);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.y(), 0x42);
+        assert_eq!(cpu.a(), 0x42);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -330,12 +327,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, false); // Zero Flag
-        cpu.PS.set_bit(7, true); // Negative Flag
-        cpu.Y = 0x0;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, false); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, true); // Negative Flag
+        cpu.set_y(0x0);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TYA;
+        mem.poke(0xFFFC, INS_TYA);
 
         let mut expected_cycles = 2;
 
@@ -344,10 +341,10 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0x0);
-        assert_eq!(cpu.A, 0x0);
-        assert_eq!(cpu.PS.get_bit(1), true);
-        assert_eq!(cpu.PS.get_bit(7), false);
+        assert_eq!(cpu.y(), 0x0);
+        assert_eq!(cpu.a(), 0x0);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), true);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), false);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
@@ -361,12 +358,12 @@ mod transfer_register_tests {
         cpu.reset(&mut mem);
         cpu_copy.reset(&mut mem);
 
-        cpu.PS.set_bit(1, true); // Zero Flag
-        cpu.PS.set_bit(7, false); // Negative Flag
-        cpu.Y = 0b10000000;
-        cpu.A = 0x32;
+        cpu.set_flag(Flags::ZeroFlagBit, true); // Zero Flag
+        cpu.set_flag(Flags::NegativeFlagBit, false); // Negative Flag
+        cpu.set_y(0b10000000);
+        cpu.set_a(0x32);
 
-        mem.Data[0xFFFC] = cpu.INS_TYA;
+        mem.poke(0xFFFC, INS_TYA);
 
         let mut expected_cycles = 2;
 
@@ -375,18 +372,33 @@ mod transfer_register_tests {
 
         //then
         assert_eq!(actual_cycles, 2);
-        assert_eq!(cpu.Y, 0b10000000);
-        assert_eq!(cpu.A, 0b10000000);
-        assert_eq!(cpu.PS.get_bit(1), false);
-        assert_eq!(cpu.PS.get_bit(7), true);
+        assert_eq!(cpu.y(), 0b10000000);
+        assert_eq!(cpu.a(), 0b10000000);
+        assert_eq!(cpu.flag(Flags::ZeroFlagBit), false);
+        assert_eq!(cpu.flag(Flags::NegativeFlagBit), true);
         verify_unmodified_flags(cpu, cpu_copy);
     }
 
     fn verify_unmodified_flags(cpu: CPU, cpu_copy: CPU) {
-        assert_eq!(cpu.PS.get_bit(0), cpu_copy.PS.get_bit(0));
-        assert_eq!(cpu.PS.get_bit(2), cpu_copy.PS.get_bit(2));
-        assert_eq!(cpu.PS.get_bit(3), cpu_copy.PS.get_bit(3));
-        assert_eq!(cpu.PS.get_bit(4), cpu_copy.PS.get_bit(4));
-        assert_eq!(cpu.PS.get_bit(6), cpu_copy.PS.get_bit(6));
+        assert_eq!(
+            cpu.flag(Flags::CarryFlagBit),
+            cpu_copy.flag(Flags::CarryFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::InterruptDisableFlagBit),
+            cpu_copy.flag(Flags::InterruptDisableFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::DecimalModeFlagBit),
+            cpu_copy.flag(Flags::DecimalModeFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::BreakFlagBit),
+            cpu_copy.flag(Flags::BreakFlagBit)
+        );
+        assert_eq!(
+            cpu.flag(Flags::OverflowFlagBit),
+            cpu_copy.flag(Flags::OverflowFlagBit)
+        );
     }
 }
@@ -0,0 +1,571 @@
+#[cfg(test)]
+mod undocumented_opcodes_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::Byte;
+    use rust_6502::Flags;
+    use rust_6502::Mem;
+    use rust_6502::CPU;
+
+    fn setup(cpu: &mut CPU, mem: &mut Mem, program: &[Byte]) {
+        cpu.reset_vector(mem, 0xFF00);
+        mem.load(0xFF00, program);
+    }
+
+    #[test]
+    fn undocumented_opcodes_take_the_nmos_cycle_counts() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        let mut timings: Vec<(Byte, isize)> = Vec::new();
+        // SLO, RLA, SRE, RRA, DCP, ISC
+        for base in [0x00, 0x20, 0x40, 0x60, 0xC0, 0xE0] {
+            timings.extend_from_slice(&[
+                (base + 0x07, 5), // ZP
+                (base + 0x17, 6), // ZPX
+                (base + 0x0F, 6), // ABS
+                (base + 0x1F, 7), // ABSX
+                (base + 0x1B, 7), // ABSY
+                (base + 0x03, 8), // INDX
+                (base + 0x13, 8), // INDY
+            ]);
+        }
+        timings.extend_from_slice(&[
+            (0x87, 3), // SAX
+            (0x97, 4),
+            (0x8F, 4),
+            (0x83, 6),
+            (0xA7, 3), // LAX
+            (0xB7, 4),
+            (0xAF, 4),
+            (0xBF, 4),
+            (0xA3, 6),
+            (0xB3, 5),
+            (0xAB, 2),
+            (0x0B, 2), // ANC, ALR, ARR, SBX, SBC, ANE
+            (0x2B, 2),
+            (0x4B, 2),
+            (0x6B, 2),
+            (0xCB, 2),
+            (0xEB, 2),
+            (0x8B, 2),
+            (0x9F, 5), // SHA, SHX, SHY, TAS, LAS
+            (0x93, 6),
+            (0x9E, 5),
+            (0x9C, 5),
+            (0x9B, 5),
+            (0xBB, 4),
+        ]);
+        for opcode in [
+            0x1A, 0x3A, 0x5A, 0x7A, 0xDA, 0xFA, 0x80, 0x82, 0x89, 0xC2, 0xE2,
+        ] {
+            timings.push((opcode, 2));
+        }
+        for opcode in [0x04, 0x44, 0x64] {
+            timings.push((opcode, 3));
+        }
+        for opcode in [
+            0x14, 0x34, 0x54, 0x74, 0xD4, 0xF4, 0x0C, 0x1C, 0x3C, 0x5C, 0x7C, 0xDC, 0xFC,
+        ] {
+            timings.push((opcode, 4));
+        }
+
+        for (opcode, expected_cycles) in timings {
+            // given:
+            cpu.set_pc(0x0200);
+            cpu.set_x(0);
+            cpu.set_y(0);
+            cpu.set_sp(0xFF);
+            mem.poke(0x0200, opcode);
+            mem.poke(0x0201, 0x40);
+            mem.poke(0x0202, 0x30);
+
+            // when:
+            let cycles_used = cpu.step(&mut mem);
+
+            // then:
+            assert_eq!(cycles_used, expected_cycles, "opcode {:02X}", opcode);
+        }
+    }
+
+    #[test]
+    fn every_opcode_can_be_executed() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        for opcode in 0..=0xFF {
+            // given:
+            let mut cpu = CPU::new();
+            cpu.set_pc(0x0200);
+            cpu.set_sp(0xFF);
+            mem.poke(0x0200, opcode);
+            mem.poke(0x0201, 0x40);
+            mem.poke(0x0202, 0x30);
+            // keep INC and DEC away from the edges, whatever they address
+            for address in [0x0040, 0x0041, 0x0101, 0x3040] {
+                mem.poke(address, 0x01);
+            }
+
+            // when:
+            let cycles_used = cpu.step(&mut mem);
+
+            // then:
+            assert!(cycles_used >= 1, "opcode {:02X}", opcode);
+        }
+    }
+
+    #[test]
+    fn lax_zero_page_loads_a_and_x() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xA7, 0x42]);
+        mem.poke(0x0042, 0x84);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 3, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 3);
+        assert_eq!(cpu.a(), 0x84);
+        assert_eq!(cpu.x(), 0x84);
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+        assert!(!cpu.flag(Flags::ZeroFlagBit)); // Z
+    }
+
+    #[test]
+    fn lax_indirect_y_takes_an_extra_cycle_when_crossing_a_page() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xB3, 0x10]);
+        cpu.set_y(0x20);
+        mem.poke(0x0010, 0xF0);
+        mem.poke(0x0011, 0x12);
+        mem.poke(0x1310, 0x37);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 6, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 6);
+        assert_eq!(cpu.a(), 0x37);
+        assert_eq!(cpu.x(), 0x37);
+    }
+
+    #[test]
+    fn sax_stores_a_and_x_without_changing_the_flags() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x8F, 0x00, 0x40]);
+        cpu.set_a(0xF0);
+        cpu.set_x(0x3C);
+        let ps = cpu.status();
+
+        // when:
+        let cycles_used = cpu.execute(&mut 4, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 4);
+        assert_eq!(mem.peek(0x4000), 0x30);
+        assert_eq!(cpu.status(), ps);
+    }
+
+    #[test]
+    fn slo_shifts_the_memory_left_and_ors_it_into_a() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x07, 0x42]);
+        cpu.set_a(0x01);
+        mem.poke(0x0042, 0x81);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0x02);
+        assert_eq!(cpu.a(), 0x03);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn rla_rotates_the_memory_left_and_ands_it_into_a() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x3F, 0xF0, 0x12]);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_a(0x0F);
+        cpu.set_x(0x20);
+        mem.poke(0x1310, 0x42);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x1310), 0x85);
+        assert_eq!(cpu.a(), 0x05);
+        assert!(!cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn sre_shifts_the_memory_right_and_eors_it_into_a() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x43, 0x10]);
+        cpu.set_a(0xFF);
+        cpu.set_x(0x02);
+        mem.poke(0x0012, 0x00);
+        mem.poke(0x0013, 0x40);
+        mem.poke(0x4000, 0x03);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 8, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 8);
+        assert_eq!(mem.peek(0x4000), 0x01);
+        assert_eq!(cpu.a(), 0xFE);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+    }
+
+    #[test]
+    fn rra_rotates_the_memory_right_and_adds_it_with_the_carry_out() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x67, 0x42]);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_a(0x10);
+        mem.poke(0x0042, 0x03);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0x01);
+        assert_eq!(cpu.a(), 0x12); // 0x10 + 0x01 + C
+    }
+
+    #[test]
+    fn dcp_decrements_the_memory_and_compares_it_with_a() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xC7, 0x42]);
+        cpu.set_a(0x41);
+        mem.poke(0x0042, 0x42);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x0042), 0x41);
+        assert_eq!(cpu.a(), 0x41);
+        assert!(cpu.flag(Flags::ZeroFlagBit)); // Z
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn isc_increments_the_memory_and_subtracts_it_from_a() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xFB, 0x00, 0x40]);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_a(0x10);
+        cpu.set_y(0x01);
+        mem.poke(0x4001, 0x04);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 7, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 7);
+        assert_eq!(mem.peek(0x4001), 0x05);
+        assert_eq!(cpu.a(), 0x0B);
+    }
+
+    #[test]
+    fn anc_copies_n_into_c() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x0B, 0xF0]);
+        cpu.set_a(0x81);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cpu.a(), 0x80);
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn alr_ands_and_shifts_right() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x4B, 0x03]);
+        cpu.set_a(0xFF);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cpu.a(), 0x01);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn arr_takes_c_from_bit_6_and_v_from_bits_6_and_5() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x6B, 0xC0]);
+        cpu.set_flag(Flags::CarryFlagBit, true); // C
+        cpu.set_a(0xFF);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cpu.a(), 0xE0);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+        assert!(!cpu.flag(Flags::OverflowFlagBit)); // V
+        assert!(cpu.flag(Flags::NegativeFlagBit)); // N
+    }
+
+    #[test]
+    fn arr_in_decimal_mode_fixes_up_the_result() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x6B, 0xFF]);
+        cpu.set_flag(Flags::DecimalModeFlagBit, true); // D
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_a(0xDB);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        // 0xDB >> 1 = 0x6D, the low nibble of 0xDB is > 5 -> 0x63,
+        // the high nibble is > 5 -> 0xC3 and C
+        assert_eq!(cpu.a(), 0xC3);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+        assert!(!cpu.flag(Flags::NegativeFlagBit)); // N
+        assert!(!cpu.flag(Flags::OverflowFlagBit)); // V
+    }
+
+    #[test]
+    fn sbx_subtracts_from_a_and_x_without_borrow() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xCB, 0x05]);
+        cpu.set_flag(Flags::CarryFlagBit, false); // C
+        cpu.set_a(0xFF);
+        cpu.set_x(0x0F);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cpu.x(), 0x0A);
+        assert_eq!(cpu.a(), 0xFF);
+        assert!(cpu.flag(Flags::CarryFlagBit)); // C
+    }
+
+    #[test]
+    fn ane_and_lxa_use_the_configured_magic_constants() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x8B, 0x3C, 0xAB, 0x0F]);
+        cpu.unstable.ane_magic = 0xFF;
+        cpu.unstable.lxa_magic = 0x00;
+        cpu.set_a(0x00);
+        cpu.set_x(0xF0);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+        let a_after_ane = cpu.a();
+        cpu.set_a(0x35);
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(a_after_ane, 0x30); // (0x00 | 0xFF) & 0xF0 & 0x3C
+        assert_eq!(cpu.a(), 0x05); // (0x35 | 0x00) & 0x0F
+        assert_eq!(cpu.x(), 0x05);
+    }
+
+    #[test]
+    fn ane_uses_0xee_by_default() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x8B, 0xFF]);
+        cpu.set_a(0x00);
+        cpu.set_x(0xFF);
+
+        // when:
+        cpu.execute(&mut 2, &mut mem);
+
+        // then:
+        assert_eq!(cpu.a(), 0xEE);
+    }
+
+    #[test]
+    fn sha_stores_a_and_x_and_the_high_byte_plus_one() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x9F, 0x00, 0x12]);
+        cpu.set_a(0xFF);
+        cpu.set_x(0xF7);
+        cpu.set_y(0x10);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 5);
+        assert_eq!(mem.peek(0x1210), 0x13); // 0xF7 & (0x12 + 1)
+    }
+
+    #[test]
+    fn shx_crossing_a_page_replaces_the_high_byte_of_the_address() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x9E, 0xF0, 0x12]);
+        cpu.set_x(0x0F);
+        cpu.set_y(0x20);
+
+        // when:
+        cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        // the value is 0x0F & 0x13, and it also becomes the high byte
+        assert_eq!(mem.peek(0x0310), 0x03);
+        assert_eq!(mem.peek(0x1310), 0x00);
+    }
+
+    #[test]
+    fn tas_sets_sp_to_a_and_x() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0x9B, 0x00, 0x40]);
+        cpu.set_a(0xF3);
+        cpu.set_x(0x3F);
+        cpu.set_y(0x01);
+
+        // when:
+        cpu.execute(&mut 5, &mut mem);
+
+        // then:
+        assert_eq!(cpu.sp(), 0x33);
+        assert_eq!(mem.peek(0x4001), 0x01); // 0x33 & 0x41
+    }
+
+    #[test]
+    fn las_loads_a_x_and_sp_with_memory_and_sp() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xBB, 0x00, 0x40]);
+        cpu.set_sp(0xF0);
+        mem.poke(0x4000, 0x3C);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 4, &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 4);
+        assert_eq!(cpu.a(), 0x30);
+        assert_eq!(cpu.x(), 0x30);
+        assert_eq!(cpu.sp(), 0x30);
+    }
+
+    #[test]
+    fn multi_byte_nops_skip_their_operands() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        // NOP #$12, NOP $12, NOP $12,X, NOP $1234, NOP $12F0,X
+        setup(
+            &mut cpu,
+            &mut mem,
+            &[
+                0x80, 0x12, 0x04, 0x12, 0x14, 0x12, 0x0C, 0x34, 0x12, 0x1C, 0xF0, 0x12,
+            ],
+        );
+        cpu.set_x(0x20);
+        let cpu_copy = cpu;
+
+        // when:
+        let cycles_used = cpu.execute(&mut (2 + 3 + 4 + 4 + 5), &mut mem);
+
+        // then:
+        assert_eq!(cycles_used, 2 + 3 + 4 + 4 + 5);
+        assert_eq!(cpu.pc(), 0xFF0C);
+        assert_eq!(cpu.a(), cpu_copy.a());
+        assert_eq!(cpu.status(), cpu_copy.status());
+    }
+
+    #[test]
+    fn jam_halts_the_cpu_until_it_is_reset() {
+        let mut mem = Mem::new();
+        let mut cpu = CPU::new();
+
+        // given:
+        setup(&mut cpu, &mut mem, &[0xEA, 0x02, 0xEA]);
+
+        // when:
+        let cycles_used = cpu.execute(&mut 100, &mut mem);
+        let pc_jammed = cpu.pc();
+        let jammed = cpu.is_jammed();
+        cpu.reset_vector(&mut mem, 0xFF00);
+
+        // then:
+        assert_eq!(cycles_used, 100);
+        assert_eq!(pc_jammed, 0xFF01);
+        assert!(jammed);
+        assert!(!cpu.is_jammed());
+    }
+}
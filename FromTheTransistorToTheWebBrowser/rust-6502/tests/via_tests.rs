@@ -0,0 +1,268 @@
+#[cfg(test)]
+mod via_tests {
+
+    use rust_6502::bus::Bus;
+    use rust_6502::debugger::Comparison;
+    use rust_6502::run::{StopCondition, StopReason};
+    use rust_6502::via::*;
+    use rust_6502::{AddressDecoder, Ram, Rom, CPU};
+
+    #[test]
+    fn the_ports_read_the_outputs_and_the_input_pins() {
+        let mut via = Via6522::new();
+
+        // when:
+        via.write(DDRA, 0x0F);
+        via.write(ORA, 0x55);
+        via.input_a = 0xA0;
+        via.write(DDRB, 0xF0);
+        via.write(ORB, 0x3C);
+        via.input_b = 0x0A;
+
+        // then:
+        assert_eq!(via.read(ORA), 0xA5);
+        assert_eq!(via.read(ORA_NO_HANDSHAKE), 0xA5);
+        assert_eq!(via.read(ORB), 0x3A);
+        assert_eq!(via.port_b(), 0x3A);
+        assert_eq!(via.read(DDRA), 0x0F);
+    }
+
+    #[test]
+    fn the_active_edge_of_c1_latches_the_port_when_the_acr_says_so() {
+        let mut via = Via6522::new();
+
+        // given:
+        via.write(PCR, PCR_CA1_RISING);
+        via.write(ACR, ACR_PA_LATCH);
+        via.input_a = 0x12;
+
+        // when:
+        via.set_ca1(true);
+        via.input_a = 0x34;
+
+        // then:
+        assert_eq!(via.peek(IFR) & IRQ_CA1, IRQ_CA1);
+        assert_eq!(via.read(ORA), 0x12);
+        assert_eq!(via.peek(IFR) & IRQ_CA1, 0);
+    }
+
+    #[test]
+    fn timer_1_times_out_once_in_one_shot_mode() {
+        let mut via = Via6522::new();
+
+        // given:
+        via.write(ACR, ACR_T1_PB7);
+        via.write(IER, IRQ_ANY | IRQ_T1);
+
+        // when:
+        via.write(T1CL, 0x10);
+        via.write(T1CH, 0x00);
+        via.tick(0x10);
+
+        // then: PB7 is low while the timer runs
+        assert_eq!(via.ifr, 0);
+        assert_eq!(via.port_b() & 0x80, 0);
+
+        // when:
+        via.tick(1);
+
+        // then:
+        assert!(via.irq());
+        assert_eq!(via.read(IFR), IRQ_ANY | IRQ_T1);
+        assert_eq!(via.port_b() & 0x80, 0x80);
+        assert_eq!(via.read(T1CH), 0xFF);
+
+        // when: reading the low counter clears the flag, and the timer
+        // keeps counting without flagging again
+        via.read(T1CL);
+        via.tick(0x20000);
+
+        // then:
+        assert!(!via.irq());
+        assert_eq!(via.read(IFR), 0);
+    }
+
+    #[test]
+    fn timer_1_reloads_from_its_latch_and_toggles_pb7_when_running_free() {
+        let mut via = Via6522::new();
+        let mut timeouts: Vec<u64> = Vec::new();
+        let mut pb7: Vec<bool> = Vec::new();
+
+        // given:
+        via.write(ACR, ACR_T1_FREE_RUN | ACR_T1_PB7);
+        via.write(T1LL, 0x08);
+        via.write(T1CH, 0x00);
+
+        // when:
+        for cycle in 1..=40 {
+            via.tick(1);
+            if via.ifr & IRQ_T1 != 0 {
+                timeouts.push(cycle);
+                pb7.push(via.port_b() & 0x80 != 0);
+                via.write(IFR, IRQ_T1);
+            }
+        }
+
+        // then: N + 1, then every N + 2 cycles
+        assert_eq!(timeouts, vec![9, 19, 29, 39]);
+        assert_eq!(pb7, vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn timer_2_times_out_once_or_counts_the_pulses_on_pb6() {
+        let mut via = Via6522::new();
+
+        // when:
+        via.write(T2CL, 0x05);
+        via.write(T2CH, 0x00);
+        via.tick(5);
+
+        // then:
+        assert_eq!(via.ifr & IRQ_T2, 0);
+
+        // when:
+        via.tick(1);
+        via.read(T2CL);
+        via.tick(0x10000);
+
+        // then: the flag was set once and cleared
+        assert_eq!(via.ifr & IRQ_T2, 0);
+
+        // when: 3 pulses
+        via.write(ACR, ACR_T2_PULSES);
+        via.write(T2CL, 0x03);
+        via.write(T2CH, 0x00);
+        via.tick(100);
+        for _ in 0..2 {
+            via.set_pb6(true);
+            via.set_pb6(false);
+        }
+
+        // then:
+        assert_eq!(via.read(T2CL), 0x01);
+        assert_eq!(via.ifr & IRQ_T2, 0);
+
+        // when:
+        via.set_pb6(true);
+        via.set_pb6(false);
+
+        // then:
+        assert_eq!(via.ifr & IRQ_T2, IRQ_T2);
+    }
+
+    #[test]
+    fn the_shift_register_shifts_8_bits_out_and_in() {
+        let mut via = Via6522::new();
+        let mut bits: Vec<bool> = Vec::new();
+
+        // given: out, clocked by the clock
+        via.write(ACR, 0b110 << 2);
+        via.write(SR, 0b1010_0011);
+
+        // when:
+        for _ in 0..8 {
+            via.tick(2);
+            bits.push(via.cb2);
+        }
+        via.tick(10);
+
+        // then: bit 7 first, the byte comes back
+        assert_eq!(
+            bits,
+            vec![true, false, true, false, false, false, true, true]
+        );
+        assert_eq!(via.peek(SR), 0b1010_0011);
+        assert_eq!(via.ifr & IRQ_SR, IRQ_SR);
+
+        // given: in, clocked by CB1
+        via.write(ACR, 0b011 << 2);
+        via.write(SR, 0x00);
+
+        // when:
+        for bit in [true, true, false, true] {
+            via.cb2 = bit;
+            via.set_cb1(true);
+            via.set_cb1(false);
+        }
+
+        // then:
+        assert_eq!(via.peek(SR), 0b0000_1101);
+        assert_eq!(via.ifr & IRQ_SR, 0);
+    }
+
+    #[test]
+    fn the_interrupt_enable_register_sets_and_clears_bits() {
+        let mut via = Via6522::new();
+
+        // when:
+        via.write(IER, IRQ_ANY | IRQ_T1 | IRQ_CB1 | IRQ_CA1);
+        via.write(IER, IRQ_CB1);
+        via.set_ca1(true);
+        via.set_ca1(false);
+        via.set_cb1(true);
+        via.set_cb1(false);
+
+        // then:
+        assert_eq!(via.read(IER), IRQ_ANY | IRQ_T1 | IRQ_CA1);
+        assert_eq!(via.read(IFR), IRQ_ANY | IRQ_CA1 | IRQ_CB1);
+
+        // when:
+        via.write(IFR, IRQ_CA1);
+
+        // then: CB1 is flagged but disabled
+        assert_eq!(via.read(IFR), IRQ_CB1);
+        assert!(!via.irq());
+    }
+
+    // A ROM at $F000 that starts timer 1 running free every 232 cycles with
+    // its interrupt, and counts the interrupts in $10:
+    //
+    //  F000  LDA #$40 / STA $900B    ACR: free running
+    //  F005  LDA #$C0 / STA $900E    IER: timer 1
+    //  F00A  LDA #$E6 / STA $9004
+    //  F00F  LDA #$00 / STA $9005    starts the timer
+    //  F014  CLI
+    //  F015  JMP $F015
+    //
+    //  F020  INC $10
+    //  F022  LDA $9004               clears the flag
+    //  F025  RTI
+    #[test]
+    fn the_timer_interrupts_the_cpu_through_the_bus() {
+        let mut rom: Vec<u8> = vec![0; 0x1000];
+        rom[..0x18].copy_from_slice(&[
+            0xA9, 0x40, 0x8D, 0x0B, 0x90, 0xA9, 0xC0, 0x8D, 0x0E, 0x90, 0xA9, 0xE6, 0x8D, 0x04,
+            0x90, 0xA9, 0x00, 0x8D, 0x05, 0x90, 0x58, 0x4C, 0x15, 0xF0,
+        ]);
+        rom[0x20..0x26].copy_from_slice(&[0xE6, 0x10, 0xAD, 0x04, 0x90, 0x40]);
+        rom[0xFFC..].copy_from_slice(&[0x00, 0xF0, 0x20, 0xF0]);
+
+        let mut bus = AddressDecoder::new();
+        bus.map(0x0000, 0x07FF, Box::new(Ram::new(0x0800)));
+        bus.map(0x9000, 0x900F, Box::new(Via6522::new()));
+        bus.map(0xF000, 0xFFFF, Box::new(Rom::new(rom)));
+        let mut cpu = CPU::new();
+
+        // given:
+        cpu.power_on(&mut bus);
+
+        // when:
+        let forty = StopCondition::Memory {
+            address: 0x0010,
+            comparison: Comparison::Equal,
+            value: 40,
+        };
+        let summary = cpu.run(&mut bus, &[forty, StopCondition::Cycles(20_000)]);
+
+        // then: the 40th interrupt comes 20 + 231 + 39 * 232 cycles in
+        assert_eq!(
+            summary.reason,
+            StopReason::Memory {
+                address: 0x0010,
+                value: 40
+            }
+        );
+        assert_eq!(summary.stats.interrupts, 40);
+        assert!((9_299..9_330).contains(&summary.stats.cycles));
+    }
+}